@@ -3,9 +3,12 @@ use clap_complete::{Shell, generate_to};
 use std::io::Error;
 use std::path::PathBuf;
 
+#[path = "src/utils/nushell.rs"]
+mod nushell;
+
 // Minimal CLI definition for build-time completion generation
 #[derive(Parser)]
-#[command(name = "claudectl")]
+#[command(name = "claudectl", version)]
 #[command(
     about = "A CLI tool for orchestrating Claude Code agents through the use of git worktrees."
 )]
@@ -15,37 +18,397 @@ struct Cli {
 
     #[arg(long, global = true, help = "Enable debug logging output")]
     debug: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Print plain text without colors, icons or box drawing"
+    )]
+    plain: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Report how long each step of the command took"
+    )]
+    timings: bool,
+
+    #[arg(
+        long,
+        global = true,
+        env = "CLAUDECTL_PROFILE",
+        value_name = "PROFILE",
+        help = "Profile from the global config to use for this run"
+    )]
+    profile: Option<String>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     Init(InitCommand),
     Task(TaskCommand),
+    Run(RunCommand),
     List(ListCommand),
+    Status(StatusCommand),
+    Overview(OverviewCommand),
+    Show(ShowCommand),
+    Diff(DiffCommand),
+    Compare(CompareCommand),
+    Open(OpenCommand),
+    Switch(SwitchCommand),
+    Exec(ExecCommand),
     Rm(RmCommand),
+    Rename(RenameCommand),
+    Clean(CleanCommand),
+    Archive(ArchiveCommand),
+    Restore(RestoreCommand),
+    Checkpoint(CheckpointCommand),
+    Rollback(RollbackCommand),
+    Summarize(SummarizeCommand),
+    Describe(DescribeCommand),
+    Resolve(ResolveCommand),
+    Merge(MergeCommand),
+    Pr(PrCommand),
+    Setup(SetupCommand),
+    SyncClaudeMd(SyncClaudeMdCommand),
+    Note(NoteCommand),
+    History(HistoryCommand),
+    Stats(StatsCommand),
+    Watch(WatchCommand),
+    Worktree(WorktreeCommand),
+    Kill(KillCommand),
+    Restart(RestartCommand),
 
     #[command(next_help_heading = "Utility Commands")]
     Completions(CompletionsCommand),
+    Man(ManCommand),
     Repair(RepairCommand),
+    Doctor(DoctorCommand),
+    Reconcile(ReconcileCommand),
+    Logs(LogsCommand),
+    Storage(StorageCommand),
+    Export(ExportCommand),
+    Import(ImportCommand),
+    Config(ConfigCommand),
+    Upgrade(UpgradeCommand),
 }
 
 #[derive(Args)]
 struct InitCommand {
-    project_name: Option<String>,
+    #[arg(long, value_parser = ["default", "acceptEdits", "plan", "bypassPermissions"])]
+    permission_mode: Option<String>,
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::DirPath)]
+    subproject: Option<String>,
+    #[arg(long = "recipe", value_name = "RECIPE")]
+    recipes: Vec<String>,
+    #[arg(long, value_name = "NAME", value_hint = ValueHint::Other)]
+    template: Option<String>,
 }
 
 #[derive(Args)]
 struct TaskCommand {
+    #[arg(required_unless_present_any = ["from_issue", "file"])]
+    task_name: Option<String>,
+    #[arg(long, value_name = "ISSUE", value_hint = ValueHint::Other)]
+    from_issue: Option<String>,
+    #[arg(
+        short = 'f',
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        conflicts_with_all = ["task_name", "from_issue", "clone", "start"]
+    )]
+    file: Option<String>,
+    #[arg(long, value_name = "REF", value_hint = ValueHint::Other, conflicts_with = "clone")]
+    base: Option<String>,
+    #[arg(long, value_name = "EXISTING_TASK", value_hint = ValueHint::Other)]
+    clone: Option<String>,
+    #[arg(long, requires = "clone")]
+    from_branch: bool,
+    #[arg(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+    #[arg(long, value_parser = ["feature", "bugfix", "chore", "experiment"])]
+    kind: Option<String>,
+    #[arg(long)]
+    start: bool,
+    #[arg(long, requires = "start")]
+    prompt: Option<String>,
+}
+
+#[derive(Args)]
+struct RunCommand {
+    #[arg(value_hint = ValueHint::Other)]
     task_name: String,
+    #[arg(long = "env", value_name = "KEY=VALUE")]
+    env: Vec<String>,
+    #[arg(long = "claude-arg", value_name = "ARG", allow_hyphen_values = true)]
+    claude_args: Vec<String>,
+    #[arg(last = true, required = true, value_name = "PROMPT")]
+    prompt: Vec<String>,
+}
+
+#[derive(Args)]
+struct StatusCommand {
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct ListCommand {
+    #[arg(short, long)]
+    verbose: bool,
+    #[arg(long, value_parser = ["ready", "working", "waiting", "unknown", "setup-failed", "crashed", "queued"])]
+    status: Option<String>,
+    #[arg(long)]
+    project: Option<String>,
+    #[arg(long)]
+    tag: Option<String>,
+    #[arg(long, value_parser = ["feature", "bugfix", "chore", "experiment"])]
+    kind: Option<String>,
+    #[arg(long, value_parser = ["created", "name", "status"])]
+    sort: Option<String>,
+    #[arg(long)]
+    filter: Option<String>,
+    #[arg(long, value_parser = ["table", "json", "tsv"], default_value = "table")]
+    format: String,
+    #[arg(long, conflicts_with = "format")]
+    json: bool,
+}
+
+#[derive(Args)]
+struct OverviewCommand {
+    #[arg(long, value_parser = ["ready", "working", "waiting", "unknown", "setup-failed", "crashed", "queued"])]
+    status: Option<String>,
+    #[arg(long, value_parser = ["created", "name", "status"])]
+    sort: Option<String>,
+    #[arg(long)]
+    filter: Option<String>,
+}
+
+#[derive(Args)]
+struct ShowCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+    #[arg(long)]
+    json: bool,
+    #[arg(long)]
+    diff: bool,
 }
 
 #[derive(Args)]
-struct ListCommand {}
+struct DiffCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+    #[arg(long, value_name = "REF")]
+    base: Option<String>,
+    #[arg(long, conflicts_with = "name_only")]
+    stat: bool,
+    #[arg(long)]
+    name_only: bool,
+}
+
+#[derive(Args)]
+struct CompareCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: String,
+    #[arg(value_hint = ValueHint::Other)]
+    other_task: String,
+    #[arg(long, conflicts_with = "output")]
+    diff: bool,
+    #[arg(long)]
+    output: bool,
+}
 
 #[derive(Args)]
 struct RmCommand {
+    #[arg(value_hint = ValueHint::Other, value_name = "TASK_NAME")]
+    task_names: Vec<String>,
+    #[arg(long, conflicts_with = "task_names")]
+    all_merged: bool,
+    #[arg(long, short)]
+    force: bool,
+}
+
+#[derive(Args)]
+struct RenameCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: String,
+    new_name: String,
+}
+
+#[derive(Args)]
+struct CleanCommand {
+    #[arg(long, conflicts_with = "yes")]
+    dry_run: bool,
+    #[arg(long, short)]
+    yes: bool,
+}
+
+#[derive(Args)]
+struct ArchiveCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+    #[arg(long, conflicts_with_all = ["task_name", "purge"])]
+    list: bool,
+    #[arg(long, value_name = "ARCHIVE", conflicts_with = "task_name")]
+    purge: Option<String>,
+}
+
+#[derive(Args)]
+struct RestoreCommand {
+    archive: String,
+}
+
+#[derive(Args)]
+struct CheckpointCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: String,
+    #[arg(short, long)]
+    message: Option<String>,
+    #[arg(long, value_name = "MINUTES")]
+    interval: Option<u64>,
+    #[arg(long)]
+    list: bool,
+}
+
+#[derive(Args)]
+struct RollbackCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: String,
+    #[arg(long, value_name = "CHECKPOINT")]
+    to: Option<u32>,
+}
+
+#[derive(Args)]
+struct SummarizeCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: String,
+}
+
+#[derive(Args)]
+struct DescribeCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+}
+
+#[derive(Args)]
+struct ResolveCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Args)]
+struct OpenCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+    #[arg(long, conflicts_with = "editor")]
+    print: bool,
+    #[arg(long)]
+    editor: bool,
+}
+
+#[derive(Args)]
+struct SwitchCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    query: Option<String>,
+}
+
+#[derive(Args)]
+struct ExecCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: String,
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    command: Vec<String>,
+}
+
+#[derive(Args)]
+struct MergeCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+    #[arg(long)]
+    fetch: bool,
+    #[arg(long)]
+    rebase: bool,
+    #[arg(long)]
+    delete: bool,
+}
+
+#[derive(Args)]
+struct PrCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+    #[arg(long, value_parser = ["github", "gitlab"])]
+    provider: Option<String>,
+    #[arg(long)]
+    draft: bool,
+    #[arg(long)]
+    title: Option<String>,
+}
+
+#[derive(Args)]
+struct SetupCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+}
+
+#[derive(Args)]
+struct SyncClaudeMdCommand {}
+
+#[derive(Args)]
+struct NoteCommand {
     #[arg(value_hint = ValueHint::Other)]
     task_name: String,
+    text: Option<String>,
+}
+
+#[derive(Args)]
+struct HistoryCommand {
+    #[arg(long, value_hint = ValueHint::Other)]
+    task: Option<String>,
+    #[arg(long)]
+    since: Option<String>,
+    #[arg(long)]
+    until: Option<String>,
+    #[arg(long)]
+    json: bool,
+    #[arg(long, conflicts_with = "json")]
+    heatmap: bool,
+}
+
+#[derive(Args)]
+struct WatchCommand {
+    #[arg(
+        long = "on-change",
+        value_name = "GLOB",
+        required_unless_present = "supervise"
+    )]
+    patterns: Vec<String>,
+    #[arg(long, value_name = "NAME", required_unless_present = "supervise")]
+    template: Option<String>,
+    #[arg(long, conflicts_with_all = ["patterns", "template"])]
+    supervise: bool,
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
+}
+
+#[derive(Args)]
+struct KillCommand {
+    #[arg(value_hint = ValueHint::Other, conflicts_with = "all")]
+    task_name: Option<String>,
+    #[arg(long)]
+    all: bool,
+    #[arg(long, value_name = "SECONDS")]
+    timeout: Option<u64>,
+}
+
+#[derive(Args)]
+struct RestartCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+    #[arg(long, value_name = "SECONDS")]
+    timeout: Option<u64>,
 }
 
 #[derive(Args)]
@@ -54,6 +417,10 @@ struct CompletionsCommand {
     shell: Option<CompletionShell>,
     #[arg(long)]
     verify: bool,
+    #[arg(long, conflicts_with_all = ["verify", "uninstall"])]
+    install: bool,
+    #[arg(long, conflicts_with = "verify")]
+    uninstall: bool,
 }
 
 #[derive(Args)]
@@ -62,13 +429,152 @@ struct RepairCommand {
     force: bool,
 }
 
+#[derive(Args)]
+struct DoctorCommand {
+    #[arg(long)]
+    project: bool,
+    #[arg(long)]
+    migrate: bool,
+}
+
+#[derive(Args)]
+struct ReconcileCommand {
+    #[arg(long)]
+    stop: bool,
+}
+
+#[derive(Args)]
+struct LogsCommand {
+    #[arg(long, short = 'n', default_value_t = 50)]
+    lines: usize,
+    #[arg(long, conflicts_with = "lines")]
+    path: bool,
+}
+
+#[derive(Args)]
+struct StorageCommand {
+    #[command(subcommand)]
+    action: StorageAction,
+}
+
+#[derive(Subcommand)]
+enum StorageAction {
+    Info(StorageInfoCommand),
+}
+
+#[derive(Args)]
+struct StorageInfoCommand {
+    #[arg(long, conflicts_with = "project")]
+    global: bool,
+    #[arg(long)]
+    project: bool,
+}
+
+#[derive(Args)]
+struct StatsCommand {
+    #[arg(long, default_value_t = 8)]
+    weeks: u64,
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct ExportCommand {
+    #[arg(value_hint = ValueHint::FilePath)]
+    file: String,
+}
+
+#[derive(Args)]
+struct ImportCommand {
+    #[arg(value_hint = ValueHint::FilePath)]
+    file: String,
+    #[arg(long = "map", value_name = "OLD=NEW")]
+    maps: Vec<String>,
+}
+
+#[derive(Args)]
+struct ConfigCommand {
+    #[command(subcommand)]
+    action: ConfigAction,
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    Get(ConfigGetCommand),
+    Set(ConfigSetCommand),
+    List(ConfigListCommand),
+}
+
+#[derive(Args)]
+struct WorktreeCommand {
+    #[command(subcommand)]
+    action: WorktreeAction,
+}
+
+#[derive(Subcommand)]
+enum WorktreeAction {
+    Sync(WorktreeSyncCommand),
+}
+
+#[derive(Args)]
+struct WorktreeSyncCommand {
+    #[arg(value_hint = ValueHint::Other)]
+    task_name: Option<String>,
+    #[arg(long)]
+    merge: bool,
+    #[arg(long)]
+    no_fetch: bool,
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Args)]
+struct ManCommand {
+    #[arg(value_name = "COMMAND")]
+    command: Vec<String>,
+    #[arg(long, conflicts_with_all = ["command", "output"])]
+    install: bool,
+    #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath, conflicts_with = "command")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct UpgradeCommand {
+    #[arg(long)]
+    check: bool,
+}
+
+#[derive(Args)]
+struct ConfigGetCommand {
+    key: String,
+    #[arg(long)]
+    global: bool,
+}
+
+#[derive(Args)]
+struct ConfigSetCommand {
+    key: String,
+    value: String,
+    #[arg(long)]
+    global: bool,
+}
+
+#[derive(Args)]
+struct ConfigListCommand {
+    #[arg(long)]
+    global: bool,
+}
+
 #[derive(ValueEnum, Clone)]
 enum CompletionShell {
     Bash,
     Zsh,
     Fish,
+    #[value(name = "powershell", alias = "pwsh")]
     PowerShell,
     Elvish,
+    #[value(alias = "nu")]
+    Nushell,
 }
 
 fn main() -> Result<(), Error> {
@@ -90,6 +596,8 @@ fn main() -> Result<(), Error> {
     for &shell in shells.iter() {
         generate_to(shell, &mut cmd, "claudectl", &completions_dir)?;
     }
+    let mut nushell_file = std::fs::File::create(completions_dir.join("claudectl.nu"))?;
+    nushell::generate(&mut cmd, "claudectl", &mut nushell_file)?;
 
     println!("cargo:rerun-if-changed=src/");
     println!("cargo:rerun-if-changed=Cargo.toml");
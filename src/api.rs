@@ -0,0 +1,184 @@
+//! Task creation, agent runs and task storage for programs that embed
+//! claudectl.
+//!
+//! Like the CLI, every function works on the project in the current
+//! directory, which must have been set up with `claudectl init`. Nothing is
+//! printed and nothing is asked of the user: what the CLI would print,
+//! including the output of setup commands, hooks and claude itself, is
+//! returned as [`Captured::output`], and questions such as approving an
+//! elevated permission mode are declined. Projects that run agents with
+//! elevated permissions need `permissions.require_approval` turned off.
+//!
+//! ```no_run
+//! use claudectl::api::{self, NewTask};
+//!
+//! let task = api::create_task(&NewTask::new("feat/login"))?.value;
+//! let run = api::run(&task.info.name, "Add a login form")?.value;
+//! println!("claude {}: {}", run.status(), run.output);
+//! # Ok::<(), claudectl::api::Error>(())
+//! ```
+
+use serde::Serialize;
+
+use crate::commands::{list::task_entry, run::RunCommand, task::TaskCommand};
+use crate::utils::{
+    config::Config,
+    fs::read_local_config_file,
+    git::find_worktree,
+    output::capture,
+    tasks::{project_task_names, task_worktree},
+};
+
+pub use crate::utils::claude::Status;
+pub use crate::utils::errors::CommandError as Error;
+pub use crate::utils::events::{Event, EventKind};
+pub use crate::utils::filter::TaskInfo;
+pub use crate::utils::lock::SessionLock;
+pub use crate::utils::tasks::{PromptRecord, RunRecord, TaskKind, TaskRecord};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A result together with what the CLI would have printed while producing
+/// it, as plain text.
+#[derive(Debug)]
+pub struct Captured<T> {
+    pub value: T,
+    pub output: String,
+}
+
+/// A task: what `claudectl list --json` says about it, and everything
+/// claudectl has stored about it.
+#[derive(Debug, Serialize)]
+pub struct Task {
+    #[serde(flatten)]
+    pub info: TaskInfo,
+    pub record: TaskRecord,
+}
+
+/// How to create a task, as with the options of `claudectl task`.
+#[derive(Debug, Clone, Default)]
+pub struct NewTask {
+    /// The branch name, or a name to turn into one
+    pub name: String,
+    /// An existing task whose base and settings to copy
+    pub clone: Option<String>,
+    /// With `clone`, start from the existing task's branch
+    pub from_branch: bool,
+    pub tags: Vec<String>,
+    pub kind: Option<TaskKind>,
+}
+
+impl NewTask {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Create a task's branch and worktree, and run the project's setup commands
+/// in it.
+pub fn create_task(new_task: &NewTask) -> Result<Captured<Task>> {
+    let command = TaskCommand {
+        task_name: Some(new_task.name.clone()),
+        from_issue: None,
+        file: None,
+        base: None,
+        clone: new_task.clone.clone(),
+        from_branch: new_task.from_branch,
+        tags: new_task.tags.clone(),
+        kind: new_task.kind,
+        start: false,
+        prompt: None,
+    };
+    captured(|| {
+        let name = command.branch_name()?;
+        command.execute()?;
+        task(&name)
+    })
+}
+
+/// Run claude non-interactively with `prompt` in a task, creating the task
+/// first if it does not exist, as `claudectl run` does.
+///
+/// A run that fails is returned like any other; check
+/// [`RunRecord::succeeded`].
+pub fn run(task_name: &str, prompt: &str) -> Result<Captured<RunRecord>> {
+    let command = RunCommand {
+        task_name: task_name.to_string(),
+        prompt: vec![prompt.to_string()],
+        env: Vec::new(),
+        claude_args: Vec::new(),
+    };
+    captured(|| Ok(command.run()?.1))
+}
+
+/// Every task in the project.
+pub fn tasks() -> Result<Vec<Task>> {
+    let project = project_name()?;
+    project_task_names()?
+        .iter()
+        .map(|name| task_in(&project, name))
+        .collect()
+}
+
+/// A task by name.
+pub fn task(name: &str) -> Result<Task> {
+    task_in(&project_name()?, name)
+}
+
+fn task_in(project: &str, name: &str) -> Result<Task> {
+    let entry = task_entry(project, task_worktree(name)?)?;
+    Ok(Task {
+        info: entry.info(),
+        record: entry.record,
+    })
+}
+
+fn project_name() -> Result<String> {
+    Ok(Config::from_str(&read_local_config_file()?)?.project_name)
+}
+
+/// Whether a task of this name exists.
+pub fn task_exists(name: &str) -> Result<bool> {
+    Ok(find_worktree(name)?.is_some())
+}
+
+/// The project's event log, oldest first, optionally only one task's.
+pub fn events(task_name: Option<&str>) -> Result<Vec<Event>> {
+    let events = crate::utils::events::read_events()?;
+    Ok(events
+        .into_iter()
+        .filter(|event| task_name.map_or(true, |name| event.task == name))
+        .collect())
+}
+
+fn captured<T>(f: impl FnOnce() -> Result<T>) -> Result<Captured<T>> {
+    let (value, output) = capture(f);
+    value.map(|value| Captured { value, output })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_task_defaults_to_plain_task() {
+        let new_task = NewTask::new("feat/login");
+        assert_eq!(new_task.name, "feat/login");
+        assert!(new_task.clone.is_none() && !new_task.from_branch);
+        assert!(new_task.tags.is_empty() && new_task.kind.is_none());
+    }
+
+    #[test]
+    fn test_captured_keeps_output_of_successful_calls() {
+        let captured = captured(|| {
+            crate::utils::output::standard("hello");
+            Ok(1)
+        })
+        .unwrap();
+        assert_eq!(captured.value, 1);
+        assert_eq!(captured.output, "hello\n");
+    }
+}
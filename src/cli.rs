@@ -0,0 +1,43 @@
+use clap::Parser;
+
+use crate::commands::Commands;
+
+#[derive(Parser)]
+#[command(name = "claudectl", version)]
+#[command(
+    about = "A CLI tool for orchestrating Claude Code agents through the use of git worktrees.",
+    help_template = "{about}\n\nUsage: claudectl [OPTIONS] [COMMAND]\n\nCommands:\n  init            Initialize the project for claudectl\n  task            Create a new task worktree\n  run             Run claude non-interactively in a task\n  list            List all task worktrees\n  status          Show the state of every task in the project\n  overview        Show in-flight tasks across all registered projects\n  show            Show everything known about a task\n  diff            Show everything changed in a task\n  compare         Compare the work of two tasks\n  open            Open a shell or editor in a task's worktree\n  switch          Pick a task and print its worktree path\n  exec            Run a command in a task's worktree\n  rm              Remove a task worktree\n  rename          Rename a task's branch and worktree\n  clean           Remove merged and stale tasks\n  archive         Snapshot a task into an archive and remove it\n  restore         Recreate a task from its archive\n  checkpoint      Snapshot a task's uncommitted work\n  rollback        Roll a task back to a checkpoint or its branch point\n  summarize       Generate a summary of a task's changes\n  describe        Print a pull request description for a task\n  resolve         Resolve a task's merge conflicts with claude\n  merge           Merge a finished task into its base branch\n  pr              Push a task and open a pull request for it\n  setup           Re-run the project's setup commands in a task\n  sync-claude-md  Copy CLAUDE.md and .claude into all task worktrees\n  note            Add or show timestamped notes on a task\n  history         Show a timeline of task activity\n  stats           Show task and session statistics for the project\n  watch           Start tasks when watched files change\n  worktree        Manage task worktrees, e.g. bring one up to date with its base\n  kill            Stop a task's running session\n  restart         Stop a task's session and resume its conversation\n\nUtility:\n  completions     Generate shell completions\n  man             Print or install man pages\n  repair          Repair shell completions and configuration\n  doctor          Check that claudectl and the project are set up\n  reconcile       Resume, stop or delete sessions that ended unexpectedly\n  logs            Show claudectl's own log of recent runs\n  storage         Show where claudectl stores its data\n  export          Write every project's settings and task metadata to a file\n  import          Bring projects in from a file written by `export`\n  config          Get, set or list configuration\n  upgrade         Update claudectl to the latest release\n  help            Print this message or the help of the given subcommand(s)\n\n{options}"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    /// Enable debug logging
+    #[arg(long, global = true, help = "Enable debug logging output")]
+    pub debug: bool,
+
+    /// Print plain text without colors or icons
+    #[arg(
+        long,
+        global = true,
+        help = "Print plain text without colors, icons or box drawing"
+    )]
+    pub plain: bool,
+
+    /// Report how long each step took
+    #[arg(
+        long,
+        global = true,
+        help = "Report how long each step of the command took"
+    )]
+    pub timings: bool,
+
+    #[arg(
+        long,
+        global = true,
+        env = "CLAUDECTL_PROFILE",
+        value_name = "PROFILE",
+        help = "Profile from the global config to use for this run"
+    )]
+    pub profile: Option<String>,
+}
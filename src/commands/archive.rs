@@ -0,0 +1,217 @@
+use std::path::PathBuf;
+
+use chrono::Utc;
+use clap::{Args, ValueHint};
+use owo_colors::OwoColorize;
+use tabled::Tabled;
+use tracing::{error, info, instrument, warn};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        archive::{ArchiveContents, Manifest, find_archive, list_archives, write_archive},
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, read_events, record_event},
+        fs::{read_local_config_file, remove_task_file},
+        git::{
+            branch_point, bundle_task, delete_branch, delete_checkpoints, remove_worktree,
+            worktree_diff,
+        },
+        lock::current_lock,
+        output::{Position, blank, confirm, standard, step, step_end, step_fail, success, table},
+        tasks::{TaskRecord, load_task, resolve_task_name, task_worktree},
+        theme::THEME,
+        time::{TimestampStyle, format_timestamp},
+    },
+};
+
+#[derive(Tabled)]
+struct ArchiveRow {
+    archive: String,
+    task: String,
+    archived: String,
+    summary: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ArchiveCommand {
+    /// The task to archive (picked interactively if omitted)
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+
+    /// List the project's archives instead
+    #[arg(long, conflicts_with_all = ["task_name", "purge"])]
+    pub list: bool,
+
+    /// Permanently delete an archive, given by name or task
+    #[arg(long, value_name = "ARCHIVE", conflicts_with = "task_name")]
+    pub purge: Option<String>,
+}
+
+impl ArchiveCommand {
+    #[instrument(name = "archive_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing archive command.");
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        if self.list {
+            return list(&config);
+        }
+        if let Some(query) = &self.purge {
+            return purge(&config, query);
+        }
+        archive(&config, &resolve_task_name(self.task_name.as_deref())?)
+    }
+}
+
+/// Snapshot a task into an archive, then remove its worktree, branch and
+/// record.
+fn archive(config: &Config, task_name: &str) -> CommandResult<()> {
+    let worktree = task_worktree(task_name)?;
+    if let Some(lock) = current_lock(task_name) {
+        return Err(CommandError::new(&format!(
+            "Task '{task_name}' is in use by {}; archive it once the session ends",
+            lock.holder()
+        )));
+    }
+    let record = load_task(task_name)?;
+
+    // 1. Write the archive; nothing is removed until it is safely on disk
+    step(&format!("Archiving '{task_name}'..."), Position::First);
+    let path = write_task_archive(config, task_name, &worktree.path, &record).inspect_err(|e| {
+        error!("Failed to archive task: {}", e);
+        step_fail();
+    })?;
+    step_end();
+    blank();
+
+    // 2. Remove the task
+    step("Removing worktree and branch...", Position::Last);
+    remove_worktree(&worktree.path)
+        .and_then(|_| delete_branch(task_name))
+        .and_then(|_| delete_checkpoints(task_name))
+        .inspect_err(|e| {
+            error!("Failed to remove archived task: {}", e);
+            step_fail();
+        })?;
+    remove_task_file(task_name)?;
+    step_end();
+    blank();
+
+    record_event(
+        EventKind::TaskArchived,
+        task_name,
+        Some(path.to_string_lossy().to_string()),
+    );
+    success(&format!("Archived '{task_name}' to {}", path.display()));
+    Ok(())
+}
+
+fn write_task_archive(
+    config: &Config,
+    task_name: &str,
+    worktree_path: &str,
+    record: &TaskRecord,
+) -> CommandResult<PathBuf> {
+    let manifest = Manifest {
+        task: task_name.to_string(),
+        project: config.project_name.clone(),
+        archived_at: Utc::now(),
+        worktree_path: worktree_path.to_string(),
+    };
+    let branch_point = branch_point(worktree_path, record.base()).ok();
+    let diff = worktree_diff(worktree_path, branch_point.as_deref().unwrap_or("HEAD"))?;
+    let mut events = String::new();
+    for event in read_events()?
+        .iter()
+        .filter(|event| event.task == task_name)
+    {
+        events.push_str(
+            &serde_json::to_string(event)
+                .map_err(|e| CommandError::new(&format!("Failed to serialize event: {e}")))?,
+        );
+        events.push('\n');
+    }
+
+    let bundle =
+        std::env::temp_dir().join(format!("claudectl-archive-{}.bundle", std::process::id()));
+    let written = bundle_task(worktree_path, task_name, branch_point.as_deref(), &bundle)
+        .map_err(CommandError::from)
+        .and_then(|_| {
+            write_archive(&ArchiveContents {
+                manifest: &manifest,
+                record,
+                events: &events,
+                diff: &diff,
+                bundle: &bundle,
+            })
+        });
+    if let Err(e) = std::fs::remove_file(&bundle) {
+        warn!("Failed to remove {}: {}", bundle.display(), e);
+    }
+    written
+}
+
+fn list(config: &Config) -> CommandResult<()> {
+    let archives = list_archives(&config.project_name)?;
+    if archives.is_empty() {
+        standard("No archived tasks");
+        return Ok(());
+    }
+    let rows: Vec<ArchiveRow> = archives
+        .iter()
+        .map(|archive| ArchiveRow {
+            archive: archive.name.color(THEME.info).to_string(),
+            task: archive.manifest.task.clone(),
+            archived: format_timestamp(archive.manifest.archived_at, TimestampStyle::Relative),
+            summary: archive
+                .record
+                .summary
+                .as_ref()
+                .and_then(|summary| summary.text.lines().next())
+                .unwrap_or("-")
+                .color(THEME.muted)
+                .to_string(),
+        })
+        .collect();
+    table(&rows, true);
+    Ok(())
+}
+
+fn purge(config: &Config, query: &str) -> CommandResult<()> {
+    let archive = find_archive(&config.project_name, query)?;
+    let confirmed = confirm(&format!(
+        "Permanently delete archive '{}' of task '{}'?",
+        archive.name.color(THEME.info),
+        archive.manifest.task
+    ))
+    .map_err(|e| CommandError::new(&format!("Failed to read input: {e}")))?;
+    if !confirmed {
+        success("Purge cancelled");
+        return Ok(());
+    }
+    std::fs::remove_file(&archive.path).map_err(|e| {
+        CommandError::new(&format!("Failed to delete {}: {e}", archive.path.display()))
+    })?;
+    success(&format!("Deleted archive '{}'", archive.name));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_command_creation() {
+        let cmd = ArchiveCommand {
+            task_name: Some("feat/login".to_string()),
+            list: false,
+            purge: None,
+        };
+        assert_eq!(cmd.task_name.as_deref(), Some("feat/login"));
+        assert!(!cmd.list);
+    }
+}
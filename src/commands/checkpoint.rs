@@ -0,0 +1,132 @@
+use std::thread;
+use std::time::Duration;
+
+use clap::{Args, ValueHint};
+use owo_colors::OwoColorize;
+use tabled::Tabled;
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        events::{EventKind, record_event},
+        fs::read_local_config_file,
+        git::{Worktree, create_checkpoint, list_checkpoints},
+        output::{standard, success, table},
+        tasks::task_worktree,
+        theme::THEME,
+    },
+};
+
+#[derive(Tabled)]
+struct CheckpointRow {
+    id: u32,
+    commit: String,
+    created: String,
+    message: String,
+}
+
+#[derive(Args, Debug)]
+pub struct CheckpointCommand {
+    /// The name of the task to checkpoint
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: String,
+
+    /// Message recorded with the checkpoint
+    #[arg(short, long)]
+    pub message: Option<String>,
+
+    /// Keep running and create a checkpoint every MINUTES
+    #[arg(long, value_name = "MINUTES", value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: Option<u64>,
+
+    /// List existing checkpoints instead of creating one
+    #[arg(long, conflicts_with_all = ["message", "interval"])]
+    pub list: bool,
+}
+
+impl CheckpointCommand {
+    #[instrument(name = "checkpoint_command", fields(task_name = %self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing checkpoint command for: {}", self.task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let worktree = task_worktree(&self.task_name)?;
+
+        if self.list {
+            return self.list_checkpoints(&worktree);
+        }
+
+        match self.interval {
+            Some(minutes) => {
+                standard(&format!(
+                    "Checkpointing '{}' every {minutes} minute(s). Press Ctrl-C to stop.",
+                    self.task_name
+                ));
+                loop {
+                    self.checkpoint(&worktree)?;
+                    thread::sleep(Duration::from_secs(minutes * 60));
+                }
+            }
+            None => self.checkpoint(&worktree),
+        }
+    }
+
+    fn checkpoint(&self, worktree: &Worktree) -> CommandResult<()> {
+        let message = self
+            .message
+            .clone()
+            .unwrap_or_else(|| format!("claudectl checkpoint of {}", self.task_name));
+
+        let checkpoint = create_checkpoint(&worktree.path, &self.task_name, &message, false)
+            .inspect_err(|e| {
+                error!("Failed to create checkpoint: {}", e);
+            })?;
+
+        match checkpoint {
+            Some(checkpoint) => {
+                record_event(
+                    EventKind::CheckpointCreated,
+                    &self.task_name,
+                    Some(format!("{}: {message}", checkpoint.id)),
+                );
+                success(&format!(
+                    "Created checkpoint {} of '{}' ({})",
+                    checkpoint.id,
+                    self.task_name,
+                    checkpoint.commit.as_str().color(THEME.muted)
+                ))
+            }
+            None => standard(&format!(
+                "No changes in '{}' since the last checkpoint",
+                self.task_name
+            )),
+        }
+
+        Ok(())
+    }
+
+    fn list_checkpoints(&self, worktree: &Worktree) -> CommandResult<()> {
+        let checkpoints = list_checkpoints(&worktree.path, &self.task_name)?;
+        if checkpoints.is_empty() {
+            standard(&format!("No checkpoints for '{}'", self.task_name));
+            return Ok(());
+        }
+
+        let rows: Vec<CheckpointRow> = checkpoints
+            .into_iter()
+            .map(|c| CheckpointRow {
+                id: c.id,
+                commit: c.commit,
+                created: c.created,
+                message: c.message,
+            })
+            .collect();
+        table(&rows, true);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use tabled::Tabled;
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, record_event},
+        fs::{read_local_config_file, remove_task_file},
+        git::{
+            branch_exists, delete_branch, delete_checkpoints, is_ancestor, prune_worktrees,
+            remove_worktree,
+        },
+        lock::current_lock,
+        output::{blank, confirm, error as output_error, is_interactive, standard, success, table},
+        tasks::{is_task_merged, load_task, project_worktrees, stored_tasks, target_branch},
+        theme::THEME,
+    },
+};
+
+/// Why a task is stale.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Reason {
+    /// The worktree's directory was deleted outside claudectl
+    MissingWorktree,
+    /// The branch is merged into the task's base
+    Merged,
+    /// A record is left over for a task whose worktree is gone
+    StaleRecord,
+}
+
+struct Candidate {
+    task: String,
+    /// The worktree to remove, if git still has one for the task
+    worktree: Option<String>,
+    target: String,
+    reason: Reason,
+    /// Whether the branch goes too; branches with unmerged commits are kept
+    delete_branch: bool,
+}
+
+impl Candidate {
+    fn reason(&self) -> String {
+        match self.reason {
+            Reason::MissingWorktree => "worktree directory missing".to_string(),
+            Reason::Merged => format!("merged into {}", self.target),
+            Reason::StaleRecord => "record without a worktree".to_string(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct CleanRow {
+    task: String,
+    reason: String,
+    branch: String,
+}
+
+#[derive(Args, Debug)]
+pub struct CleanCommand {
+    /// Only list what would be cleaned up
+    #[arg(long, conflicts_with = "yes")]
+    pub dry_run: bool,
+
+    /// Clean up without asking for confirmation
+    #[arg(long, short)]
+    pub yes: bool,
+}
+
+impl CleanCommand {
+    #[instrument(name = "clean_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing clean command.");
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let candidates = find_candidates()?;
+        if candidates.is_empty() {
+            success("Nothing to clean up");
+            return Ok(());
+        }
+
+        let rows: Vec<CleanRow> = candidates
+            .iter()
+            .map(|candidate| CleanRow {
+                task: candidate.task.color(THEME.info).to_string(),
+                reason: candidate.reason(),
+                branch: if candidate.delete_branch {
+                    "delete".to_string()
+                } else {
+                    "keep (unmerged commits)".color(THEME.muted).to_string()
+                },
+            })
+            .collect();
+        table(&rows, true);
+        blank();
+
+        if self.dry_run {
+            standard(&format!(
+                "{} task(s) would be cleaned up; run without --dry-run to clean them up",
+                candidates.len()
+            ));
+            return Ok(());
+        }
+        if !self.yes {
+            if !is_interactive() {
+                return Err(CommandError::new(
+                    "Pass --yes to clean up without a terminal",
+                ));
+            }
+            let confirmed = confirm(&format!("Clean up {} task(s)?", candidates.len()))
+                .map_err(|e| CommandError::new(&format!("Failed to read input: {e}")))?;
+            if !confirmed {
+                success("Clean up cancelled");
+                return Ok(());
+            }
+        }
+
+        // Drop git's records of deleted worktrees first so their branches
+        // are no longer checked out anywhere
+        if candidates
+            .iter()
+            .any(|candidate| candidate.reason == Reason::MissingWorktree)
+        {
+            prune_worktrees()?;
+        }
+
+        let mut failed = 0;
+        for candidate in &candidates {
+            match clean_up(candidate) {
+                Ok(()) => success(&format!("Cleaned up '{}'", candidate.task)),
+                Err(e) => {
+                    error!("Failed to clean up {}: {}", candidate.task, e);
+                    output_error(&format!("Failed to clean up '{}': {e}", candidate.task));
+                    failed += 1;
+                }
+            }
+        }
+        if failed > 0 {
+            return Err(CommandError::new(&format!(
+                "{failed} task(s) could not be cleaned up"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Tasks with a missing or merged worktree, and records of tasks that no
+/// longer have one. Locked tasks and worktrees with uncommitted changes are
+/// never considered merged.
+fn find_candidates() -> CommandResult<Vec<Candidate>> {
+    let mut candidates = Vec::new();
+    let mut with_worktree = HashSet::new();
+
+    for worktree in project_worktrees()? {
+        let Some(task) = worktree.branch else {
+            continue;
+        };
+        with_worktree.insert(task.clone());
+        let record = load_task(&task)?;
+        let target = target_branch(&record);
+        let reason = if !Path::new(&worktree.path).exists() {
+            Reason::MissingWorktree
+        } else if current_lock(&task).is_none() && is_task_merged(&record, &worktree.path)? {
+            Reason::Merged
+        } else {
+            continue;
+        };
+        candidates.push(Candidate {
+            delete_branch: is_ancestor(&task, &target).unwrap_or(false),
+            task,
+            worktree: (reason == Reason::Merged).then_some(worktree.path),
+            target,
+            reason,
+        });
+    }
+
+    for record in stored_tasks()? {
+        if with_worktree.contains(&record.name) {
+            continue;
+        }
+        let target = target_branch(&record);
+        candidates.push(Candidate {
+            delete_branch: branch_exists(&record.name)?
+                && is_ancestor(&record.name, &target).unwrap_or(false),
+            task: record.name,
+            worktree: None,
+            target,
+            reason: Reason::StaleRecord,
+        });
+    }
+    Ok(candidates)
+}
+
+fn clean_up(candidate: &Candidate) -> CommandResult<()> {
+    if let Some(path) = &candidate.worktree {
+        remove_worktree(path)?;
+    }
+    if candidate.delete_branch {
+        delete_branch(&candidate.task)?;
+    }
+    // Even with its branch kept, so a later task of the same name doesn't
+    // start out with them
+    delete_checkpoints(&candidate.task)?;
+    remove_task_file(&candidate.task)?;
+    record_event(
+        EventKind::TaskRemoved,
+        &candidate.task,
+        Some(format!("cleaned up: {}", candidate.reason())),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_reason() {
+        let candidate = Candidate {
+            task: "feat/x".to_string(),
+            worktree: None,
+            target: "main".to_string(),
+            reason: Reason::Merged,
+            delete_branch: true,
+        };
+        assert_eq!(candidate.reason(), "merged into main");
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+
+use clap::{Args, ValueHint};
+use console::Term;
+use owo_colors::OwoColorize;
+use tabled::Tabled;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        fs::read_local_config_file,
+        git::{FileChange, branch_point, compare_worktrees, worktree_changes},
+        output::{blank, colorize_diff, page, standard, success, table},
+        tasks::{TaskRecord, load_task, task_worktree},
+        theme::THEME,
+        time::format_timestamp,
+    },
+};
+
+/// Width used for `--output` when the terminal's is unknown.
+const DEFAULT_WIDTH: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct CompareCommand {
+    /// The first task, e.g. the original of a clone
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: String,
+
+    /// The task to compare it with
+    #[arg(value_hint = ValueHint::Other)]
+    pub other_task: String,
+
+    /// Show how the second task's files differ from the first's
+    #[arg(long, conflicts_with = "output")]
+    pub diff: bool,
+
+    /// Show the output of each task's last run side by side
+    #[arg(long)]
+    pub output: bool,
+}
+
+/// One side of the comparison.
+struct Side {
+    name: String,
+    worktree: String,
+    record: TaskRecord,
+}
+
+#[derive(Tabled)]
+struct ChangeRow {
+    file: String,
+    first: String,
+    second: String,
+}
+
+impl CompareCommand {
+    #[instrument(name = "compare_command", fields(task_name = %self.task_name, other_task = %self.other_task))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!(
+            "Executing compare command for: {} and {}",
+            self.task_name, self.other_task
+        );
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        if self.task_name == self.other_task {
+            return Err(CommandError::new("Pick two different tasks to compare"));
+        }
+
+        let first = side(&self.task_name)?;
+        let second = side(&self.other_task)?;
+        if self.diff {
+            let diff = compare_worktrees(&first.worktree, &second.worktree)?;
+            if diff.is_empty() {
+                success(&format!(
+                    "'{}' and '{}' have the same files",
+                    first.name, second.name
+                ));
+            } else {
+                page(&colorize_diff(&diff));
+            }
+            return Ok(());
+        }
+        if self.output {
+            print_outputs(&first, &second)?;
+            return Ok(());
+        }
+        print_changes(&first, &second, &config)
+    }
+}
+
+fn side(task_name: &str) -> CommandResult<Side> {
+    Ok(Side {
+        name: task_name.to_string(),
+        worktree: task_worktree(task_name)?.path,
+        record: load_task(task_name)?,
+    })
+}
+
+/// Each file either task changed, with the lines each changed in it.
+fn print_changes(first: &Side, second: &Side, config: &Config) -> CommandResult<()> {
+    let changes = |side: &Side| -> CommandResult<Vec<FileChange>> {
+        let since = branch_point(&side.worktree, side.record.base())?;
+        Ok(worktree_changes(&side.worktree, &since)?)
+    };
+    let mut files: BTreeMap<String, (Option<FileChange>, Option<FileChange>)> = BTreeMap::new();
+    for change in changes(first)? {
+        let path = change.path.clone();
+        files.entry(path).or_default().0 = Some(change);
+    }
+    for change in changes(second)? {
+        let path = change.path.clone();
+        files.entry(path).or_default().1 = Some(change);
+    }
+
+    if files.is_empty() {
+        success(&format!(
+            "Neither '{}' nor '{}' has changes",
+            first.name, second.name
+        ));
+        return Ok(());
+    }
+    let mut rows = vec![ChangeRow {
+        file: "File".to_string(),
+        first: first.name.color(THEME.info).to_string(),
+        second: second.name.color(THEME.info).to_string(),
+    }];
+    rows.extend(files.into_iter().map(|(file, (a, b))| ChangeRow {
+        file,
+        first: format_change(a.as_ref()),
+        second: format_change(b.as_ref()),
+    }));
+    table(&rows, false);
+
+    blank();
+    for side in [first, second] {
+        standard(&format!(
+            "{}: {}",
+            side.name.color(THEME.info),
+            last_run(&side.record, config)
+        ));
+    }
+    Ok(())
+}
+
+/// e.g. "+12 -3", or a dash for a file the task left alone.
+fn format_change(change: Option<&FileChange>) -> String {
+    match change {
+        Some(change) => format!(
+            "{} {}",
+            format!("+{}", change.added).color(THEME.success),
+            format!("-{}", change.deleted).color(THEME.error)
+        ),
+        None => "-".color(THEME.muted).to_string(),
+    }
+}
+
+fn last_run(record: &TaskRecord, config: &Config) -> String {
+    match record.runs.last() {
+        Some(run) => format!(
+            "last run {} {}",
+            run.status(),
+            format_timestamp(run.finished_at, config.timestamps)
+        ),
+        None => "no runs".to_string(),
+    }
+}
+
+fn print_outputs(first: &Side, second: &Side) -> CommandResult<()> {
+    let output = |side: &Side| -> CommandResult<String> {
+        side.record
+            .runs
+            .last()
+            .map(|run| run.output.clone())
+            .ok_or_else(|| {
+                CommandError::new(&format!(
+                    "Task '{}' has no runs; use `claudectl run` to record one",
+                    side.name
+                ))
+            })
+    };
+    let (left, right) = (output(first)?, output(second)?);
+    let width = match Term::stdout().size_checked() {
+        Some((_, cols)) => usize::from(cols),
+        None => DEFAULT_WIDTH,
+    };
+    let header = side_by_side(&first.name, &second.name, width);
+    page(&format!(
+        "{}\n{}",
+        header.bold(),
+        side_by_side(&left, &right, width)
+    ));
+    Ok(())
+}
+
+/// Lay two texts out in columns that fit `width`, wrapping long lines.
+fn side_by_side(left: &str, right: &str, width: usize) -> String {
+    let column = width.saturating_sub(3).max(20) / 2;
+    let (left, right) = (wrap(left, column), wrap(right, column));
+    (0..left.len().max(right.len()))
+        .map(|i| {
+            let l = left.get(i).map_or("", String::as_str);
+            let r = right.get(i).map_or("", String::as_str);
+            format!("{l:<column$} │ {r}").trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    text.lines()
+        .flat_map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            if chars.is_empty() {
+                return vec![String::new()];
+            }
+            chars
+                .chunks(width)
+                .map(|chunk| chunk.iter().collect())
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_side_by_side_wraps_into_columns() {
+        let text = side_by_side("short\nabcdefghijklmnopqrstuvwxyz", "other", 43);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], format!("{:<20} │ other", "short"));
+        assert_eq!(lines[1], "abcdefghijklmnopqrst │");
+        assert_eq!(lines[2], format!("{:<20} │", "uvwxyz"));
+    }
+}
@@ -0,0 +1,89 @@
+use clap::{Args, ValueEnum};
+use tracing::{info, instrument, warn};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config, fs::read_config_file, projects::registered_projects,
+        tasks::project_task_names,
+    },
+};
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum CompletionSource {
+    /// Task names in the current project
+    Tasks,
+    /// Names of all registered projects
+    Projects,
+}
+
+/// Print candidate values for shell completion scripts, one per line.
+///
+/// Completion runs on every keypress, so failures print nothing rather than
+/// an error.
+#[derive(Args, Debug)]
+pub struct CompleteCommand {
+    /// What to list
+    #[arg(value_enum)]
+    pub source: CompletionSource,
+}
+
+impl CompleteCommand {
+    #[instrument(name = "complete_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Listing completion values for: {:?}", self.source);
+        let values = match self.source {
+            CompletionSource::Tasks => task_names(),
+            CompletionSource::Projects => project_names(),
+        };
+
+        for value in values {
+            println!("{value}");
+        }
+        Ok(())
+    }
+}
+
+fn task_names() -> Vec<String> {
+    match project_task_names() {
+        Ok(names) => names,
+        Err(e) => {
+            warn!("Failed to list tasks for completion: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn project_names() -> Vec<String> {
+    let projects = match registered_projects() {
+        Ok(projects) => projects,
+        Err(e) => {
+            warn!("Failed to list projects for completion: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut names: Vec<String> = projects
+        .into_iter()
+        .filter_map(|project| project.registration.ok())
+        .filter_map(|registration| read_config_file(&registration.repo_path).ok())
+        .filter_map(|raw| Config::from_str(&raw).ok())
+        .map(|config| config.project_name)
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_command_creation() {
+        let cmd = CompleteCommand {
+            source: CompletionSource::Tasks,
+        };
+        assert!(matches!(cmd.source, CompletionSource::Tasks));
+    }
+}
@@ -1,5 +1,4 @@
 use std::fs;
-use std::io;
 use std::path::Path;
 use std::process::Command;
 
@@ -8,6 +7,12 @@ use clap_complete::{Shell, generate};
 use tracing::{info, instrument};
 
 use crate::commands::CommandResult;
+use crate::utils::completions::{
+    completion_paths, detect_shell, install, patch_completions, profile_line, profile_path,
+    uninstall,
+};
+use crate::utils::errors::CommandError;
+use crate::utils::nushell;
 use crate::utils::output::{error, standard, success};
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -15,20 +20,87 @@ pub enum CompletionShell {
     Bash,
     Zsh,
     Fish,
+    #[value(name = "powershell", alias = "pwsh")]
     PowerShell,
     Elvish,
+    #[value(alias = "nu")]
+    Nushell,
 }
 
-impl From<CompletionShell> for Shell {
-    fn from(shell: CompletionShell) -> Self {
-        match shell {
-            CompletionShell::Bash => Shell::Bash,
-            CompletionShell::Zsh => Shell::Zsh,
-            CompletionShell::Fish => Shell::Fish,
-            CompletionShell::PowerShell => Shell::PowerShell,
-            CompletionShell::Elvish => Shell::Elvish,
+impl CompletionShell {
+    /// The shell as clap_complete knows it; it has no nushell generator.
+    fn clap_shell(&self) -> Option<Shell> {
+        match self {
+            Self::Bash => Some(Shell::Bash),
+            Self::Zsh => Some(Shell::Zsh),
+            Self::Fish => Some(Shell::Fish),
+            Self::PowerShell => Some(Shell::PowerShell),
+            Self::Elvish => Some(Shell::Elvish),
+            Self::Nushell => None,
         }
     }
+
+    /// The shell's name as `$SHELL` gives it.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+            Self::PowerShell => "pwsh",
+            Self::Elvish => "elvish",
+            Self::Nushell => "nu",
+        }
+    }
+
+    /// The shell named as `$SHELL` gives it, e.g. `pwsh`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            "pwsh" | "powershell" => Some(Self::PowerShell),
+            "elvish" => Some(Self::Elvish),
+            "nu" => Some(Self::Nushell),
+            _ => None,
+        }
+    }
+
+    /// The completion script for this shell, completing task and project
+    /// names by calling back into the binary.
+    fn script(&self) -> CommandResult<String> {
+        let mut app = completable(crate::Cli::command());
+        let mut script = Vec::new();
+        match self.clap_shell() {
+            Some(shell_type) => generate(shell_type, &mut app, "claudectl", &mut script),
+            None => nushell::generate(&mut app, "claudectl", &mut script)
+                .map_err(|e| CommandError::new(&format!("Failed to write completions: {e}")))?,
+        }
+        Ok(patch_completions(
+            self.name(),
+            &String::from_utf8_lossy(&script),
+        ))
+    }
+}
+
+/// `app` without its hidden subcommands, so completions do not offer them.
+/// clap_complete's bash generator also cannot handle a subcommand named
+/// like `__complete`.
+fn completable(app: clap::Command) -> clap::Command {
+    let subcommands: Vec<clap::Command> = app
+        .get_subcommands()
+        .filter(|sub| !sub.is_hide_set())
+        .cloned()
+        .collect();
+    let mut visible = clap::Command::new(app.get_name().to_string())
+        .args(app.get_arguments().cloned())
+        .subcommands(subcommands);
+    if let Some(about) = app.get_about() {
+        visible = visible.about(about.clone());
+    }
+    if let Some(version) = app.get_version() {
+        visible = visible.version(version.to_string());
+    }
+    visible
 }
 
 #[derive(Args, Debug)]
@@ -40,6 +112,15 @@ pub struct CompletionsCommand {
     /// Verify that completions are installed and working
     #[arg(long, help = "Verify completion installation")]
     pub verify: bool,
+
+    /// Install completions for the shell, or the current one, and load them
+    /// from its startup file
+    #[arg(long, conflicts_with_all = ["verify", "uninstall"])]
+    pub install: bool,
+
+    /// Remove installed completions and the startup lines that load them
+    #[arg(long, conflicts_with = "verify")]
+    pub uninstall: bool,
 }
 
 impl CompletionsCommand {
@@ -48,6 +129,24 @@ impl CompletionsCommand {
         if self.verify {
             return self.verify_completions();
         }
+        if self.install || self.uninstall {
+            let shell = match &self.shell {
+                Some(shell) => shell.clone(),
+                None => {
+                    let name = detect_shell();
+                    CompletionShell::from_name(&name).ok_or_else(|| {
+                        CommandError::new(&format!(
+                            "Unsupported shell '{name}'; pass the shell to use"
+                        ))
+                    })?
+                }
+            };
+            return if self.install {
+                install_completions(&shell)
+            } else {
+                uninstall_completions(&shell)
+            };
+        }
 
         let shell = match &self.shell {
             Some(s) => s.clone(),
@@ -59,21 +158,17 @@ impl CompletionsCommand {
 
         info!("Generating completions for shell: {:?}", shell);
 
-        let mut app = crate::Cli::command();
-        let shell_type: Shell = shell.into();
-
-        generate(shell_type, &mut app, "claudectl", &mut io::stdout());
-
+        print!("{}", shell.script()?);
         Ok(())
     }
 
     fn verify_completions(&self) -> CommandResult<()> {
         info!("Verifying completion installation");
 
-        let shell = self.detect_shell();
+        let shell = detect_shell();
         standard(&format!("Detected shell: {shell}"));
 
-        let completion_paths = self.get_completion_paths(&shell);
+        let completion_paths = completion_paths(&shell);
         let mut found_completion = false;
         let mut completion_file = String::new();
 
@@ -88,7 +183,7 @@ impl CompletionsCommand {
 
         if !found_completion {
             error("✗ No completion files found");
-            standard("  Run: npm run setup -- repair");
+            standard("  Run: claudectl completions --install");
             return Ok(());
         }
 
@@ -106,6 +201,21 @@ impl CompletionsCommand {
             }
         }
 
+        // Shells without a completions directory load the file from their
+        // profile
+        if let Some(profile) = profile_path(&shell, Path::new(&completion_file)) {
+            let loaded = fs::read_to_string(&profile)
+                .is_ok_and(|content| content.contains(&completion_file));
+            if loaded {
+                success(&format!("✓ Loaded from {}", profile.display()));
+            } else {
+                error(&format!("✗ Not loaded from {}", profile.display()));
+                if let Some(line) = profile_line(&shell, &completion_file) {
+                    standard(&format!("  Add this line to it: {line}"));
+                }
+            }
+        }
+
         // Test basic completion
         if self.test_completion() {
             success("✓ Basic completion test passed");
@@ -117,33 +227,6 @@ impl CompletionsCommand {
         Ok(())
     }
 
-    fn detect_shell(&self) -> String {
-        std::env::var("SHELL")
-            .unwrap_or_else(|_| "bash".to_string())
-            .split('/')
-            .next_back()
-            .unwrap_or("bash")
-            .to_string()
-    }
-
-    fn get_completion_paths(&self, shell: &str) -> Vec<String> {
-        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-
-        match shell {
-            "zsh" => vec![
-                format!("{}/.zsh/completions/_claudectl", home),
-                format!("{}/.zsh_completion.d/_claudectl", home),
-                "/usr/local/share/zsh/site-functions/_claudectl".to_string(),
-            ],
-            "bash" => vec![
-                format!("{}/.bash_completion.d/claudectl", home),
-                "/etc/bash_completion.d/claudectl".to_string(),
-            ],
-            "fish" => vec![format!("{}/.config/fish/completions/claudectl.fish", home)],
-            _ => vec![],
-        }
-    }
-
     fn extract_version(&self, content: &str) -> Option<String> {
         content
             .lines()
@@ -162,15 +245,74 @@ impl CompletionsCommand {
     }
 }
 
+/// Install completions for `shell`, with dynamic task completion, and
+/// report what changed.
+pub fn install_completions(shell: &CompletionShell) -> CommandResult<()> {
+    info!("Installing completions for {:?}", shell);
+    let installed = install(shell.name(), &shell.script()?)?;
+    success(&format!(
+        "Installed completions to {}",
+        installed.file.display()
+    ));
+    if let Some(startup_file) = &installed.startup_file {
+        standard(&format!("Added loading them to {}", startup_file.display()));
+    }
+    if let Some(backup) = &installed.backup {
+        standard(&format!("Backed it up to {}", backup.display()));
+    }
+    standard("Restart your shell or run `exec $SHELL` to use them");
+    Ok(())
+}
+
+fn uninstall_completions(shell: &CompletionShell) -> CommandResult<()> {
+    info!("Uninstalling completions for {:?}", shell);
+    let removed = uninstall(shell.name())?;
+    for file in &removed.files {
+        standard(&format!("Removed {}", file.display()));
+    }
+    if let Some(startup_file) = &removed.startup_file {
+        standard(&format!(
+            "Removed the lines claudectl added to {}",
+            startup_file.display()
+        ));
+    }
+    if let Some(backup) = &removed.backup {
+        standard(&format!("Backed it up to {}", backup.display()));
+    }
+    if removed.files.is_empty() && removed.startup_file.is_none() {
+        success("No installed completions found");
+    } else {
+        success("Completions uninstalled");
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_completion_shell_conversion() {
-        assert!(matches!(Shell::from(CompletionShell::Bash), Shell::Bash));
-        assert!(matches!(Shell::from(CompletionShell::Zsh), Shell::Zsh));
-        assert!(matches!(Shell::from(CompletionShell::Fish), Shell::Fish));
+        assert!(matches!(
+            CompletionShell::Bash.clap_shell(),
+            Some(Shell::Bash)
+        ));
+        assert!(matches!(
+            CompletionShell::Zsh.clap_shell(),
+            Some(Shell::Zsh)
+        ));
+        assert!(matches!(
+            CompletionShell::Fish.clap_shell(),
+            Some(Shell::Fish)
+        ));
+        assert!(CompletionShell::Nushell.clap_shell().is_none());
+    }
+
+    #[test]
+    fn test_scripts_leave_out_hidden_subcommands() {
+        let script = CompletionShell::Bash.script().unwrap();
+        assert!(script.contains("claudectl__show"));
+        assert!(!script.contains("claudectl____complete"));
     }
 
     #[test]
@@ -178,6 +320,8 @@ mod tests {
         let cmd = CompletionsCommand {
             shell: Some(CompletionShell::Zsh),
             verify: false,
+            install: false,
+            uninstall: false,
         };
         assert!(matches!(cmd.shell, Some(CompletionShell::Zsh)));
     }
@@ -187,6 +331,8 @@ mod tests {
         let cmd = CompletionsCommand {
             shell: None,
             verify: true,
+            install: false,
+            uninstall: false,
         };
         assert!(cmd.verify);
     }
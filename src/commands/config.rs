@@ -0,0 +1,371 @@
+use clap::{Args, Subcommand};
+use owo_colors::OwoColorize;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::{Config, GlobalConfig},
+        config_keys::setting_keys,
+        errors::{CommandError, ConfigError},
+        fs::{
+            read_global_config_file, read_local_config_file, write_global_config_file,
+            write_local_config_file,
+        },
+        output::{standard, success},
+        theme::THEME,
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct ConfigCommand {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print the value of a setting
+    Get(ConfigGetCommand),
+    /// Change a setting
+    Set(ConfigSetCommand),
+    /// Show every setting, including defaults
+    List(ConfigListCommand),
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigGetCommand {
+    /// Dotted path of the setting, e.g. `permissions.mode`
+    pub key: String,
+
+    /// Read the global config instead of the project's
+    #[arg(long)]
+    pub global: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigSetCommand {
+    /// Dotted path of the setting, e.g. `permissions.mode`
+    pub key: String,
+
+    /// The new value; lists and objects are given as JSON
+    pub value: String,
+
+    /// Change the global config instead of the project's
+    #[arg(long)]
+    pub global: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigListCommand {
+    /// List the global config instead of the project's
+    #[arg(long)]
+    pub global: bool,
+}
+
+impl ConfigCommand {
+    pub fn execute(&self) -> CommandResult<()> {
+        match &self.action {
+            ConfigAction::Get(cmd) => cmd.execute(),
+            ConfigAction::Set(cmd) => cmd.execute(),
+            ConfigAction::List(cmd) => cmd.execute(),
+        }
+    }
+}
+
+impl ConfigGetCommand {
+    #[instrument(name = "config_get_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing config get for: {}", self.key);
+        let settings = Settings::load(self.global)?;
+        let value = lookup(&settings.effective, &self.key).ok_or_else(|| unknown_key(&self.key))?;
+        match value {
+            Value::String(text) => println!("{text}"),
+            other => println!("{}", pretty(other)?),
+        }
+        Ok(())
+    }
+}
+
+impl ConfigSetCommand {
+    #[instrument(name = "config_set_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing config set for: {}", self.key);
+        let mut settings = Settings::load(self.global)?;
+        let value = settings.set(&self.key, &self.value)?;
+        settings.save()?;
+        success(&format!(
+            "Set {} to {}",
+            self.key,
+            serde_json::to_string(&value).unwrap_or_default()
+        ));
+        Ok(())
+    }
+}
+
+impl ConfigListCommand {
+    #[instrument(name = "config_list_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing config list.");
+        let settings = Settings::load(self.global)?;
+        let mut entries = Vec::new();
+        flatten(&settings.effective, "", &mut entries);
+        for (key, value) in entries {
+            standard(&format!(
+                "{} = {}",
+                key.color(THEME.info),
+                serde_json::to_string(value).unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// One config file: what it holds, and every setting it resolves to once
+/// defaults are filled in.
+struct Settings {
+    global: bool,
+    /// The file as written, so saving keeps its shape
+    file: Value,
+    effective: Value,
+}
+
+impl Settings {
+    fn load(global: bool) -> CommandResult<Self> {
+        let raw = if global {
+            read_global_config_file()?.unwrap_or_else(|| "{}".to_string())
+        } else {
+            read_local_config_file()?
+        };
+        let file: Value = serde_json::from_str(&raw)
+            .map_err(|e| ConfigError::new(&format!("Failed to parse configuration JSON: {e}")))?;
+        let effective = if global {
+            resolve::<GlobalConfig>(&file)?
+        } else {
+            resolve::<Config>(&file)?
+        };
+        Ok(Self {
+            global,
+            file,
+            effective,
+        })
+    }
+
+    /// Set `key` to `raw`, read as the setting's type, and check that the
+    /// result is a valid config. Returns the value stored.
+    fn set(&mut self, key: &str, raw: &str) -> CommandResult<Value> {
+        let mut first_error = None;
+        for value in candidates(raw, lookup(&self.effective, key)) {
+            let mut file = self.file.clone();
+            assign(&mut file, key, value.clone())?;
+            match self.resolve(&file) {
+                // A key the config does not know is dropped when it is read,
+                // and so are empty values, so their key is looked up among all settings
+                Ok(effective)
+                    if lookup(&effective, key) == Some(&value)
+                        || is_empty(&value) && self.is_known(&file, key) =>
+                {
+                    self.file = file;
+                    self.effective = effective;
+                    return Ok(value);
+                }
+                Ok(_) => return Err(unknown_key(key)),
+                Err(ConfigError::Invalid { message }) => {
+                    first_error.get_or_insert(message);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(CommandError::new(&format!(
+            "Invalid value for '{key}': {}",
+            first_error.unwrap_or_default()
+        )))
+    }
+
+    fn resolve(&self, file: &Value) -> Result<Value, ConfigError> {
+        if self.global {
+            resolve::<GlobalConfig>(file)
+        } else {
+            resolve::<Config>(file)
+        }
+    }
+
+    /// Whether `file`, read as this config, has a setting at `key`, even
+    /// one left out of it for being empty.
+    fn is_known(&self, file: &Value, key: &str) -> bool {
+        let keys = if self.global {
+            keys_of::<GlobalConfig>(file)
+        } else {
+            keys_of::<Config>(file)
+        };
+        keys.is_ok_and(|keys| lookup(&keys, key).is_some())
+    }
+
+    fn save(&self) -> CommandResult<()> {
+        let content = pretty(&self.file)?;
+        if self.global {
+            write_global_config_file(&content)?;
+        } else {
+            write_local_config_file(content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read `file` as config type `T` and write it back out with every default.
+fn resolve<T: Serialize + DeserializeOwned>(file: &Value) -> Result<Value, ConfigError> {
+    let config: T =
+        serde_json::from_value(file.clone()).map_err(|e| ConfigError::invalid(&e.to_string()))?;
+    serde_json::to_value(config)
+        .map_err(|e| ConfigError::serialize_failed(&format!("JSON serialization error: {e}")))
+}
+
+/// Read `file` as config type `T` and list every setting it has.
+fn keys_of<T: Serialize + DeserializeOwned>(file: &Value) -> Result<Value, ConfigError> {
+    let config: T =
+        serde_json::from_value(file.clone()).map_err(|e| ConfigError::invalid(&e.to_string()))?;
+    setting_keys(&config)
+        .map_err(|e| ConfigError::serialize_failed(&format!("JSON serialization error: {e}")))
+}
+
+/// Ways to read `raw`, most likely first: text for text settings, and
+/// otherwise JSON with plain text as the fallback.
+fn candidates(raw: &str, current: Option<&Value>) -> Vec<Value> {
+    let text = Value::String(raw.to_string());
+    match current {
+        Some(Value::String(_)) => vec![text],
+        _ => match serde_json::from_str::<Value>(raw) {
+            Ok(parsed) if parsed != text => vec![parsed, text],
+            _ => vec![text],
+        },
+    }
+}
+
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(value, |value, part| value.as_object()?.get(part))
+}
+
+/// Set the value at a dotted `key`, creating objects along the way.
+fn assign(value: &mut Value, key: &str, new: Value) -> CommandResult<()> {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().unwrap_or_default();
+    let mut current = value;
+    for part in parts {
+        current = current
+            .as_object_mut()
+            .ok_or_else(|| unknown_key(key))?
+            .entry(part)
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    current
+        .as_object_mut()
+        .ok_or_else(|| unknown_key(key))?
+        .insert(last.to_string(), new);
+    Ok(())
+}
+
+/// Every setting under `value` as a dotted key and its value.
+fn flatten<'a>(value: &'a Value, prefix: &str, entries: &mut Vec<(String, &'a Value)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten(value, &key, entries);
+            }
+        }
+        _ => entries.push((prefix.to_string(), value)),
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => items.is_empty(),
+        Value::Object(map) => map.is_empty(),
+        Value::Null => true,
+        _ => false,
+    }
+}
+
+fn pretty(value: &Value) -> Result<String, ConfigError> {
+    serde_json::to_string_pretty(value)
+        .map_err(|e| ConfigError::serialize_failed(&format!("JSON serialization error: {e}")))
+}
+
+fn unknown_key(key: &str) -> CommandError {
+    CommandError::new(&format!(
+        "Unknown config key '{key}'; run `claudectl config list` to see the keys"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings(file: Value) -> Settings {
+        Settings {
+            global: false,
+            effective: resolve::<Config>(&file).unwrap(),
+            file,
+        }
+    }
+
+    #[test]
+    fn test_set_reads_value_as_the_settings_type() {
+        let mut settings = settings(json!({ "project_name": "demo", "project_dir": "/w" }));
+
+        assert_eq!(settings.set("progress", "false").unwrap(), json!(false));
+        assert_eq!(settings.set("context.git_log", "5").unwrap(), json!(5));
+        assert_eq!(settings.set("project_name", "42").unwrap(), json!("42"));
+        assert_eq!(settings.set("pr_template", "true").unwrap(), json!("true"));
+        assert_eq!(
+            settings
+                .set("setup_commands", r#"["pnpm install"]"#)
+                .unwrap(),
+            json!(["pnpm install"])
+        );
+        assert_eq!(settings.file["context"], json!({ "git_log": 5 }));
+    }
+
+    #[test]
+    fn test_set_rejects_invalid_values_and_unknown_keys() {
+        let mut settings = settings(json!({ "project_name": "demo", "project_dir": "/w" }));
+
+        let err = settings.set("permissions.mode", "sometimes").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Invalid value for 'permissions.mode'")
+        );
+        let err = settings.set("colour", "blue").unwrap_err();
+        assert!(err.to_string().contains("Unknown config key 'colour'"));
+        assert!(settings.file.get("colour").is_none());
+        for empty in ["[]", "{}", "null"] {
+            let err = settings.set("colour", empty).unwrap_err();
+            assert!(err.to_string().contains("Unknown config key 'colour'"));
+        }
+        let err = settings.set("permissions.colours", "[]").unwrap_err();
+        assert!(err.to_string().contains("Unknown config key"));
+        assert!(settings.file.get("colour").is_none());
+        assert_eq!(
+            settings.set("setup_commands", "[]").unwrap(),
+            Value::Array(Vec::new())
+        );
+        assert_eq!(settings.set("max_sessions", "null").unwrap(), Value::Null);
+    }
+
+    #[test]
+    fn test_flatten_lists_leaves() {
+        let value = json!({ "a": { "b": 1, "c": [] }, "d": {} });
+        let mut entries = Vec::new();
+        flatten(&value, "", &mut entries);
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["a.b", "a.c", "d"]);
+    }
+}
@@ -0,0 +1,54 @@
+use clap::{Args, ValueHint};
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        describe::task_description,
+        fs::read_local_config_file,
+        tasks::{load_task, resolve_task_name, task_worktree},
+    },
+};
+
+/// Print a Markdown description of a task for a pull request.
+///
+/// The output is meant to be piped or pasted, so it is printed as is.
+#[derive(Args, Debug)]
+pub struct DescribeCommand {
+    /// The task to describe (picked interactively if omitted)
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+}
+
+impl DescribeCommand {
+    #[instrument(name = "describe_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+        info!("Executing describe command for: {}", task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+
+        let worktree = task_worktree(&task_name)?;
+        let record = load_task(&task_name)?;
+        let base = record.base();
+        print!(
+            "{}",
+            task_description(&config, &record, &worktree.path, base)?
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_command_creation() {
+        let cmd = DescribeCommand {
+            task_name: Some("feat/x".to_string()),
+        };
+        assert_eq!(cmd.task_name.as_deref(), Some("feat/x"));
+    }
+}
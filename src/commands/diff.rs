@@ -0,0 +1,91 @@
+use clap::{Args, ValueHint};
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        fs::read_local_config_file,
+        git::{DiffView, branch_point, worktree_diff_view},
+        output::{colorize_diff, page, success},
+        tasks::{load_task, resolve_task_name, task_worktree},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct DiffCommand {
+    /// The name of the task to diff; prompts for one if omitted
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+
+    /// Compare against this ref instead of the one the task started from
+    #[arg(long, value_name = "REF")]
+    pub base: Option<String>,
+
+    /// Show how many lines changed in each file
+    #[arg(long, conflicts_with = "name_only")]
+    pub stat: bool,
+
+    /// Show only the names of changed files
+    #[arg(long)]
+    pub name_only: bool,
+}
+
+impl DiffCommand {
+    #[instrument(name = "diff_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing diff command for: {:?}", self.task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+        let worktree = task_worktree(&task_name)?;
+        let record = load_task(&task_name)?;
+        let base = self.base.as_deref().unwrap_or(record.base());
+
+        // Committed and uncommitted work alike, from where the task branched
+        let since = branch_point(&worktree.path, base)?;
+        let diff = worktree_diff_view(&worktree.path, &since, self.view())?;
+        if diff.is_empty() {
+            success(&format!("No changes in '{task_name}' since {base}"));
+            return Ok(());
+        }
+        match self.view() {
+            DiffView::Patch => page(&colorize_diff(&diff)),
+            DiffView::Stat | DiffView::NameOnly => page(&diff),
+        }
+        Ok(())
+    }
+
+    fn view(&self) -> DiffView {
+        if self.stat {
+            DiffView::Stat
+        } else if self.name_only {
+            DiffView::NameOnly
+        } else {
+            DiffView::Patch
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_command_view() {
+        let mut cmd = DiffCommand {
+            task_name: Some("feat/login".to_string()),
+            base: None,
+            stat: false,
+            name_only: false,
+        };
+        assert_eq!(cmd.view(), DiffView::Patch);
+        cmd.stat = true;
+        assert_eq!(cmd.view(), DiffView::Stat);
+        cmd.stat = false;
+        cmd.name_only = true;
+        assert_eq!(cmd.view(), DiffView::NameOnly);
+    }
+}
@@ -0,0 +1,269 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+use tabled::Tabled;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        agent::AgentAdapter,
+        claude::Invocation,
+        config::Config,
+        errors::CommandError,
+        fs::{current_dir, has_claude_files, read_local_config_file},
+        git::{origin_reachable, origin_url},
+        migrate::{Layout, pending_migrations, run_migrations},
+        output::{blank, standard, success, table},
+        theme::THEME,
+        timings::{describe_slow, load_slow_operations},
+    },
+};
+
+/// How a check came out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Health {
+    Ok,
+    /// Works without it, but claudectl is less useful
+    Warning,
+    /// Some commands will fail until it is fixed
+    Failing,
+}
+
+struct Check {
+    name: &'static str,
+    health: Health,
+    /// What was found, or how to fix it
+    detail: String,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            health: Health::Ok,
+            detail: detail.into(),
+        }
+    }
+
+    fn warning(name: &'static str, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            health: Health::Warning,
+            detail: fix.into(),
+        }
+    }
+
+    fn failing(name: &'static str, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            health: Health::Failing,
+            detail: fix.into(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct CheckRow {
+    check: String,
+    status: String,
+    detail: String,
+}
+
+#[derive(Args, Debug)]
+pub struct DoctorCommand {
+    /// Only check the current project, not the tools claudectl needs
+    #[arg(long)]
+    pub project: bool,
+
+    /// Move files left by older releases into the current layout first
+    #[arg(long)]
+    pub migrate: bool,
+}
+
+impl DoctorCommand {
+    #[instrument(name = "doctor_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing doctor command.");
+        let repo_root = current_dir()?;
+        let has_project = repo_root.join(".claudectl").is_dir();
+        let layout = Layout::current(has_project.then_some(repo_root.as_path()))?;
+        if self.migrate {
+            let done = run_migrations(&layout)?;
+            if done.is_empty() {
+                standard("Nothing to migrate");
+            }
+            for description in &done {
+                success(description);
+            }
+            blank();
+        }
+
+        let mut checks = Vec::new();
+        if !self.project {
+            let (agent, invocation) = read_local_config_file()
+                .ok()
+                .and_then(|raw| Config::from_str(&raw).ok())
+                .map(|config| (config.agent, config.invocation(None)))
+                .unwrap_or_default();
+            checks.push(agent_check(agent.adapter(), &invocation));
+            checks.push(slow_operations_check());
+        }
+        checks.extend(project_checks()?);
+        checks.push(migration_check(&layout));
+
+        let rows: Vec<CheckRow> = checks
+            .iter()
+            .map(|check| CheckRow {
+                check: check.name.to_string(),
+                status: format_health(check.health),
+                detail: check.detail.clone(),
+            })
+            .collect();
+        table(&rows, true);
+
+        let failing = checks
+            .iter()
+            .filter(|check| check.health == Health::Failing)
+            .count();
+        if failing > 0 {
+            return Err(CommandError::new(&format!(
+                "{failing} check(s) failed; see the table above for fixes"
+            )));
+        }
+        blank();
+        standard("Everything claudectl needs is in place");
+        Ok(())
+    }
+}
+
+fn agent_check(agent: &dyn AgentAdapter, invocation: &Invocation) -> Check {
+    match agent.is_installed(invocation) {
+        Ok(_) if invocation.program == agent.name() => {
+            Check::ok("agent installed", format!("{} found on PATH", agent.name()))
+        }
+        Ok(_) => Check::ok("agent installed", format!("using {}", invocation.program)),
+        Err(_) => Check::failing("agent installed", agent.install_hint()),
+    }
+}
+
+/// Git and storage operations that have been slow in earlier runs. Only
+/// ones that keep being slow are a problem; one slow fetch is the network.
+fn slow_operations_check() -> Check {
+    let stats = load_slow_operations().unwrap_or_default();
+    let mut slowest: Vec<_> = stats.iter().collect();
+    slowest.sort_by_key(|(_, operation)| std::cmp::Reverse(operation.total_ms));
+    let details: Vec<String> = slowest
+        .iter()
+        .take(3)
+        .map(|(name, operation)| describe_slow(name, operation))
+        .collect();
+    if details.is_empty() {
+        Check::ok("slow operations", "None recorded")
+    } else if slowest
+        .iter()
+        .any(|(_, operation)| operation.is_recurring())
+    {
+        Check::warning(
+            "slow operations",
+            format!(
+                "{}; run with --timings to see where the time goes",
+                details.join("; ")
+            ),
+        )
+    } else {
+        Check::ok("slow operations", details.join("; "))
+    }
+}
+
+/// Prerequisites of the project in the current directory.
+fn project_checks() -> CommandResult<Vec<Check>> {
+    let Ok(raw_config) = read_local_config_file() else {
+        return Ok(vec![Check::failing(
+            "project initialized",
+            "Run `claudectl init` in the repository",
+        )]);
+    };
+    let config = Config::from_str(&raw_config)?;
+    let mut checks = vec![Check::ok(
+        "project initialized",
+        config.project_name.clone(),
+    )];
+
+    match origin_url() {
+        Ok(url) if origin_reachable() => checks.push(Check::ok("origin reachable", url)),
+        Ok(url) => checks.push(Check::failing(
+            "origin reachable",
+            format!("Could not reach {url}; check your network and credentials"),
+        )),
+        Err(_) => checks.push(Check::failing(
+            "origin reachable",
+            "No origin remote; add one with `git remote add origin <url>`",
+        )),
+    }
+
+    checks.push(if has_claude_files(&current_dir()?) {
+        Check::ok(
+            "CLAUDE.md present",
+            "claude gets the project's instructions",
+        )
+    } else {
+        Check::warning(
+            "CLAUDE.md present",
+            "Add a CLAUDE.md describing the project for claude",
+        )
+    });
+
+    checks.push(match config.all_setup_commands() {
+        Err(e) => Check::failing("setup commands", e.to_string()),
+        Ok(commands) if commands.is_empty() => Check::warning(
+            "setup commands",
+            "Add setup_commands or setup_recipes to .claudectl/config.json to prepare new worktrees",
+        ),
+        Ok(commands) => Check::ok("setup commands", format!("{} defined", commands.len())),
+    });
+    Ok(checks)
+}
+
+fn migration_check(layout: &Layout) -> Check {
+    let pending = pending_migrations(layout);
+    if pending.is_empty() {
+        Check::ok("current layout", "No files from older releases")
+    } else {
+        Check::warning(
+            "current layout",
+            format!(
+                "Found {}; run `claudectl doctor --migrate`",
+                pending.join(", ")
+            ),
+        )
+    }
+}
+
+fn format_health(health: Health) -> String {
+    match health {
+        Health::Ok => "ok".color(THEME.success).to_string(),
+        Health::Warning => "warning".color(THEME.warning).to_string(),
+        Health::Failing => "failing".color(THEME.error).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doctor_command_creation() {
+        let cmd = DoctorCommand {
+            project: true,
+            migrate: false,
+        };
+        assert!(cmd.project);
+    }
+
+    #[test]
+    fn test_check_constructors() {
+        assert_eq!(Check::ok("a", "fine").health, Health::Ok);
+        assert_eq!(Check::warning("a", "fix").health, Health::Warning);
+        assert_eq!(Check::failing("a", "fix").detail, "fix");
+    }
+}
@@ -0,0 +1,77 @@
+use std::process::Command;
+
+use clap::{Args, ValueHint};
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config, errors::CommandError, fs::read_local_config_file, tasks::task_worktree,
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct ExecCommand {
+    /// The task to run the command in
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: String,
+
+    /// The command and its arguments, given after `--`
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    pub command: Vec<String>,
+}
+
+impl ExecCommand {
+    #[instrument(name = "exec_command", fields(task_name = %self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        let task_name = self.task_name.as_str();
+        info!("Executing exec command for: {}", task_name);
+        let worktree = task_worktree(task_name)?;
+        // As with `open`, a sub-project's tasks run in its directory
+        let dir = read_local_config_file()
+            .ok()
+            .and_then(|raw| Config::from_str(&raw).ok())
+            .map_or_else(
+                || worktree.path.clone(),
+                |config| config.task_dir(&worktree.path),
+            );
+
+        let (program, args) = self
+            .command
+            .split_first()
+            .ok_or_else(|| CommandError::new("No command given to run"))?;
+        info!("Running {} {:?} in {}", program, args, dir);
+        let status = Command::new(program)
+            .args(args)
+            .current_dir(&dir)
+            .env("CLAUDECTL_TASK", task_name)
+            .status()
+            .map_err(|e| CommandError::new(&format!("Failed to run '{program}': {e}")))?;
+
+        match status.code() {
+            Some(0) => Ok(()),
+            Some(code) => Err(CommandError::exited(
+                &format!("'{program}' exited with code {code} in '{task_name}'"),
+                code,
+            )),
+            None => Err(CommandError::exited(
+                &format!("'{program}' was killed by a signal in '{task_name}'"),
+                1,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exec_command_creation() {
+        let cmd = ExecCommand {
+            task_name: "feat/x".to_string(),
+            command: vec!["cargo".to_string(), "test".to_string()],
+        };
+        assert_eq!(cmd.command.join(" "), "cargo test");
+    }
+}
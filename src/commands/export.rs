@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use clap::{Args, ValueHint};
+use tabled::Tabled;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        errors::CommandError,
+        export::collect_export,
+        fs::write_atomic,
+        output::{blank, success, table},
+    },
+};
+
+#[derive(Tabled)]
+struct ExportRow {
+    project: String,
+    repository: String,
+    tasks: usize,
+    events: usize,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportCommand {
+    /// File to write, e.g. `claudectl.json`
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub file: PathBuf,
+}
+
+impl ExportCommand {
+    #[instrument(name = "export_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing export command to: {}", self.file.display());
+        let export = collect_export()?;
+        let json = serde_json::to_string_pretty(&export)
+            .map_err(|e| CommandError::new(&format!("Failed to serialize export: {e}")))?;
+        write_atomic(&self.file, &json)?;
+
+        let rows: Vec<ExportRow> = export
+            .projects
+            .iter()
+            .map(|project| ExportRow {
+                project: project.name.clone(),
+                repository: project.repo_path.display().to_string(),
+                tasks: project.tasks.len(),
+                events: project.events.len(),
+            })
+            .collect();
+        if !rows.is_empty() {
+            table(&rows, true);
+            blank();
+        }
+        success(&format!(
+            "Exported {} project(s) to {}",
+            rows.len(),
+            self.file.display()
+        ));
+        Ok(())
+    }
+}
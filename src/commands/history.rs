@@ -0,0 +1,315 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeZone, Utc};
+use clap::{Args, ValueHint};
+use owo_colors::OwoColorize;
+use tabled::Tabled;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::{Event, EventKind, read_events},
+        fs::read_local_config_file,
+        output::{blank, is_plain, page, render_table, standard},
+        theme::THEME,
+        time::format_timestamp,
+    },
+};
+
+/// Weeks shown by `--heatmap` when no date range is given.
+const HEATMAP_WEEKS: u64 = 12;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Heatmap cells from fewest to most sessions on a day.
+const HEAT_LEVELS: [&str; 4] = ["░", "▒", "▓", "█"];
+
+#[derive(Tabled)]
+struct EventRow {
+    time: String,
+    event: String,
+    task: String,
+    detail: String,
+}
+
+fn date_parser(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| format!("'{s}' is not a date in YYYY-MM-DD format"))
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryCommand {
+    /// Only show events for this task
+    #[arg(long, value_hint = ValueHint::Other)]
+    pub task: Option<String>,
+
+    /// Only show events on or after this date (YYYY-MM-DD)
+    #[arg(long, value_parser = date_parser)]
+    pub since: Option<NaiveDate>,
+
+    /// Only show events on or before this date (YYYY-MM-DD)
+    #[arg(long, value_parser = date_parser)]
+    pub until: Option<NaiveDate>,
+
+    /// Print events as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Show sessions started per day as a calendar heatmap
+    #[arg(long, conflicts_with = "json")]
+    pub heatmap: bool,
+}
+
+impl HistoryCommand {
+    #[instrument(name = "history_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing history command.");
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let events: Vec<Event> = read_events()?
+            .into_iter()
+            .filter(|event| self.matches(event))
+            .collect();
+
+        if self.heatmap {
+            self.print_heatmap(&events);
+            return Ok(());
+        }
+
+        if self.json {
+            let json = serde_json::to_string_pretty(&events)
+                .map_err(|e| CommandError::new(&format!("Failed to serialize events: {e}")))?;
+            println!("{json}");
+            return Ok(());
+        }
+
+        if events.is_empty() {
+            standard("No matching events");
+            return Ok(());
+        }
+
+        let rows: Vec<EventRow> = events
+            .into_iter()
+            .map(|event| EventRow {
+                time: format_timestamp(event.at, config.timestamps)
+                    .color(THEME.muted)
+                    .to_string(),
+                event: event.kind.label().to_string(),
+                task: event.task,
+                detail: event.detail.unwrap_or_default(),
+            })
+            .collect();
+        page(&render_table(&rows, true));
+
+        Ok(())
+    }
+
+    fn print_heatmap(&self, events: &[Event]) {
+        let end = self.until.unwrap_or_else(|| Local::now().date_naive());
+        let start = self.since.unwrap_or_else(|| {
+            let first = end - Days::new(HEATMAP_WEEKS * 7 - 1);
+            first - Days::new(first.weekday().num_days_from_monday().into())
+        });
+
+        let mut counts: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        for event in events {
+            if event.kind == EventKind::SessionStarted {
+                let day = event.at.with_timezone(&Local).date_naive();
+                *counts.entry(day).or_default() += 1;
+            }
+        }
+        counts.retain(|day, _| (start..=end).contains(day));
+
+        standard(&format!("Sessions started per day, {start} to {end}"));
+        blank();
+        for line in heatmap(&counts, start, end, is_plain()) {
+            standard(&line);
+        }
+        blank();
+        let total: usize = counts.values().sum();
+        match counts
+            .iter()
+            .max_by_key(|(day, count)| (**count, std::cmp::Reverse(**day)))
+        {
+            Some((day, count)) => standard(&format!(
+                "{total} session(s) on {} day(s); busiest was {day} with {count}",
+                counts.len()
+            )),
+            None => standard("No sessions in this period"),
+        }
+    }
+
+    fn matches(&self, event: &Event) -> bool {
+        if self.task.as_ref().is_some_and(|task| &event.task != task) {
+            return false;
+        }
+        if self
+            .since
+            .is_some_and(|since| event.at < start_of_day(since))
+        {
+            return false;
+        }
+        if self
+            .until
+            .is_some_and(|until| event.at >= start_of_day(until + chrono::Days::new(1)))
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// A calendar of `start..=end` with a row per weekday and a column per week,
+/// each day shaded by its count relative to the busiest day. Plain output
+/// shows the counts instead, with `+` for more than nine.
+fn heatmap(
+    counts: &BTreeMap<NaiveDate, usize>,
+    start: NaiveDate,
+    end: NaiveDate,
+    plain: bool,
+) -> Vec<String> {
+    let max = counts.values().copied().max().unwrap_or(0);
+    let first_monday = start - Days::new(start.weekday().num_days_from_monday().into());
+    let weeks = (end - first_monday).num_days() / 7 + 1;
+
+    let cell = |count: usize| -> String {
+        match (plain, count) {
+            (true, 0) => ".".to_string(),
+            (true, 1..=9) => count.to_string(),
+            (true, _) => "+".to_string(),
+            (false, 0) => "·".color(THEME.muted).to_string(),
+            (false, _) => {
+                let level = (count * HEAT_LEVELS.len()).div_ceil(max) - 1;
+                HEAT_LEVELS[level].color(THEME.success).to_string()
+            }
+        }
+    };
+
+    let mut lines: Vec<String> = WEEKDAYS
+        .iter()
+        .enumerate()
+        .map(|(weekday, name)| {
+            let mut line = format!("{name} ");
+            for week in 0..weeks {
+                let day = first_monday + Days::new((week * 7) as u64 + weekday as u64);
+                if day < start || day > end {
+                    line.push_str("  ");
+                } else {
+                    line.push(' ');
+                    line.push_str(&cell(counts.get(&day).copied().unwrap_or(0)));
+                }
+            }
+            line.trim_end().to_string()
+        })
+        .collect();
+    if !plain {
+        lines.push(String::new());
+        lines.push(format!(
+            "    less {} {} more",
+            cell(0),
+            HEAT_LEVELS
+                .iter()
+                .map(|level| level.color(THEME.success).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        ));
+    }
+    lines
+}
+
+/// Midnight of `date` in the local time zone, as UTC.
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::events::EventKind;
+
+    fn history(task: Option<&str>, since: Option<&str>, until: Option<&str>) -> HistoryCommand {
+        HistoryCommand {
+            task: task.map(str::to_string),
+            since: since.map(|s| date_parser(s).unwrap()),
+            until: until.map(|s| date_parser(s).unwrap()),
+            json: false,
+            heatmap: false,
+        }
+    }
+
+    fn event_at(task: &str, date: &str) -> Event {
+        let mut event = Event::new(EventKind::TaskCreated, task, None);
+        event.at = start_of_day(date_parser(date).unwrap()) + chrono::Duration::hours(12);
+        event
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        date_parser(s).unwrap()
+    }
+
+    #[test]
+    fn test_plain_heatmap_lays_out_weeks_by_weekday() {
+        // 2024-03-06 is a Wednesday; the range spans parts of three weeks
+        let counts: BTreeMap<NaiveDate, usize> = [
+            (date("2024-03-06"), 2),
+            (date("2024-03-11"), 1),
+            (date("2024-03-17"), 12),
+        ]
+        .into();
+        let lines = heatmap(&counts, date("2024-03-06"), date("2024-03-18"), true);
+        assert_eq!(
+            lines,
+            vec![
+                "Mon    1 .",
+                "Tue    .",
+                "Wed  2 .",
+                "Thu  . .",
+                "Fri  . .",
+                "Sat  . .",
+                "Sun  . +",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_heatmap_shades_relative_to_busiest_day() {
+        let counts: BTreeMap<NaiveDate, usize> =
+            [(date("2024-03-04"), 1), (date("2024-03-05"), 4)].into();
+        let lines = heatmap(&counts, date("2024-03-04"), date("2024-03-05"), false);
+        assert!(lines[0].contains(HEAT_LEVELS[0]));
+        assert!(lines[1].contains(HEAT_LEVELS[3]));
+        assert!(lines.last().unwrap().contains("less"));
+    }
+
+    #[test]
+    fn test_date_parser() {
+        assert!(date_parser("2024-03-01").is_ok());
+        assert!(date_parser("03/01/2024").is_err());
+    }
+
+    #[test]
+    fn test_matches_by_task() {
+        let cmd = history(Some("feat/a"), None, None);
+        assert!(cmd.matches(&event_at("feat/a", "2024-03-01")));
+        assert!(!cmd.matches(&event_at("feat/b", "2024-03-01")));
+    }
+
+    #[test]
+    fn test_matches_by_inclusive_date_range() {
+        let cmd = history(None, Some("2024-03-02"), Some("2024-03-03"));
+        assert!(!cmd.matches(&event_at("feat/a", "2024-03-01")));
+        assert!(cmd.matches(&event_at("feat/a", "2024-03-02")));
+        assert!(cmd.matches(&event_at("feat/a", "2024-03-03")));
+        assert!(!cmd.matches(&event_at("feat/a", "2024-03-04")));
+    }
+}
@@ -0,0 +1,200 @@
+use std::path::{Path, PathBuf};
+
+use clap::{Args, ValueHint};
+use owo_colors::OwoColorize;
+use tabled::Tabled;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::{Config, GlobalConfig, expand_home},
+        errors::CommandError,
+        events::read_events_in,
+        export::{ProjectExport, merge_events, merge_global, parse_path_map, read_export, remap},
+        fs::{
+            create_global_configuration_dir, current_dir, global_projects_dir, lock_event_log_in,
+            read_config_file, read_task_file_in, write_config_file, write_event_log_in,
+            write_global_config_file, write_task_file_in,
+        },
+        output::{ask, blank, is_interactive, standard, success, table},
+        projects::{register_project, registered_projects},
+        theme::THEME,
+    },
+};
+
+#[derive(Tabled)]
+struct ImportRow {
+    project: String,
+    repository: String,
+    tasks: String,
+    events: String,
+    result: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ImportCommand {
+    /// File written by `claudectl export`
+    #[arg(value_hint = ValueHint::FilePath)]
+    pub file: PathBuf,
+
+    /// Look for repositories that were under OLD under NEW instead, e.g.
+    /// `/Users/ana=/home/ana` (repeatable)
+    #[arg(long = "map", value_name = "OLD=NEW", value_parser = parse_path_map)]
+    pub maps: Vec<(PathBuf, PathBuf)>,
+}
+
+impl ImportCommand {
+    #[instrument(name = "import_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing import command from: {}", self.file.display());
+        let export = read_export(&self.file)?;
+        let total = export.projects.len();
+
+        let mut rows = Vec::new();
+        let mut failed = 0;
+        for project in &export.projects {
+            match self.import_project(project) {
+                Ok(row) => rows.push(row),
+                Err(e) => {
+                    warn!("Failed to import project '{}': {}", project.name, e);
+                    failed += 1;
+                    rows.push(ImportRow {
+                        project: project.name.clone(),
+                        repository: project.repo_path.display().to_string(),
+                        tasks: "-".to_string(),
+                        events: "-".to_string(),
+                        result: e.message().color(THEME.error).to_string(),
+                    });
+                }
+            }
+        }
+
+        let mut global = GlobalConfig::load()?;
+        let settings = merge_global(&mut global, export.global);
+        if settings > 0 {
+            let content = serde_json::to_string_pretty(&global).map_err(|e| {
+                CommandError::new(&format!("Failed to serialize global config: {e}"))
+            })?;
+            write_global_config_file(&content)?;
+        }
+
+        if !rows.is_empty() {
+            blank();
+            table(&rows, true);
+        }
+        blank();
+        if settings > 0 {
+            standard(&format!(
+                "Added {settings} profile(s) and filter(s) to the global config"
+            ));
+        }
+        if failed > 0 {
+            return Err(CommandError::new(&format!(
+                "{failed} of {total} project(s) could not be imported"
+            )));
+        }
+        success(&format!("Imported {total} project(s)"));
+        Ok(())
+    }
+
+    /// Register the project on this machine and add the tasks and events it
+    /// does not have yet. Records already here are kept as they are.
+    fn import_project(&self, project: &ProjectExport) -> CommandResult<ImportRow> {
+        let repo = self.repository(project)?;
+        if !repo.join(".git").exists() {
+            return Err(CommandError::new(&format!(
+                "No git repository at {}. Clone it first, or pass --map OLD=NEW to say where it is",
+                repo.display()
+            )));
+        }
+
+        let project_dir = match read_config_file(&repo) {
+            Ok(raw) => Config::from_str(&raw)?.project_dir,
+            Err(_) => {
+                let project_dir = match registered_dir(&repo)? {
+                    Some(dir) => dir,
+                    None => create_global_configuration_dir(&project.name)?,
+                };
+                let mut config = project
+                    .config
+                    .clone()
+                    .unwrap_or_else(|| Config::new(&project.name, ""));
+                config.project_dir = project_dir.clone();
+                write_config_file(&repo, &config.to_string()?)?;
+                project_dir
+            }
+        };
+        register_project(&project_dir, &repo)?;
+
+        let mut tasks = 0;
+        for record in &project.tasks {
+            if read_task_file_in(&repo, &record.name)?.is_none() {
+                write_task_file_in(&repo, &record.name, &record.to_string()?)?;
+                tasks += 1;
+            }
+        }
+        let _lock = lock_event_log_in(&repo)?;
+        let (log, events) = merge_events(&read_events_in(&repo)?, &project.events)?;
+        if events > 0 {
+            write_event_log_in(&repo, &log)?;
+        }
+        info!(
+            "Imported {} task(s) and {} event(s) into {}",
+            tasks,
+            events,
+            repo.display()
+        );
+
+        Ok(ImportRow {
+            project: project.name.clone(),
+            repository: repo.display().to_string(),
+            tasks: tasks.to_string(),
+            events: events.to_string(),
+            result: "imported".color(THEME.success).to_string(),
+        })
+    }
+
+    /// Where the project's repository is on this machine: mapped with
+    /// `--map`, given by the user, or where it was.
+    fn repository(&self, project: &ProjectExport) -> CommandResult<PathBuf> {
+        if let Some(path) = remap(&project.repo_path, &self.maps) {
+            return absolute(&path);
+        }
+        if !is_interactive() {
+            return Ok(project.repo_path.clone());
+        }
+        let answer = ask(
+            &format!("Repository for '{}'", project.name),
+            &project.repo_path.to_string_lossy(),
+        )
+        .map_err(|e| CommandError::new(&format!("Failed to read input: {e}")))?;
+        absolute(Path::new(&expand_home(&answer)))
+    }
+}
+
+fn absolute(path: &Path) -> CommandResult<PathBuf> {
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    Ok(current_dir()?.join(path))
+}
+
+/// The global project directory already registered for `repo`, if any.
+fn registered_dir(repo: &Path) -> CommandResult<Option<String>> {
+    let projects_dir = global_projects_dir()?;
+    Ok(registered_projects()?
+        .into_iter()
+        .find(|project| {
+            project
+                .registration
+                .as_ref()
+                .is_ok_and(|registration| registration.repo_path == repo)
+        })
+        .map(|project| {
+            projects_dir
+                .join(project.name)
+                .to_string_lossy()
+                .to_string()
+        }))
+}
@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+use clap::{Args, ValueHint};
+use owo_colors::OwoColorize;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, record_event},
+        fs::read_local_config_file,
+        git::find_worktree,
+        hooks::{Hook, HookContext, run_hook},
+        lock::{SessionLock, clear_dead_lock, current_lock, request_stop},
+        output::{standard, success},
+        process::{Termination, terminate},
+        tasks::{mark_session_stopped, project_task_names, resolve_task_name},
+        theme::THEME,
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct KillCommand {
+    /// The task whose session to stop (picked interactively if omitted)
+    #[arg(value_hint = ValueHint::Other, conflicts_with = "all")]
+    pub task_name: Option<String>,
+
+    /// Stop every running session in the project
+    #[arg(long)]
+    pub all: bool,
+
+    /// Seconds to wait after SIGTERM before sending SIGKILL (default: the
+    /// project's `stop_timeout`)
+    #[arg(long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+}
+
+impl KillCommand {
+    #[instrument(name = "kill_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing kill command.");
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+        let timeout = Duration::from_secs(self.timeout.unwrap_or(config.stop_timeout));
+
+        if self.all {
+            let running: Vec<(String, SessionLock)> = project_task_names()?
+                .into_iter()
+                .filter_map(|task_name| current_lock(&task_name).map(|lock| (task_name, lock)))
+                .collect();
+            if running.is_empty() {
+                standard("No sessions are running");
+            }
+            for (task_name, lock) in running {
+                stop_session(&task_name, &lock, timeout)?;
+            }
+            return Ok(());
+        }
+
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+        let lock = current_lock(&task_name).ok_or_else(|| {
+            CommandError::new(&format!("No session is running in task '{task_name}'"))
+        })?;
+        stop_session(&task_name, &lock, timeout)?;
+        Ok(())
+    }
+}
+
+/// Stop the session holding a task, sending SIGKILL if it has not exited
+/// `timeout` after SIGTERM. The `on-session-exit` hook runs here only when
+/// the session's own process could not run it.
+pub fn stop_session(
+    task_name: &str,
+    lock: &SessionLock,
+    timeout: Duration,
+) -> CommandResult<Termination> {
+    if lock.pid == std::process::id() {
+        return Err(CommandError::new(&format!(
+            "Task '{task_name}' is held by this claudectl process"
+        )));
+    }
+    // The pid may have been given to another process since the lock was read
+    if !lock.is_live() {
+        clear_dead_lock(task_name)?;
+        return Err(CommandError::new(&format!(
+            "No session is running in task '{task_name}'"
+        )));
+    }
+    // Both before signalling, so neither the session nor `watch
+    // --supervise` takes it ending for a crash
+    request_stop(task_name, lock)?;
+    mark_session_stopped(task_name, lock.started_at)?;
+    let termination = terminate(lock.pid, timeout)?;
+    // A killed session cannot release its own lock, nor run its hooks
+    let orphaned = clear_dead_lock(task_name)?;
+
+    let how = match termination {
+        Termination::Exited => "Stopped",
+        Termination::Killed => "Killed",
+    };
+    record_event(
+        EventKind::SessionKilled,
+        task_name,
+        Some(format!("{} {}", how.to_lowercase(), lock.holder())),
+    );
+    info!("{} session {} in {}", how, lock.holder(), task_name);
+    success(&format!(
+        "{how} the session in '{}' ({}){}",
+        task_name.color(THEME.info),
+        lock.holder(),
+        if termination == Termination::Killed {
+            " after it ignored SIGTERM"
+        } else {
+            ""
+        }
+    ));
+    if !orphaned {
+        return Ok(termination);
+    }
+    let worktree = find_worktree(task_name)?
+        .map(|worktree| worktree.path)
+        .unwrap_or_default();
+    run_hook(
+        Hook::OnSessionExit,
+        &HookContext {
+            task_name,
+            worktree: &worktree,
+            session_id: Some(lock.session_id()),
+            status: Some("stopped"),
+            exit_code: None,
+        },
+    )?;
+    Ok(termination)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_session_refuses_to_stop_its_own_process() {
+        let lock = SessionLock {
+            pid: std::process::id(),
+            command: "resolve".to_string(),
+            started_at: chrono::Utc::now(),
+            stopping: false,
+        };
+        let err = stop_session("feat/login", &lock, Duration::from_secs(1)).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Task 'feat/login' is held by this claudectl process")
+        );
+    }
+}
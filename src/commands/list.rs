@@ -1,18 +1,24 @@
-use clap::Args;
+use clap::{Args, ValueEnum};
 use owo_colors::OwoColorize;
 use tabled::Tabled;
 use tracing::{info, instrument};
 
 use crate::{
-    commands::CommandResult,
+    commands::{CommandResult, note::format_note},
     utils::{
         claude::{Status, get_session},
-        config::Config,
+        config::{Config, GlobalConfig},
+        errors::CommandError,
+        filter::{SortKey, TaskEntry, TaskFilter, TaskInfo, sort_tasks},
         fs::read_local_config_file,
-        git::worktree_list,
+        git::{Worktree, worktree_list},
         icons::ICONS,
+        lock::{SessionLock, current_lock},
         output::{error, table},
-        theme::THEME,
+        queue::queued_task,
+        tasks::{SessionRecord, TaskKind, TaskRecord, load_task, project_worktrees},
+        theme::{CATPPUCCIN, THEME},
+        time::{TimestampStyle, format_timestamp},
     },
 };
 
@@ -24,8 +30,70 @@ struct TaskRow {
     worktree: String,
 }
 
+#[derive(Tabled)]
+struct VerboseTaskRow {
+    name: String,
+    kind: String,
+    status: String,
+    commit: String,
+    worktree: String,
+    created: String,
+    tags: String,
+    summary: String,
+    notes: String,
+}
+
+/// How `list` prints tasks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ListFormat {
+    /// A colored table for people
+    #[default]
+    Table,
+    /// An array of task objects
+    Json,
+    /// Tab-separated values with a header row
+    Tsv,
+}
+
 #[derive(Args, Debug)]
-pub struct ListCommand {}
+pub struct ListCommand {
+    /// Show task summaries and other stored details
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Only show tasks with this status
+    #[arg(long, value_enum)]
+    pub status: Option<Status>,
+
+    /// Only show tasks belonging to this project
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Only show tasks with this tag
+    #[arg(long)]
+    pub tag: Option<String>,
+
+    /// Only show tasks of this kind
+    #[arg(long, value_enum)]
+    pub kind: Option<TaskKind>,
+
+    /// Sort tasks by this key
+    #[arg(long, value_enum)]
+    pub sort: Option<SortKey>,
+
+    /// Apply a filter saved in the global config; other filter options
+    /// override its fields
+    #[arg(long, value_name = "NAME")]
+    pub filter: Option<String>,
+
+    /// Print tasks in this format, for scripts and editor plugins
+    #[arg(long, value_enum, default_value_t = ListFormat::Table)]
+    pub format: ListFormat,
+
+    /// Print tasks as JSON; the same as `--format json`
+    #[arg(long, conflicts_with = "format")]
+    pub json: bool,
+}
 
 impl ListCommand {
     #[instrument(name = "list_command")]
@@ -35,38 +103,233 @@ impl ListCommand {
         let config = Config::from_str(&raw_config)?;
         info!("Loaded configuration for project: {}", config.project_name);
 
-        // 1. get all worktrees
-        let worktrees = worktree_list().inspect_err(|e| {
+        // 1. get the main checkout and the project's task worktrees
+        let mut worktrees = worktree_list().inspect_err(|e| {
             error(&format!("Failed to get active tasks: {e}"));
         })?;
+        worktrees.truncate(1);
+        worktrees.extend(project_worktrees()?);
 
-        // 2. get status of each task (worktree)
-        let data: Vec<TaskRow> = worktrees
+        // 2. get status and stored details of each task (worktree)
+        let filter = self.filter()?;
+        let mut entries: Vec<TaskEntry> = worktrees
             .into_iter()
-            .map(|wt| -> CommandResult<TaskRow> {
-                let name = wt.branch.unwrap_or_else(|| "N/A".to_string());
-                let session = get_session(name.as_str())?;
-                Ok(TaskRow {
-                    name: name.clone(),
-                    status: format_status(session.status),
-                    commit: wt.commit,
-                    worktree: wt.path.as_str().color(THEME.muted).to_string(),
-                })
-            })
+            .map(|wt| task_entry(&config.project_name, wt))
+            .filter(|entry| entry.as_ref().map_or(true, |e| filter.matches(e)))
             .collect::<Result<Vec<_>, _>>()?;
 
-        table(&data, false);
+        // 3. order and render
+        if let Some(key) = self.sort {
+            sort_tasks(&mut entries, key);
+        }
+
+        let format = if self.json {
+            ListFormat::Json
+        } else {
+            self.format
+        };
+        match format {
+            ListFormat::Table => {}
+            ListFormat::Json => {
+                let tasks: Vec<TaskInfo> = entries.iter().map(TaskEntry::info).collect();
+                let json = serde_json::to_string_pretty(&tasks)
+                    .map_err(|e| CommandError::new(&format!("Failed to serialize tasks: {e}")))?;
+                println!("{json}");
+                return Ok(());
+            }
+            ListFormat::Tsv => {
+                print!("{}", tsv(&entries));
+                return Ok(());
+            }
+        }
+
+        if self.verbose {
+            let data: Vec<VerboseTaskRow> = entries
+                .into_iter()
+                .map(|entry| verbose_row(entry, config.timestamps))
+                .collect();
+            table(&data, true);
+        } else {
+            let data: Vec<TaskRow> = entries.into_iter().map(task_row).collect();
+            table(&data, false);
+        }
 
         Ok(())
     }
+
+    fn filter(&self) -> CommandResult<TaskFilter> {
+        let filter = TaskFilter {
+            status: self.status,
+            project: self.project.clone(),
+            tag: self.tag.clone(),
+            kind: self.kind,
+        };
+        match &self.filter {
+            Some(name) => Ok(filter.or(GlobalConfig::load()?.filter(name)?)),
+            None => Ok(filter),
+        }
+    }
+}
+
+const TSV_COLUMNS: [&str; 9] = [
+    "name",
+    "status",
+    "kind",
+    "commit",
+    "worktree",
+    "base",
+    "created_at",
+    "tags",
+    "session",
+];
+
+/// One line per task, without colors. Tabs and newlines in values are
+/// replaced by spaces so every line keeps its columns.
+fn tsv(entries: &[TaskEntry]) -> String {
+    let mut out = TSV_COLUMNS.join("\t");
+    out.push('\n');
+    for entry in entries {
+        let info = entry.info();
+        let fields = [
+            info.name,
+            status_name(info.status),
+            info.kind
+                .map(|kind| kind.as_str().to_string())
+                .unwrap_or_default(),
+            info.commit,
+            info.worktree,
+            info.base,
+            info.created_at
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+            info.tags.join(","),
+            info.session.map(|lock| lock.holder()).unwrap_or_default(),
+        ];
+        let line: Vec<String> = fields
+            .iter()
+            .map(|field| field.replace(['\t', '\n', '\r'], " "))
+            .collect();
+        out.push_str(&line.join("\t"));
+        out.push('\n');
+    }
+    out
+}
+
+/// The status as it is written on the command line, e.g. `setup-failed`.
+fn status_name(status: Status) -> String {
+    status
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn task_row(entry: TaskEntry) -> TaskRow {
+    TaskRow {
+        name: format_name(&entry.record),
+        status: format_entry_status(entry.status, entry.lock.as_ref()),
+        commit: entry.worktree.commit,
+        worktree: entry.worktree.path.as_str().color(THEME.muted).to_string(),
+    }
+}
+
+fn verbose_row(entry: TaskEntry, style: TimestampStyle) -> VerboseTaskRow {
+    let record = entry.record;
+    VerboseTaskRow {
+        name: format_name(&record),
+        kind: record
+            .effective_kind()
+            .map(|kind| kind.as_str().to_string())
+            .unwrap_or_default(),
+        status: format_entry_status(entry.status, entry.lock.as_ref()),
+        commit: entry.worktree.commit,
+        worktree: entry.worktree.path.as_str().color(THEME.muted).to_string(),
+        created: record
+            .created_at
+            .map(|at| format_timestamp(at, style))
+            .unwrap_or_default(),
+        tags: record.tags.join(", "),
+        summary: record
+            .summary
+            .map(|s| s.text)
+            .unwrap_or_default()
+            .color(THEME.muted)
+            .to_string(),
+        notes: record
+            .notes
+            .iter()
+            .map(|note| format_note(note, style))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Color a task's name by its kind so different kinds of work stand apart.
+pub fn format_name(record: &TaskRecord) -> String {
+    let color = match record.effective_kind() {
+        Some(TaskKind::Feature) => CATPPUCCIN.blue,
+        Some(TaskKind::Bugfix) => CATPPUCCIN.red,
+        Some(TaskKind::Chore) => CATPPUCCIN.overlay2,
+        Some(TaskKind::Experiment) => CATPPUCCIN.mauve,
+        None => return record.name.clone(),
+    };
+    record.name.as_str().color(color).to_string()
+}
+
+/// A task worktree of the current project as every view of tasks sees it,
+/// with its stored record and the session holding it.
+pub fn task_entry(project: &str, worktree: Worktree) -> CommandResult<TaskEntry> {
+    let name = worktree.branch.clone().unwrap_or_else(|| "N/A".to_string());
+    let session = get_session(&name)?;
+    let lock = current_lock(&name);
+    let record = load_task(&name)?;
+    Ok(TaskEntry {
+        project: project.to_string(),
+        worktree,
+        status: entry_status(session.status, lock.as_ref(), &record),
+        record,
+        lock,
+    })
+}
+
+/// A task held by a session is being worked on, whatever else is known,
+/// and one with a session waiting to start is queued; otherwise a failed
+/// setup, then a crashed session, take precedence over the session's status.
+pub fn entry_status(status: Status, lock: Option<&SessionLock>, record: &TaskRecord) -> Status {
+    if lock.is_some() {
+        Status::Working
+    } else if queued_task(&record.name).is_some() {
+        Status::Queued
+    } else if record.setup_failure.is_some() {
+        Status::SetupFailed
+    } else if record
+        .last_session
+        .as_ref()
+        .is_some_and(SessionRecord::crashed)
+    {
+        Status::Crashed
+    } else {
+        status
+    }
 }
 
-fn format_status(status: Status) -> String {
+/// The status cell, naming the session that holds the task if any.
+pub fn format_entry_status(status: Status, lock: Option<&SessionLock>) -> String {
+    let status = format_status(status);
+    match lock {
+        Some(lock) => format!(
+            "{status} {}",
+            format!("locked by {}", lock.holder()).color(THEME.muted)
+        ),
+        None => status,
+    }
+}
+
+pub fn format_status(status: Status) -> String {
     let color = match status {
         Status::Ready => THEME.success,
-        Status::Working => THEME.warning,
+        Status::Working | Status::Queued => THEME.warning,
         Status::Waiting => THEME.info,
-        Status::Unknown => THEME.error,
+        Status::Unknown | Status::SetupFailed | Status::Crashed => THEME.error,
     };
 
     format!(
@@ -113,6 +376,32 @@ mod tests {
         assert!(result.contains("●"));
     }
 
+    #[test]
+    fn test_tsv_keeps_one_line_per_task() {
+        let mut record = TaskRecord::new("feat/login");
+        record.tags = vec!["api".to_string(), "auth".to_string()];
+        let entry = TaskEntry {
+            project: "app".to_string(),
+            worktree: crate::utils::git::Worktree {
+                path: "/tmp/app/feat/login".to_string(),
+                commit: "abc1234".to_string(),
+                branch: Some("feat/login".to_string()),
+            },
+            status: Status::SetupFailed,
+            record,
+            lock: None,
+        };
+
+        let out = tsv(&[entry]);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("name\tstatus\tkind"));
+        let fields: Vec<&str> = lines[1].split('\t').collect();
+        assert_eq!(fields.len(), TSV_COLUMNS.len());
+        assert_eq!(fields[..3], ["feat/login", "setup-failed", "feature"]);
+        assert_eq!(fields[7], "api,auth");
+    }
+
     #[test]
     fn test_task_row_creation() {
         // Test that TaskRow can be created successfully
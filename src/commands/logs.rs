@@ -0,0 +1,55 @@
+use clap::Args;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        fs::{last_lines, log_path, read_log},
+        output::{page, standard},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct LogsCommand {
+    /// Number of lines to show, counting back from the latest
+    #[arg(long, short = 'n', default_value_t = 50)]
+    pub lines: usize,
+
+    /// Print where the log is kept instead of its contents
+    #[arg(long, conflicts_with = "lines")]
+    pub path: bool,
+}
+
+impl LogsCommand {
+    #[instrument(name = "logs_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing logs command.");
+        if self.path {
+            println!("{}", log_path()?.display());
+            return Ok(());
+        }
+
+        let log = read_log()?;
+        if log.trim().is_empty() {
+            standard("Nothing has been logged yet");
+            return Ok(());
+        }
+        page(last_lines(&log, self.lines).trim_end());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logs_command_creation() {
+        let cmd = LogsCommand {
+            lines: 10,
+            path: false,
+        };
+        assert_eq!(cmd.lines, 10);
+        assert!(!cmd.path);
+    }
+}
@@ -0,0 +1,57 @@
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::{Args, ValueHint};
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        errors::CommandError,
+        man::{install_dir, render_page, write_pages},
+        output::{standard, success},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct ManCommand {
+    /// The subcommand whose page to print, e.g. `task` or `config set`
+    #[arg(value_name = "COMMAND")]
+    pub command: Vec<String>,
+
+    /// Install the pages of every command where `man` finds them
+    #[arg(long, conflicts_with_all = ["command", "output"])]
+    pub install: bool,
+
+    /// Write the pages of every command to this directory, e.g. for packaging
+    #[arg(long, value_name = "DIR", value_hint = ValueHint::DirPath, conflicts_with = "command")]
+    pub output: Option<PathBuf>,
+}
+
+impl ManCommand {
+    #[instrument(name = "man_command", fields(command = ?self.command))]
+    pub fn execute(&self) -> CommandResult<()> {
+        let dir = match (&self.output, self.install) {
+            (Some(dir), _) => dir.clone(),
+            (None, true) => install_dir()?,
+            (None, false) => {
+                let page = render_page(&self.command)?;
+                return std::io::stdout()
+                    .write_all(&page)
+                    .map_err(|e| CommandError::new(&format!("Failed to print the man page: {e}")));
+            }
+        };
+
+        info!("Writing man pages to {}", dir.display());
+        let pages = write_pages(&dir)?;
+        success(&format!(
+            "Wrote {} man page(s) to {}",
+            pages.len(),
+            dir.display()
+        ));
+        if self.install {
+            standard("Read them with `man claudectl` or e.g. `man claudectl-task`");
+        }
+        Ok(())
+    }
+}
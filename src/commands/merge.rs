@@ -0,0 +1,162 @@
+use clap::{Args, ValueHint};
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        errors::{CommandError, GitError},
+        events::{EventKind, record_event},
+        fs::remove_task_file,
+        git::{
+            changed_files, conflicted_files, current_branch, delete_branch, delete_checkpoints,
+            fast_forward, fetch_origin, local_branch, merge_branch, rebase, remote_branch_exists,
+            remove_worktree,
+        },
+        lock::current_lock,
+        output::{Position, blank, step, step_end, step_fail, success},
+        tasks::{load_task, resolve_task_name, task_worktree},
+    },
+};
+
+/// The main checkout, where the base branch is expected to be checked out.
+const MAIN_CHECKOUT: &str = ".";
+
+#[derive(Args, Debug)]
+pub struct MergeCommand {
+    /// The task to merge (picked interactively if omitted)
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+
+    /// Fetch origin and fast-forward the base branch before merging
+    #[arg(long)]
+    pub fetch: bool,
+
+    /// Rebase the task onto the base branch and fast-forward instead of
+    /// creating a merge commit
+    #[arg(long)]
+    pub rebase: bool,
+
+    /// Remove the task's worktree and branch once it is merged
+    #[arg(long)]
+    pub delete: bool,
+}
+
+impl MergeCommand {
+    #[instrument(name = "merge_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+        info!("Executing merge command for: {}", task_name);
+        let worktree = task_worktree(&task_name)?;
+        let record = load_task(&task_name)?;
+        let target = local_branch(record.base()).to_string();
+
+        // 1. Make sure the task is finished and the base is checked out
+        if let Some(lock) = current_lock(&task_name) {
+            return Err(CommandError::new(&format!(
+                "Task '{task_name}' is locked by {}; wait for the session to end",
+                lock.holder()
+            )));
+        }
+        let uncommitted = changed_files(&worktree.path)?;
+        if !uncommitted.is_empty() {
+            return Err(CommandError::new(&format!(
+                "Task '{task_name}' has {} uncommitted file(s); commit them before merging",
+                uncommitted.len()
+            )));
+        }
+        match current_branch(MAIN_CHECKOUT)? {
+            Some(branch) if branch == target => {}
+            checked_out => {
+                return Err(CommandError::new(&format!(
+                    "The main checkout is on {}; check out '{target}' there to merge into it",
+                    checked_out.map_or("a detached HEAD".to_string(), |b| format!("'{b}'"))
+                )));
+            }
+        }
+
+        // 2. Bring the base up to date
+        if self.fetch {
+            step("Fetching latest changes from origin...", Position::First);
+            fetch_origin(&mut |_| {}).inspect_err(|_| step_fail())?;
+            if remote_branch_exists(&target)? {
+                fast_forward(MAIN_CHECKOUT, &format!("origin/{target}")).inspect_err(|e| {
+                    error!("Failed to fast-forward {}: {}", target, e);
+                    step_fail();
+                })?;
+            }
+            step_end();
+            blank();
+        }
+
+        // 3. Merge
+        let message = format!("Merging '{task_name}' into '{target}'...");
+        step(&message, Position::Last);
+        let merged = if self.rebase {
+            rebase(&worktree.path, &target)
+                .map_err(|e| stopped_on_conflicts(e, &worktree.path, &task_name, true))
+                .and_then(|_| Ok(fast_forward(MAIN_CHECKOUT, &task_name)?))
+        } else {
+            merge_branch(MAIN_CHECKOUT, &task_name)
+                .map_err(|e| stopped_on_conflicts(e, MAIN_CHECKOUT, &task_name, false))
+        };
+        merged.inspect_err(|e| {
+            error!("Failed to merge task: {}", e);
+            step_fail();
+        })?;
+        step_end();
+        blank();
+        let how = if self.rebase { " (rebased)" } else { "" };
+        record_event(
+            EventKind::Merged,
+            &task_name,
+            Some(format!("into {target}{how}")),
+        );
+        success(&format!("Merged '{task_name}' into '{target}'"));
+
+        // 4. Clean up
+        if self.delete {
+            remove_worktree(&worktree.path)?;
+            delete_branch(&task_name)?;
+            delete_checkpoints(&task_name)?;
+            remove_task_file(&task_name)?;
+            record_event(EventKind::TaskRemoved, &task_name, None);
+            success(&format!("Removed task '{task_name}' and its worktree"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Explain how to continue when git stopped on conflicts, or pass the
+/// error on unchanged.
+fn stopped_on_conflicts(e: GitError, dir: &str, task_name: &str, rebasing: bool) -> CommandError {
+    match conflicted_files(dir) {
+        Ok(files) if !files.is_empty() && rebasing => CommandError::new(&format!(
+            "The rebase stopped on conflicts in {} file(s). Run `claudectl resolve {task_name}`, \
+             then `git rebase --continue` in the worktree and merge again",
+            files.len()
+        )),
+        Ok(files) if !files.is_empty() => CommandError::new(&format!(
+            "The merge stopped on conflicts in {} file(s) in the main checkout. Resolve them and \
+             commit, or run `git merge --abort`",
+            files.len()
+        )),
+        _ => e.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_command_creation() {
+        let cmd = MergeCommand {
+            task_name: Some("feat/x".to_string()),
+            fetch: false,
+            rebase: true,
+            delete: false,
+        };
+        assert!(cmd.rebase);
+    }
+}
@@ -1,9 +1,48 @@
+pub mod archive;
+pub mod checkpoint;
+pub mod clean;
+pub mod compare;
+pub mod complete;
 pub mod completions;
+pub mod config;
+pub mod describe;
+pub mod diff;
+pub mod doctor;
+pub mod exec;
+pub mod export;
+pub mod history;
+pub mod import;
 pub mod init;
+pub mod kill;
 pub mod list;
+pub mod logs;
+pub mod man;
+pub mod merge;
+pub mod note;
+pub mod open;
+pub mod overview;
+pub mod pr;
+pub mod reconcile;
+pub mod rename;
 pub mod repair;
+pub mod resolve;
+pub mod restart;
+pub mod restore;
 pub mod rm;
+pub mod rollback;
+pub mod run;
+pub mod setup;
+pub mod show;
+pub mod stats;
+pub mod status;
+pub mod storage;
+pub mod summarize;
+pub mod switch;
+pub mod sync_claude_md;
 pub mod task;
+pub mod upgrade;
+pub mod watch;
+pub mod worktree;
 
 use crate::utils::errors::CommandError;
 use clap::Subcommand;
@@ -14,26 +53,145 @@ pub enum Commands {
     Init(init::InitCommand),
     /// Create a new task worktree  
     Task(task::TaskCommand),
+    /// Run claude non-interactively in a task
+    Run(run::RunCommand),
     /// List all task worktrees
     List(list::ListCommand),
+    /// Show the state of every task in the project
+    Status(status::StatusCommand),
+    /// Show in-flight tasks across all registered projects
+    Overview(overview::OverviewCommand),
+    /// Show everything known about a task
+    Show(show::ShowCommand),
+    /// Show everything changed in a task
+    Diff(diff::DiffCommand),
+    /// Compare the work of two tasks
+    Compare(compare::CompareCommand),
+    /// Open a shell or editor in a task's worktree
+    Open(open::OpenCommand),
+    /// Pick a task and print its worktree path, for `cd "$(claudectl switch)"`
+    Switch(switch::SwitchCommand),
+    /// Run a command in a task's worktree
+    Exec(exec::ExecCommand),
     /// Remove a task worktree
     Rm(rm::RmCommand),
+    /// Rename a task's branch and worktree
+    Rename(rename::RenameCommand),
+    /// Remove merged and stale tasks
+    Clean(clean::CleanCommand),
+    /// Snapshot a task into an archive and remove it
+    Archive(archive::ArchiveCommand),
+    /// Recreate a task from its archive
+    Restore(restore::RestoreCommand),
+    /// Snapshot a task's uncommitted work
+    Checkpoint(checkpoint::CheckpointCommand),
+    /// Roll a task back to a checkpoint or its branch point
+    Rollback(rollback::RollbackCommand),
+    /// Generate a summary of a task's changes
+    Summarize(summarize::SummarizeCommand),
+    /// Print a pull request description for a task
+    Describe(describe::DescribeCommand),
+    /// Resolve a task's merge conflicts with claude
+    Resolve(resolve::ResolveCommand),
+    /// Merge a finished task into its base branch
+    Merge(merge::MergeCommand),
+    /// Push a task and open a pull request for it
+    Pr(pr::PrCommand),
+    /// Re-run the project's setup commands in a task
+    Setup(setup::SetupCommand),
+    /// Copy CLAUDE.md and .claude into all task worktrees
+    SyncClaudeMd(sync_claude_md::SyncClaudeMdCommand),
+    /// Add or show timestamped notes on a task
+    Note(note::NoteCommand),
+    /// Show a timeline of task activity
+    History(history::HistoryCommand),
+    /// Show task and session statistics for the project
+    Stats(stats::StatsCommand),
+    /// Start tasks when watched files change
+    Watch(watch::WatchCommand),
+    /// Manage task worktrees, e.g. bring one up to date with its base
+    Worktree(worktree::WorktreeCommand),
+    /// Stop a task's running session
+    Kill(kill::KillCommand),
+    /// Stop a task's session and resume its conversation
+    Restart(restart::RestartCommand),
 
     #[command(next_help_heading = "Utility Commands")]
     /// Generate shell completions
     Completions(completions::CompletionsCommand),
+    /// Print or install man pages
+    Man(man::ManCommand),
     /// Repair shell completions and configuration
     Repair(repair::RepairCommand),
+    /// Check that claudectl and the project are set up
+    Doctor(doctor::DoctorCommand),
+    /// Resume, stop or delete sessions that ended unexpectedly
+    Reconcile(reconcile::ReconcileCommand),
+    /// Show claudectl's own log of recent runs
+    Logs(logs::LogsCommand),
+    /// Show where claudectl stores its data
+    Storage(storage::StorageCommand),
+    /// Write every project's settings and task metadata to a file
+    Export(export::ExportCommand),
+    /// Bring projects in from a file written by `export`
+    Import(import::ImportCommand),
+    /// Get, set or list configuration
+    Config(config::ConfigCommand),
+    /// Update claudectl to the latest release
+    Upgrade(upgrade::UpgradeCommand),
+
+    /// Print completion candidates for shell scripts
+    #[command(name = "__complete", hide = true)]
+    Complete(complete::CompleteCommand),
 }
 
 pub fn handle_command(command: Commands) -> CommandResult<()> {
     match command {
         Commands::Init(cmd) => cmd.execute(),
         Commands::Task(cmd) => cmd.execute(),
+        Commands::Run(cmd) => cmd.execute(),
         Commands::List(cmd) => cmd.execute(),
+        Commands::Status(cmd) => cmd.execute(),
+        Commands::Overview(cmd) => cmd.execute(),
+        Commands::Show(cmd) => cmd.execute(),
+        Commands::Diff(cmd) => cmd.execute(),
+        Commands::Compare(cmd) => cmd.execute(),
+        Commands::Open(cmd) => cmd.execute(),
+        Commands::Switch(cmd) => cmd.execute(),
+        Commands::Exec(cmd) => cmd.execute(),
         Commands::Rm(cmd) => cmd.execute(),
+        Commands::Rename(cmd) => cmd.execute(),
+        Commands::Clean(cmd) => cmd.execute(),
+        Commands::Archive(cmd) => cmd.execute(),
+        Commands::Restore(cmd) => cmd.execute(),
+        Commands::Checkpoint(cmd) => cmd.execute(),
+        Commands::Rollback(cmd) => cmd.execute(),
+        Commands::Summarize(cmd) => cmd.execute(),
+        Commands::Describe(cmd) => cmd.execute(),
+        Commands::Resolve(cmd) => cmd.execute(),
+        Commands::Merge(cmd) => cmd.execute(),
+        Commands::Pr(cmd) => cmd.execute(),
+        Commands::Setup(cmd) => cmd.execute(),
+        Commands::SyncClaudeMd(cmd) => cmd.execute(),
+        Commands::Note(cmd) => cmd.execute(),
+        Commands::History(cmd) => cmd.execute(),
+        Commands::Stats(cmd) => cmd.execute(),
+        Commands::Watch(cmd) => cmd.execute(),
+        Commands::Worktree(cmd) => cmd.execute(),
+        Commands::Kill(cmd) => cmd.execute(),
+        Commands::Restart(cmd) => cmd.execute(),
         Commands::Completions(cmd) => cmd.execute(),
+        Commands::Man(cmd) => cmd.execute(),
         Commands::Repair(cmd) => cmd.execute(),
+        Commands::Doctor(cmd) => cmd.execute(),
+        Commands::Reconcile(cmd) => cmd.execute(),
+        Commands::Logs(cmd) => cmd.execute(),
+        Commands::Storage(cmd) => cmd.execute(),
+        Commands::Export(cmd) => cmd.execute(),
+        Commands::Import(cmd) => cmd.execute(),
+        Commands::Config(cmd) => cmd.execute(),
+        Commands::Upgrade(cmd) => cmd.execute(),
+        Commands::Complete(cmd) => cmd.execute(),
     }
 }
 
@@ -0,0 +1,102 @@
+use clap::{Args, ValueHint};
+use owo_colors::OwoColorize;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, record_event},
+        fs::read_local_config_file,
+        output::{standard, success},
+        tasks::{Note, load_task, save_task, task_worktree},
+        theme::THEME,
+        time::{TimestampStyle, format_timestamp},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct NoteCommand {
+    /// The name of the task to annotate
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: String,
+
+    /// The note to add; omit to show the task's notes
+    pub text: Option<String>,
+}
+
+impl NoteCommand {
+    #[instrument(name = "note_command", fields(task_name = %self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing note command for: {}", self.task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        task_worktree(&self.task_name)?;
+
+        let mut record = load_task(&self.task_name)?;
+
+        match self.text.as_deref().map(str::trim) {
+            Some("") => Err(CommandError::new("Note text cannot be empty")),
+            Some(text) => {
+                record.add_note(text);
+                save_task(&record)?;
+                record_event(
+                    EventKind::NoteAdded,
+                    &self.task_name,
+                    Some(text.to_string()),
+                );
+                info!("Added note to task: {}", self.task_name);
+                success(&format!("Added note to '{}'", self.task_name));
+                Ok(())
+            }
+            None => {
+                if record.notes.is_empty() {
+                    standard(&format!("No notes for '{}'", self.task_name));
+                }
+                for note in &record.notes {
+                    standard(&format_note(note, config.timestamps));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Render a note as `<timestamp> <text>`.
+pub fn format_note(note: &Note, style: TimestampStyle) -> String {
+    format!(
+        "{} {}",
+        format_timestamp(note.created_at, style).color(THEME.muted),
+        note.text
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_note_command_creation() {
+        let cmd = NoteCommand {
+            task_name: "feat/notes".to_string(),
+            text: Some("waiting on design input".to_string()),
+        };
+        assert_eq!(cmd.task_name, "feat/notes");
+        assert_eq!(cmd.text.as_deref(), Some("waiting on design input"));
+    }
+
+    #[test]
+    fn test_format_note_includes_text() {
+        let note = Note {
+            text: "waiting on review".to_string(),
+            created_at: Utc::now(),
+        };
+        let formatted = format_note(&note, TimestampStyle::Relative);
+        assert!(formatted.contains("waiting on review"));
+        assert!(formatted.contains("just now"));
+    }
+}
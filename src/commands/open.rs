@@ -0,0 +1,119 @@
+use std::process::Command;
+
+use clap::{Args, ValueHint};
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        fs::read_local_config_file,
+        output::{blank, standard},
+        tasks::{resolve_task_name, task_worktree},
+    },
+};
+
+const DEFAULT_SHELL: &str = "/bin/sh";
+
+#[derive(Args, Debug)]
+pub struct OpenCommand {
+    /// The task to open (picked interactively if omitted)
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+
+    /// Print the worktree path instead, e.g. for `cd "$(claudectl open --print <task>)"`
+    #[arg(long, conflicts_with = "editor")]
+    pub print: bool,
+
+    /// Open the worktree in $VISUAL or $EDITOR instead of a shell
+    #[arg(long)]
+    pub editor: bool,
+}
+
+impl OpenCommand {
+    #[instrument(name = "open_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+        info!("Executing open command for: {}", task_name);
+        let worktree = task_worktree(&task_name)?;
+        // A sub-project's tasks open in its directory; opening a task does
+        // not otherwise need the project's config
+        let dir = read_local_config_file()
+            .ok()
+            .and_then(|raw| Config::from_str(&raw).ok())
+            .map_or_else(
+                || worktree.path.clone(),
+                |config| config.task_dir(&worktree.path),
+            );
+
+        if self.print {
+            println!("{dir}");
+            return Ok(());
+        }
+
+        let (program, args) = if self.editor {
+            let visual = std::env::var("VISUAL").ok();
+            let editor = std::env::var("EDITOR").ok();
+            let editor = editor_command(visual.as_deref(), editor.as_deref()).ok_or_else(|| {
+                CommandError::new("Neither $VISUAL nor $EDITOR is set; set one to use --editor")
+            })?;
+            let mut parts = editor.split_whitespace().map(str::to_string);
+            let program = parts.next().unwrap_or_default();
+            let mut args: Vec<String> = parts.collect();
+            args.push(dir.clone());
+            (program, args)
+        } else {
+            let shell = std::env::var("SHELL")
+                .ok()
+                .filter(|shell| !shell.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_SHELL.to_string());
+            standard(&format!("Opening a shell in {dir}; exit it to return"));
+            blank();
+            (shell, Vec::new())
+        };
+
+        info!("Running {} {:?} in {}", program, args, dir);
+        Command::new(&program)
+            .args(&args)
+            .current_dir(&dir)
+            .status()
+            .map_err(|e| CommandError::new(&format!("Failed to run '{program}': {e}")))?;
+        Ok(())
+    }
+}
+
+/// The editor to run: `$VISUAL` wins over `$EDITOR`, and blank values count
+/// as unset. The command may carry arguments, e.g. `code --wait`.
+fn editor_command<'a>(visual: Option<&'a str>, editor: Option<&'a str>) -> Option<&'a str> {
+    [visual, editor]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|command| !command.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_command_creation() {
+        let cmd = OpenCommand {
+            task_name: Some("feat/x".to_string()),
+            print: true,
+            editor: false,
+        };
+        assert!(cmd.print);
+    }
+
+    #[test]
+    fn test_editor_command_prefers_visual() {
+        assert_eq!(
+            editor_command(Some("code --wait"), Some("vi")),
+            Some("code --wait")
+        );
+        assert_eq!(editor_command(Some("  "), Some("vi")), Some("vi"));
+        assert_eq!(editor_command(None, None), None);
+    }
+}
@@ -0,0 +1,176 @@
+use std::path::Path;
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use tabled::Tabled;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    commands::{
+        CommandResult,
+        list::{entry_status, format_entry_status, format_name},
+    },
+    utils::{
+        claude::{Status, get_session},
+        config::{Config, GlobalConfig},
+        errors::CommandError,
+        filter::{SortKey, TaskEntry, TaskFilter, sort_tasks},
+        fs::read_config_file,
+        git::worktree_list_in,
+        lock::current_lock_in,
+        output::{blank, error, standard, table},
+        projects::{ProjectRegistration, registered_projects},
+        tasks::load_task_in,
+        theme::THEME,
+    },
+};
+
+#[derive(Tabled)]
+struct OverviewRow {
+    project: String,
+    task: String,
+    status: String,
+    commit: String,
+    worktree: String,
+}
+
+#[derive(Args, Debug)]
+pub struct OverviewCommand {
+    /// Only show tasks with this status
+    #[arg(long, value_enum)]
+    pub status: Option<Status>,
+
+    /// Sort tasks by this key (grouped by project otherwise)
+    #[arg(long, value_enum)]
+    pub sort: Option<SortKey>,
+
+    /// Apply a filter saved in the global config; --status overrides its
+    /// status
+    #[arg(long, value_name = "NAME")]
+    pub filter: Option<String>,
+}
+
+impl OverviewCommand {
+    #[instrument(name = "overview_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing overview command.");
+        let mut filter = TaskFilter {
+            status: self.status,
+            ..TaskFilter::default()
+        };
+        if let Some(name) = &self.filter {
+            filter = filter.or(GlobalConfig::load()?.filter(name)?);
+        }
+        let projects = registered_projects()?;
+        if projects.is_empty() {
+            standard("No projects registered. Run `claudectl init` in a repository to add one.");
+            return Ok(());
+        }
+
+        // 1. load every project's tasks, setting aside the ones that fail
+        let mut entries: Vec<TaskEntry> = Vec::new();
+        let mut failures: Vec<(String, CommandError)> = Vec::new();
+        for project in projects {
+            match project.registration.and_then(|r| load_project_tasks(&r)) {
+                Ok(tasks) => entries.extend(tasks.into_iter().filter(|e| filter.matches(e))),
+                Err(e) => {
+                    warn!("Failed to load project '{}': {}", project.name, e);
+                    failures.push((project.name, e));
+                }
+            }
+        }
+
+        // 2. order and render
+        if let Some(key) = self.sort {
+            sort_tasks(&mut entries, key);
+        }
+
+        if entries.is_empty() {
+            standard("No tasks in flight");
+        } else {
+            let data: Vec<OverviewRow> = entries.into_iter().map(overview_row).collect();
+            table(&data, true);
+        }
+
+        if !failures.is_empty() {
+            blank();
+            for (name, e) in failures {
+                error(&format!("Skipped project '{name}': {e}"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Load the tasks of one registered project; the main checkout is not a task.
+fn load_project_tasks(registration: &ProjectRegistration) -> CommandResult<Vec<TaskEntry>> {
+    let repo = registration.repo_path.as_path();
+    if !repo.join(".git").exists() {
+        return Err(CommandError::new(&format!(
+            "Repository not found at {}",
+            repo.display()
+        )));
+    }
+    let config = Config::from_str(&read_config_file(repo)?)?;
+
+    worktree_list_in(repo)?
+        .into_iter()
+        .filter(|wt| !same_path(Path::new(&wt.path), repo))
+        .map(|wt| {
+            let name = wt.branch.clone().unwrap_or_else(|| "N/A".to_string());
+            let session = get_session(name.as_str())?;
+            let lock = current_lock_in(repo, &name);
+            let record = load_task_in(repo, &name)?;
+            Ok(TaskEntry {
+                project: config.project_name.clone(),
+                worktree: wt,
+                status: entry_status(session.status, lock.as_ref(), &record),
+                record,
+                lock,
+            })
+        })
+        .collect()
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn overview_row(entry: TaskEntry) -> OverviewRow {
+    OverviewRow {
+        project: entry.project,
+        task: format_name(&entry.record),
+        status: format_entry_status(entry.status, entry.lock.as_ref()),
+        commit: entry.worktree.commit,
+        worktree: entry.worktree.path.as_str().color(THEME.muted).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_overview_command_creation() {
+        let cmd = OverviewCommand {
+            status: Some(Status::Waiting),
+            sort: None,
+            filter: None,
+        };
+        assert_eq!(cmd.status, Some(Status::Waiting));
+    }
+
+    #[test]
+    fn test_load_project_tasks_fails_for_missing_repository() {
+        let registration = ProjectRegistration {
+            repo_path: PathBuf::from("/nonexistent/claudectl-repo"),
+        };
+        let err = load_project_tasks(&registration).err().unwrap();
+        assert!(err.to_string().contains("Repository not found"));
+    }
+}
@@ -0,0 +1,293 @@
+use std::process::Command;
+
+use clap::{Args, ValueEnum, ValueHint};
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        describe::{description_title, task_description},
+        errors::CommandError,
+        events::{EventKind, record_event},
+        fs::read_local_config_file,
+        git::{local_branch, origin_url, push_branch},
+        output::{Position, blank, step, step_end, step_fail, success},
+        tasks::{load_task, resolve_task_name, task_worktree},
+    },
+};
+
+/// Where the repository is hosted, which decides the CLI used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Provider {
+    /// GitHub, through `gh`
+    Github,
+    /// GitLab, through `glab`
+    Gitlab,
+}
+
+impl Provider {
+    /// Recognize the provider from a remote URL, in SSH or HTTPS form, by
+    /// its host: `github.com`, `gitlab.com` and self-hosted names like
+    /// `gitlab.example.com`. The path is ignored, so a repository merely
+    /// named after a provider does not count.
+    pub fn from_url(url: &str) -> Option<Self> {
+        let host = url_host(url)?.to_lowercase();
+        let labels: Vec<&str> = host.split('.').collect();
+        if labels.contains(&"github") {
+            Some(Self::Github)
+        } else if labels.contains(&"gitlab") {
+            Some(Self::Gitlab)
+        } else {
+            None
+        }
+    }
+
+    fn program(&self) -> &'static str {
+        match self {
+            Self::Github => "gh",
+            Self::Gitlab => "glab",
+        }
+    }
+
+    /// Arguments that open a pull (or merge) request non-interactively.
+    fn create_args(&self, request: &PullRequest) -> Vec<String> {
+        let mut args: Vec<String> = match self {
+            Self::Github => vec![
+                "pr".into(),
+                "create".into(),
+                "--title".into(),
+                request.title.clone(),
+                "--body".into(),
+                request.body.clone(),
+                "--base".into(),
+                request.base.clone(),
+                "--head".into(),
+                request.head.clone(),
+            ],
+            Self::Gitlab => vec![
+                "mr".into(),
+                "create".into(),
+                "--title".into(),
+                request.title.clone(),
+                "--description".into(),
+                request.body.clone(),
+                "--target-branch".into(),
+                request.base.clone(),
+                "--source-branch".into(),
+                request.head.clone(),
+                "--yes".into(),
+            ],
+        };
+        if request.draft {
+            args.push("--draft".into());
+        }
+        args
+    }
+}
+
+/// The host of a remote URL: `scheme://[user@]host[:port]/path` or the SCP
+/// form `[user@]host:path`. Local paths have none.
+fn url_host(url: &str) -> Option<&str> {
+    let authority = match url.split_once("://") {
+        Some(("file", _)) => return None,
+        Some((_, rest)) => rest
+            .split('/')
+            .next()?
+            .rsplit('@')
+            .next()?
+            .split(':')
+            .next()?,
+        None => {
+            let (authority, _) = url.split_once(':')?;
+            if authority.contains('/') {
+                return None;
+            }
+            authority.rsplit('@').next()?
+        }
+    };
+    Some(authority).filter(|host| !host.is_empty())
+}
+
+struct PullRequest {
+    title: String,
+    body: String,
+    base: String,
+    head: String,
+    draft: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct PrCommand {
+    /// The task to open a pull request for (picked interactively if omitted)
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+
+    /// Hosting provider; detected from the origin URL if omitted
+    #[arg(long, value_enum)]
+    pub provider: Option<Provider>,
+
+    /// Open the pull request as a draft
+    #[arg(long)]
+    pub draft: bool,
+
+    /// Title of the pull request; defaults to the task's title
+    #[arg(long)]
+    pub title: Option<String>,
+}
+
+impl PrCommand {
+    #[instrument(name = "pr_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+        info!("Executing pr command for: {}", task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+
+        let worktree = task_worktree(&task_name)?;
+        let record = load_task(&task_name)?;
+        let provider = match self.provider {
+            Some(provider) => provider,
+            None => {
+                let url = origin_url()?;
+                Provider::from_url(&url).ok_or_else(|| {
+                    CommandError::new(&format!(
+                        "Could not tell where origin ({url}) is hosted; pass --provider"
+                    ))
+                })?
+            }
+        };
+        let base = record.base();
+        let request = PullRequest {
+            title: self
+                .title
+                .clone()
+                .unwrap_or_else(|| description_title(&record)),
+            body: task_description(&config, &record, &worktree.path, base)?,
+            base: local_branch(base).to_string(),
+            head: task_name.clone(),
+            draft: self.draft,
+        };
+
+        // 1. Publish the branch
+        step(
+            &format!("Pushing '{task_name}' to origin..."),
+            Position::First,
+        );
+        push_branch(&worktree.path, &task_name).inspect_err(|e| {
+            error!("Failed to push task: {}", e);
+            step_fail();
+        })?;
+        step_end();
+        blank();
+
+        // 2. Open the pull request
+        step("Opening pull request...", Position::Last);
+        let url = create_pull_request(provider, &request, &worktree.path).inspect_err(|e| {
+            error!("Failed to open pull request: {}", e);
+            step_fail();
+        })?;
+        step_end();
+        blank();
+
+        record_event(EventKind::PullRequestOpened, &task_name, Some(url.clone()));
+        success(&format!("Opened pull request: {url}"));
+        Ok(())
+    }
+}
+
+/// Run the provider's CLI and return what it printed, the request's URL.
+fn create_pull_request(
+    provider: Provider,
+    request: &PullRequest,
+    dir: &str,
+) -> CommandResult<String> {
+    let program = provider.program();
+    let output = Command::new(program)
+        .args(provider.create_args(request))
+        .current_dir(dir)
+        .output()
+        .map_err(|e| {
+            CommandError::new(&format!(
+                "Failed to run '{program}': {e}. Install it and log in to open pull requests"
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(CommandError::new(&format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+        .trim()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(draft: bool) -> PullRequest {
+        PullRequest {
+            title: "Fix login".to_string(),
+            body: "Body".to_string(),
+            base: "main".to_string(),
+            head: "fix/login".to_string(),
+            draft,
+        }
+    }
+
+    #[test]
+    fn test_provider_from_url() {
+        assert_eq!(
+            Provider::from_url("git@github.com:owner/repo.git"),
+            Some(Provider::Github)
+        );
+        assert_eq!(
+            Provider::from_url("https://gitlab.example.com/group/repo.git"),
+            Some(Provider::Gitlab)
+        );
+        assert_eq!(
+            Provider::from_url("ssh://git@github.com:22/owner/repo.git"),
+            Some(Provider::Github)
+        );
+        assert_eq!(Provider::from_url("/srv/git/repo.git"), None);
+        assert_eq!(Provider::from_url("file:///srv/github/repo.git"), None);
+        assert_eq!(
+            Provider::from_url("https://git.example.com/mirrors/github-tools.git"),
+            None
+        );
+        assert_eq!(
+            Provider::from_url("git@git.example.com:gitlab/repo.git"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_create_args() {
+        assert_eq!(
+            Provider::Github.create_args(&request(false)),
+            [
+                "pr",
+                "create",
+                "--title",
+                "Fix login",
+                "--body",
+                "Body",
+                "--base",
+                "main",
+                "--head",
+                "fix/login"
+            ]
+        );
+        let gitlab = Provider::Gitlab.create_args(&request(true));
+        assert_eq!(&gitlab[..2], ["mr", "create"]);
+        assert!(gitlab.contains(&"--target-branch".to_string()));
+        assert_eq!(gitlab.last().map(String::as_str), Some("--draft"));
+    }
+}
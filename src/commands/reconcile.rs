@@ -0,0 +1,216 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::{CommandResult, restart::resume_conversation, rm::RmCommand},
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, record_event},
+        fs::{read_local_config_file, remove_task_file},
+        git::find_worktree,
+        lock::{StaleLock, stale_locks},
+        output::{blank, error as output_error, is_interactive, pick, standard, success},
+        theme::THEME,
+        time::{TimestampStyle, format_timestamp},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct ReconcileCommand {
+    /// Mark every stale session stopped without asking
+    #[arg(long)]
+    pub stop: bool,
+}
+
+/// What to do with a session that ended without claudectl noticing.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Resume,
+    Stop,
+    Delete,
+    Later,
+}
+
+impl Action {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Resume => "Resume the conversation",
+            Self::Stop => "Mark it stopped",
+            Self::Delete => "Delete the task",
+            Self::Later => "Decide later",
+        }
+    }
+}
+
+impl ReconcileCommand {
+    #[instrument(name = "reconcile_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing reconcile command.");
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+
+        let stale = stale_locks();
+        if stale.is_empty() {
+            success("No stale sessions");
+            return Ok(());
+        }
+        report(&stale);
+
+        if self.stop {
+            for session in &stale {
+                stop(session)?;
+            }
+            blank();
+            success(&format!("Marked {} session(s) stopped", stale.len()));
+            return Ok(());
+        }
+        if !is_interactive() {
+            return Err(CommandError::new(
+                "Run `claudectl reconcile` in a terminal to choose what happens to each session, or pass --stop",
+            ));
+        }
+        for session in &stale {
+            reconcile(&config, session)?;
+        }
+        Ok(())
+    }
+}
+
+/// Offer to reconcile stale sessions before a command runs. Only done in a
+/// terminal, and problems are reported without stopping the command.
+pub fn reconcile_on_startup() {
+    if !is_interactive() {
+        return;
+    }
+    let Some(config) = read_local_config_file()
+        .ok()
+        .and_then(|raw| Config::from_str(&raw).ok())
+    else {
+        return;
+    };
+    let stale = stale_locks();
+    if stale.is_empty() {
+        return;
+    }
+    report(&stale);
+    for session in &stale {
+        if let Err(e) = reconcile(&config, session) {
+            error!(
+                "Failed to reconcile session in {}: {}",
+                session.task_name, e
+            );
+            output_error(&e.message());
+        }
+    }
+    blank();
+}
+
+fn report(stale: &[StaleLock]) {
+    standard(&format!(
+        "Found {} session(s) that ended without claudectl noticing:",
+        stale.len()
+    ));
+    for session in stale {
+        standard(&format!("  {}", describe(session)));
+    }
+    blank();
+}
+
+/// e.g. "feat/login: task --start (pid 4242), started 2 hours ago"
+fn describe(session: &StaleLock) -> String {
+    format!(
+        "{}: {}, started {}",
+        session.task_name.color(THEME.info),
+        session.lock.holder(),
+        format_timestamp(session.lock.started_at, TimestampStyle::Relative)
+    )
+}
+
+fn reconcile(config: &Config, session: &StaleLock) -> CommandResult<()> {
+    let has_worktree = find_worktree(&session.task_name)?.is_some();
+    let actions: Vec<Action> = [Action::Resume, Action::Stop, Action::Delete, Action::Later]
+        .into_iter()
+        .filter(|action| has_worktree || *action != Action::Resume)
+        .collect();
+    let labels: Vec<String> = actions
+        .iter()
+        .map(|action| action.label().to_string())
+        .collect();
+    let question = if has_worktree {
+        format!(
+            "What should happen to the session in '{}'?",
+            session.task_name
+        )
+    } else {
+        format!(
+            "The worktree of '{}' is gone. What should happen to its session?",
+            session.task_name
+        )
+    };
+    let choice = pick(&question, &labels)
+        .map_err(|e| CommandError::new(&format!("Failed to read input: {e}")))?;
+
+    match choice.map_or(Action::Later, |index| actions[index]) {
+        // The stale lock is taken over for the resumed session
+        Action::Resume => resume_conversation(
+            config,
+            &session.task_name,
+            "resume",
+            format!("resumed after {}", session.lock.holder()),
+        ),
+        Action::Stop => {
+            stop(session)?;
+            success(&format!(
+                "Marked the session in '{}' stopped",
+                session.task_name
+            ));
+            Ok(())
+        }
+        Action::Delete => delete(session),
+        Action::Later => Ok(()),
+    }
+}
+
+fn stop(session: &StaleLock) -> CommandResult<()> {
+    session.clear()?;
+    record_event(
+        EventKind::SessionStopped,
+        &session.task_name,
+        Some(session.lock.holder()),
+    );
+    Ok(())
+}
+
+fn delete(session: &StaleLock) -> CommandResult<()> {
+    let task_name = session.task_name.as_str();
+    if find_worktree(task_name)?.is_some() {
+        RmCommand {
+            task_names: vec![task_name.to_string()],
+            all_merged: false,
+            force: false,
+        }
+        .execute()?;
+        if find_worktree(task_name)?.is_some() {
+            // Removal was cancelled; the session is left to decide later
+            return Ok(());
+        }
+    } else {
+        remove_task_file(task_name)?;
+        record_event(EventKind::TaskRemoved, task_name, None);
+        success(&format!("Removed what was left of task '{task_name}'"));
+    }
+    session.clear()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconcile_command_creation() {
+        let cmd = ReconcileCommand { stop: true };
+        assert!(cmd.stop);
+    }
+}
@@ -0,0 +1,173 @@
+use clap::{Args, ValueHint};
+use tracing::{info, instrument, warn};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, record_event, rename_task_events},
+        fs::{read_local_config_file, read_task_file, remove_task_file},
+        git::{branch_exists, find_worktree, move_worktree, rename_branch, rename_checkpoints},
+        guard::retarget_guard_rails,
+        lock::current_lock,
+        output::success,
+        slug::slugify,
+        tasks::{TaskRecord, load_task, save_task, stored_tasks, task_worktree},
+        transaction::{Step, Transaction},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct RenameCommand {
+    /// The task to rename
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: String,
+
+    /// Its new name, used for both the branch and the worktree directory
+    pub new_name: String,
+}
+
+impl RenameCommand {
+    #[instrument(name = "rename_command", fields(task_name = %self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!(
+            "Executing rename command for: {} -> {}",
+            self.task_name, self.new_name
+        );
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let old_name = self.task_name.as_str();
+        let worktree = task_worktree(old_name)?;
+        let new_name = self.new_name()?;
+        if let Some(lock) = current_lock(old_name) {
+            return Err(CommandError::new(&format!(
+                "Task '{old_name}' is in use by {}; rename it once the session ends",
+                lock.holder()
+            )));
+        }
+
+        // The directory is only moved when it is named after the task, as
+        // claudectl names it
+        let new_path = worktree
+            .path
+            .strip_suffix(old_name)
+            .filter(|root| root.ends_with('/'))
+            .map(|root| format!("{root}{new_name}"));
+
+        // Everything from here on is undone if a later step fails
+        let mut transaction = Transaction::journaled()?;
+        transaction.record(Step::BranchRenamed {
+            from: old_name.to_string(),
+            to: new_name.clone(),
+        })?;
+        rename_branch(old_name, &new_name)?;
+        transaction.record(Step::CheckpointsRenamed {
+            from: old_name.to_string(),
+            to: new_name.clone(),
+        })?;
+        rename_checkpoints(old_name, &new_name)?;
+
+        if let Some(new_path) = &new_path {
+            transaction.record(Step::WorktreeMoved {
+                from: worktree.path.clone(),
+                to: new_path.clone(),
+            })?;
+            move_worktree(&worktree.path, new_path)?;
+        }
+
+        let previous = read_task_file(old_name)?;
+        if previous.is_some() {
+            let mut record = load_task(old_name)?;
+            record.name = new_name.clone();
+            record.display_name = None;
+            save_record(&mut transaction, &record)?;
+            transaction.record(Step::TaskFileWritten {
+                task_name: old_name.to_string(),
+                previous,
+            })?;
+            remove_task_file(old_name)?;
+        }
+
+        // Clones keep pointing at the task they came from
+        for mut record in stored_tasks()? {
+            if record.cloned_from.as_deref() == Some(old_name) {
+                record.cloned_from = Some(new_name.clone());
+                save_record(&mut transaction, &record)?;
+            }
+        }
+        transaction.commit();
+
+        let path = new_path.as_ref().unwrap_or(&worktree.path);
+        if let Err(e) = retarget_guard_rails(path, &new_name) {
+            warn!("Failed to point guard rails at {}: {}", new_name, e);
+        }
+
+        // The history follows the task, but losing it is not worth failing
+        // a rename that already happened
+        if let Err(e) = rename_task_events(old_name, &new_name) {
+            warn!("Failed to rename task in event log: {}", e);
+        }
+        record_event(
+            EventKind::TaskRenamed,
+            &new_name,
+            Some(format!("renamed from {old_name}")),
+        );
+
+        info!("Renamed task {} to {}", old_name, new_name);
+        success(&format!(
+            "Renamed '{old_name}' to '{new_name}'{}",
+            new_path
+                .map(|path| format!(", now at {path}"))
+                .unwrap_or_default()
+        ));
+        Ok(())
+    }
+
+    /// The new name as a branch name. Names already taken are rejected.
+    fn new_name(&self) -> CommandResult<String> {
+        let slug = slugify(&self.new_name);
+        if slug.is_empty() {
+            return Err(CommandError::new(&format!(
+                "Task name '{}' has no characters usable in a branch name",
+                self.new_name
+            )));
+        }
+        if slug == self.task_name {
+            return Err(CommandError::new(&format!(
+                "Task '{}' already has that name",
+                self.task_name
+            )));
+        }
+        if branch_exists(&slug)? || find_worktree(&slug)?.is_some() {
+            return Err(CommandError::new(&format!("Task '{slug}' already exists")));
+        }
+        Ok(slug)
+    }
+}
+
+/// Save a task record as a step of `transaction`.
+fn save_record(transaction: &mut Transaction, record: &TaskRecord) -> CommandResult<()> {
+    transaction.record(Step::TaskFileWritten {
+        task_name: record.name.clone(),
+        previous: read_task_file(&record.name)?,
+    })?;
+    save_task(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_command_creation() {
+        let cmd = RenameCommand {
+            task_name: "feat/login".to_string(),
+            new_name: "feat/sign-in".to_string(),
+        };
+        assert_eq!(cmd.task_name, "feat/login");
+        assert_eq!(cmd.new_name, "feat/sign-in");
+    }
+}
@@ -0,0 +1,264 @@
+use std::path::Path;
+
+use chrono::Utc;
+use clap::{Args, ValueHint};
+use tabled::Tabled;
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        claude::approve_permissions,
+        config::Config,
+        context::prepend_context,
+        errors::CommandError,
+        events::{EventKind, record_event, record_timed_event},
+        fs::read_local_config_file,
+        git::conflicted_files,
+        hooks::{Hook, HookContext, run_hook, run_session_end_hooks},
+        lock::TaskLock,
+        output::{blank, standard, success, table},
+        queue::queue_session,
+        tasks::{SessionRecord, load_task, resolve_task_name, save_task, task_worktree},
+        terminal::{session_finished, session_started},
+    },
+};
+
+/// Conflict hunks beyond this are left for claude to find itself, keeping
+/// the prompt within the command-line argument limit.
+const MAX_HUNK_CHARS: usize = 60_000;
+
+const RESOLVE_PROMPT: &str = "A rebase or merge in this worktree stopped on conflicts. \
+Resolve each conflict below by editing the file so the intent of both sides is kept, remove \
+the conflict markers, and stage the file with `git add`. Do not commit, and do not continue \
+or abort the rebase or merge: the resolution will be reviewed first.";
+
+const CONFLICT_START: &str = "<<<<<<<";
+const CONFLICT_END: &str = ">>>>>>>";
+
+#[derive(Tabled)]
+struct ResolutionRow {
+    file: String,
+    status: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ResolveCommand {
+    /// The task whose conflicts to resolve (picked interactively if omitted)
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+
+    /// Start the session even if another session holds the task
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl ResolveCommand {
+    #[instrument(name = "resolve_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+        info!("Executing resolve command for: {}", task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+
+        let worktree = task_worktree(&task_name)?;
+        let conflicts = conflicted_files(&worktree.path)?;
+        if conflicts.is_empty() {
+            standard(&format!("Task '{task_name}' has no conflicts to resolve"));
+            return Ok(());
+        }
+        info!("Found {} conflicted file(s)", conflicts.len());
+
+        let queued = queue_session(config.max_sessions, &task_name, "resolve")?;
+        let lock = TaskLock::acquire(&task_name, "resolve", self.force)?;
+        drop(queued);
+        if !approve_permissions(&config.permissions, "The resolving claude session")? {
+            return Err(CommandError::new(
+                "Elevated permission mode was not approved",
+            ));
+        }
+        let mut hook_context = HookContext {
+            task_name: &task_name,
+            worktree: &worktree.path,
+            session_id: lock.session_id(),
+            ..HookContext::default()
+        };
+        run_hook(Hook::PreSession, &hook_context)?;
+
+        // 1. Hand the conflicts to claude
+        let prompt_text = resolve_prompt(&worktree.path, &conflicts);
+        let prompt = config
+            .initial_prompt(Some(&prompt_text))
+            .map(|prompt| prepend_context(&config.context, &worktree.path, &prompt));
+        let mut record = load_task(&task_name)?;
+        let session_profile = config.session_profile(record.profile.as_deref())?;
+        let invocation = config.invocation(session_profile.as_ref().map(|(_, profile)| profile));
+        record.add_prompt(&prompt_text, "resolve");
+        save_task(&record)?;
+        standard(&format!(
+            "Starting claude session to resolve {} conflicted file(s)...",
+            conflicts.len()
+        ));
+        record_event(
+            EventKind::SessionStarted,
+            &task_name,
+            Some(format!("resolving {} conflicted file(s)", conflicts.len())),
+        );
+        let task_dir = config.task_dir(&worktree.path);
+        let agent = config.agent.adapter();
+        let started_at = Utc::now();
+        session_started(&task_name);
+        let session = agent.spawn(
+            &task_dir,
+            &config.permissions,
+            prompt.as_deref(),
+            &invocation,
+        );
+        let mut ended = SessionRecord::ended(started_at, &session);
+        ended.stopped = lock.stop_requested();
+        session_finished(&task_name, !ended.crashed());
+        let mut record = load_task(&task_name)?;
+        record.last_session = Some(ended.clone());
+        save_task(&record)?;
+        hook_context.status = Some(ended.outcome());
+        hook_context.exit_code = ended.exit_code;
+        run_session_end_hooks(&hook_context)?;
+        session.inspect_err(|e| {
+            error!("Failed to start claude session: {}", e);
+        })?;
+        let detail = ended.crashed().then(|| ended.status());
+        record_timed_event(EventKind::SessionEnded, &task_name, detail, started_at);
+        if ended.crashed() {
+            return Err(ended.failure(&task_name));
+        }
+
+        // 2. Report what is left for review
+        let remaining = conflicted_files(&worktree.path)?;
+        let rows: Vec<ResolutionRow> = conflicts
+            .iter()
+            .map(|file| ResolutionRow {
+                file: file.clone(),
+                status: resolution_status(&worktree.path, file, &remaining).to_string(),
+            })
+            .collect();
+        let resolved = rows.iter().filter(|row| row.status != "conflicted").count();
+        record_event(
+            EventKind::ConflictsResolved,
+            &task_name,
+            Some(format!("{resolved} of {} file(s)", conflicts.len())),
+        );
+
+        blank();
+        table(&rows, true);
+        blank();
+        if resolved == conflicts.len() {
+            success(&format!(
+                "Resolved all {resolved} conflicted file(s). Review them with `git diff --cached` before continuing"
+            ));
+        } else {
+            standard(&format!(
+                "Resolved {resolved} of {} conflicted file(s)",
+                conflicts.len()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// The session's first message: instructions followed by every conflict hunk.
+fn resolve_prompt(worktree_path: &str, files: &[String]) -> String {
+    let mut prompt = RESOLVE_PROMPT.to_string();
+    let mut budget = MAX_HUNK_CHARS;
+    for file in files {
+        let content = std::fs::read_to_string(Path::new(worktree_path).join(file));
+        let hunks = content.map(|c| conflict_hunks(&c)).unwrap_or_default();
+        prompt.push_str(&format!("\n\n## {file}\n"));
+        if hunks.is_empty() {
+            prompt.push_str("\nNo conflict markers; the conflict is over the file itself.\n");
+            continue;
+        }
+        for hunk in hunks {
+            if hunk.len() > budget {
+                prompt.push_str("\nMore conflicts not shown; run `git diff` to see them.\n");
+                return prompt;
+            }
+            budget -= hunk.len();
+            prompt.push_str(&format!("\n```\n{hunk}\n```\n"));
+        }
+    }
+    prompt
+}
+
+/// The blocks between conflict markers in a file, markers included.
+fn conflict_hunks(content: &str) -> Vec<String> {
+    let mut hunks = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    for line in content.lines() {
+        if line.starts_with(CONFLICT_START) {
+            current = Some(vec![line]);
+        } else if let Some(lines) = current.as_mut() {
+            lines.push(line);
+            if line.starts_with(CONFLICT_END) {
+                hunks.push(lines.join("\n"));
+                current = None;
+            }
+        }
+    }
+    hunks
+}
+
+/// Whether a file that was conflicted is resolved: free of conflict markers,
+/// and staged unless git still lists it as unmerged.
+fn resolution_status(worktree_path: &str, file: &str, remaining: &[String]) -> &'static str {
+    let has_markers = std::fs::read_to_string(Path::new(worktree_path).join(file))
+        .map(|content| !conflict_hunks(&content).is_empty())
+        .unwrap_or(false);
+    if has_markers {
+        "conflicted"
+    } else if remaining.iter().any(|f| f == file) {
+        "resolved, not staged"
+    } else {
+        "resolved"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFLICTED: &str =
+        "fn main() {\n<<<<<<< HEAD\n    run();\n=======\n    run_fast();\n>>>>>>> feat/speed\n}\n";
+
+    #[test]
+    fn test_resolve_command_creation() {
+        let cmd = ResolveCommand {
+            task_name: Some("feat/merge".to_string()),
+            force: false,
+        };
+        assert_eq!(cmd.task_name.as_deref(), Some("feat/merge"));
+    }
+
+    #[test]
+    fn test_conflict_hunks() {
+        let hunks = conflict_hunks(CONFLICTED);
+        assert_eq!(
+            hunks,
+            vec!["<<<<<<< HEAD\n    run();\n=======\n    run_fast();\n>>>>>>> feat/speed"]
+        );
+        assert!(conflict_hunks("fn main() {}\n").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_prompt_includes_hunks() {
+        let dir = std::env::temp_dir().join(format!("claudectl-resolve-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.rs"), CONFLICTED).unwrap();
+
+        let prompt = resolve_prompt(&dir.to_string_lossy(), &["main.rs".to_string()]);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(prompt.starts_with(RESOLVE_PROMPT));
+        assert!(prompt.contains("## main.rs"));
+        assert!(prompt.contains("run_fast();"));
+    }
+}
@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use clap::{Args, ValueHint};
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::{CommandResult, kill::stop_session},
+    utils::{
+        claude::approve_permissions,
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, record_event, record_timed_event},
+        fs::read_local_config_file,
+        hooks::{Hook, HookContext, run_hook, run_session_end_hooks},
+        lock::{TaskLock, current_lock},
+        output::{blank, standard},
+        queue::queue_session,
+        tasks::{SessionRecord, load_task, resolve_task_name, save_task, task_worktree},
+        terminal::{session_finished, session_started},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct RestartCommand {
+    /// The task whose session to restart (picked interactively if omitted)
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+
+    /// Seconds to wait after SIGTERM before sending SIGKILL (default: the
+    /// project's `stop_timeout`)
+    #[arg(long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+}
+
+impl RestartCommand {
+    #[instrument(name = "restart_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+        info!("Executing restart command for: {}", task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+
+        task_worktree(&task_name)?;
+        let detail = match current_lock(&task_name) {
+            Some(lock) => {
+                let timeout = self.timeout.unwrap_or(config.stop_timeout);
+                stop_session(&task_name, &lock, Duration::from_secs(timeout))?;
+                blank();
+                format!("restarted after {}", lock.holder())
+            }
+            None => {
+                standard(&format!(
+                    "No session was running in '{task_name}'; starting one"
+                ));
+                "restarted".to_string()
+            }
+        };
+        resume_conversation(&config, &task_name, "restart", detail)
+    }
+}
+
+/// Reopen the task's most recent conversation in a new session, started by
+/// `command` and logged with `detail`.
+pub fn resume_conversation(
+    config: &Config,
+    task_name: &str,
+    command: &str,
+    detail: String,
+) -> CommandResult<()> {
+    let worktree = task_worktree(task_name)?;
+    if !approve_permissions(&config.permissions, "The resumed claude session")? {
+        return Err(CommandError::new(
+            "Elevated permission mode was not approved",
+        ));
+    }
+    let record = load_task(task_name)?;
+    let session_profile = config.session_profile(record.profile.as_deref())?;
+    let invocation = config.invocation(session_profile.as_ref().map(|(_, profile)| profile));
+
+    let queued = queue_session(config.max_sessions, task_name, command)?;
+    let lock = TaskLock::acquire(task_name, command, false)?;
+    drop(queued);
+    let mut hook_context = HookContext {
+        task_name,
+        worktree: &worktree.path,
+        session_id: lock.session_id(),
+        ..HookContext::default()
+    };
+    run_hook(Hook::PreSession, &hook_context)?;
+    record_event(EventKind::SessionStarted, task_name, Some(detail));
+    standard(&format!("Resuming the conversation in '{task_name}'..."));
+    let agent = config.agent.adapter();
+    let started_at = Utc::now();
+    session_started(task_name);
+    let session = agent.resume(
+        &config.task_dir(&worktree.path),
+        &config.permissions,
+        &invocation,
+    );
+    let mut ended = SessionRecord::ended(started_at, &session);
+    ended.stopped = lock.stop_requested();
+    session_finished(task_name, !ended.crashed());
+    let mut record = load_task(task_name)?;
+    record.last_session = Some(ended.clone());
+    save_task(&record)?;
+    hook_context.status = Some(ended.outcome());
+    hook_context.exit_code = ended.exit_code;
+    run_session_end_hooks(&hook_context)?;
+    session.inspect_err(|e| {
+        error!("Failed to resume claude session: {}", e);
+    })?;
+    let detail = ended.crashed().then(|| ended.status());
+    record_timed_event(EventKind::SessionEnded, task_name, detail, started_at);
+    if ended.crashed() {
+        return Err(ended.failure(task_name));
+    }
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use std::path::Path;
+
+use clap::Args;
+use tracing::{error, info, instrument, warn};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        archive::{Archive, extract_bundle, find_archive},
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, record_event},
+        fs::read_local_config_file,
+        git::{
+            branch_exists, create_worktree, find_worktree, restore_snapshot, unbundle_task,
+            worktree_exists,
+        },
+        output::{Position, blank, step, step_end, step_fail, success},
+        tasks::save_task,
+        transaction::{Step, Transaction},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct RestoreCommand {
+    /// The archive to restore: its name, its path, or the task it holds
+    pub archive: String,
+}
+
+impl RestoreCommand {
+    #[instrument(name = "restore_command", fields(archive = %self.archive))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing restore command for: {}", self.archive);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let archive = find_archive(&config.project_name, &self.archive)?;
+        let task_name = archive.manifest.task.clone();
+        if branch_exists(&task_name)? || find_worktree(&task_name)?.is_some() {
+            return Err(CommandError::new(&format!(
+                "Task '{task_name}' already exists; rename or remove it before restoring"
+            )));
+        }
+        let session_profile = config.session_profile(archive.record.profile.as_deref())?;
+        let worktree_dir =
+            config.worktree_dir(session_profile.as_ref().map(|(_, profile)| profile));
+        let worktree_path = format!("{worktree_dir}/{task_name}");
+        if worktree_exists(&worktree_path)? {
+            return Err(CommandError::new(&format!(
+                "Worktree already exists at path: {worktree_path}"
+            )));
+        }
+
+        let bundle =
+            std::env::temp_dir().join(format!("claudectl-restore-{}.bundle", std::process::id()));
+        let restored = restore(&config, &archive, &bundle, &worktree_path);
+        if let Err(e) = std::fs::remove_file(&bundle) {
+            warn!("Failed to remove {}: {}", bundle.display(), e);
+        }
+        restored?;
+
+        // The task is live again, so its archive is no longer needed
+        if let Err(e) = std::fs::remove_file(&archive.path) {
+            warn!("Failed to remove {}: {}", archive.path.display(), e);
+        }
+        record_event(EventKind::TaskRestored, &task_name, Some(archive.name));
+        success(&format!("Restored '{task_name}' at {worktree_path}"));
+        Ok(())
+    }
+}
+
+/// Recreate the task's branch, checkpoints, worktree and record from the
+/// archive, with its uncommitted work in place.
+fn restore(
+    config: &Config,
+    archive: &Archive,
+    bundle: &Path,
+    worktree_path: &str,
+) -> CommandResult<()> {
+    let task_name = archive.manifest.task.as_str();
+
+    // 1. Fetch the branch and checkpoints
+    step("Restoring branch...", Position::First);
+    let snapshot = extract_bundle(&archive.path, bundle)
+        .and_then(|_| Ok(unbundle_task(bundle, task_name)?))
+        .inspect_err(|e| {
+            error!("Failed to restore branch: {}", e);
+            step_fail();
+        })?;
+    step_end();
+    blank();
+
+    // 2. Recreate the worktree; undone if anything fails
+    step("Recreating worktree...", Position::Last);
+    let mut transaction = Transaction::journaled()?;
+    transaction.record(Step::BranchCreated(task_name.to_string()))?;
+    transaction.record(Step::WorktreeAdded(worktree_path.to_string()))?;
+    create_worktree(
+        task_name,
+        worktree_path,
+        &format!("{snapshot}^"),
+        config.subproject.as_deref(),
+        &mut |_| {},
+    )
+    .and_then(|_| restore_snapshot(worktree_path, &snapshot))
+    .inspect_err(|e| {
+        error!("Failed to recreate worktree: {}", e);
+        step_fail();
+    })?;
+    transaction.record(Step::TaskFileWritten {
+        task_name: task_name.to_string(),
+        previous: None,
+    })?;
+    save_task(&archive.record)?;
+    transaction.commit();
+    step_end();
+    blank();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_command_creation() {
+        let cmd = RestoreCommand {
+            archive: "feat__login-20240901-160300".to_string(),
+        };
+        assert_eq!(cmd.archive, "feat__login-20240901-160300");
+    }
+}
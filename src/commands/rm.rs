@@ -1,7 +1,8 @@
-use std::io::{self, Write};
+use std::path::Path;
 
 use clap::{Args, ValueHint};
 use owo_colors::OwoColorize;
+use tabled::Tabled;
 use tracing::{error, info, instrument, warn};
 
 use crate::{
@@ -9,10 +10,15 @@ use crate::{
     utils::{
         config::Config,
         errors::CommandError,
-        fs::read_local_config_file,
-        git::{remove_worktree, worktree_list},
-        icons::ICONS,
-        output::{error as output_error, success},
+        events::{EventKind, record_event},
+        fs::{read_local_config_file, remove_task_file},
+        git::{delete_checkpoints, remove_worktree, worktree_list},
+        lock::current_lock,
+        output::{blank, confirm, error as output_error, success, table},
+        tasks::{
+            is_task_merged, load_task, project_worktrees, resolve_task_name, target_branch,
+            task_worktree,
+        },
         theme::THEME,
     },
 };
@@ -23,80 +29,198 @@ fn task_name_parser(s: &str) -> Result<String, String> {
     Ok(s.to_string())
 }
 
+#[derive(Tabled)]
+struct RemovalRow {
+    task: String,
+    branch: String,
+}
+
+/// A task to remove, and whether its work has landed in its target branch.
+struct Removal {
+    task: String,
+    worktree: String,
+    target: String,
+    merged: bool,
+}
+
 #[derive(Args, Debug)]
 pub struct RmCommand {
-    /// The name of the task/branch to remove
+    /// The tasks/branches to remove; prompts for one if none are given
     #[arg(
         value_parser = task_name_parser,
         value_hint = ValueHint::Other,
-        help = "The name of the task to remove"
+        value_name = "TASK_NAME",
+        help = "The names of the tasks to remove (prompts for one if omitted)"
     )]
-    pub task_name: String,
+    pub task_names: Vec<String>,
+
+    /// Remove every task whose branch is merged into its base
+    #[arg(long, conflicts_with = "task_names")]
+    pub all_merged: bool,
+
+    /// Remove without asking for confirmation, even a task a session is
+    /// running in
+    #[arg(long, short)]
+    pub force: bool,
 }
 
 impl RmCommand {
-    #[instrument(name = "rm_command", fields(task_name = %self.task_name))]
+    #[instrument(name = "rm_command", fields(task_names = ?self.task_names))]
     pub fn execute(&self) -> CommandResult<()> {
-        info!("Executing rm command for: {}", self.task_name);
+        info!("Executing rm command for: {:?}", self.task_names);
 
         let raw_config = read_local_config_file()?;
         let config = Config::from_str(&raw_config)?;
         info!("Loaded configuration for project: {}", config.project_name);
 
-        // 1. Get all worktrees to find the one to remove
-        let worktrees = worktree_list().inspect_err(|e| {
-            output_error(&format!("Failed to get tasks: {e}"));
-        })?;
+        // 1. Find the worktrees of the tasks to remove
+        let removals = if self.all_merged {
+            merged_tasks()?
+        } else {
+            let task_names = match self.task_names.as_slice() {
+                [] => vec![resolve_task_name(None)?],
+                names => names.to_vec(),
+            };
+            task_names
+                .iter()
+                .map(|task_name| removal(task_name, self.force))
+                .collect::<CommandResult<Vec<_>>>()?
+        };
+        if removals.is_empty() {
+            success("No merged tasks to remove");
+            return Ok(());
+        }
 
-        // 2. Find the worktree that matches the task name
-        let target_worktree = worktrees
-            .into_iter()
-            .find(|wt| wt.branch.as_ref() == Some(&self.task_name))
-            .ok_or_else(|| CommandError::new(&format!("Task '{}' not found", self.task_name)))?;
-
-        let worktree_path = &target_worktree.path;
-        info!(
-            "Found worktree for task '{}' at: {}",
-            self.task_name, worktree_path
-        );
-
-        // 3. Confirmation prompt
-        print!(
-            "{} Are you sure you want to remove task '{}' and its worktree? (y/N): ",
-            ICONS.status.warning.color(THEME.warning),
-            self.task_name.color(THEME.info)
-        );
-        io::stdout()
-            .flush()
-            .map_err(|e| CommandError::new(&format!("Failed to flush stdout: {e}")))?;
-
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .map_err(|e| CommandError::new(&format!("Failed to read input: {e}")))?;
-
-        let input = input.trim().to_lowercase();
-        if input != "y" && input != "yes" {
+        // 2. Confirmation prompt, warning about work that would be lost
+        if !self.force && !confirm_removal(&removals)? {
             info!("Task removal cancelled by user");
             success("Task removal cancelled");
             return Ok(());
         }
 
-        // 4. Remove the worktree
-        info!("Removing worktree at: {}", worktree_path);
-        remove_worktree(worktree_path).inspect_err(|e| {
-            error!("Failed to remove worktree: {}", e);
-            output_error(&format!("Failed to remove worktree: {e}"));
-        })?;
+        // 3. Remove the worktrees, carrying on past failures
+        let mut failed = 0;
+        for removal in &removals {
+            match remove(removal) {
+                Ok(()) => success(&format!(
+                    "Successfully removed task '{}' and its worktree",
+                    removal.task
+                )),
+                Err(e) => {
+                    error!("Failed to remove {}: {}", removal.task, e);
+                    output_error(&format!("Failed to remove '{}': {e}", removal.task));
+                    failed += 1;
+                }
+            }
+        }
+        if failed > 0 {
+            return Err(CommandError::new(&format!(
+                "{failed} of {} task(s) could not be removed",
+                removals.len()
+            )));
+        }
+        Ok(())
+    }
+}
 
-        info!("Successfully removed task: {}", self.task_name);
-        success(&format!(
-            "Successfully removed task '{}' and its worktree",
-            self.task_name
-        ));
+/// The task to remove. One held by a running session is refused unless
+/// `force` is set.
+fn removal(task_name: &str, force: bool) -> CommandResult<Removal> {
+    let worktree = task_worktree(task_name)?;
+    if let Some(lock) = current_lock(task_name).filter(|_| !force) {
+        return Err(CommandError::new(&format!(
+            "Task '{task_name}' is in use by {}; stop it with `claudectl kill {task_name}` first, or pass --force",
+            lock.holder()
+        )));
+    }
+    info!(
+        "Found worktree for task '{}' at: {}",
+        task_name, worktree.path
+    );
+    let record = load_task(task_name)?;
+    Ok(Removal {
+        task: task_name.to_string(),
+        merged: is_task_merged(&record, &worktree.path)?,
+        target: target_branch(&record),
+        worktree: worktree.path,
+    })
+}
 
-        Ok(())
+/// Tasks whose work has all landed in their target branch. Tasks with a
+/// running session are left alone.
+fn merged_tasks() -> CommandResult<Vec<Removal>> {
+    let mut removals = Vec::new();
+    for worktree in project_worktrees()? {
+        let Some(task) = worktree.branch else {
+            continue;
+        };
+        if !Path::new(&worktree.path).exists() || current_lock(&task).is_some() {
+            continue;
+        }
+        let record = load_task(&task)?;
+        if is_task_merged(&record, &worktree.path)? {
+            removals.push(Removal {
+                target: target_branch(&record),
+                task,
+                worktree: worktree.path,
+                merged: true,
+            });
+        }
     }
+    Ok(removals)
+}
+
+fn confirm_removal(removals: &[Removal]) -> CommandResult<bool> {
+    let unmerged = removals.iter().filter(|removal| !removal.merged).count();
+    let message = match removals {
+        [removal] if removal.merged => format!(
+            "Are you sure you want to remove task '{}' and its worktree?",
+            removal.task.color(THEME.info)
+        ),
+        [removal] => format!(
+            "Task '{}' has work not merged into {}. Are you sure you want to remove it and its worktree?",
+            removal.task.color(THEME.info),
+            removal.target
+        ),
+        _ => {
+            let rows: Vec<RemovalRow> = removals
+                .iter()
+                .map(|removal| RemovalRow {
+                    task: removal.task.color(THEME.info).to_string(),
+                    branch: if removal.merged {
+                        format!("merged into {}", removal.target)
+                    } else {
+                        format!("not merged into {}", removal.target)
+                            .color(THEME.warning)
+                            .to_string()
+                    },
+                })
+                .collect();
+            table(&rows, true);
+            blank();
+            if unmerged > 0 {
+                format!(
+                    "{unmerged} of these task(s) have unmerged work. Remove all {} task(s) and their worktrees?",
+                    removals.len()
+                )
+            } else {
+                format!("Remove {} task(s) and their worktrees?", removals.len())
+            }
+        }
+    };
+    confirm(&message).map_err(|e| CommandError::new(&format!("Failed to read input: {e}")))
+}
+
+fn remove(removal: &Removal) -> CommandResult<()> {
+    info!("Removing worktree at: {}", removal.worktree);
+    remove_worktree(&removal.worktree)?;
+    // The branch is kept, but a later task of the same name shouldn't start
+    // out with these
+    delete_checkpoints(&removal.task)?;
+    remove_task_file(&removal.task)?;
+    record_event(EventKind::TaskRemoved, &removal.task, None);
+    info!("Successfully removed task: {}", removal.task);
+    Ok(())
 }
 
 #[allow(dead_code)]
@@ -133,8 +257,10 @@ mod tests {
     #[test]
     fn test_rm_command_creation() {
         let cmd = RmCommand {
-            task_name: "test-task".to_string(),
+            task_names: vec!["test-task".to_string()],
+            all_merged: false,
+            force: false,
         };
-        assert_eq!(cmd.task_name, "test-task");
+        assert_eq!(cmd.task_names, ["test-task"]);
     }
 }
@@ -0,0 +1,133 @@
+use clap::{Args, ValueHint};
+use owo_colors::OwoColorize;
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, record_event},
+        fs::read_local_config_file,
+        git::{
+            branch_point, create_checkpoint, list_checkpoints, reset_worktree, restore_checkpoint,
+        },
+        output::{confirm, standard, success},
+        tasks::{load_task, task_worktree},
+        theme::THEME,
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct RollbackCommand {
+    /// The name of the task to roll back
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: String,
+
+    /// Checkpoint id to restore (defaults to the task's branch point)
+    #[arg(long, value_name = "CHECKPOINT")]
+    pub to: Option<u32>,
+}
+
+impl RollbackCommand {
+    #[instrument(name = "rollback_command", fields(task_name = %self.task_name, to = ?self.to))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing rollback command for: {}", self.task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let worktree = task_worktree(&self.task_name)?;
+        let record = load_task(&self.task_name)?;
+
+        // 1. Resolve the rollback target before touching anything
+        let checkpoint = match self.to {
+            Some(id) => Some(
+                list_checkpoints(&worktree.path, &self.task_name)?
+                    .into_iter()
+                    .find(|c| c.id == id)
+                    .ok_or_else(|| {
+                        CommandError::new(&format!(
+                            "Checkpoint {id} not found for task '{}'",
+                            self.task_name
+                        ))
+                    })?,
+            ),
+            None => None,
+        };
+        let target = match &checkpoint {
+            Some(c) => format!("checkpoint {}", c.id),
+            None => "its branch point".to_string(),
+        };
+
+        // 2. Confirmation prompt
+        let confirmed = confirm(&format!(
+            "Roll back task '{}' to {target}?",
+            self.task_name.color(THEME.info)
+        ))
+        .map_err(|e| CommandError::new(&format!("Failed to read input: {e}")))?;
+        if !confirmed {
+            info!("Rollback cancelled by user");
+            success("Rollback cancelled");
+            return Ok(());
+        }
+
+        // 3. Save the current state, including commits the reset would drop,
+        //    so the rollback itself can be undone
+        if let Some(saved) = create_checkpoint(
+            &worktree.path,
+            &self.task_name,
+            &format!("Before rollback to {target}"),
+            true,
+        )? {
+            standard(&format!(
+                "Saved current state as checkpoint {} ({})",
+                saved.id,
+                saved.commit.as_str().color(THEME.muted)
+            ));
+        }
+
+        // 4. Reset the worktree
+        match &checkpoint {
+            Some(c) => restore_checkpoint(&worktree.path, c),
+            None => branch_point(&worktree.path, record.base())
+                .and_then(|commit| reset_worktree(&worktree.path, &commit)),
+        }
+        .inspect_err(|e| {
+            error!("Failed to roll back worktree: {}", e);
+        })?;
+
+        record_event(EventKind::RolledBack, &self.task_name, Some(target.clone()));
+        info!("Rolled back task '{}' to {}", self.task_name, target);
+        success(&format!(
+            "Rolled back task '{}' to {target}",
+            self.task_name
+        ));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback_command_creation() {
+        let cmd = RollbackCommand {
+            task_name: "feat/test".to_string(),
+            to: Some(2),
+        };
+        assert_eq!(cmd.task_name, "feat/test");
+        assert_eq!(cmd.to, Some(2));
+    }
+
+    #[test]
+    fn test_rollback_command_defaults_to_branch_point() {
+        let cmd = RollbackCommand {
+            task_name: "feat/test".to_string(),
+            to: None,
+        };
+        assert!(cmd.to.is_none());
+    }
+}
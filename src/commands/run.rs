@@ -0,0 +1,208 @@
+use chrono::Utc;
+use clap::{Args, ValueHint};
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::{CommandResult, task::TaskCommand},
+    utils::{
+        claude::{approve_permissions, parse_env_var},
+        config::Config,
+        context::prepend_context,
+        errors::CommandError,
+        events::{EventKind, record_event, record_timed_event},
+        fs::read_local_config_file,
+        git::find_worktree,
+        hooks::{Hook, HookContext, run_hook, run_session_end_hooks},
+        lock::TaskLock,
+        output::{blank, standard, success},
+        queue::queue_session,
+        redact::Redactor,
+        slug::slugify,
+        tasks::{RUN_OUTPUT_LIMIT, RunRecord, load_task, output_tail, save_task, task_worktree},
+        terminal::{session_finished, session_started},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct RunCommand {
+    /// The task to run in; it is created first if it does not exist
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: String,
+
+    /// Set an environment variable on claude, over the project's and the
+    /// profile's; repeatable
+    #[arg(long = "env", value_name = "KEY=VALUE", value_parser = parse_env_var)]
+    pub env: Vec<(String, String)>,
+
+    /// Pass an extra argument to claude, e.g. `--claude-arg=--model
+    /// --claude-arg=opus`; repeatable
+    #[arg(long = "claude-arg", value_name = "ARG", allow_hyphen_values = true)]
+    pub claude_args: Vec<String>,
+
+    /// The prompt for claude, given after `--`
+    #[arg(last = true, required = true, value_name = "PROMPT")]
+    pub prompt: Vec<String>,
+}
+
+impl RunCommand {
+    pub fn execute(&self) -> CommandResult<()> {
+        let (task_name, run) = self.run()?;
+        blank();
+        if !run.succeeded() {
+            return Err(CommandError::new(&format!(
+                "claude {} in '{task_name}'",
+                run.status()
+            )));
+        }
+        success(&format!("claude finished in '{task_name}'"));
+        Ok(())
+    }
+
+    /// Run claude in the task, creating it first if needed, and return the
+    /// task's name and the run's record. A failing run is not an error here.
+    #[instrument(name = "run_command", fields(task_name = %self.task_name))]
+    pub fn run(&self) -> CommandResult<(String, RunRecord)> {
+        info!("Executing run command for: {}", self.task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let prompt_text = self.prompt.join(" ");
+        if prompt_text.trim().is_empty() {
+            return Err(CommandError::new("The prompt for claude is empty"));
+        }
+        if !approve_permissions(&config.permissions, "The task's claude run")? {
+            return Err(CommandError::new(
+                "Elevated permission mode was not approved",
+            ));
+        }
+
+        // 1. Find the task, creating it as `claudectl task` would
+        let task_name = match find_worktree(&self.task_name)? {
+            Some(_) => self.task_name.clone(),
+            None => {
+                self.create_task()?;
+                blank();
+                slugify(&self.task_name)
+            }
+        };
+        let worktree = task_worktree(&task_name)?;
+        let queued = queue_session(config.max_sessions, &task_name, "run")?;
+        let lock = TaskLock::acquire(&task_name, "run", false)?;
+        drop(queued);
+        let mut hook_context = HookContext {
+            task_name: &task_name,
+            worktree: &worktree.path,
+            session_id: lock.session_id(),
+            ..HookContext::default()
+        };
+        run_hook(Hook::PreSession, &hook_context)?;
+
+        // 2. Run claude without a terminal session
+        let mut record = load_task(&task_name)?;
+        let session_profile = config.session_profile(record.profile.as_deref())?;
+        let mut invocation =
+            config.invocation(session_profile.as_ref().map(|(_, profile)| profile));
+        invocation.env.extend(self.env.iter().cloned());
+        invocation.args.extend(self.claude_args.iter().cloned());
+        let prompt = config
+            .initial_prompt(Some(&prompt_text))
+            .map(|prompt| prepend_context(&config.context, &worktree.path, &prompt))
+            .unwrap_or_else(|| prompt_text.clone());
+        record.add_prompt(&prompt_text, "run");
+        save_task(&record)?;
+        record_event(
+            EventKind::SessionStarted,
+            &task_name,
+            Some(
+                session_profile
+                    .map(|(name, _)| format!("run, profile {name}"))
+                    .unwrap_or("run".to_string()),
+            ),
+        );
+        standard(&format!("Running claude in '{task_name}'..."));
+        blank();
+
+        let started_at = Utc::now();
+        let task_dir = config.task_dir(&worktree.path);
+        let agent = config.agent.adapter();
+        session_started(&task_name);
+        let run = agent.send(&task_dir, &config.permissions, &prompt, &invocation);
+        session_finished(
+            &task_name,
+            run.as_ref().is_ok_and(|run| run.exit_code == Some(0)),
+        );
+        if let Err(e) = &run {
+            error!("Failed to run claude: {}", e);
+            hook_context.status = Some("crashed");
+            run_session_end_hooks(&hook_context)?;
+        }
+        let run = run?;
+
+        // 3. Keep the result with the task
+        let output = Redactor::new(&config.redaction)?.redact(&run.output);
+        let kept = output_tail(&output, RUN_OUTPUT_LIMIT);
+        let run = RunRecord {
+            started_at,
+            finished_at: Utc::now(),
+            exit_code: run.exit_code,
+            cost_usd: agent.cost(&run.output),
+            output_truncated: kept.len() < output.len(),
+            output: kept.to_string(),
+        };
+        let mut record = load_task(&task_name)?;
+        record.runs.push(run.clone());
+        save_task(&record)?;
+        hook_context.status = Some(if lock.stop_requested() {
+            "stopped"
+        } else if run.succeeded() {
+            "succeeded"
+        } else {
+            "crashed"
+        });
+        hook_context.exit_code = run.exit_code;
+        run_session_end_hooks(&hook_context)?;
+        record_timed_event(
+            EventKind::RunFinished,
+            &task_name,
+            Some(run.status()),
+            run.started_at,
+        );
+        info!("Run in {} {}", task_name, run.status());
+        Ok((task_name, run))
+    }
+
+    fn create_task(&self) -> CommandResult<()> {
+        info!("Creating task for run: {}", self.task_name);
+        TaskCommand {
+            task_name: Some(self.task_name.clone()),
+            from_issue: None,
+            file: None,
+            base: None,
+            clone: None,
+            from_branch: false,
+            tags: Vec::new(),
+            kind: None,
+            start: false,
+            prompt: None,
+        }
+        .execute()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_command_creation() {
+        let cmd = RunCommand {
+            task_name: "feat/login".to_string(),
+            prompt: vec!["fix".to_string(), "the bug".to_string()],
+            env: Vec::new(),
+            claude_args: Vec::new(),
+        };
+        assert_eq!(cmd.task_name, "feat/login");
+        assert_eq!(cmd.prompt.join(" "), "fix the bug");
+    }
+}
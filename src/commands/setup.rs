@@ -0,0 +1,74 @@
+use clap::{Args, ValueHint};
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, record_event},
+        fs::read_local_config_file,
+        output::{standard, success},
+        setup::run_setup_commands,
+        tasks::{load_task, resolve_task_name, save_task, task_worktree},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct SetupCommand {
+    /// The task whose worktree to set up (picked interactively if omitted)
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+}
+
+impl SetupCommand {
+    #[instrument(name = "setup_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+        info!("Executing setup command for: {}", task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        let setup_commands = config.all_setup_commands()?;
+        if setup_commands.is_empty() {
+            standard("No setup commands configured");
+            return Ok(());
+        }
+
+        let worktree = task_worktree(&task_name)?;
+        let mut record = load_task(&task_name)?;
+
+        let result = run_setup_commands(&setup_commands, &config.task_dir(&worktree.path));
+        if let Err(failure) = &result {
+            error!("{}", failure);
+            record_event(
+                EventKind::SetupFailed,
+                &task_name,
+                Some(failure.command.clone()),
+            );
+        }
+        let message = result.as_ref().err().map(ToString::to_string);
+        record.setup_failure = result.err();
+        save_task(&record)?;
+
+        match message {
+            Some(message) => Err(CommandError::new(&message)),
+            None => {
+                success(&format!("Task '{task_name}' is set up"));
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_command_creation() {
+        let cmd = SetupCommand {
+            task_name: Some("feat/x".to_string()),
+        };
+        assert_eq!(cmd.task_name.as_deref(), Some("feat/x"));
+    }
+}
@@ -0,0 +1,423 @@
+use chrono::{DateTime, Utc};
+use clap::{Args, ValueHint};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use tabled::Tabled;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::{
+        CommandResult,
+        list::{format_name, format_status, task_entry},
+        note::format_note,
+    },
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::{Event, read_events},
+        filter::TaskInfo,
+        fs::read_local_config_file,
+        git::{branch_point, changed_files, list_checkpoints, worktree_diff},
+        output::{blank, colorize_diff, page, standard, table},
+        tasks::{
+            Note, PromptRecord, RunRecord, SessionRecord, SetupFailure, TaskRecord,
+            resolve_task_name, task_worktree,
+        },
+        theme::THEME,
+        time::{TimestampStyle, format_timestamp},
+    },
+};
+
+/// How many of the task's most recent events to include.
+const RECENT_EVENTS: usize = 5;
+
+#[derive(Tabled)]
+struct FieldRow {
+    field: String,
+    value: String,
+}
+
+/// Everything claudectl knows about a task: what `list --json` has, the
+/// rest of its record and the state of its worktree.
+#[derive(Serialize)]
+struct TaskDetails {
+    #[serde(flatten)]
+    task: TaskInfo,
+    #[serde(flatten)]
+    stored: StoredDetails,
+    changed_files: Vec<String>,
+    checkpoints: usize,
+    recent_events: Vec<Event>,
+    /// Everything changed since the task branched, with `--diff`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diff: Option<String>,
+    #[serde(skip)]
+    record: TaskRecord,
+}
+
+/// The parts of a task's record that [`TaskInfo`] leaves out.
+#[derive(Serialize)]
+struct StoredDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cloned_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summarized_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<Note>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    prompts: Vec<PromptRecord>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    runs: Vec<RunRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    setup_failure: Option<SetupFailure>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_session: Option<SessionRecord>,
+}
+
+impl From<&TaskRecord> for StoredDetails {
+    fn from(record: &TaskRecord) -> Self {
+        Self {
+            display_name: record.display_name.clone(),
+            cloned_from: record.cloned_from.clone(),
+            profile: record.profile.clone(),
+            summarized_at: record.summary.as_ref().map(|summary| summary.generated_at),
+            notes: record.notes.clone(),
+            prompts: record.prompts.clone(),
+            runs: record.runs.clone(),
+            setup_failure: record.setup_failure.clone(),
+            last_session: record.last_session.clone(),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ShowCommand {
+    /// The name of the task to show; prompts for one if omitted
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+
+    /// Print the task's details as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Also show everything changed since the task branched
+    #[arg(long)]
+    pub diff: bool,
+}
+
+impl ShowCommand {
+    #[instrument(name = "show_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing show command for: {:?}", self.task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+
+        let entry = task_entry(&config.project_name, task_worktree(&task_name)?)?;
+
+        let mut recent_events: Vec<Event> = read_events()?
+            .into_iter()
+            .filter(|event| event.task == task_name)
+            .collect();
+        recent_events.drain(..recent_events.len().saturating_sub(RECENT_EVENTS));
+
+        let worktree = &entry.worktree.path;
+        let diff = if self.diff {
+            Some(
+                branch_point(worktree, entry.record.base())
+                    .and_then(|base| worktree_diff(worktree, &base))?,
+            )
+        } else {
+            None
+        };
+
+        let details = TaskDetails {
+            task: entry.info(),
+            stored: StoredDetails::from(&entry.record),
+            changed_files: changed_files(worktree)?,
+            checkpoints: list_checkpoints(worktree, &task_name)?.len(),
+            recent_events,
+            diff,
+            record: entry.record,
+        };
+
+        if self.json {
+            let json = serde_json::to_string_pretty(&details)
+                .map_err(|e| CommandError::new(&format!("Failed to serialize task: {e}")))?;
+            println!("{json}");
+            return Ok(());
+        }
+
+        print_details(details, config.timestamps);
+        Ok(())
+    }
+}
+
+fn print_details(details: TaskDetails, style: TimestampStyle) {
+    let record = details.record;
+    let diff = details.diff;
+    let changes = match details.changed_files.len() {
+        0 => "clean".to_string(),
+        n => format!("{n} uncommitted file(s)")
+            .color(THEME.warning)
+            .to_string(),
+    };
+
+    let kind = record.effective_kind();
+    let mut fields = vec![
+        ("branch", format_name(&record)),
+        ("status", format_status(details.task.status)),
+        ("base", record.base.unwrap_or_else(|| "unknown".to_string())),
+        ("worktree", details.task.worktree),
+        ("commit", details.task.commit),
+        ("changes", changes),
+        ("checkpoints", details.checkpoints.to_string()),
+    ];
+    if let Some(lock) = details.task.session {
+        let started = format_timestamp(lock.started_at, style);
+        fields.insert(
+            2,
+            ("locked by", format!("{}, started {started}", lock.holder())),
+        );
+    }
+    if let Some(failure) = &record.setup_failure {
+        fields.insert(
+            2,
+            (
+                "setup failed",
+                format!("`{}` {}", failure.command, failure.reason)
+                    .color(THEME.error)
+                    .to_string(),
+            ),
+        );
+    }
+    if let Some(display_name) = record.display_name {
+        fields.insert(1, ("title", display_name));
+    }
+    if let Some(kind) = kind {
+        fields.push(("kind", kind.as_str().to_string()));
+    }
+    if let Some(created_at) = record.created_at {
+        fields.push(("created", format_timestamp(created_at, style)));
+    }
+    if let Some(session) = &record.last_session {
+        let status = if session.crashed() {
+            session.status().color(THEME.error).to_string()
+        } else {
+            session.status()
+        };
+        let ended = format_timestamp(session.ended_at, style);
+        fields.push(("last session", format!("{status}, ended {ended}")));
+    }
+    if let Some(profile) = record.profile {
+        fields.push(("profile", profile));
+    }
+    if let Some(source) = record.cloned_from {
+        fields.push(("cloned from", source));
+    }
+    if let Some(issue) = record.issue {
+        fields.push(("issue", issue));
+    }
+    if !record.tags.is_empty() {
+        fields.push(("tags", record.tags.join(", ")));
+    }
+
+    let rows: Vec<FieldRow> = fields
+        .into_iter()
+        .map(|(field, value)| FieldRow {
+            field: field.color(THEME.muted).to_string(),
+            value,
+        })
+        .collect();
+    table(&rows, false);
+
+    if let Some(summary) = record.summary {
+        blank();
+        standard(&"Summary".bold().to_string());
+        standard(&summary.text);
+    }
+
+    if !record.notes.is_empty() {
+        blank();
+        standard(&"Notes".bold().to_string());
+        for note in &record.notes {
+            standard(&format_note(note, style));
+        }
+    }
+
+    if !record.prompts.is_empty() {
+        blank();
+        standard(&"Prompts".bold().to_string());
+        for prompt in &record.prompts {
+            standard(&format_prompt(prompt, style));
+        }
+    }
+
+    if !record.runs.is_empty() {
+        blank();
+        standard(&"Runs".bold().to_string());
+        for run in &record.runs {
+            standard(&format_run(run, style));
+        }
+    }
+
+    if !details.recent_events.is_empty() {
+        blank();
+        standard(&"Recent activity".bold().to_string());
+        for event in details.recent_events {
+            let time = format_timestamp(event.at, style);
+            let detail = event.detail.map(|d| format!(": {d}")).unwrap_or_default();
+            standard(&format!(
+                "{} {}{detail}",
+                time.color(THEME.muted),
+                event.kind.label()
+            ));
+        }
+    }
+
+    if let Some(diff) = diff {
+        blank();
+        standard(&"Changes".bold().to_string());
+        if diff.is_empty() {
+            standard(&"No changes".color(THEME.muted).to_string());
+        } else {
+            page(&colorize_diff(&diff));
+        }
+    }
+}
+
+/// Prompts longer than this are cut in the details view; `--json` has them
+/// in full.
+const PROMPT_PREVIEW_CHARS: usize = 100;
+
+/// One line per prompt: when, from which command, and how it starts.
+fn format_prompt(prompt: &PromptRecord, style: TimestampStyle) -> String {
+    let first_line = prompt.text.lines().next().unwrap_or_default();
+    let mut preview: String = first_line.chars().take(PROMPT_PREVIEW_CHARS).collect();
+    if preview.len() < prompt.text.trim_end().len() {
+        preview.push('…');
+    }
+    format!(
+        "{} {} {preview}",
+        format_timestamp(prompt.sent_at, style).color(THEME.muted),
+        format!("({})", prompt.source).color(THEME.muted)
+    )
+}
+
+/// One line per run: when it started, how it ended, and the first line of
+/// what claude printed.
+fn format_run(run: &RunRecord, style: TimestampStyle) -> String {
+    let status = if run.succeeded() {
+        run.status().color(THEME.success).to_string()
+    } else {
+        run.status().color(THEME.error).to_string()
+    };
+    let cost = run
+        .cost_usd
+        .map(|cost| format!(" (${cost:.2})").color(THEME.muted).to_string())
+        .unwrap_or_default();
+    let first_line = run.output.lines().find(|line| !line.trim().is_empty());
+    let preview: String = first_line
+        .unwrap_or_default()
+        .chars()
+        .take(PROMPT_PREVIEW_CHARS)
+        .collect();
+    format!(
+        "{} {status}{cost} {preview}",
+        format_timestamp(run.started_at, style).color(THEME.muted)
+    )
+    .trim_end()
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_show_command_creation() {
+        let cmd = ShowCommand {
+            task_name: Some("feat/show".to_string()),
+            json: true,
+            diff: false,
+        };
+        assert_eq!(cmd.task_name.as_deref(), Some("feat/show"));
+        assert!(cmd.json);
+    }
+
+    #[test]
+    fn test_format_prompt_previews_first_line() {
+        let mut record = TaskRecord::new("feat/x");
+        record.add_prompt("fix the bug\nthen add a test", "resolve");
+        record.add_prompt("short", "task --start");
+
+        let multi_line = format_prompt(&record.prompts[0], TimestampStyle::Absolute);
+        assert!(multi_line.contains("(resolve)"));
+        assert!(multi_line.ends_with("fix the bug…"));
+        let single = format_prompt(&record.prompts[1], TimestampStyle::Absolute);
+        assert!(single.ends_with(" short"));
+    }
+
+    #[test]
+    fn test_format_run_shows_status_and_first_output_line() {
+        let run = RunRecord {
+            started_at: chrono::Utc::now(),
+            finished_at: chrono::Utc::now(),
+            exit_code: Some(1),
+            output: "\nTests fail\nin two places\n".to_string(),
+            output_truncated: false,
+            cost_usd: Some(0.25),
+        };
+        let line = format_run(&run, TimestampStyle::Absolute);
+        assert!(line.contains("exited with code 1"));
+        assert!(line.contains("($0.25)"));
+        assert!(line.ends_with("Tests fail"));
+    }
+
+    #[test]
+    fn test_task_details_json_adds_to_the_list_fields() {
+        let mut record = TaskRecord::new("feat/show");
+        record.tags = vec!["api".to_string()];
+        record.display_name = Some("Show a task".to_string());
+        record.summary = Some(crate::utils::tasks::Summary {
+            text: "Adds show".to_string(),
+            generated_at: chrono::Utc::now(),
+        });
+        let entry = crate::utils::filter::TaskEntry {
+            project: "app".to_string(),
+            worktree: crate::utils::git::Worktree {
+                path: "/tmp/feat/show".to_string(),
+                commit: "abc1234".to_string(),
+                branch: Some("feat/show".to_string()),
+            },
+            status: crate::utils::claude::Status::Ready,
+            record,
+            lock: None,
+        };
+        let details = TaskDetails {
+            task: entry.info(),
+            stored: StoredDetails::from(&entry.record),
+            changed_files: vec!["src/lib.rs".to_string()],
+            checkpoints: 2,
+            recent_events: Vec::new(),
+            diff: None,
+            record: entry.record,
+        };
+
+        let json: serde_json::Value = serde_json::to_value(&details).unwrap();
+        let list: serde_json::Value = serde_json::to_value(&details.task).unwrap();
+        for (field, value) in list.as_object().unwrap() {
+            assert_eq!(&json[field], value, "{field}");
+        }
+        assert_eq!(json["summary"], "Adds show");
+        assert_eq!(json["display_name"], "Show a task");
+        assert_eq!(json["changed_files"][0], "src/lib.rs");
+        assert!(json.get("diff").is_none());
+    }
+}
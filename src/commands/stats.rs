@@ -0,0 +1,135 @@
+use chrono::Local;
+use clap::Args;
+use tabled::Tabled;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::read_events,
+        fs::read_local_config_file,
+        output::{blank, standard, table},
+        stats::{Cost, CostSource, Stats, ccusage_cost, compute_stats},
+        tasks::{project_worktrees, stored_tasks},
+        time::format_span,
+    },
+};
+
+#[derive(Tabled)]
+struct WeekRow {
+    week: String,
+    created: usize,
+    completed: usize,
+}
+
+#[derive(Args, Debug)]
+pub struct StatsCommand {
+    /// Weeks shown in the weekly breakdown, up to this one
+    #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u64).range(1..))]
+    pub weeks: u64,
+
+    /// Print the statistics as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl StatsCommand {
+    #[instrument(name = "stats_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing stats command.");
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+
+        let mut stats = compute_stats(&read_events()?, self.weeks, Local::now().date_naive());
+        stats.cost = cost(&config)?;
+
+        if self.json {
+            let json = serde_json::to_string_pretty(&stats)
+                .map_err(|e| CommandError::new(&format!("Failed to serialize stats: {e}")))?;
+            println!("{json}");
+            return Ok(());
+        }
+        print_stats(&config, &stats);
+        Ok(())
+    }
+}
+
+/// What agents in the project cost: every session when ccusage is
+/// installed, otherwise what `claudectl run` recorded, if anything.
+fn cost(config: &Config) -> CommandResult<Option<Cost>> {
+    let mut roots = vec![config.project_dir.clone()];
+    // Profiles can put worktrees outside the project directory
+    roots.extend(
+        project_worktrees()?
+            .into_iter()
+            .map(|worktree| worktree.path),
+    );
+    if let Some(usd) = ccusage_cost(&roots) {
+        return Ok(Some(Cost {
+            usd,
+            source: CostSource::Ccusage,
+        }));
+    }
+    let costs: Vec<f64> = stored_tasks()?
+        .iter()
+        .flat_map(|record| record.runs.iter().filter_map(|run| run.cost_usd))
+        .collect();
+    Ok((!costs.is_empty()).then(|| Cost {
+        usd: costs.iter().sum(),
+        source: CostSource::Runs,
+    }))
+}
+
+fn print_stats(config: &Config, stats: &Stats) {
+    let rows: Vec<WeekRow> = stats
+        .weeks
+        .iter()
+        .map(|week| WeekRow {
+            week: week.week.format("%Y-%m-%d").to_string(),
+            created: week.created,
+            completed: week.completed,
+        })
+        .collect();
+    standard(&format!("Tasks per week in {}", config.project_name));
+    blank();
+    table(&rows, true);
+    blank();
+
+    standard(&format!(
+        "Tasks: {} created, {} completed",
+        stats.tasks_created, stats.tasks_completed
+    ));
+    match stats.average_lifetime_secs {
+        Some(secs) => standard(&format!(
+            "Average lifetime: {} over {} finished task(s)",
+            format_span(secs),
+            stats.tasks_finished
+        )),
+        None => standard("Average lifetime: no finished tasks yet"),
+    }
+    standard(&format!(
+        "Sessions: {}, {} in total",
+        stats.sessions,
+        format_span(stats.session_secs)
+    ));
+    standard(&format!(
+        "Runs: {}, {} in total",
+        stats.runs,
+        format_span(stats.run_secs)
+    ));
+    match &stats.cost {
+        Some(Cost {
+            usd,
+            source: CostSource::Ccusage,
+        }) => standard(&format!("Cost: ${usd:.2}")),
+        Some(Cost {
+            usd,
+            source: CostSource::Runs,
+        }) => standard(&format!(
+            "Cost: ${usd:.2} for runs; install ccusage to include sessions"
+        )),
+        None => standard("Cost: unknown; install ccusage to see it"),
+    }
+}
@@ -0,0 +1,201 @@
+use clap::Args;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use tabled::Tabled;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    commands::{
+        CommandResult,
+        list::{format_entry_status, format_name, task_entry},
+    },
+    utils::{
+        claude::Status,
+        config::Config,
+        errors::CommandError,
+        filter::{TaskEntry, TaskInfo},
+        fs::read_local_config_file,
+        git::{ahead_behind, changed_files},
+        output::{blank, standard, table},
+        process::{Usage, usage},
+        tasks::{TaskRecord, project_worktrees},
+        terminal::show_session_counts,
+        theme::THEME,
+    },
+};
+
+#[derive(Serialize)]
+struct ProjectStatus {
+    project: String,
+    tasks: Vec<TaskStatus>,
+}
+
+/// A task as `list --json` describes it, and how far along it is.
+#[derive(Serialize)]
+struct TaskStatus {
+    #[serde(flatten)]
+    task: TaskInfo,
+    /// Memory and CPU used by the running session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<Usage>,
+    uncommitted_files: usize,
+    /// Commits on the task branch that are not on its base; unknown if the
+    /// base cannot be found
+    ahead: Option<usize>,
+    /// Commits on the base that are not on the task branch
+    behind: Option<usize>,
+    #[serde(skip)]
+    record: TaskRecord,
+}
+
+#[derive(Tabled)]
+struct StatusRow {
+    task: String,
+    status: String,
+    usage: String,
+    changes: String,
+    base: String,
+    commits: String,
+}
+
+#[derive(Args, Debug)]
+pub struct StatusCommand {
+    /// Print the project's status as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl StatusCommand {
+    #[instrument(name = "status_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing status command.");
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let tasks = project_worktrees()?
+            .into_iter()
+            .map(|worktree| task_entry(&config.project_name, worktree).and_then(task_status))
+            .collect::<CommandResult<Vec<_>>>()?;
+        let status = ProjectStatus {
+            project: config.project_name,
+            tasks,
+        };
+
+        if self.json {
+            let json = serde_json::to_string_pretty(&status)
+                .map_err(|e| CommandError::new(&format!("Failed to serialize status: {e}")))?;
+            println!("{json}");
+            return Ok(());
+        }
+
+        print_status(status);
+        Ok(())
+    }
+}
+
+fn task_status(entry: TaskEntry) -> CommandResult<TaskStatus> {
+    let task = entry.info();
+    let (ahead, behind) = match ahead_behind(&task.worktree, &task.base) {
+        Ok((ahead, behind)) => (Some(ahead), Some(behind)),
+        Err(e) => {
+            warn!(
+                "Failed to compare '{}' with {}: {}",
+                task.name, task.base, e
+            );
+            (None, None)
+        }
+    };
+
+    Ok(TaskStatus {
+        usage: task.session.as_ref().and_then(|lock| usage(lock.pid)),
+        uncommitted_files: changed_files(&task.worktree)?.len(),
+        ahead,
+        behind,
+        task,
+        record: entry.record,
+    })
+}
+
+fn print_status(status: ProjectStatus) {
+    standard(&format!("Project {}", status.project.color(THEME.primary)));
+    blank();
+    if status.tasks.is_empty() {
+        standard("No tasks");
+        return;
+    }
+
+    let working = status
+        .tasks
+        .iter()
+        .filter(|task| task.task.status == Status::Working)
+        .count();
+    let waiting = status
+        .tasks
+        .iter()
+        .filter(|task| task.task.status == Status::Waiting)
+        .count();
+    show_session_counts(working, waiting);
+    let dirty = status
+        .tasks
+        .iter()
+        .filter(|task| task.uncommitted_files > 0)
+        .count();
+    let total = status.tasks.len();
+
+    let rows: Vec<StatusRow> = status.tasks.into_iter().map(status_row).collect();
+    table(&rows, true);
+    blank();
+    standard(&format!(
+        "{total} task(s): {working} working, {dirty} with uncommitted changes"
+    ));
+}
+
+fn status_row(task: TaskStatus) -> StatusRow {
+    StatusRow {
+        task: format_name(&task.record),
+        status: format_entry_status(task.task.status, task.task.session.as_ref()),
+        usage: task.usage.map_or_else(
+            || "-".color(THEME.muted).to_string(),
+            |usage| usage.describe(),
+        ),
+        changes: match task.uncommitted_files {
+            0 => "clean".color(THEME.muted).to_string(),
+            n => format!("{n} uncommitted").color(THEME.warning).to_string(),
+        },
+        base: task.task.base,
+        commits: format_commits(task.ahead, task.behind),
+    }
+}
+
+/// e.g. "2 ahead, 1 behind"
+fn format_commits(ahead: Option<usize>, behind: Option<usize>) -> String {
+    match (ahead, behind) {
+        (Some(0), Some(0)) => "up to date".color(THEME.muted).to_string(),
+        (Some(ahead), Some(0)) => format!("{ahead} ahead"),
+        (Some(0), Some(behind)) => format!("{behind} behind").color(THEME.warning).to_string(),
+        (Some(ahead), Some(behind)) => format!("{ahead} ahead, {behind} behind")
+            .color(THEME.warning)
+            .to_string(),
+        _ => "unknown".color(THEME.error).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_command_creation() {
+        let cmd = StatusCommand { json: true };
+        assert!(cmd.json);
+    }
+
+    #[test]
+    fn test_format_commits() {
+        assert!(format_commits(Some(0), Some(0)).contains("up to date"));
+        assert_eq!(format_commits(Some(2), Some(0)), "2 ahead");
+        assert!(format_commits(Some(2), Some(1)).contains("2 ahead, 1 behind"));
+        assert!(format_commits(None, None).contains("unknown"));
+    }
+}
@@ -0,0 +1,167 @@
+use std::path::Path;
+
+use clap::{Args, Subcommand};
+use owo_colors::OwoColorize;
+use tabled::Tabled;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::{Config, GlobalConfig},
+        events::read_events,
+        fs::{
+            event_log_path, global_config_path, global_projects_dir, local_config_dir,
+            local_tasks_dir, log_path, read_local_config_file, read_log, task_file_count,
+        },
+        output::table,
+        projects::registered_projects,
+        tasks::project_worktrees,
+        theme::THEME,
+    },
+};
+
+#[derive(Tabled)]
+struct StorageRow {
+    scope: String,
+    item: String,
+    path: String,
+    records: String,
+}
+
+#[derive(Args, Debug)]
+pub struct StorageCommand {
+    #[command(subcommand)]
+    pub action: StorageAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum StorageAction {
+    /// Show where claudectl keeps its data and how much is stored
+    Info(StorageInfoCommand),
+}
+
+#[derive(Args, Debug)]
+pub struct StorageInfoCommand {
+    /// Only show global storage shared by all projects
+    #[arg(long, conflicts_with = "project")]
+    pub global: bool,
+
+    /// Only show the current project's storage
+    #[arg(long)]
+    pub project: bool,
+}
+
+impl StorageCommand {
+    pub fn execute(&self) -> CommandResult<()> {
+        match &self.action {
+            StorageAction::Info(cmd) => cmd.execute(),
+        }
+    }
+}
+
+impl StorageInfoCommand {
+    #[instrument(name = "storage_info_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing storage info command.");
+        let mut rows = Vec::new();
+        if !self.global {
+            rows.extend(project_rows()?);
+        }
+        if !self.project {
+            rows.extend(global_rows()?);
+        }
+        table(&rows, true);
+        Ok(())
+    }
+}
+
+fn row(scope: &str, item: &str, path: &Path, records: String) -> StorageRow {
+    StorageRow {
+        scope: scope.to_string(),
+        item: item.to_string(),
+        path: path.to_string_lossy().color(THEME.muted).to_string(),
+        records,
+    }
+}
+
+fn project_rows() -> CommandResult<Vec<StorageRow>> {
+    let config_path = local_config_dir()?.join("config.json");
+    let Ok(raw_config) = read_local_config_file() else {
+        return Ok(vec![row(
+            "project",
+            "config",
+            &config_path,
+            "not initialized".color(THEME.warning).to_string(),
+        )]);
+    };
+    let config = Config::from_str(&raw_config)?;
+
+    let events = match read_events() {
+        Ok(events) => events.len().to_string(),
+        Err(e) => format!("unreadable ({e})").color(THEME.error).to_string(),
+    };
+    let worktrees = project_worktrees()?.len();
+
+    Ok(vec![
+        row("project", "config", &config_path, config.project_name),
+        row(
+            "project",
+            "task records",
+            &local_tasks_dir()?,
+            task_file_count()?.to_string(),
+        ),
+        row("project", "event log", &event_log_path()?, events),
+        row(
+            "project",
+            "worktrees",
+            Path::new(&config.project_dir),
+            worktrees.to_string(),
+        ),
+    ])
+}
+
+fn global_rows() -> CommandResult<Vec<StorageRow>> {
+    let profiles = match GlobalConfig::load() {
+        Ok(global) => format!("{} profile(s)", global.profiles.len()),
+        Err(e) => format!("unreadable ({e})").color(THEME.error).to_string(),
+    };
+    Ok(vec![
+        row("global", "config", &global_config_path()?, profiles),
+        row(
+            "global",
+            "projects",
+            &global_projects_dir()?,
+            registered_projects()?.len().to_string(),
+        ),
+        row(
+            "global",
+            "diagnostics log",
+            &log_path()?,
+            format!("{} line(s)", read_log()?.lines().count()),
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_info_command_creation() {
+        let cmd = StorageInfoCommand {
+            global: true,
+            project: false,
+        };
+        assert!(cmd.global);
+        assert!(!cmd.project);
+    }
+
+    #[test]
+    fn test_row_formats_path() {
+        let row = row("global", "config", Path::new("/tmp/claudectl"), "1".into());
+        assert_eq!(row.scope, "global");
+        assert!(row.path.contains("/tmp/claudectl"));
+        assert_eq!(row.records, "1");
+    }
+}
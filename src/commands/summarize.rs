@@ -0,0 +1,131 @@
+use clap::{Args, ValueHint};
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        events::{EventKind, record_event},
+        fs::read_local_config_file,
+        git::{branch_point, worktree_diff},
+        output::{Position, blank, standard, step, step_end, step_fail},
+        redact::Redactor,
+        tasks::{load_task, save_task, task_worktree},
+    },
+};
+
+/// Diffs larger than this are truncated before being sent to claude.
+const MAX_DIFF_CHARS: usize = 100_000;
+
+const SUMMARY_PROMPT: &str = "Summarize the git diff provided on stdin in two to four \
+sentences for a pull request description. Describe what changed and why it matters. \
+Respond with the summary only.";
+
+#[derive(Args, Debug)]
+pub struct SummarizeCommand {
+    /// The name of the task to summarize
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: String,
+}
+
+impl SummarizeCommand {
+    #[instrument(name = "summarize_command", fields(task_name = %self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing summarize command for: {}", self.task_name);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let worktree = task_worktree(&self.task_name)?;
+        let record = load_task(&self.task_name)?;
+
+        // 1. Collect the task's changes
+        step("Collecting changes...", Position::First);
+        let diff = branch_point(&worktree.path, record.base())
+            .and_then(|base| worktree_diff(&worktree.path, &base))
+            .inspect_err(|e| {
+                error!("Failed to diff task: {}", e);
+                step_fail();
+            })?;
+        if diff.is_empty() {
+            step_fail();
+            return Err(CommandError::new(&format!(
+                "Task '{}' has no changes to summarize",
+                self.task_name
+            )));
+        }
+        step_end();
+        blank();
+
+        // 2. Ask claude for a summary
+        let mut record = load_task(&self.task_name)?;
+        let session_profile = config.session_profile(record.profile.as_deref())?;
+        let invocation = config.invocation(session_profile.as_ref().map(|(_, profile)| profile));
+        step("Generating summary...", Position::Last);
+        let summary = config
+            .agent
+            .adapter()
+            .one_shot(
+                SUMMARY_PROMPT,
+                truncate_diff(&diff),
+                &worktree.path,
+                &invocation,
+            )
+            .inspect_err(|e| {
+                error!("Failed to generate summary: {}", e);
+                step_fail();
+            })?;
+        let summary = Redactor::new(&config.redaction)?.redact(&summary);
+        step_end();
+        blank();
+
+        // 3. Store it with the task
+        record.set_summary(&summary);
+        save_task(&record)?;
+        record_event(EventKind::SummaryGenerated, &self.task_name, None);
+        info!("Saved summary for task: {}", self.task_name);
+
+        blank();
+        standard(&summary);
+
+        Ok(())
+    }
+}
+
+fn truncate_diff(diff: &str) -> &str {
+    if diff.len() <= MAX_DIFF_CHARS {
+        return diff;
+    }
+    let mut end = MAX_DIFF_CHARS;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    &diff[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_command_creation() {
+        let cmd = SummarizeCommand {
+            task_name: "feat/summary".to_string(),
+        };
+        assert_eq!(cmd.task_name, "feat/summary");
+    }
+
+    #[test]
+    fn test_truncate_diff_keeps_small_diffs() {
+        assert_eq!(truncate_diff("+ line"), "+ line");
+    }
+
+    #[test]
+    fn test_truncate_diff_respects_char_boundaries() {
+        let diff = "é".repeat(MAX_DIFF_CHARS);
+        let truncated = truncate_diff(&diff);
+        assert!(truncated.len() <= MAX_DIFF_CHARS);
+        assert!(truncated.chars().all(|c| c == 'é'));
+    }
+}
@@ -0,0 +1,125 @@
+use clap::{Args, ValueHint};
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        errors::CommandError,
+        fs::read_local_config_file,
+        git::Worktree,
+        output::{can_pick_on_stderr, is_dumb_terminal, pick, pick_numbered},
+        tasks::project_worktrees,
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct SwitchCommand {
+    /// Only offer tasks whose name or path fuzzy-matches this; a single
+    /// match is chosen without asking
+    #[arg(value_hint = ValueHint::Other)]
+    pub query: Option<String>,
+}
+
+impl SwitchCommand {
+    #[instrument(name = "switch_command", fields(query = ?self.query))]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing switch command.");
+        let mut tasks: Vec<Worktree> = project_worktrees()?
+            .into_iter()
+            .filter(|wt| wt.branch.is_some())
+            .collect();
+        if let Some(query) = &self.query {
+            // A task named exactly like the query wins over fuzzy matches
+            if let Some(exact) = tasks
+                .iter()
+                .position(|wt| wt.branch.as_ref() == Some(query))
+            {
+                tasks = vec![tasks.swap_remove(exact)];
+            } else {
+                tasks.retain(|wt| fuzzy_matches(query, &label(wt)));
+            }
+        }
+
+        let worktree = match tasks.len() {
+            0 if self.query.is_some() => {
+                return Err(CommandError::new(&format!(
+                    "No task matches '{}'",
+                    self.query.as_deref().unwrap_or_default()
+                )));
+            }
+            0 => return Err(CommandError::new("No tasks to choose from")),
+            1 => tasks.swap_remove(0),
+            _ => {
+                let selection = choose(&tasks)?;
+                tasks.swap_remove(selection)
+            }
+        };
+
+        // Like `open --print`, a sub-project's tasks switch to its directory
+        let dir = read_local_config_file()
+            .ok()
+            .and_then(|raw| Config::from_str(&raw).ok())
+            .map_or_else(
+                || worktree.path.clone(),
+                |config| config.task_dir(&worktree.path),
+            );
+        info!("Switching to {}", dir);
+        println!("{dir}");
+        Ok(())
+    }
+}
+
+/// Ask which task to switch to. The picker is drawn on stderr so stdout can
+/// be captured, as in `cd "$(claudectl switch)"`.
+fn choose(tasks: &[Worktree]) -> CommandResult<usize> {
+    if !can_pick_on_stderr() {
+        return Err(CommandError::new(
+            "Several tasks match. Pass a task name when not running in a terminal",
+        ));
+    }
+    let items: Vec<String> = tasks.iter().map(label).collect();
+    let selection = if is_dumb_terminal() {
+        pick_numbered("Switch to task", &items)
+    } else {
+        pick("Switch to task", &items)
+    };
+    selection
+        .map_err(|e| CommandError::new(&format!("Failed to read input: {e}")))?
+        .ok_or_else(|| CommandError::new("No task selected"))
+}
+
+/// What the picker shows, and the query is matched against: the task's
+/// branch and worktree path.
+fn label(worktree: &Worktree) -> String {
+    format!(
+        "{}  {}",
+        worktree.branch.as_deref().unwrap_or_default(),
+        worktree.path
+    )
+}
+
+/// Whether the characters of `query` appear in `text` in order, ignoring
+/// case, e.g. `fl` in `feat/login`.
+fn fuzzy_matches(query: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|q| text.any(|c| c == q))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_matches_characters_in_order() {
+        assert!(fuzzy_matches("fl", "feat/login  /tmp/wt/feat/login"));
+        assert!(fuzzy_matches("LOGIN", "feat/login"));
+        assert!(fuzzy_matches("", "feat/login"));
+        assert!(!fuzzy_matches("lf", "feat/login"));
+        assert!(!fuzzy_matches("crash", "feat/login"));
+    }
+}
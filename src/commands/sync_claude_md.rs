@@ -0,0 +1,76 @@
+use std::path::Path;
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use tracing::{info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        config::Config,
+        fs::{current_dir, has_claude_files, read_local_config_file, sync_claude_files},
+        git::worktree_list,
+        output::{error, standard, success},
+        theme::THEME,
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct SyncClaudeMdCommand {}
+
+impl SyncClaudeMdCommand {
+    #[instrument(name = "sync_claude_md_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing sync-claude-md command.");
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        info!("Loaded configuration for project: {}", config.project_name);
+
+        let repo_root = current_dir()?;
+        if !has_claude_files(&repo_root) {
+            standard("No CLAUDE.md or .claude directory found in this repository");
+            return Ok(());
+        }
+
+        let worktrees = worktree_list().inspect_err(|e| {
+            error(&format!("Failed to get active tasks: {e}"));
+        })?;
+
+        // Only task worktrees managed by claudectl live under the project directory
+        let mut synced = 0;
+        for wt in worktrees
+            .iter()
+            .filter(|wt| Path::new(&wt.path).starts_with(&config.project_dir))
+        {
+            let name = wt.branch.as_deref().unwrap_or("N/A");
+            let updated = sync_claude_files(&repo_root, Path::new(&config.task_dir(&wt.path)))?;
+            info!("Synced {} file(s) into {}", updated, wt.path);
+            if updated > 0 {
+                synced += 1;
+                standard(&format!(
+                    "{name}: updated {updated} file(s) {}",
+                    wt.path.as_str().color(THEME.muted)
+                ));
+            }
+        }
+
+        if synced == 0 {
+            success("All task worktrees are up to date");
+        } else {
+            success(&format!("Synced Claude instructions into {synced} task(s)"));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_claude_md_command_creation() {
+        let cmd = SyncClaudeMdCommand {};
+        assert!(format!("{cmd:?}").contains("SyncClaudeMdCommand"));
+    }
+}
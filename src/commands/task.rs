@@ -1,40 +1,154 @@
 use crate::commands::CommandResult;
-use crate::utils::config::Config;
+use crate::utils::batch::read_task_specs;
+use crate::utils::claude::approve_permissions;
+use crate::utils::config::{Config, active_profile};
+use crate::utils::context::prepend_context;
 use crate::utils::errors::CommandError;
-use crate::utils::fs::read_local_config_file;
-use crate::utils::git::{create_worktree, fetch_origin, worktree_exists};
-use crate::utils::output::{Position, blank, step, step_end, step_fail, success};
-use clap::Args;
+use crate::utils::events::{EventKind, record_event, record_timed_event};
+use crate::utils::fs::{
+    TASK_CONTEXT_FILE, current_dir, has_claude_files, read_local_config_file, read_task_file,
+    sync_claude_files, write_task_context,
+};
+use crate::utils::git::{
+    DEFAULT_BASE, GitProgress, branch_commit, branch_exists, create_worktree, exclude_path,
+    fetch_origin, find_worktree, worktree_exists,
+};
+use crate::utils::guard::install_guard_rails;
+use crate::utils::hooks::{Hook, HookContext, run_hook, run_session_end_hooks};
+use crate::utils::issues::{Issue, fetch_issue};
+use crate::utils::lock::TaskLock;
+use crate::utils::output::{
+    Position, blank, standard, step, step_end, step_fail, step_progress, step_skip, success, table,
+};
+use crate::utils::queue::queue_session;
+use crate::utils::setup::run_setup_commands;
+use crate::utils::slug::slugify;
+use crate::utils::tasks::{
+    SessionRecord, TaskKind, TaskRecord, load_task, save_task, task_worktree,
+};
+use crate::utils::terminal::{session_finished, session_started};
+use crate::utils::theme::THEME;
+use crate::utils::transaction::{Step, Transaction};
+use chrono::Utc;
+use clap::{Args, ValueHint};
+use owo_colors::OwoColorize;
+use std::path::{Path, PathBuf};
+use tabled::Tabled;
 use tracing::{error, info, instrument, warn};
 
 #[derive(Debug, Args)]
 pub struct TaskCommand {
-    /// The name of the task/branch (e.g., feat/new-feature)
-    pub task_name: String,
+    /// The name of the task/branch (e.g., feat/new-feature); other names are
+    /// turned into one (e.g., "Fix login crash" becomes fix-login-crash).
+    /// Defaults to the issue's number and title with --from-issue
+    #[arg(required_unless_present_any = ["from_issue", "file"])]
+    pub task_name: Option<String>,
+
+    /// Create the task from a GitHub issue, by number or URL, giving the
+    /// agent its description in .claudectl/task.md
+    #[arg(long, value_name = "ISSUE", value_hint = ValueHint::Other)]
+    pub from_issue: Option<String>,
+
+    /// Create every task listed in a YAML or JSON file
+    #[arg(
+        short = 'f',
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        conflicts_with_all = ["task_name", "from_issue", "clone", "start"]
+    )]
+    pub file: Option<PathBuf>,
+
+    /// Branch the task from this ref instead of origin/main
+    #[arg(long, value_name = "REF", value_hint = ValueHint::Other, conflicts_with = "clone")]
+    pub base: Option<String>,
+
+    /// Clone the base and settings of an existing task
+    #[arg(long, value_name = "EXISTING_TASK", value_hint = ValueHint::Other)]
+    pub clone: Option<String>,
+
+    /// Start a cloned task from the existing task's branch instead of its base
+    #[arg(long, requires = "clone")]
+    pub from_branch: bool,
+
+    /// Tag the task for filtering (repeatable)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Kind of work; names without a prefix get the kind's prefix (e.g. fix/)
+    #[arg(long, value_enum)]
+    pub kind: Option<TaskKind>,
+
+    /// Start a claude session in the new worktree once it is created
+    #[arg(long)]
+    pub start: bool,
+
+    /// Initial prompt for the session started with --start
+    #[arg(long, requires = "start")]
+    pub prompt: Option<String>,
 }
 
 impl TaskCommand {
-    #[instrument(name = "task_command", fields(task_name = %self.task_name))]
+    #[instrument(name = "task_command", fields(task_name = ?self.task_name))]
     pub fn execute(&self) -> CommandResult<()> {
-        info!("Executing task command for: {}", self.task_name);
+        if let Some(file) = &self.file {
+            return self.execute_batch(file);
+        }
+        info!("Executing task command for: {:?}", self.task_name);
         let raw_config = read_local_config_file()?;
         let config = Config::from_str(&raw_config)?;
         info!("Loaded configuration for project: {}", config.project_name);
+        let issue = self.issue()?;
+        let task_name = self.branch_name_for(&self.name(issue.as_ref()))?;
+
+        if self.start && !approve_permissions(&config.permissions, "The task's claude session")? {
+            return Err(CommandError::new(
+                "Elevated permission mode was not approved",
+            ));
+        }
+
+        let source = self.clone_source()?;
+        // Checked up front so an unknown profile fails before anything is created
+        let task_profile = active_profile()
+            .map(str::to_string)
+            .or_else(|| source.as_ref().and_then(|source| source.profile.clone()));
+        let session_profile = config.session_profile(task_profile.as_deref())?;
+        let start_point = match &source {
+            Some(source) if self.from_branch => source.name.clone(),
+            Some(source) => source.base.clone().unwrap_or(DEFAULT_BASE.to_string()),
+            None => self.base.clone().unwrap_or(DEFAULT_BASE.to_string()),
+        };
+
+        let worktree_dir =
+            config.worktree_dir(session_profile.as_ref().map(|(_, profile)| profile));
+        let worktree_path = format!("{worktree_dir}/{task_name}");
+        run_hook(
+            Hook::PreTask,
+            &HookContext {
+                task_name: &task_name,
+                worktree: &worktree_path,
+                ..HookContext::default()
+            },
+        )?;
 
         // 1. Fetch latest changes from origin
-        step("Fetching latest changes from origin...", Position::First);
-        fetch_origin().inspect_err(|e| {
-            error!("Failed to fetch from origin: {}", e);
-            step_fail();
-        })?;
+        let message = "Fetching latest changes from origin...";
+        step(message, Position::First);
+        fetch_origin(&mut progress_reporter(&config, message, Position::First)).inspect_err(
+            |e| {
+                error!("Failed to fetch from origin: {}", e);
+                step_fail();
+            },
+        )?;
         info!("Successfully fetched latest changes from origin");
+        step_progress(message, Position::First, None);
         step_end();
         blank();
 
         // 2. Check if worktree already exists
-        let worktree_path = format!("{}/{}", config.project_dir, self.task_name);
         info!("Checking for existing worktree at: {}", worktree_path);
-        step("Creating git worktree...", Position::Last);
+        let message = "Creating git worktree...";
+        step(message, Position::Normal);
         let exists = worktree_exists(&worktree_path).inspect_err(|e| {
             error!("Failed to check worktree existence: {}", e);
             step_fail();
@@ -47,26 +161,367 @@ impl TaskCommand {
             )));
         }
         info!("Worktree path is available");
-        create_worktree(&self.task_name, &worktree_path).inspect_err(|e| {
+        // Everything from here on is undone if a later step fails
+        let mut transaction = Transaction::journaled()?;
+        transaction.record(Step::BranchCreated(task_name.clone()))?;
+        transaction.record(Step::WorktreeAdded(worktree_path.clone()))?;
+        create_worktree(
+            &task_name,
+            &worktree_path,
+            &start_point,
+            config.subproject.as_deref(),
+            &mut progress_reporter(&config, message, Position::Normal),
+        )
+        .inspect_err(|e| {
             error!("Failed to create worktree: {}", e);
             step_fail();
         })?;
         info!(
             "Successfully created worktree '{}' at: {}",
-            self.task_name, worktree_path
+            task_name, worktree_path
         );
+        if config.guard_rails.enabled {
+            install_guard_rails(&worktree_path, &task_name, &config.guard_rails).inspect_err(
+                |e| {
+                    error!("Failed to install guard rails: {}", e);
+                    step_fail();
+                },
+            )?;
+        }
+        step_progress(message, Position::Normal, None);
         step_end();
         blank();
 
+        // 3. Make sure the agent sees the repository's Claude instructions
+        step("Syncing Claude instructions...", Position::Last);
+        let task_dir = config.task_dir(&worktree_path);
+        let repo_root = current_dir()?;
+        if has_claude_files(&repo_root) {
+            let updated = sync_claude_files(&repo_root, Path::new(&task_dir)).inspect_err(|e| {
+                error!("Failed to sync Claude instructions: {}", e);
+                step_fail();
+            })?;
+            info!("Synced {} Claude instruction file(s)", updated);
+            step_end();
+        } else {
+            step_skip();
+        }
+        if let Some(issue) = &issue {
+            write_context(&task_dir, &issue.context()).inspect_err(|e| {
+                error!("Failed to write the issue into the worktree: {}", e);
+            })?;
+        }
+        blank();
+
+        let mut record = TaskRecord::new(&task_name);
+        if let Some(source) = &source {
+            record.inherit_from(source);
+        }
+        let display_name = match (&self.task_name, &issue) {
+            (None, Some(issue)) => issue.title.trim().to_string(),
+            _ => self.name(None).trim().to_string(),
+        };
+        if slugify(&display_name) != display_name {
+            record.display_name = Some(display_name);
+        }
+        record.issue = issue.as_ref().map(|issue| issue.url.clone());
+        record.base = Some(start_point);
+        record.base_commit = Some(branch_commit(&task_name)?);
+        record.created_at = Some(Utc::now());
+        if self.kind.is_some() {
+            record.kind = self.kind;
+        }
+        record.profile = task_profile;
+        for tag in &self.tags {
+            if !record.tags.contains(tag) {
+                record.tags.push(tag.clone());
+            }
+        }
+        let previous = read_task_file(&task_name)?;
+        transaction.record(Step::TaskFileWritten {
+            task_name: task_name.clone(),
+            previous,
+        })?;
+        save_task(&record)?;
+        transaction.commit();
+
+        let detail = match &source {
+            Some(source) => format!("{worktree_path} (cloned from {})", source.name),
+            None => worktree_path.clone(),
+        };
+        record_event(EventKind::TaskCreated, &task_name, Some(detail));
+
         blank();
         success(&format!(
             "Task worktree '{}' created successfully at: {}",
-            self.task_name, worktree_path
+            task_name, worktree_path
         ));
 
+        // 4. Prepare the worktree, keeping the task but not starting an
+        // agent if that fails
+        let setup_commands = config.all_setup_commands()?;
+        if !setup_commands.is_empty() {
+            blank();
+            standard("Running setup commands...");
+            if let Err(failure) = run_setup_commands(&setup_commands, &task_dir) {
+                error!("{}", failure);
+                run_post_task_hook(&task_name, &worktree_path, "setup-failed")?;
+                record_event(
+                    EventKind::SetupFailed,
+                    &task_name,
+                    Some(failure.command.clone()),
+                );
+                let message = format!(
+                    "{failure}. Fix the problem and run `claudectl setup {task_name}` to retry"
+                );
+                record.setup_failure = Some(failure);
+                save_task(&record)?;
+                return Err(CommandError::new(&message));
+            }
+            success("Setup complete");
+        }
+        run_post_task_hook(&task_name, &worktree_path, "ready")?;
+
+        // 5. Hand the worktree over to the agent
+        if self.start {
+            blank();
+            standard("Starting claude session...");
+            let issue_prompt = issue.as_ref().map(|issue| {
+                format!(
+                    "Resolve issue #{} as described in {TASK_CONTEXT_FILE}",
+                    issue.number
+                )
+            });
+            let prompt = config
+                .initial_prompt(self.prompt.as_deref().or(issue_prompt.as_deref()))
+                .map(|prompt| prepend_context(&config.context, &worktree_path, &prompt));
+            let queued = queue_session(config.max_sessions, &task_name, "task --start")?;
+            let lock = TaskLock::acquire(&task_name, "task --start", false)?;
+            drop(queued);
+            let mut hook_context = HookContext {
+                task_name: &task_name,
+                worktree: &worktree_path,
+                session_id: lock.session_id(),
+                ..HookContext::default()
+            };
+            run_hook(Hook::PreSession, &hook_context)?;
+            if let Some(prompt) = &self.prompt {
+                record.add_prompt(prompt, "task --start");
+                save_task(&record)?;
+            }
+            let invocation =
+                config.invocation(session_profile.as_ref().map(|(_, profile)| profile));
+            let agent = config.agent.adapter();
+            let detail = session_profile.map(|(name, _)| format!("profile {name}"));
+            record_event(EventKind::SessionStarted, &task_name, detail);
+            let started_at = Utc::now();
+            session_started(&task_name);
+            let session = agent.spawn(
+                &task_dir,
+                &config.permissions,
+                prompt.as_deref(),
+                &invocation,
+            );
+            let mut ended = SessionRecord::ended(started_at, &session);
+            ended.stopped = lock.stop_requested();
+            session_finished(&task_name, !ended.crashed());
+            let mut record = load_task(&task_name)?;
+            record.last_session = Some(ended.clone());
+            save_task(&record)?;
+            hook_context.status = Some(ended.outcome());
+            hook_context.exit_code = ended.exit_code;
+            run_session_end_hooks(&hook_context)?;
+            session.inspect_err(|e| {
+                error!("Failed to start claude session: {}", e);
+            })?;
+            let detail = ended.crashed().then(|| ended.status());
+            record_timed_event(EventKind::SessionEnded, &task_name, detail, started_at);
+            if ended.crashed() {
+                return Err(ended.failure(&task_name));
+            }
+        }
+
         info!("Task command completed successfully");
         Ok(())
     }
+
+    /// Create each task listed in `file`, carrying on past failures, and
+    /// report how each went.
+    fn execute_batch(&self, file: &Path) -> CommandResult<()> {
+        let specs = read_task_specs(file)?;
+        info!("Creating {} task(s) from {}", specs.len(), file.display());
+        let config = Config::from_str(&read_local_config_file()?)?;
+
+        let mut rows = Vec::new();
+        let mut failed = 0;
+        for spec in specs {
+            standard(&format!("Creating task '{}'...", spec.name));
+            blank();
+            let mut tags = self.tags.clone();
+            tags.extend(
+                spec.tags
+                    .iter()
+                    .filter(|tag| !self.tags.contains(tag))
+                    .cloned(),
+            );
+            let command = TaskCommand {
+                task_name: Some(spec.name.clone()),
+                from_issue: None,
+                file: None,
+                base: spec.base.clone().or(self.base.clone()),
+                clone: None,
+                from_branch: false,
+                tags,
+                kind: spec.kind.or(self.kind),
+                start: false,
+                prompt: None,
+            };
+            let created = command.branch_name().and_then(|branch| {
+                command.execute()?;
+                if let Some(prompt) = &spec.prompt {
+                    let worktree = task_worktree(&branch)?;
+                    write_context(&config.task_dir(&worktree.path), prompt)?;
+                    let mut record = load_task(&branch)?;
+                    record.add_prompt(prompt, "task --file");
+                    save_task(&record)?;
+                }
+                Ok(branch)
+            });
+            blank();
+            rows.push(match created {
+                Ok(branch) => BatchRow {
+                    task: branch,
+                    result: "created".color(THEME.success).to_string(),
+                },
+                Err(e) => {
+                    error!("Failed to create task '{}': {}", spec.name, e);
+                    failed += 1;
+                    BatchRow {
+                        task: spec.name,
+                        result: e.message().color(THEME.error).to_string(),
+                    }
+                }
+            });
+        }
+
+        table(&rows, true);
+        let total = rows.len();
+        if failed > 0 {
+            return Err(CommandError::new(&format!(
+                "{failed} of {total} task(s) could not be created"
+            )));
+        }
+        blank();
+        success(&format!("Created {total} task(s)"));
+        Ok(())
+    }
+
+    /// The branch to create: the slug of the given name, prefixed by its
+    /// kind. A slug that is already taken is rejected rather than reused.
+    pub(crate) fn branch_name(&self) -> CommandResult<String> {
+        let issue = self.issue()?;
+        self.branch_name_for(&self.name(issue.as_ref()))
+    }
+
+    fn branch_name_for(&self, task_name: &str) -> CommandResult<String> {
+        let slug = slugify(task_name);
+        if slug.is_empty() {
+            return Err(CommandError::new(&format!(
+                "Task name '{task_name}' has no characters usable in a branch name"
+            )));
+        }
+        let kind = match self.kind {
+            Some(kind) => Some(kind),
+            None => Config::from_str(&read_local_config_file()?)?.default_task_kind,
+        };
+        let branch = match kind {
+            Some(kind) => kind.branch_name(&slug),
+            None => slug,
+        };
+
+        if branch_exists(&branch)? || find_worktree(&branch)?.is_some() {
+            return Err(if branch == task_name {
+                CommandError::new(&format!("Task '{branch}' already exists"))
+            } else {
+                CommandError::new(&format!(
+                    "Task name '{task_name}' becomes '{branch}', which is already in use"
+                ))
+            });
+        }
+        info!("Using branch name: {}", branch);
+        Ok(branch)
+    }
+
+    /// The name the task was given, else the one derived from its issue.
+    fn name(&self, issue: Option<&Issue>) -> String {
+        match (&self.task_name, issue) {
+            (Some(name), _) => name.clone(),
+            (None, Some(issue)) => issue.task_name(),
+            (None, None) => String::new(),
+        }
+    }
+
+    /// Fetch the issue the task is created from, if any.
+    fn issue(&self) -> CommandResult<Option<Issue>> {
+        let Some(reference) = &self.from_issue else {
+            return Ok(None);
+        };
+        info!("Fetching issue: {}", reference);
+        Ok(Some(fetch_issue(reference, &current_dir()?)?))
+    }
+
+    /// Load the record of the task being cloned, if any.
+    fn clone_source(&self) -> CommandResult<Option<TaskRecord>> {
+        let Some(existing) = &self.clone else {
+            return Ok(None);
+        };
+
+        task_worktree(existing)?;
+        info!("Cloning settings from task: {}", existing);
+        Ok(Some(load_task(existing)?))
+    }
+}
+
+#[derive(Tabled)]
+struct BatchRow {
+    task: String,
+    result: String,
+}
+
+/// Write the task's context for the agent into `task_dir`, keeping it out
+/// of git's view of the worktree so it is never taken for the task's work.
+fn write_context(task_dir: &str, content: &str) -> CommandResult<()> {
+    write_task_context(Path::new(task_dir), content)?;
+    exclude_path(task_dir, &format!("**/{TASK_CONTEXT_FILE}"))?;
+    Ok(())
+}
+
+/// Run the `post-task` hook for a task that ended up `status`.
+fn run_post_task_hook(task_name: &str, worktree_path: &str, status: &str) -> CommandResult<()> {
+    run_hook(
+        Hook::PostTask,
+        &HookContext {
+            task_name,
+            worktree: worktree_path,
+            status: Some(status),
+            ..HookContext::default()
+        },
+    )
+}
+
+/// Draw git's progress on the current step line, unless the project has
+/// turned progress bars off.
+fn progress_reporter(
+    config: &Config,
+    message: &'static str,
+    position: Position,
+) -> impl FnMut(&GitProgress) {
+    let enabled = config.progress;
+    move |progress| {
+        if enabled {
+            step_progress(message, position, Some((&progress.label, progress.percent)));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -76,10 +531,19 @@ mod tests {
     #[test]
     fn test_task_command_creation() {
         let cmd = TaskCommand {
-            task_name: "feat/test-feature".to_string(),
+            task_name: Some("feat/test-feature".to_string()),
+            from_issue: None,
+            file: None,
+            base: None,
+            clone: None,
+            from_branch: false,
+            tags: Vec::new(),
+            kind: None,
+            start: false,
+            prompt: None,
         };
 
-        assert_eq!(cmd.task_name, "feat/test-feature");
+        assert_eq!(cmd.task_name.as_deref(), Some("feat/test-feature"));
     }
 
     #[test]
@@ -93,10 +557,18 @@ mod tests {
 
         for task_name in test_cases {
             let cmd = TaskCommand {
-                task_name: task_name.to_string(),
+                task_name: Some(task_name.to_string()),
+                from_issue: None,
+                file: None,
+                base: None,
+                clone: None,
+                from_branch: false,
+                tags: Vec::new(),
+                kind: None,
+                start: false,
+                prompt: None,
             };
-            assert_eq!(cmd.task_name, task_name);
-            assert!(!cmd.task_name.is_empty());
+            assert_eq!(cmd.task_name.as_deref(), Some(task_name));
         }
     }
 
@@ -112,7 +584,16 @@ mod tests {
     #[test]
     fn test_task_command_debug_formatting() {
         let cmd = TaskCommand {
-            task_name: "feat/debug-test".to_string(),
+            task_name: Some("feat/debug-test".to_string()),
+            from_issue: None,
+            file: None,
+            base: None,
+            clone: None,
+            from_branch: false,
+            tags: Vec::new(),
+            kind: None,
+            start: false,
+            prompt: None,
         };
 
         let debug_str = format!("{cmd:?}");
@@ -0,0 +1,76 @@
+use std::process::Command;
+
+use clap::Args;
+use owo_colors::OwoColorize;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        errors::CommandError,
+        output::{standard, success},
+        theme::THEME,
+        upgrade::{current_version, install_release, installed_by_npm, is_newer, latest_release},
+    },
+};
+
+#[derive(Args, Debug)]
+pub struct UpgradeCommand {
+    /// Only report whether a newer release is available
+    #[arg(long)]
+    pub check: bool,
+}
+
+impl UpgradeCommand {
+    #[instrument(name = "upgrade_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        let current = current_version();
+        let release = latest_release()?;
+        info!("Latest release is {}, running {}", release.version, current);
+
+        if !is_newer(&release.version, current) {
+            success(&format!("claudectl {current} is up to date"));
+            return Ok(());
+        }
+        if self.check {
+            standard(&format!(
+                "claudectl {} is available (installed: {current}). Run {} to install it",
+                release.version,
+                "claudectl upgrade".color(THEME.info)
+            ));
+            return Ok(());
+        }
+
+        let exe = std::env::current_exe()
+            .and_then(|exe| exe.canonicalize())
+            .map_err(|e| CommandError::new(&format!("Failed to find claudectl itself: {e}")))?;
+        if installed_by_npm(&exe) {
+            return Err(CommandError::new(&format!(
+                "claudectl was installed with npm; run `npm install -g claudectl@{}` instead",
+                release.version
+            )));
+        }
+
+        standard(&format!(
+            "Upgrading claudectl {current} to {}",
+            release.version
+        ));
+        install_release(&release, &exe)?;
+        reinstall_completions(&exe);
+        success(&format!("Upgraded claudectl to {}", release.version));
+        Ok(())
+    }
+}
+
+/// Install the new binary's completions, which may cover commands this one
+/// didn't have. A failure here leaves the upgrade in place.
+fn reinstall_completions(exe: &std::path::Path) {
+    match Command::new(exe)
+        .args(["completions", "--install"])
+        .status()
+    {
+        Ok(status) if status.success() => {}
+        Ok(status) => warn!("Installing completions exited with {}", status),
+        Err(e) => warn!("Failed to install completions: {}", e),
+    }
+}
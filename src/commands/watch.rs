@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::thread::sleep;
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Local, Utc};
+use clap::Args;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::gitignore::Gitignore;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    commands::{CommandResult, task::TaskCommand},
+    utils::{
+        config::Config,
+        errors::CommandError,
+        fs::{
+            LOG_LINES, current_dir, is_ignored, read_ignore_rules, read_local_config_file, trim_log,
+        },
+        git::tracked_files,
+        lock::stale_locks,
+        output::{blank, error, standard, success},
+        supervise::{Outcome, RestartPolicy, Supervisor},
+        tasks::{SessionRecord, stored_tasks},
+    },
+};
+
+/// Modification time and size of each watched file, by path.
+type Snapshot = BTreeMap<String, (SystemTime, u64)>;
+
+/// How often a supervising watch trims the diagnostics log it keeps writing.
+const LOG_TRIM_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Args, Debug)]
+pub struct WatchCommand {
+    /// Start a task when files matching this pattern change (repeatable)
+    #[arg(
+        long = "on-change",
+        value_name = "GLOB",
+        required_unless_present = "supervise"
+    )]
+    pub patterns: Vec<String>,
+
+    /// Prompt template from the project config for the task's session
+    #[arg(long, value_name = "NAME", required_unless_present = "supervise")]
+    pub template: Option<String>,
+
+    /// Watch the project's sessions instead, handling crashed ones as the
+    /// project config's supervision policy says
+    #[arg(long, conflicts_with_all = ["patterns", "template"])]
+    pub supervise: bool,
+
+    /// Seconds between checks for changes
+    #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: u64,
+}
+
+impl WatchCommand {
+    #[instrument(name = "watch_command")]
+    pub fn execute(&self) -> CommandResult<()> {
+        info!("Executing watch command for: {:?}", self.patterns);
+        let raw_config = read_local_config_file()?;
+        let config = Config::from_str(&raw_config)?;
+        let interval = Duration::from_secs(self.interval);
+        if self.supervise {
+            return supervise(&config, interval);
+        }
+        let template = self.template.as_deref().unwrap_or_default();
+        // Checked up front so a typo fails before anything is watched
+        config.render_template(template, &[])?;
+        let globs = glob_set(&self.patterns)?;
+        let root = current_dir()?;
+        let ignored = read_ignore_rules(&root)?;
+
+        let mut known = snapshot(&root, &globs, &ignored)?;
+        standard(&format!(
+            "Watching {} file(s) matching {}. Press Ctrl-C to stop.",
+            known.len(),
+            self.patterns.join(", ")
+        ));
+
+        loop {
+            sleep(interval);
+            let mut current = snapshot(&root, &globs, &ignored)?;
+            if current == known {
+                continue;
+            }
+            // Wait for the changes to settle so one save burst starts one task
+            loop {
+                sleep(interval);
+                let next = snapshot(&root, &globs, &ignored)?;
+                if next == current {
+                    break;
+                }
+                current = next;
+            }
+            let changed = changed_paths(&known, &current);
+            known = current;
+            info!("Watched files changed: {:?}", changed);
+
+            blank();
+            standard(&format!("{} watched file(s) changed", changed.len()));
+            if let Err(e) = start_task(&config, template, &changed) {
+                warn!("Failed to start task for changes: {}", e);
+                blank();
+                error(&format!("Failed to start task: {}", e.message()));
+            }
+            blank();
+            standard("Watching for changes...");
+        }
+    }
+}
+
+/// Create a task for `changed` and run its session, which blocks until the
+/// session exits.
+fn start_task(config: &Config, template: &str, changed: &[String]) -> CommandResult<()> {
+    let prompt = config.render_template(template, changed)?;
+    let task_name = format!(
+        "watch/{}-{}",
+        template,
+        Local::now().format("%Y%m%d-%H%M%S")
+    );
+    TaskCommand {
+        task_name: Some(task_name),
+        from_issue: None,
+        file: None,
+        base: None,
+        clone: None,
+        from_branch: false,
+        tags: vec!["watch".to_string()],
+        kind: None,
+        start: true,
+        prompt: Some(prompt),
+    }
+    .execute()
+}
+
+/// Check on the project's sessions every `interval` until stopped. Sessions
+/// whose claudectl process is gone without releasing its lock have crashed,
+/// as have those that recorded a failed exit since supervision began; they
+/// are restarted or marked stopped.
+fn supervise(config: &Config, interval: Duration) -> CommandResult<()> {
+    let policy = &config.supervision;
+    let mut supervisor = Supervisor::new(policy.clone());
+    standard(&match policy.restart {
+        RestartPolicy::Never => format!(
+            "Supervising sessions in '{}'; crashed ones are marked stopped. Press Ctrl-C to stop.",
+            config.project_name
+        ),
+        RestartPolicy::OnCrash => format!(
+            "Supervising sessions in '{}'; crashed ones are restarted up to {} time(s). Press Ctrl-C to stop.",
+            config.project_name, policy.max_restarts
+        ),
+        RestartPolicy::Always => format!(
+            "Supervising sessions in '{}'; ended ones are restarted up to {} time(s). Press Ctrl-C to stop.",
+            config.project_name, policy.max_restarts
+        ),
+    });
+
+    let supervising_since = Utc::now();
+    // When the crashed session last handled in each task ended
+    let mut handled: BTreeMap<String, DateTime<Utc>> = BTreeMap::new();
+    let mut log_trimmed = Instant::now();
+    loop {
+        // Sessions `kill` is stopping are not crashes; it clears their locks
+        for session in stale_locks().into_iter().filter(|s| !s.lock.stopping) {
+            info!("Session in '{}' crashed", session.task_name);
+            let outcome = supervisor.handle_crash(&session);
+            report(&session.task_name, &session.lock.holder(), outcome);
+        }
+        for record in stored_tasks().unwrap_or_default() {
+            let Some(session) = record.last_session.filter(SessionRecord::crashed) else {
+                continue;
+            };
+            let since = handled
+                .get(&record.name)
+                .copied()
+                .unwrap_or(supervising_since);
+            if session.ended_at <= since {
+                continue;
+            }
+            handled.insert(record.name.clone(), session.ended_at);
+            info!("Session in '{}' {}", record.name, session.status());
+            let outcome = supervisor.handle_exit(&record.name, false, &session.status());
+            report(&record.name, &session.status(), outcome);
+        }
+        for (task_name, succeeded) in supervisor.reap() {
+            if succeeded {
+                success(&format!("Restarted session in '{task_name}' finished"));
+            } else {
+                error(&format!("Restarted session in '{task_name}' failed"));
+            }
+            let cause = if succeeded {
+                "restarted run finished"
+            } else {
+                "restarted run failed"
+            };
+            let outcome = supervisor.handle_exit(&task_name, succeeded, cause);
+            report(&task_name, cause, outcome);
+        }
+        for (task_name, started) in supervisor.start_due() {
+            match started {
+                Ok(restart) => standard(&format!(
+                    "Restarted the session in '{task_name}' (restart {restart} of {})",
+                    policy.max_restarts
+                )),
+                Err(e) => {
+                    warn!("Failed to restart '{}': {}", task_name, e);
+                    error(&format!(
+                        "Failed to restart the session in '{task_name}': {}",
+                        e.message()
+                    ));
+                }
+            }
+        }
+        if log_trimmed.elapsed() >= LOG_TRIM_INTERVAL {
+            if let Err(e) = trim_log(LOG_LINES) {
+                warn!("Failed to trim the log: {}", e);
+            }
+            log_trimmed = Instant::now();
+        }
+        sleep(interval);
+    }
+}
+
+/// Say what the supervisor made of a session in `task_name` that ended,
+/// described by `cause`.
+fn report(task_name: &str, cause: &str, outcome: CommandResult<Outcome>) {
+    match outcome {
+        Ok(Outcome::Restarting(delay)) => standard(&format!(
+            "Session in '{task_name}' ({cause}) ended; restarting it in {}s",
+            delay.as_secs()
+        )),
+        Ok(Outcome::Stopped) => standard(&format!(
+            "Session in '{task_name}' ({cause}) crashed; marked it stopped"
+        )),
+        Ok(Outcome::Finished) => {}
+        Err(e) => {
+            warn!("Failed to handle crash in '{}': {}", task_name, e);
+            error(&format!(
+                "Failed to handle the crashed session in '{task_name}': {}",
+                e.message()
+            ));
+        }
+    }
+}
+
+fn glob_set(patterns: &[String]) -> CommandResult<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|e| CommandError::new(&format!("Invalid pattern '{pattern}': {e}")))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| CommandError::new(&format!("Invalid patterns: {e}")))
+}
+
+/// Files under `root` matching `globs`, skipping anything git or
+/// `.claudectlignore` ignores.
+fn snapshot(root: &Path, globs: &GlobSet, ignored: &Gitignore) -> CommandResult<Snapshot> {
+    let files = tracked_files(&root.to_string_lossy())?;
+    Ok(files
+        .into_iter()
+        .filter(|file| globs.is_match(file) && !is_ignored(ignored, file))
+        .filter_map(|file| {
+            // Tracked files deleted from the checkout drop out, which counts
+            // as a change
+            let metadata = std::fs::metadata(root.join(&file)).ok()?;
+            Some((file, (metadata.modified().ok()?, metadata.len())))
+        })
+        .collect())
+}
+
+/// Paths added, modified or removed between two snapshots.
+fn changed_paths(before: &Snapshot, after: &Snapshot) -> Vec<String> {
+    let mut changed: Vec<String> = after
+        .iter()
+        .filter(|(path, state)| before.get(*path) != Some(state))
+        .map(|(path, _)| path.clone())
+        .collect();
+    changed.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .cloned(),
+    );
+    changed.sort();
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_set_matches_relative_paths() {
+        let globs = glob_set(&["tests/**".to_string(), "*.toml".to_string()]).unwrap();
+        assert!(globs.is_match("tests/integration/task.rs"));
+        assert!(globs.is_match("Cargo.toml"));
+        assert!(!globs.is_match("src/main.rs"));
+        assert!(!globs.is_match("npm/package.toml"));
+        assert!(glob_set(&["tests/[".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_changed_paths() {
+        let t0 = SystemTime::UNIX_EPOCH;
+        let t1 = t0 + Duration::from_secs(1);
+        let before: Snapshot = [
+            ("a.rs".to_string(), (t0, 1)),
+            ("b.rs".to_string(), (t0, 1)),
+            ("c.rs".to_string(), (t0, 1)),
+        ]
+        .into();
+        let after: Snapshot = [
+            ("a.rs".to_string(), (t0, 1)),
+            ("b.rs".to_string(), (t1, 1)),
+            ("d.rs".to_string(), (t0, 1)),
+        ]
+        .into();
+        assert_eq!(changed_paths(&before, &after), vec!["b.rs", "c.rs", "d.rs"]);
+        assert!(changed_paths(&before, &before).is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+use clap::{Args, Subcommand, ValueHint};
+use owo_colors::OwoColorize;
+use tabled::Tabled;
+use tracing::{error, info, instrument};
+
+use crate::{
+    commands::CommandResult,
+    utils::{
+        errors::{CommandError, GitError},
+        events::{EventKind, record_event},
+        git::{
+            ahead_behind, branch_point, changed_files, conflicted_files, fetch_origin,
+            merge_into_worktree, origin_url, rebase, remote_branch_exists,
+        },
+        lock::current_lock,
+        output::{
+            Position, blank, error as output_error, standard, step, step_end, step_fail, success,
+            table,
+        },
+        tasks::{load_task, resolve_task_name, save_task, task_worktree},
+        theme::THEME,
+    },
+};
+
+#[derive(Tabled)]
+struct ConflictRow {
+    file: String,
+}
+
+#[derive(Args, Debug)]
+pub struct WorktreeCommand {
+    #[command(subcommand)]
+    pub action: WorktreeAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WorktreeAction {
+    /// Bring a task up to date with its base branch
+    Sync(WorktreeSyncCommand),
+}
+
+#[derive(Args, Debug)]
+pub struct WorktreeSyncCommand {
+    /// The task to sync (picked interactively if omitted)
+    #[arg(value_hint = ValueHint::Other)]
+    pub task_name: Option<String>,
+
+    /// Merge the base branch into the task instead of rebasing onto it
+    #[arg(long)]
+    pub merge: bool,
+
+    /// Sync with the base as it is locally, without fetching origin first
+    #[arg(long)]
+    pub no_fetch: bool,
+
+    /// Sync even if a session holds the task, e.g. from inside that session
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl WorktreeCommand {
+    pub fn execute(&self) -> CommandResult<()> {
+        match &self.action {
+            WorktreeAction::Sync(cmd) => cmd.execute(),
+        }
+    }
+}
+
+impl WorktreeSyncCommand {
+    #[instrument(name = "worktree_sync_command", fields(task_name = ?self.task_name))]
+    pub fn execute(&self) -> CommandResult<()> {
+        let task_name = resolve_task_name(self.task_name.as_deref())?;
+        info!("Executing worktree sync command for: {}", task_name);
+        let worktree = task_worktree(&task_name)?;
+        let mut record = load_task(&task_name)?;
+
+        // 1. Make sure git can rewrite the worktree
+        if let Some(lock) = current_lock(&task_name).filter(|_| !self.force) {
+            return Err(CommandError::new(&format!(
+                "Task '{task_name}' is locked by {}; wait for the session to end or pass --force",
+                lock.holder()
+            )));
+        }
+        let uncommitted = changed_files(&worktree.path)?;
+        if !uncommitted.is_empty() {
+            return Err(CommandError::new(&format!(
+                "Task '{task_name}' has {} uncommitted file(s); commit or stash them before syncing",
+                uncommitted.len()
+            )));
+        }
+
+        // 2. Bring the base up to date
+        if !self.no_fetch && origin_url().is_ok() {
+            step("Fetching latest changes from origin...", Position::First);
+            fetch_origin(&mut |_| {}).inspect_err(|_| step_fail())?;
+            step_end();
+        }
+        let target = sync_target(record.base())?;
+        let (_, behind) = ahead_behind(&worktree.path, &target)?;
+        if behind == 0 {
+            blank();
+            success(&format!("Task '{task_name}' is up to date with {target}"));
+            return Ok(());
+        }
+
+        // 3. Rebase or merge
+        let message = if self.merge {
+            format!("Merging {behind} new commit(s) from {target} into '{task_name}'...")
+        } else {
+            format!("Rebasing '{task_name}' onto {behind} new commit(s) from {target}...")
+        };
+        step(&message, Position::Last);
+        let synced = if self.merge {
+            merge_into_worktree(&worktree.path, &target)
+        } else {
+            rebase(&worktree.path, &target)
+        };
+        if let Err(e) = synced {
+            error!("Failed to sync task: {}", e);
+            step_fail();
+            blank();
+            return Err(self.stopped_on_conflicts(e, &worktree.path, &task_name));
+        }
+        step_end();
+        blank();
+
+        // The task now forks from the new base, so a task without commits
+        // of its own still doesn't count as merged
+        record.base_commit = Some(branch_point(&worktree.path, &target)?);
+        save_task(&record)?;
+
+        let how = if self.merge { "merged" } else { "rebased onto" };
+        record_event(
+            EventKind::Synced,
+            &task_name,
+            Some(format!("{how} {target} ({behind} commit(s))")),
+        );
+        success(&format!("Synced '{task_name}' with {target}"));
+        Ok(())
+    }
+
+    /// List the conflicting files and explain how to carry on, or pass the
+    /// error on unchanged when git failed for another reason.
+    fn stopped_on_conflicts(&self, e: GitError, dir: &str, task_name: &str) -> CommandError {
+        let files = match conflicted_files(dir) {
+            Ok(files) if !files.is_empty() => files,
+            _ => return e.into(),
+        };
+        output_error(&format!(
+            "Syncing '{task_name}' stopped on conflicts in {} file(s):",
+            files.len()
+        ));
+        let rows: Vec<ConflictRow> = files
+            .iter()
+            .map(|file| ConflictRow {
+                file: file.as_str().color(THEME.warning).to_string(),
+            })
+            .collect();
+        table(&rows, false);
+        blank();
+        let (continue_with, abort_with) = if self.merge {
+            ("git commit", "git merge --abort")
+        } else {
+            ("git rebase --continue", "git rebase --abort")
+        };
+        standard(&format!(
+            "Resolve them in {dir}, or run `claudectl resolve {task_name}`, then `{continue_with}` there"
+        ));
+        CommandError::new(&format!(
+            "Sync stopped on conflicts; `{abort_with}` in the worktree undoes it"
+        ))
+    }
+}
+
+/// What a task with this base is synced with: the base itself, or its local
+/// branch when origin doesn't have it.
+fn sync_target(base: &str) -> CommandResult<String> {
+    match base.strip_prefix("origin/") {
+        Some(branch) if !remote_branch_exists(branch)? => Ok(branch.to_string()),
+        _ => Ok(base.to_string()),
+    }
+}
@@ -0,0 +1,20 @@
+//! claudectl orchestrates Claude Code agents through git worktrees.
+//!
+//! The [`api`] module is the entry point for embedding it in other tools.
+//! The rest is the command-line interface's own code, public so the binary
+//! can use it, and may change between releases.
+
+// Config and task records parse their JSON with `from_str`, which returns
+// claudectl's own errors rather than implementing `FromStr`
+#![allow(clippy::should_implement_trait)]
+
+pub mod api;
+#[doc(hidden)]
+pub mod cli;
+#[doc(hidden)]
+pub mod commands;
+#[doc(hidden)]
+pub mod utils;
+
+#[doc(hidden)]
+pub use cli::Cli;
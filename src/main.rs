@@ -1,30 +1,27 @@
-use crate::utils::output::{blank, error};
+use std::sync::Mutex;
+
 use clap::Parser;
+use claudectl::commands::reconcile::reconcile_on_startup;
+use claudectl::utils::config::set_active_profile;
+use claudectl::utils::fs::{LOG_LINES, open_log};
+use claudectl::utils::output::{blank, error, set_plain, standard};
+use claudectl::utils::timings::{TimingLayer, print_timings, record_slow_operations, timings};
+use claudectl::utils::transaction::recover_interrupted;
+use claudectl::{Cli, commands};
 use tracing::{error as log_error, info};
-
-mod commands;
-mod utils;
-
-#[derive(Parser)]
-#[command(name = "claudectl")]
-#[command(
-    about = "A CLI tool for orchestrating Claude Code agents through the use of git worktrees.",
-    help_template = "{about}\n\nUsage: claudectl [OPTIONS] [COMMAND]\n\nCommands:\n  init         Initialize the project for claudectl\n  task         Create a new task worktree\n  list         List all task worktrees\n  rm           Remove a task worktree\n\nUtility:\n  completions  Generate shell completions\n  repair       Repair shell completions and configuration\n  help         Print this message or the help of the given subcommand(s)\n\n{options}"
-)]
-pub struct Cli {
-    #[command(subcommand)]
-    command: Option<commands::Commands>,
-
-    /// Enable debug logging
-    #[arg(long, global = true, help = "Enable debug logging output")]
-    debug: bool,
-}
+use tracing_subscriber::{
+    EnvFilter, Layer, filter::filter_fn, fmt, layer::SubscriberExt, util::SubscriberInitExt,
+};
 
 fn main() {
     let cli = Cli::parse();
 
-    // Initialize logging based on debug flag
-    init_logging(cli.debug);
+    // Completions run on every keypress, so they stay out of the log
+    let keep_log = !matches!(cli.command, Some(commands::Commands::Complete(_)));
+    let show_timings = cli.timings;
+    init_logging(cli.debug, keep_log, show_timings);
+    set_plain(cli.plain);
+    set_active_profile(cli.profile);
 
     info!("Starting claudectl");
 
@@ -37,34 +34,111 @@ fn main() {
         }
     };
 
-    if let Err(err) = commands::handle_command(command) {
+    // Completion output is parsed by the shell, so leave recovery to a
+    // command the user runs
+    if !matches!(
+        command,
+        commands::Commands::Complete(_) | commands::Commands::Completions(_)
+    ) {
+        recover();
+    }
+
+    // Sessions a crash left behind are offered up before anything else runs
+    // in them; `reconcile` does this itself
+    if !matches!(
+        command,
+        commands::Commands::Complete(_)
+            | commands::Commands::Completions(_)
+            | commands::Commands::Reconcile(_)
+    ) {
+        reconcile_on_startup();
+    }
+
+    let result = commands::handle_command(command);
+    finish_timings(show_timings, keep_log);
+    if let Err(err) = result {
         log_error!("Command failed: {}", err);
         blank();
         error(&err.message());
-        std::process::exit(1);
+        std::process::exit(err.exit_code());
     }
 
     info!("Command completed successfully");
 }
 
-fn init_logging(debug: bool) {
-    if debug {
-        // Enable debug logging to stderr when --debug flag is used
-        tracing_subscriber::fmt()
+/// Report this run's timings if asked to, and keep its slow operations for
+/// `doctor`.
+fn finish_timings(show: bool, record: bool) {
+    let timings = timings();
+    if show {
+        print_timings(&timings);
+    }
+    if record {
+        record_slow_operations(&timings);
+    }
+}
+
+/// Roll back whatever an interrupted run of claudectl left half done.
+fn recover() {
+    match recover_interrupted() {
+        Ok(recoveries) => {
+            for recovery in &recoveries {
+                standard(&format!(
+                    "Rolled back {} step(s) of an operation that was interrupted",
+                    recovery.undone
+                ));
+                for failure in &recovery.failures {
+                    error(&format!("Could not roll back: {failure}"));
+                }
+                blank();
+            }
+        }
+        Err(e) => {
+            log_error!("Failed to recover interrupted operation: {}", e);
+            error(&format!("Could not recover an interrupted operation: {e}"));
+            blank();
+        }
+    }
+}
+
+fn init_logging(debug: bool, keep_log: bool, show_timings: bool) {
+    // Always keep a log of recent runs, so a failure can be looked into
+    // with `claudectl logs` without re-running it under --debug
+    let file_layer = keep_log
+        .then(|| open_log(LOG_LINES).ok())
+        .flatten()
+        .map(|file| {
+            fmt::layer()
+                .with_writer(Mutex::new(file))
+                .with_ansi(false)
+                .with_target(true)
+                .with_filter(EnvFilter::new("claudectl=debug"))
+        });
+
+    // Enable debug logging to stderr when --debug flag is used
+    let stderr_layer = debug.then(|| {
+        fmt::layer()
             .with_writer(std::io::stderr)
-            .with_env_filter(
-                tracing_subscriber::EnvFilter::try_from_default_env()
-                    .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("claudectl=debug")),
-            )
             .with_target(true)
             .with_file(true)
             .with_line_number(true)
-            .init();
-    } else {
-        // Disable logging when debug flag is not used
-        tracing_subscriber::fmt()
-            .with_writer(std::io::sink)
-            .with_env_filter(tracing_subscriber::EnvFilter::new("off"))
-            .init();
-    }
+            .with_filter(
+                EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| EnvFilter::new("claudectl=debug")),
+            )
+    });
+
+    // Timings are kept for `--timings`, and slow operations for `doctor`
+    // whenever the run is logged
+    let timing_layer = (show_timings || keep_log).then(|| {
+        TimingLayer::new(show_timings).with_filter(filter_fn(|metadata| {
+            metadata.target().starts_with("claudectl")
+        }))
+    });
+
+    tracing_subscriber::registry()
+        .with(file_layer)
+        .with(stderr_layer)
+        .with(timing_layer)
+        .init();
 }
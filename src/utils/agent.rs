@@ -0,0 +1,172 @@
+use crate::utils::claude::{
+    Invocation, RunOutput, is_claude_installed, launch_session, one_shot, resume_session, run_print,
+};
+use crate::utils::config::Permissions;
+use crate::utils::errors::ClaudeError;
+use clap::ValueEnum;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+type AgentResult<T> = Result<T, ClaudeError>;
+
+/// A command-line coding agent that tasks can run.
+///
+/// Commands start, resume and prompt agents through this trait, so adding
+/// another CLI agent means implementing it and adding an `AgentKind`.
+pub trait AgentAdapter {
+    /// The agent's name as users know it, e.g. in messages.
+    fn name(&self) -> &'static str;
+
+    /// Whether the program `invocation` runs can be found.
+    fn is_installed(&self, invocation: &Invocation) -> AgentResult<bool>;
+
+    /// How to install the agent when it cannot be found.
+    fn install_hint(&self) -> &'static str;
+
+    /// Start an interactive session in `cwd`, returning its exit code once
+    /// it exits (`None` if killed by a signal). `prompt` is sent as its first
+    /// message.
+    fn spawn(
+        &self,
+        cwd: &str,
+        permissions: &Permissions,
+        prompt: Option<&str>,
+        invocation: &Invocation,
+    ) -> AgentResult<Option<i32>>;
+
+    /// Reopen the most recent session in `cwd`, returning its exit code once
+    /// it exits.
+    fn resume(
+        &self,
+        cwd: &str,
+        permissions: &Permissions,
+        invocation: &Invocation,
+    ) -> AgentResult<Option<i32>>;
+
+    /// Send `prompt` without a terminal session, streaming the response to
+    /// the terminal and returning it once the agent exits.
+    fn send(
+        &self,
+        cwd: &str,
+        permissions: &Permissions,
+        prompt: &str,
+        invocation: &Invocation,
+    ) -> AgentResult<RunOutput>;
+
+    /// Ask for a single response to `prompt` about `input`, e.g. a summary.
+    fn one_shot(
+        &self,
+        prompt: &str,
+        input: &str,
+        cwd: &str,
+        invocation: &Invocation,
+    ) -> AgentResult<String>;
+
+    /// What the agent reported spending, in US dollars, if `output` says.
+    fn cost(&self, output: &str) -> Option<f64>;
+}
+
+/// The agents claudectl can run.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentKind {
+    /// Claude Code, run through the `claude` CLI
+    #[default]
+    Claude,
+}
+
+impl AgentKind {
+    pub fn adapter(&self) -> &'static dyn AgentAdapter {
+        match self {
+            Self::Claude => &Claude,
+        }
+    }
+}
+
+/// Claude Code, run through the `claude` CLI.
+pub struct Claude;
+
+impl AgentAdapter for Claude {
+    fn name(&self) -> &'static str {
+        "claude"
+    }
+
+    fn is_installed(&self, invocation: &Invocation) -> AgentResult<bool> {
+        is_claude_installed(&invocation.program)
+    }
+
+    fn install_hint(&self) -> &'static str {
+        "Install Claude Code and make sure `claude` is on your PATH"
+    }
+
+    fn spawn(
+        &self,
+        cwd: &str,
+        permissions: &Permissions,
+        prompt: Option<&str>,
+        invocation: &Invocation,
+    ) -> AgentResult<Option<i32>> {
+        launch_session(cwd, permissions, prompt, invocation)
+    }
+
+    fn resume(
+        &self,
+        cwd: &str,
+        permissions: &Permissions,
+        invocation: &Invocation,
+    ) -> AgentResult<Option<i32>> {
+        resume_session(cwd, permissions, invocation)
+    }
+
+    fn send(
+        &self,
+        cwd: &str,
+        permissions: &Permissions,
+        prompt: &str,
+        invocation: &Invocation,
+    ) -> AgentResult<RunOutput> {
+        run_print(cwd, permissions, prompt, invocation)
+    }
+
+    fn one_shot(
+        &self,
+        prompt: &str,
+        input: &str,
+        cwd: &str,
+        invocation: &Invocation,
+    ) -> AgentResult<String> {
+        one_shot(prompt, input, cwd, invocation)
+    }
+
+    fn cost(&self, output: &str) -> Option<f64> {
+        // `--output-format json` reports `total_cost_usd`; `/cost` prints
+        // `Total cost: $0.0123`
+        let cost =
+            Regex::new(r#"(?:"total_cost_usd"\s*:\s*|Total cost:\s*\$)([0-9]+(?:\.[0-9]+)?)"#)
+                .ok()?;
+        cost.captures_iter(output)
+            .last()
+            .and_then(|captures| captures[1].parse().ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_agent_is_claude() {
+        assert_eq!(AgentKind::default().adapter().name(), "claude");
+    }
+
+    #[test]
+    fn test_claude_cost_from_json_and_text() {
+        let claude = AgentKind::Claude.adapter();
+        assert_eq!(
+            claude.cost(r#"{"type":"result","total_cost_usd":0.0421,"is_error":false}"#),
+            Some(0.0421)
+        );
+        assert_eq!(claude.cost("Total cost:            $1.20\n"), Some(1.2));
+        assert_eq!(claude.cost("Done."), None);
+    }
+}
@@ -0,0 +1,261 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Deserialize, Serialize};
+use tar::{Archive as Tarball, Builder, Header};
+
+use crate::utils::errors::{CommandError, FileSystemError};
+use crate::utils::fs::{archive_files, archives_dir};
+use crate::utils::tasks::TaskRecord;
+
+const EXTENSION: &str = ".tar.gz";
+const MANIFEST_FILE: &str = "manifest.json";
+const TASK_FILE: &str = "task.json";
+const EVENTS_FILE: &str = "events.jsonl";
+const DIFF_FILE: &str = "changes.diff";
+const BUNDLE_FILE: &str = "task.bundle";
+
+/// What an archive holds, stored in it as `manifest.json`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Manifest {
+    pub task: String,
+    pub project: String,
+    pub archived_at: DateTime<Utc>,
+    /// Where the worktree was when the task was archived
+    pub worktree_path: String,
+}
+
+/// A task archived by `claudectl archive`.
+#[derive(Debug)]
+pub struct Archive {
+    /// File name without the extension, e.g. `feat__login-20240901-160300`
+    pub name: String,
+    pub path: PathBuf,
+    pub manifest: Manifest,
+    pub record: TaskRecord,
+}
+
+/// Everything written to a new archive.
+pub struct ArchiveContents<'a> {
+    pub manifest: &'a Manifest,
+    pub record: &'a TaskRecord,
+    /// The task's lines from the project's event log
+    pub events: &'a str,
+    /// Everything done in the task, for reading without git
+    pub diff: &'a str,
+    /// A git bundle of the branch, its checkpoints and uncommitted work
+    pub bundle: &'a Path,
+}
+
+/// Name for an archive of `task_name` taken at `at`; branch separators are
+/// flattened as in task file names.
+pub fn archive_name(task_name: &str, at: DateTime<Utc>) -> String {
+    format!(
+        "{}-{}",
+        task_name.replace('/', "__"),
+        at.format("%Y%m%d-%H%M%S")
+    )
+}
+
+/// Write a gzipped tarball of `contents` to the project's archive directory
+/// and return its path.
+pub fn write_archive(contents: &ArchiveContents) -> Result<PathBuf, CommandError> {
+    let manifest = contents.manifest;
+    let dir = archives_dir(&manifest.project)?;
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        FileSystemError::new(
+            &format!("Failed to create archive directory ({e})"),
+            &dir.to_string_lossy(),
+        )
+    })?;
+    let path = dir.join(format!(
+        "{}{EXTENSION}",
+        archive_name(&manifest.task, manifest.archived_at)
+    ));
+    let temp_path = path.with_extension("tmp");
+    let write_failed = |e: std::io::Error| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    };
+
+    let file = File::create(&temp_path).map_err(write_failed)?;
+    let mut builder = Builder::new(GzEncoder::new(file, Compression::default()));
+    let manifest_json = serde_json::to_string_pretty(manifest)
+        .map_err(|e| CommandError::new(&format!("Failed to serialize manifest: {e}")))?;
+    let entries = [
+        (MANIFEST_FILE, manifest_json),
+        (TASK_FILE, contents.record.to_string()?),
+        (EVENTS_FILE, contents.events.to_string()),
+        (DIFF_FILE, contents.diff.to_string()),
+    ];
+    for (name, content) in &entries {
+        let mut header = Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(manifest.archived_at.timestamp().max(0) as u64);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, name, content.as_bytes())
+            .map_err(write_failed)?;
+    }
+    builder
+        .append_path_with_name(contents.bundle, BUNDLE_FILE)
+        .map_err(write_failed)?;
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(write_failed)?;
+
+    std::fs::rename(&temp_path, &path).map_err(write_failed)?;
+    Ok(path)
+}
+
+/// Read an archive's manifest and task record.
+pub fn read_archive(path: &Path) -> Result<Archive, CommandError> {
+    let mut manifest = None;
+    let mut record = None;
+    for_each_entry(path, |name, entry| {
+        match name {
+            MANIFEST_FILE => manifest = Some(read_json(entry, path)?),
+            TASK_FILE => {
+                let mut raw = String::new();
+                entry
+                    .read_to_string(&mut raw)
+                    .map_err(|e| read_failed(e, path))?;
+                record = Some(TaskRecord::from_str(&raw)?);
+            }
+            _ => {}
+        }
+        Ok(())
+    })?;
+
+    let (Some(manifest), Some(record)) = (manifest, record) else {
+        return Err(CommandError::new(&format!(
+            "{} is not a claudectl archive",
+            path.display()
+        )));
+    };
+    Ok(Archive {
+        name: file_stem(path),
+        path: path.to_path_buf(),
+        manifest,
+        record,
+    })
+}
+
+/// Copy an archive's git bundle to `dest`.
+pub fn extract_bundle(path: &Path, dest: &Path) -> Result<(), CommandError> {
+    let mut found = false;
+    for_each_entry(path, |name, entry| {
+        if name == BUNDLE_FILE {
+            let mut file = File::create(dest).map_err(|e| {
+                FileSystemError::write_failed(&format!("IO error: {e}"), &dest.to_string_lossy())
+            })?;
+            std::io::copy(entry, &mut file).map_err(|e| read_failed(e, path))?;
+            found = true;
+        }
+        Ok(())
+    })?;
+    if !found {
+        return Err(CommandError::new(&format!(
+            "{} has no git bundle to restore from",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Every archive of a project, oldest first. Files that are not archives
+/// are skipped.
+pub fn list_archives(project_name: &str) -> Result<Vec<Archive>, CommandError> {
+    let mut archives: Vec<Archive> = archive_files(project_name)?
+        .iter()
+        .filter(|path| path.to_string_lossy().ends_with(EXTENSION))
+        .filter_map(|path| read_archive(path).ok())
+        .collect();
+    archives.sort_by_key(|archive| archive.manifest.archived_at);
+    Ok(archives)
+}
+
+/// Find an archive by path, by name, or by the task it holds, in which case
+/// the newest archive of the task is used.
+pub fn find_archive(project_name: &str, query: &str) -> Result<Archive, CommandError> {
+    let path = Path::new(query);
+    if path.is_file() {
+        return read_archive(path);
+    }
+    let name = query.strip_suffix(EXTENSION).unwrap_or(query);
+    list_archives(project_name)?
+        .into_iter()
+        .rev()
+        .find(|archive| archive.name == name || archive.manifest.task == query)
+        .ok_or_else(|| {
+            CommandError::new(&format!(
+                "No archive named '{query}'; run `claudectl archive --list` to see them"
+            ))
+        })
+}
+
+fn for_each_entry(
+    path: &Path,
+    mut visit: impl FnMut(&str, &mut dyn Read) -> Result<(), CommandError>,
+) -> Result<(), CommandError> {
+    let file = File::open(path).map_err(|e| read_failed(e, path))?;
+    let mut tarball = Tarball::new(GzDecoder::new(file));
+    for entry in tarball.entries().map_err(|e| read_failed(e, path))? {
+        let mut entry = entry.map_err(|e| read_failed(e, path))?;
+        let name = entry
+            .path()
+            .map_err(|e| read_failed(e, path))?
+            .to_string_lossy()
+            .to_string();
+        visit(&name, &mut entry)?;
+    }
+    Ok(())
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(
+    entry: &mut dyn Read,
+    path: &Path,
+) -> Result<T, CommandError> {
+    serde_json::from_reader(entry)
+        .map_err(|e| CommandError::new(&format!("Failed to parse archive {}: {e}", path.display())))
+}
+
+fn read_failed(e: std::io::Error, path: &Path) -> FileSystemError {
+    FileSystemError::read_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+}
+
+fn file_stem(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    name.strip_suffix(EXTENSION).unwrap_or(&name).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_archive_name_flattens_branch_separators() {
+        let at = Utc.with_ymd_and_hms(2024, 9, 1, 16, 3, 0).unwrap();
+        assert_eq!(
+            archive_name("feat/login", at),
+            "feat__login-20240901-160300"
+        );
+    }
+
+    #[test]
+    fn test_file_stem_drops_extension() {
+        assert_eq!(
+            file_stem(Path::new("/a/feat__x-20240901-160300.tar.gz")),
+            "feat__x-20240901-160300"
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::utils::errors::{CommandError, ConfigError, FileSystemError};
+use crate::utils::tasks::TaskKind;
+
+/// One task to create, as listed in a batch file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskSpec {
+    /// Task name, slugified like names given to `task`
+    pub name: String,
+    /// Ref to branch from instead of the default base
+    #[serde(default)]
+    pub base: Option<String>,
+    /// What the agent should do, written to the worktree for it
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub kind: Option<TaskKind>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// A batch file is a list of tasks, or an object with a `tasks` list.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BatchFile {
+    List(Vec<TaskSpec>),
+    Tasks { tasks: Vec<TaskSpec> },
+}
+
+/// Read the tasks listed in a YAML or JSON file.
+pub fn read_task_specs(path: &Path) -> Result<Vec<TaskSpec>, CommandError> {
+    let raw = std::fs::read_to_string(path).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    })?;
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    let batch = if is_json {
+        serde_json::from_str(&raw).map_err(|e| e.to_string())
+    } else {
+        serde_norway::from_str(&raw).map_err(|e| e.to_string())
+    };
+    let specs = match batch.map_err(|e| {
+        ConfigError::invalid(&format!(
+            "{} does not list tasks: {e}. Give a list of tasks with a name and optionally base, prompt, kind and tags",
+            path.display()
+        ))
+    })? {
+        BatchFile::List(specs) | BatchFile::Tasks { tasks: specs } => specs,
+    };
+    if specs.is_empty() {
+        return Err(CommandError::new(&format!(
+            "{} lists no tasks",
+            path.display()
+        )));
+    }
+    Ok(specs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_read_yaml_and_json_batches() {
+        let temp_dir = TempDir::new().unwrap();
+        let yaml = temp_dir.path().join("tasks.yaml");
+        std::fs::write(
+            &yaml,
+            "tasks:\n  - name: login\n    kind: bugfix\n    prompt: Fix the crash\n  - name: docs\n    base: origin/develop\n    tags: [docs]\n",
+        )
+        .unwrap();
+        let specs = read_task_specs(&yaml).unwrap();
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0].kind, Some(TaskKind::Bugfix));
+        assert_eq!(specs[0].prompt.as_deref(), Some("Fix the crash"));
+        assert_eq!(specs[1].base.as_deref(), Some("origin/develop"));
+        assert_eq!(specs[1].tags, ["docs"]);
+
+        let json = temp_dir.path().join("tasks.json");
+        std::fs::write(&json, r#"[{ "name": "login" }]"#).unwrap();
+        assert_eq!(read_task_specs(&json).unwrap()[0].name, "login");
+    }
+
+    #[test]
+    fn test_rejects_files_without_tasks() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tasks.yaml");
+        std::fs::write(&path, "tasks: []\n").unwrap();
+        assert!(read_task_specs(&path).is_err());
+        std::fs::write(&path, "- title: login\n").unwrap();
+        assert!(read_task_specs(&path).is_err());
+    }
+}
@@ -1,20 +1,32 @@
+use crate::utils::config::{Permissions, SessionLimits};
 use crate::utils::errors::ClaudeError;
+use crate::utils::output::{confirm, is_capturing, passthrough, passthrough_err};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Command, Stdio};
+use tracing::instrument;
 
 type ClaudeResult<T> = Result<T, ClaudeError>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Status {
     ///Ready for initial user input
     Ready,
     ///The agent is actively working
-    #[allow(dead_code)]
     Working,
     ///The agent is waiting for user input
-    #[allow(dead_code)]
     Waiting,
     ///Claudectl is unable to communicate with the agent process
-    #[allow(dead_code)]
     Unknown,
+    ///The project's setup commands failed in the worktree
+    #[serde(rename = "setup-failed")]
+    SetupFailed,
+    ///The task's last session exited with an error
+    Crashed,
+    ///A session is waiting for a free slot to start in the task
+    Queued,
 }
 
 pub struct Session {
@@ -23,18 +35,80 @@ pub struct Session {
     pub status: Status,
 }
 
-pub fn is_claude_installed() -> ClaudeResult<bool> {
+/// How to run `claude`: which binary, and what to add to claudectl's own
+/// arguments and environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    /// `claude` from the PATH unless the project names another binary
+    pub program: String,
+    /// Passed after claudectl's own arguments, e.g. `--model opus`
+    pub args: Vec<String>,
+    /// Set on the process, e.g. to select a profile; later entries win
+    pub env: Vec<(String, String)>,
+    /// Caps on the process's memory and CPU time
+    pub limits: SessionLimits,
+}
+
+impl Default for Invocation {
+    fn default() -> Self {
+        Self {
+            program: "claude".to_string(),
+            args: Vec::new(),
+            env: Vec::new(),
+            limits: SessionLimits::default(),
+        }
+    }
+}
+
+impl Invocation {
+    /// The command running the binary with `args` and then the extra
+    /// arguments, in `cwd`.
+    fn command(&self, args: &[String], cwd: &str) -> Command {
+        let line = self.limits.wrap(&self.program);
+        let mut command = Command::new(&line[0]);
+        command
+            .args(&line[1..])
+            .args(args)
+            .args(&self.args)
+            .current_dir(cwd)
+            .envs(self.env.iter().map(|(k, v)| (k, v)));
+        command
+    }
+
+    fn failed_to_start(&self, e: std::io::Error) -> ClaudeError {
+        if self.limits.memory.is_some() && e.kind() == std::io::ErrorKind::NotFound {
+            return ClaudeError::new(&format!(
+                "Failed to execute 'systemd-run', which applies limits.memory: {e}"
+            ));
+        }
+        ClaudeError::new(&format!("Failed to execute '{}': {e}", self.program))
+    }
+}
+
+/// Parse a `KEY=VALUE` environment variable given to `run --env`.
+pub fn parse_env_var(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got '{value}'")),
+    }
+}
+
+pub fn is_claude_installed(program: &str) -> ClaudeResult<bool> {
     let output = std::process::Command::new("which")
-        .arg("claude")
+        .arg(program)
         .output()
-        .map_err(|e| ClaudeError::new(&format!("Failed to execute 'which claude': {e}")))?;
+        .map_err(|e| ClaudeError::new(&format!("Failed to execute 'which {program}': {e}")))?;
 
     if output.status.success() {
         Ok(true)
-    } else {
+    } else if program == "claude" {
         Err(ClaudeError::new(
             "Claude is not installed or not found in PATH.",
         ))
+    } else {
+        Err(ClaudeError::new(&format!(
+            "'{program}' is not an executable or not found in PATH."
+        )))
     }
 }
 
@@ -46,15 +120,209 @@ pub fn get_session(name: &str) -> ClaudeResult<Session> {
     })
 }
 
+/// Run `claude` in print mode with `input` on stdin and return its response.
+pub fn one_shot(
+    prompt: &str,
+    input: &str,
+    cwd: &str,
+    invocation: &Invocation,
+) -> ClaudeResult<String> {
+    let mut child = invocation
+        .command(&[], cwd)
+        .args(["-p", "--", prompt])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| invocation.failed_to_start(e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // claude exiting before reading its input closes the pipe; its exit
+        // status below says why
+        match stdin.write_all(input.as_bytes()) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => {
+                return Err(ClaudeError::command_failed(&format!(
+                    "Failed to write prompt: {e}"
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| ClaudeError::command_failed(&format!("Failed to wait for claude: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ClaudeError::command_failed(stderr.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Start an interactive `claude` session in `cwd`, returning its exit code
+/// once it exits. A non-zero exit is not an error, so the caller can record
+/// it.
+///
+/// The optional `prompt` is sent as the session's first message.
+#[instrument(skip_all, fields(cwd = %cwd))]
+pub fn launch_session(
+    cwd: &str,
+    permissions: &Permissions,
+    prompt: Option<&str>,
+    invocation: &Invocation,
+) -> ClaudeResult<Option<i32>> {
+    let mut command = invocation.command(&permission_args(permissions), cwd);
+    if let Some(prompt) = prompt {
+        command.args(["--", prompt]);
+    }
+    wait_for_session(command, invocation)
+}
+
+/// Reopen the most recent conversation in `cwd` with `claude --continue`,
+/// returning its exit code once it exits.
+#[instrument(skip_all, fields(cwd = %cwd))]
+pub fn resume_session(
+    cwd: &str,
+    permissions: &Permissions,
+    invocation: &Invocation,
+) -> ClaudeResult<Option<i32>> {
+    let mut command = invocation.command(&permission_args(permissions), cwd);
+    command.arg("--continue");
+    wait_for_session(command, invocation)
+}
+
+/// Run the session in the foreground and return its exit code, `None` when
+/// it was killed by a signal.
+fn wait_for_session(mut command: Command, invocation: &Invocation) -> ClaudeResult<Option<i32>> {
+    let status = command
+        .status()
+        .map_err(|e| invocation.failed_to_start(e))?;
+    Ok(status.code())
+}
+
+/// What a print-mode run of `claude` produced.
+pub struct RunOutput {
+    /// Everything it wrote to stdout
+    pub output: String,
+    /// `None` when it was killed by a signal
+    pub exit_code: Option<i32>,
+}
+
+/// Run `claude` in print mode with `prompt` in `cwd`, streaming its output to
+/// the terminal as it arrives and returning it once the process exits. While
+/// capturing, its output and its errors go to the capture buffer instead.
+///
+/// A non-zero exit is reported in the result rather than as an error, so the
+/// caller can record it.
+#[instrument(skip_all, fields(cwd = %cwd))]
+pub fn run_print(
+    cwd: &str,
+    permissions: &Permissions,
+    prompt: &str,
+    invocation: &Invocation,
+) -> ClaudeResult<RunOutput> {
+    let mut command = invocation.command(&permission_args(permissions), cwd);
+    command
+        .args(["-p", "--", prompt])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped());
+    if is_capturing() {
+        command.stderr(Stdio::piped());
+    }
+    let mut child = command.spawn().map_err(|e| invocation.failed_to_start(e))?;
+    // Read alongside stdout, so neither pipe fills up and stalls claude
+    let stderr = child.stderr.take().map(|mut stderr| {
+        std::thread::spawn(move || {
+            let mut errors = Vec::new();
+            let _ = stderr.read_to_end(&mut errors);
+            errors
+        })
+    });
+
+    let mut output = String::new();
+    let read = child.stdout.take().map_or(Ok(()), |stdout| {
+        // Read as bytes, so output that isn't UTF-8 can't end the run early
+        for line in BufReader::new(stdout).split(b'\n') {
+            let line = line?;
+            let line = String::from_utf8_lossy(&line);
+            let line = line.strip_suffix('\r').unwrap_or(&line);
+            passthrough(line);
+            output.push_str(line);
+            output.push('\n');
+        }
+        Ok::<(), std::io::Error>(())
+    });
+    if let Err(e) = read {
+        // Not left running, nor unreaped, without anyone reading it
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(ClaudeError::command_failed(&format!(
+            "Failed to read claude output: {e}"
+        )));
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| ClaudeError::command_failed(&format!("Failed to wait for claude: {e}")))?;
+    if let Some(errors) = stderr.and_then(|reader| reader.join().ok()) {
+        passthrough_err(&String::from_utf8_lossy(&errors));
+    }
+    Ok(RunOutput {
+        output,
+        exit_code: status.code(),
+    })
+}
+
+/// Build the `claude` CLI arguments for a project's permission settings.
+///
+/// Each tool list is passed as one comma-separated value: `claude` reads
+/// every argument after `--allowedTools` as a tool otherwise, up to the next
+/// flag, prompts included. Callers still put `--` before a prompt, since
+/// the project's extra arguments come after these.
+pub fn permission_args(permissions: &Permissions) -> Vec<String> {
+    let mut args = vec![
+        "--permission-mode".to_string(),
+        permissions.mode.as_str().to_string(),
+    ];
+    if !permissions.allowed_tools.is_empty() {
+        args.push("--allowedTools".to_string());
+        args.push(permissions.allowed_tools.join(","));
+    }
+    if !permissions.disallowed_tools.is_empty() {
+        args.push("--disallowedTools".to_string());
+        args.push(permissions.disallowed_tools.join(","));
+    }
+    args
+}
+
+/// Ask the user to approve an elevated permission mode before it takes effect.
+///
+/// Returns `Ok(true)` without prompting when the mode is not elevated or the
+/// project has opted out of approval.
+pub fn approve_permissions(permissions: &Permissions, target: &str) -> ClaudeResult<bool> {
+    if !permissions.mode.is_elevated() || !permissions.require_approval {
+        return Ok(true);
+    }
+
+    confirm(&format!(
+        "{target} will run with elevated permission mode '{}'. Continue?",
+        permissions.mode.as_str()
+    ))
+    .map_err(|e| ClaudeError::new(&format!("Failed to read approval: {e}")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::config::PermissionMode;
 
     #[test]
     fn test_is_claude_installed_success() {
         // This test will pass if claude is actually installed
         // In a real CI environment, you might want to mock this
-        let result = is_claude_installed();
+        let result = is_claude_installed("claude");
 
         // Since we can't guarantee claude is installed in all test environments,
         // we just verify the function doesn't panic and returns a Result
@@ -79,4 +347,59 @@ mod tests {
         // Verify that command execution can fail (for reference)
         assert!(output.is_err());
     }
+
+    #[test]
+    fn test_parse_env_var() {
+        assert_eq!(
+            parse_env_var("DEBUG=claude:*=1").unwrap(),
+            ("DEBUG".to_string(), "claude:*=1".to_string())
+        );
+        assert_eq!(
+            parse_env_var("EMPTY=").unwrap(),
+            ("EMPTY".to_string(), String::new())
+        );
+        assert!(parse_env_var("NO_VALUE").is_err());
+        assert!(parse_env_var("=value").is_err());
+    }
+
+    #[test]
+    fn test_permission_args_default_mode() {
+        let args = permission_args(&Permissions::default());
+        assert_eq!(args, vec!["--permission-mode", "default"]);
+    }
+
+    #[test]
+    fn test_permission_args_with_tools() {
+        let permissions = Permissions {
+            mode: PermissionMode::Plan,
+            allowed_tools: vec!["Read".to_string(), "Edit".to_string()],
+            disallowed_tools: vec!["Bash(rm:*)".to_string()],
+            require_approval: true,
+        };
+        let args = permission_args(&permissions);
+        assert_eq!(
+            args,
+            vec![
+                "--permission-mode",
+                "plan",
+                "--allowedTools",
+                "Read,Edit",
+                "--disallowedTools",
+                "Bash(rm:*)"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_approve_permissions_skips_prompt_for_safe_modes() {
+        let permissions = Permissions::default();
+        assert!(approve_permissions(&permissions, "Session").unwrap());
+
+        let permissions = Permissions {
+            mode: PermissionMode::BypassPermissions,
+            require_approval: false,
+            ..Permissions::default()
+        };
+        assert!(approve_permissions(&permissions, "Session").unwrap());
+    }
 }
@@ -0,0 +1,491 @@
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use tracing::{info, warn};
+
+use crate::utils::errors::{CommandError, FileSystemError};
+
+/// Subcommands whose first positional argument is a task name.
+pub const TASK_COMMANDS: &[&str] = &[
+    "run",
+    "show",
+    "diff",
+    "compare",
+    "open",
+    "switch",
+    "exec",
+    "rm",
+    "rename",
+    "archive",
+    "checkpoint",
+    "rollback",
+    "summarize",
+    "describe",
+    "resolve",
+    "merge",
+    "pr",
+    "setup",
+    "note",
+    "kill",
+    "restart",
+];
+
+/// Starts the lines claudectl adds to a shell's startup file.
+const MARKER: &str = "# Added by claudectl";
+
+/// Name of the user's shell, from `$SHELL`.
+pub fn detect_shell() -> String {
+    std::env::var("SHELL")
+        .unwrap_or_else(|_| "bash".to_string())
+        .split('/')
+        .next_back()
+        .unwrap_or("bash")
+        .to_string()
+}
+
+/// Where installed completions for `shell` may live, most likely first.
+pub fn completion_paths(shell: &str) -> Vec<String> {
+    let home = home_dir();
+    let config = std::env::var("XDG_CONFIG_HOME").unwrap_or_else(|_| format!("{home}/.config"));
+
+    match shell {
+        "zsh" => vec![
+            format!("{}/.zsh/completions/_claudectl", home),
+            format!("{}/.zsh_completion.d/_claudectl", home),
+            "/usr/local/share/zsh/site-functions/_claudectl".to_string(),
+        ],
+        "bash" => vec![
+            format!("{}/.bash_completion.d/claudectl", home),
+            "/etc/bash_completion.d/claudectl".to_string(),
+        ],
+        "fish" => vec![format!("{}/.config/fish/completions/claudectl.fish", home)],
+        "nu" => vec![
+            format!("{config}/nushell/completions/claudectl.nu"),
+            format!("{home}/Library/Application Support/nushell/completions/claudectl.nu"),
+        ],
+        "pwsh" | "powershell" => vec![
+            format!("{config}/powershell/completions/claudectl.ps1"),
+            format!("{home}/Documents/PowerShell/completions/claudectl.ps1"),
+        ],
+        _ => vec![],
+    }
+}
+
+/// The profile that has to load `completion_file`, for shells that do not
+/// pick completions up from a directory. It sits in the shell's config
+/// directory, one level above the completions.
+pub fn profile_path(shell: &str, completion_file: &Path) -> Option<PathBuf> {
+    let config_dir = completion_file.parent()?.parent()?;
+    match shell {
+        "nu" => Some(config_dir.join("config.nu")),
+        "pwsh" | "powershell" => Some(config_dir.join("Microsoft.PowerShell_profile.ps1")),
+        _ => None,
+    }
+}
+
+/// The profile line that loads `completion_file`.
+pub fn profile_line(shell: &str, completion_file: &str) -> Option<String> {
+    match shell {
+        "nu" => Some(format!("source \"{completion_file}\"")),
+        "pwsh" | "powershell" => Some(format!(". \"{completion_file}\"")),
+        _ => None,
+    }
+}
+
+fn home_dir() -> String {
+    std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string())
+}
+
+/// What installing completions changed.
+#[derive(Debug)]
+pub struct Installed {
+    pub file: PathBuf,
+    /// The startup file lines were added to, if the shell needs them
+    pub startup_file: Option<PathBuf>,
+    /// Copy of the startup file from before it was changed
+    pub backup: Option<PathBuf>,
+}
+
+/// What uninstalling completions removed.
+#[derive(Debug, Default)]
+pub struct Removed {
+    pub files: Vec<PathBuf>,
+    pub startup_file: Option<PathBuf>,
+    pub backup: Option<PathBuf>,
+}
+
+/// Install `script`, the completions for `shell`, and make the shell load
+/// it.
+///
+/// The file goes to the first of the shell's completion directories that
+/// can be written to. Startup files are backed up before lines are added.
+pub fn install(shell: &str, script: &str) -> Result<Installed, CommandError> {
+    let candidates = completion_paths(shell);
+    if candidates.is_empty() {
+        return Err(CommandError::new(&format!(
+            "Installing completions for {shell} is not supported; generate them with `claudectl completions <shell>`"
+        )));
+    }
+
+    let content = format!(
+        "# Version: {}\n# Generated by claudectl completions --install\n\n{}",
+        env!("CARGO_PKG_VERSION"),
+        script
+    );
+    let file = candidates
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| {
+            let written = path
+                .parent()
+                .is_some_and(|dir| std::fs::create_dir_all(dir).is_ok())
+                && std::fs::write(path, &content).is_ok();
+            if !written {
+                info!("Cannot write completions to {}", path.display());
+            }
+            written
+        })
+        .ok_or_else(|| {
+            CommandError::new(&format!(
+                "None of the {shell} completion directories can be written to"
+            ))
+        })?;
+    info!("Installed completions to {}", file.display());
+
+    let Some((startup_file, lines)) = startup_lines(shell, &file) else {
+        return Ok(Installed {
+            file,
+            startup_file: None,
+            backup: None,
+        });
+    };
+    let existing = std::fs::read_to_string(&startup_file).unwrap_or_default();
+    if existing.contains(MARKER) {
+        return Ok(Installed {
+            file,
+            startup_file: None,
+            backup: None,
+        });
+    }
+    let backup = back_up(&startup_file)?;
+    let separator = if existing.is_empty() || existing.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    write(
+        &startup_file,
+        &format!("{existing}{separator}\n{MARKER}\n{lines}\n"),
+    )?;
+    Ok(Installed {
+        file,
+        startup_file: Some(startup_file),
+        backup,
+    })
+}
+
+/// Remove installed completions for `shell` and the lines that load them.
+pub fn uninstall(shell: &str) -> Result<Removed, CommandError> {
+    let mut removed = Removed::default();
+    for path in completion_paths(shell).iter().map(PathBuf::from) {
+        let ours =
+            std::fs::read_to_string(&path).is_ok_and(|content| content.contains("claudectl"));
+        if !ours {
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => removed.files.push(path),
+            Err(e) => warn!("Failed to remove {}: {}", path.display(), e),
+        }
+    }
+
+    let file = completion_paths(shell).first().map(PathBuf::from);
+    let startup_file = file.and_then(|file| startup_lines(shell, &file).map(|(path, _)| path));
+    if let Some(startup_file) = startup_file {
+        let existing = std::fs::read_to_string(&startup_file).unwrap_or_default();
+        if let Some(stripped) = strip_startup_lines(&existing) {
+            removed.backup = back_up(&startup_file)?;
+            write(&startup_file, &stripped)?;
+            removed.startup_file = Some(startup_file);
+        }
+    }
+    Ok(removed)
+}
+
+/// The startup file that has to load completions installed at `file`, and
+/// the lines that do it. Shells that load a directory by themselves have
+/// none.
+fn startup_lines(shell: &str, file: &Path) -> Option<(PathBuf, String)> {
+    let home = PathBuf::from(home_dir());
+    let path = file.to_string_lossy();
+    match shell {
+        "zsh" => Some((
+            home.join(".zshrc"),
+            format!(
+                "fpath+=\"{}\"\nautoload -U compinit && compinit",
+                file.parent()?.display()
+            ),
+        )),
+        "bash" => Some((
+            home.join(".bashrc"),
+            format!("[ -f \"{path}\" ] && source \"{path}\""),
+        )),
+        _ => Some((profile_path(shell, file)?, profile_line(shell, &path)?)),
+    }
+}
+
+/// `content` without the lines claudectl added, or `None` if it has none.
+/// The added lines run from the marker to the next blank line.
+fn strip_startup_lines(content: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.iter().position(|line| line.trim() == MARKER)?;
+    let end = lines[start..]
+        .iter()
+        .position(|line| line.trim().is_empty())
+        .map_or(lines.len(), |offset| start + offset);
+    // Drop the blank line the lines were separated by, too
+    let start = if start > 0 && lines[start - 1].trim().is_empty() {
+        start - 1
+    } else {
+        start
+    };
+    let mut kept: Vec<&str> = lines[..start].to_vec();
+    kept.extend(&lines[end..]);
+    let mut stripped = kept.join("\n");
+    if !stripped.is_empty() {
+        stripped.push('\n');
+    }
+    Some(stripped)
+}
+
+/// Copy `path` aside before changing it, if it exists.
+fn back_up(path: &Path) -> Result<Option<PathBuf>, CommandError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".claudectl-backup");
+    let backup = PathBuf::from(backup);
+    std::fs::copy(path, &backup).map_err(|e| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &backup.to_string_lossy())
+    })?;
+    Ok(Some(backup))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), CommandError> {
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    std::fs::write(path, content).map_err(|e| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy()).into()
+    })
+}
+
+/// Add completion of task and project names, backed by
+/// `claudectl __complete`, to the completions clap generated for `shell`.
+pub fn patch_completions(shell: &str, script: &str) -> String {
+    let replace = |content: &str, pattern: &str, with: &str| -> String {
+        Regex::new(pattern)
+            .map(|re| re.replace_all(content, with).to_string())
+            .unwrap_or_else(|_| content.to_string())
+    };
+    let insert = |content: &str, pattern: &str, with: &str| -> String {
+        Regex::new(pattern)
+            .map(|re| re.replacen(content, 1, with).to_string())
+            .unwrap_or_else(|_| content.to_string())
+    };
+
+    match shell {
+        "zsh" => {
+            let content = replace(
+                script,
+                r"('::?task_name(?: -- [^:']*)?:)'",
+                "${1}_claudectl_tasks'",
+            );
+            let content = replace(
+                &content,
+                r"('--(?:clone|task)=\[[^\]]*\]:[A-Z_]+:)'",
+                "${1}_claudectl_tasks'",
+            );
+            let content = replace(
+                &content,
+                r"('--project=\[[^\]]*\]:PROJECT:)_default'",
+                "${1}_claudectl_projects'",
+            );
+            insert(
+                &content,
+                r#"(?m)^(if \[ "\$funcstack\[1\]" = "_claudectl" \]; then)"#,
+                &format!("{}\n\n$1", ZSH_FUNCTIONS.replace('$', "$$")),
+            )
+        }
+        "bash" => format!(
+            "{script}{}",
+            BASH_FUNCTIONS.replace("{task_commands}", &TASK_COMMANDS.join("|"))
+        ),
+        "fish" => format!(
+            "{script}{}",
+            FISH_COMPLETIONS.replace("{task_commands}", &TASK_COMMANDS.join(" "))
+        ),
+        "nu" => {
+            let content = replace(
+                script,
+                &format!(
+                    r#"(export extern "claudectl (?:{})" \[\n    task_name\??: string)"#,
+                    TASK_COMMANDS.join("|")
+                ),
+                "${1}@_claudectl_tasks",
+            );
+            let content = replace(
+                &content,
+                r"(    --(?:clone|task): string)(\s)",
+                "${1}@_claudectl_tasks${2}",
+            );
+            let content = replace(
+                &content,
+                r"(    --project: string)(\s)",
+                "${1}@_claudectl_projects${2}",
+            );
+            insert(
+                &content,
+                r"(?m)^module completions \{\n",
+                &format!("module completions {{\n{}", NU_FUNCTIONS.replace('$', "$$")),
+            )
+        }
+        "pwsh" | "powershell" => {
+            let content = replace(
+                script,
+                &format!(r"('claudectl;(?:{})' \{{\n)", TASK_COMMANDS.join("|")),
+                "${1}            _claudectl_tasks\n",
+            );
+            insert(
+                &content,
+                r"(?m)^Register-ArgumentCompleter",
+                &format!(
+                    "{}\nRegister-ArgumentCompleter",
+                    POWERSHELL_FUNCTIONS.replace('$', "$$")
+                ),
+            )
+        }
+        _ => script.to_string(),
+    }
+}
+
+const ZSH_FUNCTIONS: &str = r#"
+# Dynamic completion backed by `claudectl __complete`
+_claudectl_tasks() {
+  local -a tasks
+  tasks=(${(f)"$(claudectl __complete tasks 2>/dev/null)"})
+  _describe 'available tasks' tasks
+}
+
+_claudectl_projects() {
+  local -a projects
+  projects=(${(f)"$(claudectl __complete projects 2>/dev/null)"})
+  _describe 'registered projects' projects
+}"#;
+
+const BASH_FUNCTIONS: &str = r#"
+# Dynamic completion backed by `claudectl __complete`
+_claudectl_tasks() {
+  local cur="${COMP_WORDS[COMP_CWORD]}"
+  COMPREPLY=($(compgen -W "$(claudectl __complete tasks 2>/dev/null)" -- "$cur"))
+}
+
+_claudectl_projects() {
+  local cur="${COMP_WORDS[COMP_CWORD]}"
+  COMPREPLY=($(compgen -W "$(claudectl __complete projects 2>/dev/null)" -- "$cur"))
+}
+
+_claudectl_dynamic() {
+  local prev="${COMP_WORDS[COMP_CWORD-1]}"
+  case "$prev" in
+    --clone|--task) _claudectl_tasks; return 0 ;;
+    --project) _claudectl_projects; return 0 ;;
+  esac
+  case "${COMP_WORDS[1]}" in
+    {task_commands})
+      if [ "$COMP_CWORD" -eq 2 ] && [[ "${COMP_WORDS[2]}" != -* ]]; then
+        _claudectl_tasks
+        return 0
+      fi
+      ;;
+  esac
+  _claudectl "$@"
+}
+
+complete -F _claudectl_dynamic -o nosort -o bashdefault -o default claudectl"#;
+
+const FISH_COMPLETIONS: &str = r#"
+# Dynamic completion backed by `claudectl __complete`
+complete -c claudectl -n "__fish_seen_subcommand_from {task_commands}" -f -a "(claudectl __complete tasks 2>/dev/null)"
+complete -c claudectl -n "__fish_seen_subcommand_from task" -l clone -x -a "(claudectl __complete tasks 2>/dev/null)"
+complete -c claudectl -n "__fish_seen_subcommand_from history" -l task -x -a "(claudectl __complete tasks 2>/dev/null)"
+complete -c claudectl -n "__fish_seen_subcommand_from list" -l project -x -a "(claudectl __complete projects 2>/dev/null)"
+"#;
+
+const NU_FUNCTIONS: &str = r#"
+  # Dynamic completion backed by `claudectl __complete`
+  def _claudectl_tasks [] {
+    ^claudectl __complete tasks | lines
+  }
+
+  def _claudectl_projects [] {
+    ^claudectl __complete projects | lines
+  }
+"#;
+
+const POWERSHELL_FUNCTIONS: &str = r#"
+# Dynamic completion backed by `claudectl __complete`
+function _claudectl_tasks {
+    claudectl __complete tasks 2>$null | ForEach-Object {
+        [System.Management.Automation.CompletionResult]::new($_, $_, [System.Management.Automation.CompletionResultType]::ParameterValue, $_)
+    }
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_path_and_line() {
+        let file = Path::new("/home/me/.config/nushell/completions/claudectl.nu");
+        assert_eq!(
+            profile_path("nu", file),
+            Some(PathBuf::from("/home/me/.config/nushell/config.nu"))
+        );
+        assert_eq!(profile_path("zsh", file), None);
+        assert_eq!(
+            profile_line("pwsh", "/p/claudectl.ps1").as_deref(),
+            Some(". \"/p/claudectl.ps1\"")
+        );
+    }
+
+    #[test]
+    fn test_patch_zsh_completes_task_names() {
+        let script = "'::task_name -- The task:' \\\n'--clone=[Clone]:EXISTING_TASK:' \\\nif [ \"$funcstack[1]\" = \"_claudectl\" ]; then\n";
+        let patched = patch_completions("zsh", script);
+        assert!(patched.contains("'::task_name -- The task:_claudectl_tasks'"));
+        assert!(patched.contains("'--clone=[Clone]:EXISTING_TASK:_claudectl_tasks'"));
+        assert!(patched.contains("tasks=(${(f)\"$(claudectl __complete tasks"));
+        assert!(patched.ends_with("if [ \"$funcstack[1]\" = \"_claudectl\" ]; then\n"));
+    }
+
+    #[test]
+    fn test_patch_bash_lists_task_commands() {
+        let patched = patch_completions("bash", "_claudectl() {\n}\n");
+        assert!(patched.starts_with("_claudectl() {\n}\n"));
+        assert!(patched.contains("    run|show|diff|"));
+        assert!(patched.contains("COMPREPLY=($(compgen"));
+    }
+
+    #[test]
+    fn test_strip_startup_lines() {
+        let content = "export A=1\n\n# Added by claudectl\nfpath+=\"/x\"\nautoload -U compinit && compinit\n\nexport B=2\n";
+        assert_eq!(
+            strip_startup_lines(content).as_deref(),
+            Some("export A=1\n\nexport B=2\n")
+        );
+        assert_eq!(strip_startup_lines("export A=1\n"), None);
+    }
+}
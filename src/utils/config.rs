@@ -1,5 +1,19 @@
-use crate::utils::errors::ConfigError;
+use crate::utils::agent::AgentKind;
+use crate::utils::claude::Invocation;
+use crate::utils::errors::{CommandError, ConfigError};
+use crate::utils::filter::TaskFilter;
+use crate::utils::fs::read_global_config_file;
+use crate::utils::guard::GuardRails;
+use crate::utils::recipes::{Recipe, all_recipes};
+use crate::utils::supervise::Supervision;
+use crate::utils::tasks::TaskKind;
+use crate::utils::terminal::TerminalSettings;
+use crate::utils::time::TimestampStyle;
+use clap::ValueEnum;
+use directories::BaseDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
 
 type ConfigResult<T> = Result<T, ConfigError>;
 
@@ -7,6 +21,347 @@ type ConfigResult<T> = Result<T, ConfigError>;
 pub struct Config {
     pub project_name: String,
     pub project_dir: String,
+    /// The agent tasks run
+    #[serde(default)]
+    pub agent: AgentKind,
+    #[serde(default)]
+    pub permissions: Permissions,
+    /// How sessions run `claude`: another binary, extra arguments and
+    /// environment variables
+    #[serde(default)]
+    pub claude: ClaudeCommand,
+    #[serde(default)]
+    pub redaction: Redaction,
+    /// Hooks that stop agents rewriting history outside their task branch
+    #[serde(default)]
+    pub guard_rails: GuardRails,
+    #[serde(default)]
+    pub context: PromptContext,
+    /// Text prepended to the initial prompt of every agent session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_preamble: Option<String>,
+    /// Show live progress bars while long git operations run
+    #[serde(default = "default_true")]
+    pub progress: bool,
+    /// Show timestamps as local date and time or as time elapsed
+    #[serde(default)]
+    pub timestamps: TimestampStyle,
+    /// Kind given to tasks created without `--kind`, so their branches get
+    /// its prefix
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_task_kind: Option<TaskKind>,
+    /// Profile used unless `--profile` or the task picks another
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude_profile: Option<String>,
+    /// Named prompts for tasks started by `watch`; `{files}` is replaced
+    /// with the changed paths
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prompt_templates: BTreeMap<String, String>,
+    /// Shell commands run in each new task worktree before the agent
+    /// starts, e.g. `pnpm install`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub setup_commands: Vec<String>,
+    /// Recipes run in each new task worktree before `setup_commands`, e.g.
+    /// `rust` to share the main checkout's build directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub setup_recipes: Vec<String>,
+    /// The project's own recipes, added to the built-in ones
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub recipes: BTreeMap<String, Recipe>,
+    /// Markdown file, relative to the repository root, used to describe
+    /// tasks for pull requests instead of the built-in template
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pr_template: Option<String>,
+    /// For a sub-project of a monorepo, its directory relative to the
+    /// repository root; task worktrees only check this directory out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subproject: Option<String>,
+    /// What `watch --supervise` does about crashed sessions
+    #[serde(default)]
+    pub supervision: Supervision,
+    /// Seconds `kill` and `restart` wait after SIGTERM before sending
+    /// SIGKILL, unless given `--timeout`
+    #[serde(default = "default_stop_timeout")]
+    pub stop_timeout: u64,
+    /// Most sessions that may run in the project at once; commands starting
+    /// a session wait in a queue for a free slot beyond that
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_sessions: Option<usize>,
+    #[serde(default)]
+    pub limits: SessionLimits,
+}
+
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Select the profile given with `--profile` or `CLAUDECTL_PROFILE` for the
+/// rest of the run. It is checked against the global config when first used.
+pub fn set_active_profile(name: Option<String>) {
+    if let Some(name) = name {
+        let _ = ACTIVE_PROFILE.set(name);
+    }
+}
+
+pub fn active_profile() -> Option<&'static str> {
+    ACTIVE_PROFILE.get().map(String::as_str)
+}
+
+/// Settings shared by every project, kept in the global config directory.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct GlobalConfig {
+    /// Named setups such as `work` and `oss`
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Named filters for `list --filter` and `overview --filter`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub filters: BTreeMap<String, TaskFilter>,
+    /// Window titles and notifications in the terminal
+    #[serde(default)]
+    pub terminal: TerminalSettings,
+}
+
+/// A named setup, e.g. a work and a personal account with their own login,
+/// model and worktree location.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// Claude's config directory holding this profile's login; `~` is expanded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_dir: Option<String>,
+    /// Model sessions use unless the profile's `env` sets `ANTHROPIC_MODEL`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Directory new task worktrees are created under, in a folder per
+    /// project; `~` is expanded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_root: Option<String>,
+    /// Extra environment variables, e.g. `ANTHROPIC_API_KEY`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+impl Profile {
+    /// Environment variables to set on `claude` processes using this profile.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = self
+            .config_dir
+            .iter()
+            .map(|dir| ("CLAUDE_CONFIG_DIR".to_string(), expand_home(dir)))
+            .collect();
+        vars.extend(
+            self.model
+                .iter()
+                .map(|model| ("ANTHROPIC_MODEL".to_string(), model.clone())),
+        );
+        vars.extend(self.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        vars
+    }
+}
+
+/// `path` with a leading `~/` replaced by the home directory.
+pub fn expand_home(path: &str) -> String {
+    match (path.strip_prefix("~/"), BaseDirs::new()) {
+        (Some(rest), Some(dirs)) => dirs.home_dir().join(rest).to_string_lossy().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+impl GlobalConfig {
+    /// Load the global config; a missing file means no settings.
+    pub fn load() -> Result<Self, CommandError> {
+        match read_global_config_file()? {
+            Some(raw) => Ok(Self::from_str(&raw)?),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_str(json_str: &str) -> ConfigResult<Self> {
+        serde_json::from_str(json_str).map_err(|e| {
+            ConfigError::new(&format!("Failed to parse global configuration JSON: {e}"))
+        })
+    }
+
+    /// Look up a saved filter by name.
+    pub fn filter(&self, name: &str) -> ConfigResult<&TaskFilter> {
+        self.filters.get(name).ok_or_else(|| {
+            ConfigError::invalid(&format!(
+                "Unknown filter '{name}'. Define it under \"filters\" in the global config"
+            ))
+        })
+    }
+
+    /// Look up a profile by name.
+    pub fn profile(&self, name: &str) -> ConfigResult<&Profile> {
+        self.profiles.get(name).ok_or_else(|| {
+            ConfigError::invalid(&format!(
+                "Unknown profile '{name}'. Define it under \"profiles\" in the global config"
+            ))
+        })
+    }
+}
+
+/// Permission mode passed to `claude --permission-mode` when a session is launched.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "camelCase")]
+#[value(rename_all = "camelCase")]
+pub enum PermissionMode {
+    /// Prompt for permission on first use of each tool
+    #[default]
+    Default,
+    /// Automatically accept file edits
+    AcceptEdits,
+    /// Analyze only, without modifying files or running commands
+    Plan,
+    /// Skip all permission prompts
+    BypassPermissions,
+}
+
+impl PermissionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::AcceptEdits => "acceptEdits",
+            Self::Plan => "plan",
+            Self::BypassPermissions => "bypassPermissions",
+        }
+    }
+
+    /// Modes that let an agent act without asking first.
+    pub fn is_elevated(&self) -> bool {
+        matches!(self, Self::AcceptEdits | Self::BypassPermissions)
+    }
+}
+
+/// Per-project permission settings for agent sessions.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Permissions {
+    #[serde(default)]
+    pub mode: PermissionMode,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disallowed_tools: Vec<String>,
+    /// Ask for confirmation before launching a session with an elevated mode
+    #[serde(default = "default_true")]
+    pub require_approval: bool,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Self {
+            mode: PermissionMode::default(),
+            allowed_tools: Vec::new(),
+            disallowed_tools: Vec::new(),
+            require_approval: true,
+        }
+    }
+}
+
+/// How a project's sessions run `claude`, on top of claudectl's own
+/// arguments.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ClaudeCommand {
+    /// Binary to run instead of `claude` from the PATH; `~` is expanded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Arguments added to every invocation, e.g. `["--model", "opus"]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Environment variables set on every session
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// Caps on what the agent may use in each session; unset ones are not
+/// applied.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct SessionLimits {
+    /// Most memory the agent and every process it starts may use, in
+    /// systemd's notation, e.g. `4G`. Applied with `systemd-run --user
+    /// --scope`, so it needs a systemd user session
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<String>,
+    /// Seconds of CPU time each of the agent's processes may use before it
+    /// is stopped, as with `ulimit -t`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_seconds: Option<u64>,
+}
+
+impl SessionLimits {
+    /// The command line running `program` within these limits, which is
+    /// just `program` when none are set.
+    pub fn wrap(&self, program: &str) -> Vec<String> {
+        let mut line: Vec<String> = Vec::new();
+        if let Some(memory) = &self.memory {
+            line.extend(
+                ["systemd-run", "--user", "--scope", "--quiet", "--collect"].map(String::from),
+            );
+            line.push(format!("--property=MemoryMax={memory}"));
+            line.push("--".to_string());
+        }
+        if let Some(seconds) = self.cpu_seconds {
+            // The shell sets the limit and then becomes the agent, keeping
+            // its process ID
+            line.extend(["sh", "-c", r#"ulimit -t "$0" && exec "$@""#].map(String::from));
+            line.push(seconds.to_string());
+        }
+        line.push(program.to_string());
+        line
+    }
+}
+
+/// Secret redaction applied to agent output before claudectl stores it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Redaction {
+    /// Use the built-in detectors for common API keys, tokens and private keys
+    #[serde(default = "default_true")]
+    pub builtin: bool,
+    /// Additional regular expressions whose matches are redacted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<String>,
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self {
+            builtin: true,
+            patterns: Vec::new(),
+        }
+    }
+}
+
+/// Project context added to the initial prompt of every agent session.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptContext {
+    /// Files to include, relative to the worktree, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    /// Number of recent commits to include as a one-line log
+    #[serde(default)]
+    pub git_log: usize,
+    /// Size limit for the whole context, in characters
+    #[serde(default = "default_context_chars")]
+    pub max_chars: usize,
+}
+
+impl Default for PromptContext {
+    fn default() -> Self {
+        Self {
+            files: Vec::new(),
+            git_log: 0,
+            max_chars: default_context_chars(),
+        }
+    }
+}
+
+fn default_context_chars() -> usize {
+    20_000
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_stop_timeout() -> u64 {
+    10
 }
 
 impl Config {
@@ -14,6 +369,132 @@ impl Config {
         Self {
             project_name: project_name.to_string(),
             project_dir: project_dir.to_string(),
+            agent: AgentKind::default(),
+            permissions: Permissions::default(),
+            claude: ClaudeCommand::default(),
+            redaction: Redaction::default(),
+            guard_rails: GuardRails::default(),
+            context: PromptContext::default(),
+            prompt_preamble: None,
+            progress: true,
+            timestamps: TimestampStyle::default(),
+            default_task_kind: None,
+            claude_profile: None,
+            prompt_templates: BTreeMap::new(),
+            setup_commands: Vec::new(),
+            setup_recipes: Vec::new(),
+            recipes: BTreeMap::new(),
+            pr_template: None,
+            subproject: None,
+            supervision: Supervision::default(),
+            stop_timeout: default_stop_timeout(),
+            max_sessions: None,
+            limits: SessionLimits::default(),
+        }
+    }
+
+    /// Where work on a task happens: the worktree itself, or the
+    /// sub-project's directory inside it.
+    pub fn task_dir(&self, worktree_path: &str) -> String {
+        match &self.subproject {
+            Some(subproject) => format!("{worktree_path}/{subproject}"),
+            None => worktree_path.to_string(),
+        }
+    }
+
+    /// The initial prompt to send an agent, with the project's preamble
+    /// prepended. Without a prompt there is nothing to prepend to.
+    pub fn initial_prompt(&self, prompt: Option<&str>) -> Option<String> {
+        let prompt = prompt?;
+        match self.prompt_preamble.as_deref().map(str::trim) {
+            Some(preamble) if !preamble.is_empty() => Some(format!("{preamble}\n\n{prompt}")),
+            _ => Some(prompt.to_string()),
+        }
+    }
+
+    /// How a session runs `claude`: as the project says, with the variables
+    /// of the session's profile, if any, set over the project's.
+    pub fn invocation(&self, profile: Option<&Profile>) -> Invocation {
+        let mut env: Vec<(String, String)> = self
+            .claude
+            .env
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        env.extend(profile.map(Profile::env_vars).unwrap_or_default());
+        Invocation {
+            program: self
+                .claude
+                .path
+                .as_deref()
+                .map(expand_home)
+                .unwrap_or_else(|| Invocation::default().program),
+            args: self.claude.args.clone(),
+            env,
+            limits: self.limits.clone(),
+        }
+    }
+
+    /// The profile a task's sessions use: the one selected with `--profile`,
+    /// else the task's own choice, else the project's default, else none.
+    pub fn session_profile(
+        &self,
+        task_profile: Option<&str>,
+    ) -> Result<Option<(String, Profile)>, CommandError> {
+        let name = active_profile()
+            .or(task_profile)
+            .or(self.claude_profile.as_deref());
+        let Some(name) = name else {
+            return Ok(None);
+        };
+        let profile = GlobalConfig::load()?.profile(name)?.clone();
+        Ok(Some((name.to_string(), profile)))
+    }
+
+    /// Every command that prepares a new worktree: those of the project's
+    /// recipes, in order, then its setup commands.
+    pub fn all_setup_commands(&self) -> ConfigResult<Vec<String>> {
+        let recipes = all_recipes(&self.recipes);
+        let mut commands = Vec::new();
+        for name in &self.setup_recipes {
+            let recipe = recipes.get(name).ok_or_else(|| {
+                ConfigError::invalid(&format!(
+                    "Unknown setup recipe '{name}'. Use one of {} or define it under \"recipes\" in the project config",
+                    recipes.keys().cloned().collect::<Vec<_>>().join(", ")
+                ))
+            })?;
+            commands.extend(recipe.commands.iter().cloned());
+        }
+        commands.extend(self.setup_commands.iter().cloned());
+        Ok(commands)
+    }
+
+    /// The prompt from template `name`, with `{files}` replaced by `files`
+    /// one per line, or the files appended if the template does not use it.
+    pub fn render_template(&self, name: &str, files: &[String]) -> ConfigResult<String> {
+        let template = self.prompt_templates.get(name).ok_or_else(|| {
+            ConfigError::invalid(&format!(
+                "Unknown prompt template '{name}'. Define it under \"prompt_templates\" in the project config"
+            ))
+        })?;
+        let list = files.join("\n");
+        if template.contains("{files}") {
+            Ok(template.replace("{files}", &list))
+        } else {
+            Ok(format!("{template}\n\nChanged files:\n{list}"))
+        }
+    }
+
+    /// Directory new task worktrees are created in: under the profile's
+    /// worktree root if it sets one, else the project directory.
+    pub fn worktree_dir(&self, profile: Option<&Profile>) -> String {
+        match profile.and_then(|profile| profile.worktree_root.as_deref()) {
+            Some(root) => format!(
+                "{}/{}",
+                expand_home(root).trim_end_matches('/'),
+                self.project_name
+            ),
+            None => self.project_dir.clone(),
         }
     }
 
@@ -32,6 +513,156 @@ impl Config {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_profile_env_vars() {
+        let global = GlobalConfig::from_str(
+            r#"{
+                "profiles": {
+                    "work": {
+                        "config_dir": "/opt/claude-work",
+                        "model": "opus",
+                        "env": { "ANTHROPIC_BASE_URL": "https://gateway.example" }
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let vars = global.profile("work").unwrap().env_vars();
+        assert_eq!(
+            vars,
+            vec![
+                (
+                    "CLAUDE_CONFIG_DIR".to_string(),
+                    "/opt/claude-work".to_string()
+                ),
+                ("ANTHROPIC_MODEL".to_string(), "opus".to_string()),
+                (
+                    "ANTHROPIC_BASE_URL".to_string(),
+                    "https://gateway.example".to_string()
+                ),
+            ]
+        );
+        assert!(global.profile("personal").is_err());
+    }
+
+    #[test]
+    fn test_invocation_sets_profile_env_over_the_projects() {
+        let mut config = Config::new("demo", "/data/projects/demo");
+        assert_eq!(config.invocation(None), Invocation::default());
+
+        config.claude = ClaudeCommand {
+            path: Some("/opt/claude/bin/claude".to_string()),
+            args: vec!["--model".to_string(), "sonnet".to_string()],
+            env: BTreeMap::from([("ANTHROPIC_MODEL".to_string(), "sonnet".to_string())]),
+        };
+        let profile = Profile {
+            model: Some("opus".to_string()),
+            ..Profile::default()
+        };
+        let invocation = config.invocation(Some(&profile));
+        assert_eq!(invocation.program, "/opt/claude/bin/claude");
+        assert_eq!(invocation.args, ["--model", "sonnet"]);
+        assert_eq!(
+            invocation.env,
+            [
+                ("ANTHROPIC_MODEL".to_string(), "sonnet".to_string()),
+                ("ANTHROPIC_MODEL".to_string(), "opus".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_session_limits_wrap_the_program() {
+        assert_eq!(SessionLimits::default().wrap("claude"), ["claude"]);
+
+        let limits = SessionLimits {
+            memory: Some("4G".to_string()),
+            cpu_seconds: Some(600),
+        };
+        let line = limits.wrap("claude");
+        assert_eq!(
+            line[..6],
+            [
+                "systemd-run",
+                "--user",
+                "--scope",
+                "--quiet",
+                "--collect",
+                "--property=MemoryMax=4G"
+            ]
+        );
+        assert_eq!(line[6..9], ["--", "sh", "-c"]);
+        assert_eq!(line[10..], ["600", "claude"]);
+    }
+
+    #[test]
+    fn test_worktree_dir_uses_profile_root() {
+        let config = Config::new("demo", "/data/projects/demo");
+        assert_eq!(config.worktree_dir(None), "/data/projects/demo");
+        let profile = Profile {
+            worktree_root: Some("/work/trees/".to_string()),
+            ..Profile::default()
+        };
+        assert_eq!(config.worktree_dir(Some(&profile)), "/work/trees/demo");
+        assert_eq!(
+            config.worktree_dir(Some(&Profile::default())),
+            "/data/projects/demo"
+        );
+    }
+
+    #[test]
+    fn test_render_template() {
+        let mut config = Config::new("demo", "/tmp/demo");
+        config.prompt_templates.insert(
+            "fix-tests".to_string(),
+            "These tests changed:\n{files}\nMake them pass.".to_string(),
+        );
+        config
+            .prompt_templates
+            .insert("review".to_string(), "Review the change.".to_string());
+        let files = vec!["tests/a.rs".to_string(), "tests/b.rs".to_string()];
+
+        assert_eq!(
+            config.render_template("fix-tests", &files).unwrap(),
+            "These tests changed:\ntests/a.rs\ntests/b.rs\nMake them pass."
+        );
+        assert_eq!(
+            config.render_template("review", &files).unwrap(),
+            "Review the change.\n\nChanged files:\ntests/a.rs\ntests/b.rs"
+        );
+        assert!(config.render_template("missing", &files).is_err());
+    }
+
+    #[test]
+    fn test_all_setup_commands_run_recipes_first() {
+        let mut config = Config::new("demo", "/tmp/demo");
+        config.setup_commands = vec!["make dev".to_string()];
+        config.setup_recipes = vec!["node".to_string()];
+        assert_eq!(
+            config.all_setup_commands().unwrap(),
+            ["pnpm install --prefer-offline", "make dev"]
+        );
+
+        config.setup_recipes = vec!["haskell".to_string()];
+        assert!(config.all_setup_commands().is_err());
+    }
+
+    #[test]
+    fn test_expand_home() {
+        assert_eq!(expand_home("/abs/path"), "/abs/path");
+        assert!(!expand_home("~/.claude-work").starts_with('~'));
+    }
+
+    #[test]
+    fn test_config_progress_defaults_on() {
+        let json = r#"{ "project_name": "p", "project_dir": "/tmp/p" }"#;
+        assert!(Config::from_str(json).unwrap().progress);
+
+        let json = r#"{ "project_name": "p", "project_dir": "/tmp/p", "progress": false }"#;
+        assert!(!Config::from_str(json).unwrap().progress);
+    }
+
     #[test]
     fn test_config_from_str_valid_json() {
         let json = r#"{
@@ -67,4 +698,56 @@ mod tests {
         assert_eq!(config.project_name, parsed_config.project_name);
         assert_eq!(config.project_dir, parsed_config.project_dir);
     }
+
+    #[test]
+    fn test_config_permissions_default_when_missing() {
+        let json = r#"{
+            "project_name": "test-project",
+            "project_dir": "/path/to/project"
+        }"#;
+
+        let config = Config::from_str(json).unwrap();
+        assert_eq!(config.permissions.mode, PermissionMode::Default);
+        assert!(config.permissions.require_approval);
+    }
+
+    #[test]
+    fn test_config_permissions_from_json() {
+        let json = r#"{
+            "project_name": "test-project",
+            "project_dir": "/path/to/project",
+            "permissions": { "mode": "acceptEdits", "allowed_tools": ["Bash(git:*)"] }
+        }"#;
+
+        let config = Config::from_str(json).unwrap();
+        assert_eq!(config.permissions.mode, PermissionMode::AcceptEdits);
+        assert_eq!(config.permissions.allowed_tools, vec!["Bash(git:*)"]);
+        assert!(config.permissions.mode.is_elevated());
+    }
+
+    #[test]
+    fn test_initial_prompt_prepends_preamble() {
+        let mut config = Config::new("test-project", "/test/dir");
+        assert_eq!(
+            config.initial_prompt(Some("fix it")).as_deref(),
+            Some("fix it")
+        );
+
+        config.prompt_preamble = Some("Follow CONTRIBUTING.md.\n".to_string());
+        assert_eq!(
+            config.initial_prompt(Some("fix it")).as_deref(),
+            Some("Follow CONTRIBUTING.md.\n\nfix it")
+        );
+        assert_eq!(config.initial_prompt(None), None);
+    }
+
+    #[test]
+    fn test_blank_preamble_is_ignored() {
+        let mut config = Config::new("test-project", "/test/dir");
+        config.prompt_preamble = Some("  ".to_string());
+        assert_eq!(
+            config.initial_prompt(Some("fix it")).as_deref(),
+            Some("fix it")
+        );
+    }
 }
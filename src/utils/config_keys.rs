@@ -0,0 +1,438 @@
+use serde::Serialize;
+use serde::ser::{self, Impossible};
+use serde_json::value::Serializer as ValueSerializer;
+use serde_json::{Error, Map, Value};
+
+/// `config` as JSON, like `serde_json::to_value`, except that struct fields
+/// left out because they are empty are kept as `null`. Every setting the
+/// config has is then a key, whatever its value.
+pub fn setting_keys<T: Serialize>(config: &T) -> Result<Value, Error> {
+    config.serialize(SettingKeys)
+}
+
+/// Serializes to a [`Value`], recursing into structs and maps so their
+/// skipped fields are kept too. Lists and enum variants hold no settings
+/// and are serialized as they are.
+struct SettingKeys;
+
+impl ser::Serializer for SettingKeys {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = <ValueSerializer as ser::Serializer>::SerializeSeq;
+    type SerializeTuple = <ValueSerializer as ser::Serializer>::SerializeTuple;
+    type SerializeTupleStruct = <ValueSerializer as ser::Serializer>::SerializeTupleStruct;
+    type SerializeTupleVariant = <ValueSerializer as ser::Serializer>::SerializeTupleVariant;
+    type SerializeMap = Fields;
+    type SerializeStruct = Fields;
+    type SerializeStructVariant = <ValueSerializer as ser::Serializer>::SerializeStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        ValueSerializer.serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        ValueSerializer.serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        ValueSerializer.serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        ValueSerializer.serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        ValueSerializer.serialize_i64(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        ValueSerializer.serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        ValueSerializer.serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        ValueSerializer.serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        ValueSerializer.serialize_u64(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        ValueSerializer.serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        ValueSerializer.serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        ValueSerializer.serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        ValueSerializer.serialize_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        ValueSerializer.serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        ValueSerializer.serialize_unit_variant(name, index, variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        ValueSerializer.serialize_newtype_variant(name, index, variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        ValueSerializer.serialize_seq(len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Error> {
+        ValueSerializer.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        ValueSerializer.serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        ValueSerializer.serialize_tuple_variant(name, index, variant, len)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Fields, Error> {
+        Ok(Fields::default())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Fields, Error> {
+        Ok(Fields::default())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        ValueSerializer.serialize_struct_variant(name, index, variant, len)
+    }
+}
+
+/// The fields of a struct, or the entries of a map, collected so far.
+#[derive(Default)]
+struct Fields {
+    fields: Map<String, Value>,
+    next_key: Option<String>,
+}
+
+impl ser::SerializeStruct for Fields {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.fields
+            .insert(key.to_string(), value.serialize(SettingKeys)?);
+        Ok(())
+    }
+
+    // Called for a field its `skip_serializing_if` left out
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Error> {
+        self.fields.insert(key.to_string(), Value::Null);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(self.fields))
+    }
+}
+
+impl ser::SerializeMap for Fields {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        self.next_key = Some(key.serialize(KeyName)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| ser::Error::custom("map value without a key"))?;
+        self.fields.insert(key, value.serialize(SettingKeys)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Object(self.fields))
+    }
+}
+
+/// Serializes a map key, which in a config is always text.
+struct KeyName;
+
+impl ser::Serializer for KeyName {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+
+    fn serialize_str(self, v: &str) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, Error> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_none(self) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(not_text())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(not_text())
+    }
+}
+
+fn not_text() -> Error {
+    ser::Error::custom("config keys must be text")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Default)]
+    struct Limits {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        memory: Option<String>,
+        cpu_seconds: u64,
+    }
+
+    #[derive(Serialize, Default)]
+    struct Settings {
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        commands: Vec<String>,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        quiet: bool,
+        limits: Limits,
+        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+        profiles: BTreeMap<String, Limits>,
+    }
+
+    #[test]
+    fn test_setting_keys_keeps_skipped_fields() {
+        let settings = Settings {
+            profiles: BTreeMap::from([("work".to_string(), Limits::default())]),
+            ..Settings::default()
+        };
+        assert_eq!(
+            setting_keys(&settings).unwrap(),
+            json!({
+                "commands": null,
+                "quiet": null,
+                "limits": { "memory": null, "cpu_seconds": 0 },
+                "profiles": { "work": { "memory": null, "cpu_seconds": 0 } },
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(Settings::default()).unwrap(),
+            json!({ "limits": { "cpu_seconds": 0 } })
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use tracing::{info, warn};
+
+use crate::utils::config::PromptContext;
+use crate::utils::git::recent_commits;
+
+/// Room kept for a section's heading and truncation marker, so a section
+/// that does not fit is dropped rather than cut to a few characters.
+const MIN_SECTION_CHARS: usize = 200;
+
+/// Prepend the project's configured context to a session's initial prompt.
+///
+/// Sections are added in order (files as listed, then the git log) until
+/// `max_chars` is used up; the section that crosses the limit is cut at a
+/// line break, and any after it are named but left out.
+pub fn prepend_context(settings: &PromptContext, worktree_path: &str, prompt: &str) -> String {
+    match build_context(settings, worktree_path) {
+        Some(context) => format!("{context}\n\n{prompt}"),
+        None => prompt.to_string(),
+    }
+}
+
+fn build_context(settings: &PromptContext, worktree_path: &str) -> Option<String> {
+    let mut sections: Vec<(String, String)> = Vec::new();
+    for file in &settings.files {
+        match std::fs::read_to_string(Path::new(worktree_path).join(file)) {
+            Ok(content) => sections.push((file.clone(), content)),
+            Err(e) => warn!("Skipping context file '{}': {}", file, e),
+        }
+    }
+    if settings.git_log > 0 {
+        match recent_commits(worktree_path, settings.git_log) {
+            Ok(log) if !log.is_empty() => sections.push(("Recent commits".to_string(), log)),
+            Ok(_) => {}
+            Err(e) => warn!("Skipping git log in context: {}", e),
+        }
+    }
+    if sections.is_empty() {
+        return None;
+    }
+
+    let context = pack_sections(sections, settings.max_chars);
+    info!("Built {} characters of project context", context.len());
+    Some(context)
+}
+
+fn pack_sections(sections: Vec<(String, String)>, max_chars: usize) -> String {
+    let mut context = String::from("# Project context");
+    let mut budget = max_chars;
+    let mut omitted = Vec::new();
+    for (title, content) in sections {
+        let content = content.trim();
+        if budget < MIN_SECTION_CHARS {
+            omitted.push(title);
+            continue;
+        }
+        let body = if content.len() + MIN_SECTION_CHARS <= budget {
+            content.to_string()
+        } else {
+            truncate_lines(content, budget - MIN_SECTION_CHARS)
+        };
+        budget = budget.saturating_sub(body.len() + title.len());
+        context.push_str(&format!("\n\n## {title}\n\n{body}"));
+    }
+    if !omitted.is_empty() {
+        context.push_str(&format!(
+            "\n\nLeft out to save space: {}",
+            omitted.join(", ")
+        ));
+    }
+    context
+}
+
+/// Cut `text` to at most `max_chars`, at a line break where possible, and
+/// say how much was dropped.
+fn truncate_lines(text: &str, max_chars: usize) -> String {
+    let mut end = max_chars.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    if let Some(newline) = text[..end].rfind('\n') {
+        end = newline;
+    }
+    let dropped = text[end..].lines().filter(|line| !line.is_empty()).count();
+    format!(
+        "{}\n[... {dropped} more line(s) truncated]",
+        text[..end].trim_end()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn section(title: &str, content: &str) -> (String, String) {
+        (title.to_string(), content.to_string())
+    }
+
+    #[test]
+    fn test_pack_sections_keeps_what_fits() {
+        let context = pack_sections(
+            vec![
+                section("README.md", "# Demo\nA demo project.\n"),
+                section("Recent commits", "abc123 Add login"),
+            ],
+            10_000,
+        );
+        assert_eq!(
+            context,
+            "# Project context\n\n## README.md\n\n# Demo\nA demo project.\n\n## Recent commits\n\nabc123 Add login"
+        );
+    }
+
+    #[test]
+    fn test_pack_sections_truncates_and_omits_over_budget() {
+        let long = (0..100)
+            .map(|i| format!("line {i:03} of the architecture notes"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let context = pack_sections(
+            vec![
+                section("ARCHITECTURE.md", &long),
+                section("Recent commits", "abc123 Add login"),
+            ],
+            1_000,
+        );
+        assert!(context.contains("line 000"));
+        assert!(!context.contains("line 099"));
+        assert!(context.contains("more line(s) truncated]"));
+        assert!(context.ends_with("Left out to save space: Recent commits"));
+        assert!(context.len() < 1_200);
+    }
+
+    #[test]
+    fn test_truncate_lines_cuts_at_line_break() {
+        assert_eq!(
+            truncate_lines("one\ntwo\nthree\n", 9),
+            "one\ntwo\n[... 1 more line(s) truncated]"
+        );
+        assert_eq!(
+            truncate_lines("αβγδ", 3),
+            "α\n[... 1 more line(s) truncated]"
+        );
+    }
+
+    #[test]
+    fn test_prepend_context_without_settings_keeps_prompt() {
+        let prompt = prepend_context(&PromptContext::default(), "/nonexistent", "Fix it");
+        assert_eq!(prompt, "Fix it");
+    }
+}
@@ -0,0 +1,177 @@
+use crate::utils::{
+    config::Config,
+    errors::{CommandError, FileSystemError},
+    fs::current_dir,
+    git::{branch_point, commit_subjects},
+    tasks::TaskRecord,
+};
+
+/// Used unless the project config points `pr_template` at its own file.
+pub const DEFAULT_PR_TEMPLATE: &str = "## Summary
+
+{summary}
+
+## Changes
+
+{commits}
+
+## Notes
+
+{notes}
+";
+
+/// What a task's description is assembled from.
+pub struct DescriptionFields<'a> {
+    pub record: &'a TaskRecord,
+    pub base: &'a str,
+    /// Subjects of the task's commits, oldest first
+    pub commits: &'a [String],
+}
+
+/// The pull request description of a task whose worktree is at
+/// `worktree_path`, from the project's template.
+pub fn task_description(
+    config: &Config,
+    record: &TaskRecord,
+    worktree_path: &str,
+    base: &str,
+) -> Result<String, CommandError> {
+    let template = match &config.pr_template {
+        Some(path) => {
+            let path = current_dir()?.join(path);
+            std::fs::read_to_string(&path).map_err(|e| {
+                FileSystemError::read_failed(
+                    &format!("Failed to read PR template: {e}"),
+                    &path.to_string_lossy(),
+                )
+            })?
+        }
+        None => DEFAULT_PR_TEMPLATE.to_string(),
+    };
+    let commits = commit_subjects(worktree_path, &branch_point(worktree_path, base)?)?;
+    Ok(render_description(
+        &template,
+        &DescriptionFields {
+            record,
+            base,
+            commits: &commits,
+        },
+    ))
+}
+
+/// Fill a Markdown template's placeholders with a task's details:
+/// `{title}`, `{task}`, `{base}`, `{summary}`, `{commits}` and `{notes}`.
+///
+/// Sections under a `##` heading that end up empty are left out, so a task
+/// without notes gets no Notes heading.
+pub fn render_description(template: &str, fields: &DescriptionFields) -> String {
+    let record = fields.record;
+    let bullets = |items: Vec<&str>| {
+        items
+            .into_iter()
+            .map(|item| format!("- {item}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let rendered = template
+        .replace("{title}", &description_title(record))
+        .replace("{task}", &record.name)
+        .replace("{base}", fields.base)
+        .replace(
+            "{summary}",
+            record.summary.as_ref().map_or("", |s| s.text.as_str()),
+        )
+        .replace(
+            "{commits}",
+            &bullets(fields.commits.iter().map(String::as_str).collect()),
+        )
+        .replace(
+            "{notes}",
+            &bullets(record.notes.iter().map(|n| n.text.as_str()).collect()),
+        );
+    drop_empty_sections(&rendered)
+}
+
+/// The task's title if it was given one, otherwise its branch name.
+pub fn description_title(record: &TaskRecord) -> String {
+    record
+        .display_name
+        .clone()
+        .unwrap_or_else(|| record.name.clone())
+}
+
+fn drop_empty_sections(markdown: &str) -> String {
+    fn flush<'a>(section: &mut Vec<&'a str>, kept: &mut Vec<&'a str>) {
+        let has_content = section.iter().skip(1).any(|line| !line.trim().is_empty());
+        let is_heading = section.first().is_some_and(|line| line.starts_with("## "));
+        if has_content || !is_heading {
+            kept.append(section);
+        }
+        section.clear();
+    }
+
+    let mut kept: Vec<&str> = Vec::new();
+    let mut section: Vec<&str> = Vec::new();
+    for line in markdown.lines() {
+        if line.starts_with("## ") {
+            flush(&mut section, &mut kept);
+        }
+        section.push(line);
+    }
+    flush(&mut section, &mut kept);
+
+    let mut description = kept.join("\n").trim().to_string();
+    description.push('\n');
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields<'a>(record: &'a TaskRecord, commits: &'a [String]) -> DescriptionFields<'a> {
+        DescriptionFields {
+            record,
+            base: "origin/main",
+            commits,
+        }
+    }
+
+    #[test]
+    fn test_default_template_lists_commits_and_notes() {
+        let mut record = TaskRecord::new("feat/login");
+        record.set_summary("Adds a login form.");
+        record.add_note("needs design review");
+        let commits = vec!["add form".to_string(), "add tests".to_string()];
+
+        assert_eq!(
+            render_description(DEFAULT_PR_TEMPLATE, &fields(&record, &commits)),
+            "## Summary\n\nAdds a login form.\n\n## Changes\n\n- add form\n- add tests\n\n\
+             ## Notes\n\n- needs design review\n"
+        );
+    }
+
+    #[test]
+    fn test_empty_sections_are_dropped() {
+        let record = TaskRecord::new("feat/bare");
+        let commits = vec!["only commit".to_string()];
+
+        assert_eq!(
+            render_description(DEFAULT_PR_TEMPLATE, &fields(&record, &commits)),
+            "## Changes\n\n- only commit\n"
+        );
+    }
+
+    #[test]
+    fn test_custom_template_placeholders() {
+        let mut record = TaskRecord::new("fix-login-crash");
+        record.display_name = Some("Fix login crash".to_string());
+        let template = "# {title}\n\nBranch `{task}` onto `{base}`.\n";
+
+        assert_eq!(
+            render_description(template, &fields(&record, &[])),
+            "# Fix login crash\n\nBranch `fix-login-crash` onto `origin/main`.\n"
+        );
+    }
+}
@@ -16,6 +16,11 @@ pub enum CommandError {
 
     #[error(transparent)]
     Claude(#[from] ClaudeError),
+
+    /// A program claudectl ran on the user's behalf failed, and its exit
+    /// code is passed on
+    #[error("{message}")]
+    Exited { message: String, code: i32 },
 }
 
 impl CommandError {
@@ -23,6 +28,21 @@ impl CommandError {
         Self::General(message.to_string())
     }
 
+    pub fn exited(message: &str, code: i32) -> Self {
+        Self::Exited {
+            message: message.to_string(),
+            code,
+        }
+    }
+
+    /// The code claudectl exits with when a command fails with this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Exited { code, .. } => *code,
+            _ => 1,
+        }
+    }
+
     pub fn message(&self) -> String {
         self.to_string()
     }
@@ -48,6 +68,39 @@ pub enum GitError {
 
     #[error("Failed to remove worktree: {message}")]
     WorktreeRemoveFailed { message: String },
+
+    #[error("Failed to checkpoint worktree: {message}")]
+    CheckpointFailed { message: String },
+
+    #[error("Failed to reset worktree: {message}")]
+    ResetFailed { message: String },
+
+    #[error("Failed to diff worktree: {message}")]
+    DiffFailed { message: String },
+
+    #[error("Failed to delete branch: {message}")]
+    BranchDeleteFailed { message: String },
+
+    #[error("Failed to list files: {message}")]
+    ListFilesFailed { message: String },
+
+    #[error("Failed to read git log: {message}")]
+    LogFailed { message: String },
+
+    #[error("Failed to merge: {message}")]
+    MergeFailed { message: String },
+
+    #[error("Failed to push: {message}")]
+    PushFailed { message: String },
+
+    #[error("Failed to rename: {message}")]
+    RenameFailed { message: String },
+
+    #[error("Failed to archive: {message}")]
+    ArchiveFailed { message: String },
+
+    #[error("Failed to configure git: {message}")]
+    ConfigFailed { message: String },
 }
 
 impl GitError {
@@ -59,11 +112,22 @@ impl GitError {
             GitAction::WorktreeList => Self::WorktreeListFailed { message },
             GitAction::WorktreeAdd => Self::WorktreeAddFailed { message },
             GitAction::WorktreeRemove => Self::WorktreeRemoveFailed { message },
+            GitAction::Checkpoint => Self::CheckpointFailed { message },
+            GitAction::Reset => Self::ResetFailed { message },
+            GitAction::Diff => Self::DiffFailed { message },
+            GitAction::BranchDelete => Self::BranchDeleteFailed { message },
+            GitAction::ListFiles => Self::ListFilesFailed { message },
+            GitAction::Log => Self::LogFailed { message },
+            GitAction::Merge => Self::MergeFailed { message },
+            GitAction::Push => Self::PushFailed { message },
+            GitAction::Rename => Self::RenameFailed { message },
+            GitAction::Archive => Self::ArchiveFailed { message },
+            GitAction::Config => Self::ConfigFailed { message },
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum GitAction {
     /// Not a Git repository
     #[allow(dead_code)]
@@ -72,6 +136,17 @@ pub enum GitAction {
     WorktreeList,
     WorktreeAdd,
     WorktreeRemove,
+    Checkpoint,
+    Reset,
+    Diff,
+    BranchDelete,
+    ListFiles,
+    Log,
+    Merge,
+    Push,
+    Rename,
+    Archive,
+    Config,
 }
 
 // =================================================
@@ -84,7 +159,6 @@ pub enum ClaudeError {
     NotInstalled { message: String },
 
     #[error("Claude command failed: {message}")]
-    #[allow(dead_code)]
     CommandFailed { message: String },
 }
 
@@ -94,6 +168,12 @@ impl ClaudeError {
             message: message.to_string(),
         }
     }
+
+    pub fn command_failed(message: &str) -> Self {
+        Self::CommandFailed {
+            message: message.to_string(),
+        }
+    }
 }
 
 // =================================================
@@ -158,7 +238,6 @@ pub enum ConfigError {
     SerializeFailed { message: String },
 
     #[error("Invalid configuration: {message}")]
-    #[allow(dead_code)]
     Invalid { message: String },
 }
 
@@ -175,7 +254,6 @@ impl ConfigError {
         }
     }
 
-    #[allow(dead_code)]
     pub fn invalid(message: &str) -> Self {
         Self::Invalid {
             message: message.to_string(),
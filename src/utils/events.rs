@@ -0,0 +1,214 @@
+use crate::utils::errors::{CommandError, ConfigError};
+use crate::utils::fs::{
+    append_event_log, lock_event_log, read_event_log, read_event_log_in, write_event_log,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::warn;
+
+/// Something that happened to a task, as recorded in `.claudectl/events.jsonl`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Event {
+    pub at: DateTime<Utc>,
+    pub kind: EventKind,
+    pub task: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// How long the session or run that just ended took
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    TaskCreated,
+    TaskRemoved,
+    CheckpointCreated,
+    RolledBack,
+    SummaryGenerated,
+    NoteAdded,
+    SessionStarted,
+    ConflictsResolved,
+    SetupFailed,
+    Merged,
+    PullRequestOpened,
+    TaskRenamed,
+    TaskArchived,
+    TaskRestored,
+    RunFinished,
+    SessionStopped,
+    SessionKilled,
+    SessionRestarted,
+    SessionEnded,
+    Synced,
+}
+
+impl EventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::TaskCreated => "task created",
+            Self::TaskRemoved => "task removed",
+            Self::CheckpointCreated => "checkpoint",
+            Self::RolledBack => "rollback",
+            Self::SummaryGenerated => "summary",
+            Self::NoteAdded => "note",
+            Self::SessionStarted => "session started",
+            Self::ConflictsResolved => "conflicts resolved",
+            Self::SetupFailed => "setup failed",
+            Self::Merged => "merged",
+            Self::PullRequestOpened => "pull request opened",
+            Self::TaskRenamed => "task renamed",
+            Self::TaskArchived => "task archived",
+            Self::TaskRestored => "task restored",
+            Self::RunFinished => "run finished",
+            Self::SessionStopped => "session marked stopped",
+            Self::SessionKilled => "session killed",
+            Self::SessionRestarted => "session restarted",
+            Self::SessionEnded => "session ended",
+            Self::Synced => "synced",
+        }
+    }
+}
+
+impl Event {
+    pub fn new(kind: EventKind, task: &str, detail: Option<String>) -> Self {
+        Self {
+            at: Utc::now(),
+            kind,
+            task: task.to_string(),
+            detail,
+            duration_secs: None,
+        }
+    }
+}
+
+/// Append an event to the project's log.
+///
+/// The log is a record of work that already happened, so failures are
+/// reported in the debug log rather than failing the command.
+pub fn record_event(kind: EventKind, task: &str, detail: Option<String>) {
+    append_event(Event::new(kind, task, detail));
+}
+
+/// Like [`record_event`], for the end of a session or run that started at
+/// `started_at`, keeping how long it took for `stats`.
+pub fn record_timed_event(
+    kind: EventKind,
+    task: &str,
+    detail: Option<String>,
+    started_at: DateTime<Utc>,
+) {
+    let mut event = Event::new(kind, task, detail);
+    event.duration_secs =
+        Some(u64::try_from((event.at - started_at).num_seconds()).unwrap_or_default());
+    append_event(event);
+}
+
+fn append_event(event: Event) {
+    match serde_json::to_string(&event) {
+        Ok(line) => {
+            if let Err(e) = append_event_log(&line) {
+                warn!("Failed to record event: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize event: {}", e),
+    }
+}
+
+/// Read every event in the project's log, oldest first.
+pub fn read_events() -> Result<Vec<Event>, CommandError> {
+    Ok(parse_events(&read_event_log()?))
+}
+
+/// Like [`read_events`], for the project whose repository is at `repo_root`.
+pub fn read_events_in(repo_root: &Path) -> Result<Vec<Event>, CommandError> {
+    Ok(parse_events(&read_event_log_in(repo_root)?))
+}
+
+/// Point every event about task `from` at its new name `to`, so the task's
+/// history follows it. Events recorded meanwhile wait for the rewrite.
+pub fn rename_task_events(from: &str, to: &str) -> Result<(), CommandError> {
+    let _lock = lock_event_log()?;
+    let mut renamed = false;
+    let mut log = String::new();
+    for line in read_event_log()?.lines() {
+        match serde_json::from_str::<Event>(line) {
+            Ok(mut event) if event.task == from => {
+                event.task = to.to_string();
+                let line = serde_json::to_string(&event).map_err(|e| {
+                    ConfigError::serialize_failed(&format!("JSON serialization error: {e}"))
+                })?;
+                log.push_str(&line);
+                renamed = true;
+            }
+            // entries that cannot be read are kept as they are
+            _ => log.push_str(line),
+        }
+        log.push('\n');
+    }
+    if renamed {
+        write_event_log(&log)?;
+    }
+    Ok(())
+}
+
+/// Parse the event log, one JSON entry per line. Lines that cannot be read,
+/// e.g. one cut short by a crash, are skipped with a warning so the rest of
+/// the history stays usable.
+fn parse_events(log: &str) -> Vec<Event> {
+    log.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(index, line)| match serde_json::from_str(line) {
+            Ok(event) => Some(event),
+            Err(e) => {
+                warn!("Skipping event log line {}: {}", index + 1, e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_events_skips_blank_lines() {
+        let log = format!(
+            "{}\n\n{}\n",
+            serde_json::to_string(&Event::new(EventKind::TaskCreated, "feat/a", None)).unwrap(),
+            serde_json::to_string(&Event::new(
+                EventKind::NoteAdded,
+                "feat/a",
+                Some("hi".to_string())
+            ))
+            .unwrap()
+        );
+
+        let events = parse_events(&log);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, EventKind::TaskCreated);
+        assert_eq!(events[1].detail.as_deref(), Some("hi"));
+    }
+
+    #[test]
+    fn test_parse_events_skips_corrupt_lines() {
+        let log = format!(
+            "{}\nnot json\n{{\"at\":\"2026-01-01T00:00:00Z\",\"kind\":\"task_cr\n",
+            serde_json::to_string(&Event::new(EventKind::TaskCreated, "feat/a", None)).unwrap()
+        );
+
+        let events = parse_events(&log);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].task, "feat/a");
+    }
+
+    #[test]
+    fn test_event_kind_serializes_snake_case() {
+        let json = serde_json::to_string(&EventKind::CheckpointCreated).unwrap();
+        assert_eq!(json, "\"checkpoint_created\"");
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashSet;
+use std::collections::btree_map::Entry;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::utils::config::{Config, GlobalConfig};
+use crate::utils::errors::{CommandError, ConfigError, FileSystemError};
+use crate::utils::events::{Event, read_events_in};
+use crate::utils::fs::read_config_file;
+use crate::utils::projects::registered_projects;
+use crate::utils::tasks::{TaskRecord, stored_tasks_in};
+
+/// Format of export files; bumped when older releases could not read them.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Everything claudectl knows about the projects on this machine, as written
+/// by `claudectl export`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Export {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Profiles, filters and terminal settings; profile environment
+    /// variables are left out since they often hold API keys
+    #[serde(default)]
+    pub global: GlobalConfig,
+    #[serde(default)]
+    pub projects: Vec<ProjectExport>,
+}
+
+/// One registered project and its task metadata.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectExport {
+    /// Name of its global project directory
+    pub name: String,
+    /// Where the repository was on the exporting machine
+    pub repo_path: PathBuf,
+    /// Its `.claudectl/config.json`, if it could be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<Config>,
+    #[serde(default)]
+    pub tasks: Vec<TaskRecord>,
+    #[serde(default)]
+    pub events: Vec<Event>,
+}
+
+/// Collect every registered project whose repository still exists.
+/// Projects that cannot be read are skipped with a warning.
+pub fn collect_export() -> Result<Export, CommandError> {
+    let mut global = GlobalConfig::load()?;
+    for profile in global.profiles.values_mut() {
+        profile.env.clear();
+    }
+
+    let mut projects = Vec::new();
+    for project in registered_projects()? {
+        let registration = match project.registration {
+            Ok(registration) => registration,
+            Err(e) => {
+                warn!("Skipping unreadable project '{}': {}", project.name, e);
+                continue;
+            }
+        };
+        let repo_path = registration.repo_path;
+        if !repo_path.is_dir() {
+            warn!(
+                "Skipping project '{}': {} no longer exists",
+                project.name,
+                repo_path.display()
+            );
+            continue;
+        }
+        let config = read_config_file(&repo_path)
+            .ok()
+            .and_then(|raw| Config::from_str(&raw).ok());
+        projects.push(ProjectExport {
+            name: project.name,
+            tasks: stored_tasks_in(&repo_path)?,
+            events: read_events_in(&repo_path)?,
+            config,
+            repo_path,
+        });
+    }
+
+    Ok(Export {
+        version: EXPORT_VERSION,
+        exported_at: Utc::now(),
+        global,
+        projects,
+    })
+}
+
+/// Read a file written by `claudectl export`.
+pub fn read_export(path: &Path) -> Result<Export, CommandError> {
+    let raw = std::fs::read_to_string(path).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    })?;
+    let export: Export = serde_json::from_str(&raw).map_err(|e| {
+        ConfigError::invalid(&format!(
+            "{} is not a claudectl export: {e}",
+            path.display()
+        ))
+    })?;
+    if export.version > EXPORT_VERSION {
+        return Err(CommandError::new(&format!(
+            "{} was exported by a newer claudectl (format {}). Upgrade claudectl to import it",
+            path.display(),
+            export.version
+        )));
+    }
+    Ok(export)
+}
+
+/// Parse an `OLD=NEW` path mapping given to `import --map`.
+pub fn parse_path_map(value: &str) -> Result<(PathBuf, PathBuf), String> {
+    match value.split_once('=') {
+        Some((old, new)) if !old.is_empty() && !new.is_empty() => {
+            Ok((PathBuf::from(old), PathBuf::from(new)))
+        }
+        _ => Err(format!("expected OLD=NEW, got '{value}'")),
+    }
+}
+
+/// `path` with the first mapping whose old prefix it starts with applied.
+pub fn remap(path: &Path, maps: &[(PathBuf, PathBuf)]) -> Option<PathBuf> {
+    maps.iter().find_map(|(old, new)| {
+        path.strip_prefix(old).ok().map(|rest| {
+            if rest.as_os_str().is_empty() {
+                new.clone()
+            } else {
+                new.join(rest)
+            }
+        })
+    })
+}
+
+/// Add the profiles and filters of `imported` that `global` does not have
+/// yet, returning how many were added.
+pub fn merge_global(global: &mut GlobalConfig, imported: GlobalConfig) -> usize {
+    let mut added = 0;
+    for (name, profile) in imported.profiles {
+        if let Entry::Vacant(entry) = global.profiles.entry(name) {
+            entry.insert(profile);
+            added += 1;
+        }
+    }
+    for (name, filter) in imported.filters {
+        if let Entry::Vacant(entry) = global.filters.entry(name) {
+            entry.insert(filter);
+            added += 1;
+        }
+    }
+    added
+}
+
+/// `existing` with the events of `imported` it does not already have
+/// appended, as event log lines, and how many were added.
+pub fn merge_events(
+    existing: &[Event],
+    imported: &[Event],
+) -> Result<(String, usize), CommandError> {
+    let to_line = |event: &Event| {
+        serde_json::to_string(event)
+            .map_err(|e| ConfigError::serialize_failed(&format!("JSON serialization error: {e}")))
+    };
+    let mut log = String::new();
+    let mut seen = HashSet::new();
+    for event in existing {
+        let line = to_line(event)?;
+        log.push_str(&line);
+        log.push('\n');
+        seen.insert(line);
+    }
+    let mut added = 0;
+    for event in imported {
+        let line = to_line(event)?;
+        if seen.insert(line.clone()) {
+            log.push_str(&line);
+            log.push('\n');
+            added += 1;
+        }
+    }
+    Ok((log, added))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::events::EventKind;
+
+    #[test]
+    fn test_remap_replaces_the_first_matching_prefix() {
+        let maps = vec![
+            parse_path_map("/Users/ana/src=/home/ana/code").unwrap(),
+            parse_path_map("/Users/ana=/home/ana").unwrap(),
+        ];
+        assert_eq!(
+            remap(Path::new("/Users/ana/src/app"), &maps),
+            Some(PathBuf::from("/home/ana/code/app"))
+        );
+        assert_eq!(
+            remap(Path::new("/Users/ana/notes"), &maps),
+            Some(PathBuf::from("/home/ana/notes"))
+        );
+        assert_eq!(
+            remap(Path::new("/Users/ana/src"), &maps),
+            Some(PathBuf::from("/home/ana/code"))
+        );
+        // Prefixes match whole components only
+        assert_eq!(remap(Path::new("/Users/anabel/app"), &maps), None);
+        assert!(parse_path_map("/Users/ana").is_err());
+        assert!(parse_path_map("=/home/ana").is_err());
+    }
+
+    #[test]
+    fn test_merge_events_skips_known_ones() {
+        let created = Event::new(EventKind::TaskCreated, "feat/login", None);
+        let note = Event::new(EventKind::NoteAdded, "feat/login", Some("wip".into()));
+        let (log, added) =
+            merge_events(std::slice::from_ref(&created), &[created.clone(), note]).unwrap();
+        assert_eq!(added, 1);
+        assert_eq!(log.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_merge_global_keeps_existing_settings() {
+        let mut global: GlobalConfig =
+            serde_json::from_str(r#"{ "profiles": { "work": { "model": "opus" } } }"#).unwrap();
+        let imported: GlobalConfig =
+            serde_json::from_str(r#"{ "profiles": { "work": { "model": "haiku" }, "oss": {} } }"#)
+                .unwrap();
+        assert_eq!(merge_global(&mut global, imported), 1);
+        assert_eq!(global.profiles["work"].model.as_deref(), Some("opus"));
+        assert!(global.profiles.contains_key("oss"));
+    }
+}
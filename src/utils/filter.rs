@@ -0,0 +1,257 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::claude::Status;
+use crate::utils::git::Worktree;
+use crate::utils::lock::SessionLock;
+use crate::utils::tasks::{TaskKind, TaskRecord};
+
+/// A task as seen by listing views, independent of how it is rendered.
+pub struct TaskEntry {
+    pub project: String,
+    pub worktree: Worktree,
+    pub status: Status,
+    pub record: TaskRecord,
+    /// The session holding the task, if one is running
+    pub lock: Option<SessionLock>,
+}
+
+impl TaskEntry {
+    pub fn name(&self) -> &str {
+        &self.record.name
+    }
+
+    pub fn info(&self) -> TaskInfo {
+        TaskInfo {
+            name: self.record.name.clone(),
+            project: self.project.clone(),
+            kind: self.record.effective_kind(),
+            status: self.status,
+            session: self.lock.clone(),
+            worktree: self.worktree.path.clone(),
+            commit: self.worktree.commit.clone(),
+            base: self.record.base().to_string(),
+            created_at: self.record.created_at,
+            tags: self.record.tags.clone(),
+            issue: self.record.issue.clone(),
+            summary: self.record.summary.as_ref().map(|s| s.text.clone()),
+        }
+    }
+}
+
+/// A task as machine-readable output describes it. Fields are only ever
+/// added, so scripts and editor plugins can rely on the ones here.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TaskInfo {
+    pub name: String,
+    pub project: String,
+    /// Set at creation or inferred from the branch name
+    pub kind: Option<TaskKind>,
+    pub status: Status,
+    /// The session holding the task, if one is running
+    pub session: Option<SessionLock>,
+    pub worktree: String,
+    pub commit: String,
+    /// The ref the task's branch was created from
+    pub base: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    /// URL of the issue the task was created from
+    pub issue: Option<String>,
+    pub summary: Option<String>,
+}
+
+/// Criteria for narrowing a set of tasks; unset fields match everything.
+///
+/// Named filters saved in the global config use the same fields.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<TaskKind>,
+}
+
+impl TaskFilter {
+    /// Fill the fields left unset from a saved filter.
+    pub fn or(self, saved: &TaskFilter) -> Self {
+        Self {
+            status: self.status.or(saved.status),
+            project: self.project.or_else(|| saved.project.clone()),
+            tag: self.tag.or_else(|| saved.tag.clone()),
+            kind: self.kind.or(saved.kind),
+        }
+    }
+
+    pub fn matches(&self, entry: &TaskEntry) -> bool {
+        self.status.map_or(true, |status| entry.status == status)
+            && self
+                .project
+                .as_ref()
+                .map_or(true, |project| &entry.project == project)
+            && self
+                .tag
+                .as_ref()
+                .map_or(true, |tag| entry.record.tags.contains(tag))
+            && self
+                .kind
+                .map_or(true, |kind| entry.record.effective_kind() == Some(kind))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortKey {
+    /// Oldest first; tasks without a creation time last
+    Created,
+    /// Alphabetically by task name
+    Name,
+    /// Tasks needing attention first
+    Status,
+}
+
+/// Sort tasks in place; ties keep their original order.
+pub fn sort_tasks(entries: &mut [TaskEntry], key: SortKey) {
+    entries.sort_by(|a, b| match key {
+        SortKey::Created => match (a.record.created_at, b.record.created_at) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+        SortKey::Name => a.name().cmp(b.name()),
+        SortKey::Status => status_rank(a.status).cmp(&status_rank(b.status)),
+    });
+}
+
+fn status_rank(status: Status) -> u8 {
+    match status {
+        Status::Waiting => 0,
+        Status::Crashed => 1,
+        Status::SetupFailed => 2,
+        Status::Unknown => 3,
+        Status::Queued => 4,
+        Status::Working => 5,
+        Status::Ready => 6,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn entry(name: &str, status: Status, tags: &[&str], age_hours: Option<i64>) -> TaskEntry {
+        let mut record = TaskRecord::new(name);
+        record.tags = tags.iter().map(|t| t.to_string()).collect();
+        record.created_at = age_hours.map(|h| Utc::now() - Duration::hours(h));
+        TaskEntry {
+            project: "project".to_string(),
+            worktree: Worktree {
+                path: format!("/tmp/{name}"),
+                commit: "abc1234".to_string(),
+                branch: Some(name.to_string()),
+            },
+            status,
+            record,
+            lock: None,
+        }
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = TaskFilter::default();
+        assert!(filter.matches(&entry("a", Status::Ready, &[], None)));
+    }
+
+    #[test]
+    fn test_filter_by_status_project_and_tag() {
+        let task = entry("a", Status::Working, &["backend"], None);
+
+        let filter = TaskFilter {
+            status: Some(Status::Working),
+            project: Some("project".to_string()),
+            tag: Some("backend".to_string()),
+            kind: None,
+        };
+        assert!(filter.matches(&task));
+
+        let filter = TaskFilter {
+            status: Some(Status::Ready),
+            ..TaskFilter::default()
+        };
+        assert!(!filter.matches(&task));
+
+        let filter = TaskFilter {
+            project: Some("other".to_string()),
+            ..TaskFilter::default()
+        };
+        assert!(!filter.matches(&task));
+
+        let filter = TaskFilter {
+            tag: Some("frontend".to_string()),
+            ..TaskFilter::default()
+        };
+        assert!(!filter.matches(&task));
+    }
+
+    #[test]
+    fn test_sort_by_name_and_created() {
+        let mut entries = vec![
+            entry("b", Status::Ready, &[], None),
+            entry("c", Status::Ready, &[], Some(1)),
+            entry("a", Status::Ready, &[], Some(5)),
+        ];
+
+        sort_tasks(&mut entries, SortKey::Name);
+        let names: Vec<&str> = entries.iter().map(TaskEntry::name).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+
+        sort_tasks(&mut entries, SortKey::Created);
+        let names: Vec<&str> = entries.iter().map(TaskEntry::name).collect();
+        assert_eq!(names, vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn test_sort_by_status_puts_waiting_first() {
+        let mut entries = vec![
+            entry("ready", Status::Ready, &[], None),
+            entry("waiting", Status::Waiting, &[], None),
+            entry("working", Status::Working, &[], None),
+        ];
+
+        sort_tasks(&mut entries, SortKey::Status);
+        let names: Vec<&str> = entries.iter().map(TaskEntry::name).collect();
+        assert_eq!(names, vec!["waiting", "working", "ready"]);
+    }
+
+    #[test]
+    fn test_filter_by_inferred_kind() {
+        let filter = TaskFilter {
+            kind: Some(TaskKind::Bugfix),
+            ..TaskFilter::default()
+        };
+        assert!(filter.matches(&entry("fix/crash", Status::Ready, &[], None)));
+        assert!(!filter.matches(&entry("feat/login", Status::Ready, &[], None)));
+    }
+
+    #[test]
+    fn test_saved_filter_fills_unset_fields() {
+        let saved: TaskFilter =
+            serde_json::from_str(r#"{ "status": "setup-failed", "tag": "urgent" }"#).unwrap();
+        let filter = TaskFilter {
+            tag: Some("backend".to_string()),
+            ..TaskFilter::default()
+        }
+        .or(&saved);
+        assert_eq!(filter.status, Some(Status::SetupFailed));
+        assert_eq!(filter.tag.as_deref(), Some("backend"));
+        assert_eq!(filter.project, None);
+    }
+}
@@ -1,16 +1,49 @@
+use chrono::Utc;
 use directories::ProjectDirs;
-use std::path::PathBuf;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tracing::instrument;
 
 use crate::utils::errors::FileSystemError;
+use crate::utils::tasks::task_file_name;
 
 type FileSystemResult<T> = Result<T, FileSystemError>;
 
-fn current_dir() -> FileSystemResult<PathBuf> {
+/// Name of the file in a global project directory that records its repository.
+const PROJECT_FILE: &str = "project.json";
+
+/// File of gitignore-style patterns for paths claudectl skips when scanning
+/// a checkout, on top of what git ignores.
+const IGNORE_FILE: &str = ".claudectlignore";
+
+/// Directory in a project's `.claudectl` directory holding a journal per
+/// operation in progress.
+const JOURNALS_DIR: &str = "journals";
+const LOG_FILE: &str = "claudectl.log";
+
+/// Lines of the diagnostics log kept from earlier runs.
+pub const LOG_LINES: usize = 2000;
+
+/// How long a claudectl process may hold a file's lock, e.g. to trim the
+/// log, before the lock is taken for one left by a crash.
+const LOCK_FILE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How long a claudectl process waits for another to finish with the event
+/// log before giving up.
+const EVENT_LOG_LOCK_WAIT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How long a line of the diagnostics log waits for a trim to finish before
+/// it is written anyway, e.g. past a lock left by a crash.
+const LOG_LOCK_WAIT: std::time::Duration = std::time::Duration::from_millis(200);
+
+pub fn current_dir() -> FileSystemResult<PathBuf> {
     std::env::current_dir()
         .map_err(|_| FileSystemError::new("Failed to get current directory", "./"))
 }
 
-fn config_dir() -> FileSystemResult<PathBuf> {
+/// claudectl's global configuration directory, e.g. `~/.config/claudectl`.
+pub fn config_dir() -> FileSystemResult<PathBuf> {
     ProjectDirs::from("com", "claudectl", "claudectl")
         .ok_or_else(|| {
             FileSystemError::new(
@@ -21,9 +54,42 @@ fn config_dir() -> FileSystemResult<PathBuf> {
         .map(|dirs| dirs.config_dir().to_path_buf())
 }
 
+pub fn global_projects_dir() -> FileSystemResult<PathBuf> {
+    Ok(config_dir()?.join("projects"))
+}
+
+/// Settings shared by every project, such as Claude credential profiles.
+pub fn global_config_path() -> FileSystemResult<PathBuf> {
+    Ok(config_dir()?.join("config.json"))
+}
+
+/// Read the global config file, if one has been created.
+pub fn read_global_config_file() -> FileSystemResult<Option<String>> {
+    let path = global_config_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    std::fs::read_to_string(&path).map(Some).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    })
+}
+
+/// Write the global config file, creating its directory if needed.
+pub fn write_global_config_file(content: &str) -> FileSystemResult<()> {
+    let path = global_config_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            FileSystemError::new(
+                &format!("Failed to create config directory ({e})"),
+                &dir.to_string_lossy(),
+            )
+        })?;
+    }
+    write_atomic(&path, content)
+}
+
 pub fn create_global_configuration_dir(project_name: &str) -> FileSystemResult<String> {
-    let claudectl_config = config_dir()?;
-    let global_projects_dir = claudectl_config.join("projects");
+    let global_projects_dir = global_projects_dir()?;
 
     std::fs::create_dir_all(&global_projects_dir).map_err(|e| {
         FileSystemError::new(
@@ -87,6 +153,7 @@ pub fn read_local_config_file() -> FileSystemResult<String> {
     })
 }
 
+#[instrument(skip(config))]
 pub fn write_local_config_file(config: String) -> FileSystemResult<()> {
     let local_config_dir = current_dir()?.join(".claudectl");
     let config_file_path = local_config_dir.join("config.json");
@@ -101,3 +168,842 @@ pub fn write_local_config_file(config: String) -> FileSystemResult<()> {
 
     Ok(())
 }
+
+/// Read the claudectl config of the project whose repository is at `repo_root`.
+pub fn read_config_file(repo_root: &Path) -> FileSystemResult<String> {
+    let config_file_path = repo_root.join(".claudectl").join("config.json");
+    if !config_file_path.exists() {
+        return Err(FileSystemError::config_not_found(
+            "Project is no longer initialized",
+            &config_file_path.to_string_lossy(),
+        ));
+    }
+
+    std::fs::read_to_string(&config_file_path).map_err(|e| {
+        FileSystemError::read_failed(
+            &format!("IO error: {e}"),
+            &config_file_path.to_string_lossy(),
+        )
+    })
+}
+
+/// Write the claudectl config of the project whose repository is at
+/// `repo_root`.
+pub fn write_config_file(repo_root: &Path, content: &str) -> FileSystemResult<()> {
+    let config_dir = repo_root.join(".claudectl");
+    std::fs::create_dir_all(&config_dir).map_err(|e| {
+        FileSystemError::new(
+            &format!("Failed to create configuration directory ({e})"),
+            &config_dir.to_string_lossy(),
+        )
+    })?;
+    write_atomic(&config_dir.join("config.json"), content)
+}
+
+/// Global project directories, one per initialized project.
+pub fn global_project_dirs() -> FileSystemResult<Vec<PathBuf>> {
+    let global_projects_dir = global_projects_dir()?;
+    if !global_projects_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = std::fs::read_dir(&global_projects_dir).map_err(|e| {
+        FileSystemError::read_failed(
+            &format!("IO error: {e}"),
+            &global_projects_dir.to_string_lossy(),
+        )
+    })?;
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Read the registration file of a global project directory, or `None` if
+/// the project has not been registered.
+pub fn read_project_file(project_dir: &Path) -> FileSystemResult<Option<String>> {
+    let project_file_path = project_dir.join(PROJECT_FILE);
+    if !project_file_path.exists() {
+        return Ok(None);
+    }
+
+    std::fs::read_to_string(&project_file_path)
+        .map(Some)
+        .map_err(|e| {
+            FileSystemError::read_failed(
+                &format!("IO error: {e}"),
+                &project_file_path.to_string_lossy(),
+            )
+        })
+}
+
+pub fn write_project_file(project_dir: &Path, content: String) -> FileSystemResult<()> {
+    std::fs::create_dir_all(project_dir).map_err(|e| {
+        FileSystemError::new(
+            &format!("Failed to create global project directory ({e})"),
+            &project_dir.to_string_lossy(),
+        )
+    })?;
+
+    let project_file_path = project_dir.join(PROJECT_FILE);
+    std::fs::write(&project_file_path, content).map_err(|e| {
+        FileSystemError::write_failed(
+            &format!("IO error: {e}"),
+            &project_file_path.to_string_lossy(),
+        )
+    })
+}
+
+fn tasks_dir(repo_root: &Path) -> PathBuf {
+    repo_root.join(".claudectl").join("tasks")
+}
+
+/// Where the lock claiming a task's worktree for a session is kept.
+pub fn lock_file_path_in(repo_root: &Path, task_name: &str) -> PathBuf {
+    locks_dir(repo_root).join(task_file_name(task_name))
+}
+
+fn locks_dir(repo_root: &Path) -> PathBuf {
+    repo_root.join(".claudectl").join("locks")
+}
+
+/// Every lock file in the project at `repo_root`, sorted by name.
+pub fn lock_files_in(repo_root: &Path) -> FileSystemResult<Vec<PathBuf>> {
+    json_files_in(&locks_dir(repo_root))
+}
+
+fn queue_dir(repo_root: &Path) -> PathBuf {
+    repo_root.join(".claudectl").join("queue")
+}
+
+/// Where a task waiting for a free session slot is queued.
+pub fn queue_file_path_in(repo_root: &Path, task_name: &str) -> PathBuf {
+    queue_dir(repo_root).join(task_file_name(task_name))
+}
+
+/// Every queue entry in the project at `repo_root`, sorted by name.
+pub fn queue_files_in(repo_root: &Path) -> FileSystemResult<Vec<PathBuf>> {
+    json_files_in(&queue_dir(repo_root))
+}
+
+fn json_files_in(dir: &Path) -> FileSystemResult<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = std::fs::read_dir(dir).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &dir.to_string_lossy())
+    })?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+pub fn write_lock_file(path: &Path, content: String) -> FileSystemResult<()> {
+    write_state_file(path, content, "locks")
+}
+
+/// Create a lock file holding `content`, or return `false` if there already
+/// is one. The file appears with all of its content, so it is never read
+/// half-written.
+pub fn create_lock_file(path: &Path, content: &str) -> FileSystemResult<bool> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(format!(".{}.tmp", std::process::id()));
+    let temp_path = PathBuf::from(temp_path);
+    write_state_file(&temp_path, content.to_string(), "locks")?;
+    let linked = std::fs::hard_link(&temp_path, path);
+    let _ = std::fs::remove_file(&temp_path);
+    match linked {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(FileSystemError::write_failed(
+            &format!("IO error: {e}"),
+            &path.to_string_lossy(),
+        )),
+    }
+}
+
+pub fn remove_lock_file(path: &Path) -> FileSystemResult<()> {
+    remove_state_file(path)
+}
+
+pub fn write_queue_file(path: &Path, content: String) -> FileSystemResult<()> {
+    write_state_file(path, content, "queue")
+}
+
+pub fn remove_queue_file(path: &Path) -> FileSystemResult<()> {
+    remove_state_file(path)
+}
+
+/// Write a file describing a running or waiting session, creating its
+/// `what` directory if needed.
+fn write_state_file(path: &Path, content: String, what: &str) -> FileSystemResult<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            FileSystemError::new(
+                &format!("Failed to create {what} directory ({e})"),
+                &dir.to_string_lossy(),
+            )
+        })?;
+    }
+    std::fs::write(path, content).map_err(|e| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    })
+}
+
+fn remove_state_file(path: &Path) -> FileSystemResult<()> {
+    std::fs::remove_file(path).map_err(|e| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    })
+}
+
+/// The current project's `.claudectl` directory; it may not exist yet.
+pub fn local_config_dir() -> FileSystemResult<PathBuf> {
+    Ok(current_dir()?.join(".claudectl"))
+}
+
+pub fn local_tasks_dir() -> FileSystemResult<PathBuf> {
+    Ok(tasks_dir(&current_dir()?))
+}
+
+/// Read a task's stored record, or `None` if claudectl has none for it yet.
+pub fn read_task_file(task_name: &str) -> FileSystemResult<Option<String>> {
+    read_task_file_in(&current_dir()?, task_name)
+}
+
+/// Like [`read_task_file`], for the project whose repository is at `repo_root`.
+pub fn read_task_file_in(repo_root: &Path, task_name: &str) -> FileSystemResult<Option<String>> {
+    let task_file_path = tasks_dir(repo_root).join(task_file_name(task_name));
+    if !task_file_path.exists() {
+        return Ok(None);
+    }
+
+    std::fs::read_to_string(&task_file_path)
+        .map(Some)
+        .map_err(|e| {
+            FileSystemError::read_failed(
+                &format!("IO error: {e}"),
+                &task_file_path.to_string_lossy(),
+            )
+        })
+}
+
+#[instrument(skip(content))]
+pub fn write_task_file(task_name: &str, content: String) -> FileSystemResult<()> {
+    write_task_file_in(&current_dir()?, task_name, &content)
+}
+
+/// Like [`write_task_file`], for the project whose repository is at `repo_root`.
+pub fn write_task_file_in(
+    repo_root: &Path,
+    task_name: &str,
+    content: &str,
+) -> FileSystemResult<()> {
+    let tasks_dir = tasks_dir(repo_root);
+    std::fs::create_dir_all(&tasks_dir).map_err(|e| {
+        FileSystemError::new(
+            &format!("Failed to create tasks directory ({e})"),
+            &tasks_dir.to_string_lossy(),
+        )
+    })?;
+
+    write_atomic(&tasks_dir.join(task_file_name(task_name)), content)
+}
+
+/// Write `content` to a temporary file next to `path` and rename it into
+/// place, so readers see the old contents or the new but never a partial
+/// write.
+pub fn write_atomic(path: &Path, content: &str) -> FileSystemResult<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    std::fs::write(&temp_path, content)
+        .and_then(|_| std::fs::rename(&temp_path, path))
+        .map_err(|e| {
+            FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+        })
+}
+
+/// A new file for this process to journal an operation's steps in; it only
+/// exists while the operation runs, or after it was interrupted.
+pub fn new_journal_path() -> FileSystemResult<PathBuf> {
+    let dir = local_config_dir()?.join(JOURNALS_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        FileSystemError::new(
+            &format!("Failed to create journals directory ({e})"),
+            &dir.to_string_lossy(),
+        )
+    })?;
+    let nanos = Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    Ok(dir.join(format!("{}-{nanos}.json", std::process::id())))
+}
+
+/// Every operation journal in the current project, sorted by name.
+pub fn journal_files() -> FileSystemResult<Vec<PathBuf>> {
+    json_files_in(&local_config_dir()?.join(JOURNALS_DIR))
+}
+
+/// Number of task records stored for the current project.
+pub fn task_file_count() -> FileSystemResult<usize> {
+    let tasks_dir = local_tasks_dir()?;
+    if !tasks_dir.exists() {
+        return Ok(0);
+    }
+
+    let entries = std::fs::read_dir(&tasks_dir).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &tasks_dir.to_string_lossy())
+    })?;
+    Ok(entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .count())
+}
+
+/// The contents of every task record stored for the current project.
+pub fn read_task_files() -> FileSystemResult<Vec<String>> {
+    read_task_files_in(&current_dir()?)
+}
+
+/// Like [`read_task_files`], for the project whose repository is at `repo_root`.
+pub fn read_task_files_in(repo_root: &Path) -> FileSystemResult<Vec<String>> {
+    let tasks_dir = tasks_dir(repo_root);
+    if !tasks_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = std::fs::read_dir(&tasks_dir).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &tasks_dir.to_string_lossy())
+    })?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| {
+            std::fs::read_to_string(path).map_err(|e| {
+                FileSystemError::read_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+            })
+        })
+        .collect()
+}
+
+pub fn remove_task_file(task_name: &str) -> FileSystemResult<()> {
+    let task_file_path = local_tasks_dir()?.join(task_file_name(task_name));
+    if !task_file_path.exists() {
+        return Ok(());
+    }
+
+    std::fs::remove_file(&task_file_path).map_err(|e| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &task_file_path.to_string_lossy())
+    })
+}
+
+/// File in a task's worktree describing its work for the agent, such as the
+/// issue it was created from.
+pub const TASK_CONTEXT_FILE: &str = ".claudectl/task.md";
+
+/// Write `content` to `TASK_CONTEXT_FILE` in `task_dir`.
+pub fn write_task_context(task_dir: &Path, content: &str) -> FileSystemResult<()> {
+    let path = task_dir.join(TASK_CONTEXT_FILE);
+    let write_failed = |e: std::io::Error| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(write_failed)?;
+    }
+    std::fs::write(&path, content).map_err(write_failed)
+}
+
+/// Files and directories that carry Claude instructions for a repository.
+pub const CLAUDE_FILES: [&str; 2] = ["CLAUDE.md", ".claude"];
+
+/// Whether the repository at `root` has any Claude instruction files.
+pub fn has_claude_files(root: &Path) -> bool {
+    CLAUDE_FILES.iter().any(|name| root.join(name).exists())
+}
+
+/// The `.claudectlignore` rules at `root`; without the file nothing extra is
+/// ignored.
+pub fn read_ignore_rules(root: &Path) -> FileSystemResult<Gitignore> {
+    let path = root.join(IGNORE_FILE);
+    let mut builder = GitignoreBuilder::new(root);
+    if path.exists() {
+        if let Some(e) = builder.add(&path) {
+            return Err(FileSystemError::new(
+                &format!("Failed to read ignore rules: {e}"),
+                &path.to_string_lossy(),
+            ));
+        }
+    }
+    builder.build().map_err(|e| {
+        FileSystemError::new(
+            &format!("Invalid ignore rules: {e}"),
+            &path.to_string_lossy(),
+        )
+    })
+}
+
+/// Whether `path`, relative to the root the rules were read from, or one of
+/// its parent directories is ignored.
+pub fn is_ignored(rules: &Gitignore, path: &str) -> bool {
+    rules.matched_path_or_any_parents(path, false).is_ignore()
+}
+
+/// Copy the repository's Claude instruction files from `source_root` into
+/// `dest_root`, returning how many files were created or updated.
+pub fn sync_claude_files(source_root: &Path, dest_root: &Path) -> FileSystemResult<usize> {
+    let mut updated = 0;
+    for name in CLAUDE_FILES {
+        let source = source_root.join(name);
+        if source.exists() {
+            updated += sync_path(&source, &dest_root.join(name))?;
+        }
+    }
+    Ok(updated)
+}
+
+fn sync_path(source: &Path, dest: &Path) -> FileSystemResult<usize> {
+    if source.is_dir() {
+        std::fs::create_dir_all(dest).map_err(|e| {
+            FileSystemError::new(
+                &format!("Failed to create directory ({e})"),
+                &dest.to_string_lossy(),
+            )
+        })?;
+        let entries = std::fs::read_dir(source).map_err(|e| {
+            FileSystemError::read_failed(&format!("IO error: {e}"), &source.to_string_lossy())
+        })?;
+
+        let mut updated = 0;
+        for entry in entries {
+            let entry = entry.map_err(|e| {
+                FileSystemError::read_failed(&format!("IO error: {e}"), &source.to_string_lossy())
+            })?;
+            updated += sync_path(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+        return Ok(updated);
+    }
+
+    let content = std::fs::read(source).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &source.to_string_lossy())
+    })?;
+    if std::fs::read(dest).is_ok_and(|existing| existing == content) {
+        return Ok(0);
+    }
+    std::fs::write(dest, content).map_err(|e| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &dest.to_string_lossy())
+    })?;
+    Ok(1)
+}
+
+pub fn event_log_path() -> FileSystemResult<PathBuf> {
+    Ok(event_log_path_in(&current_dir()?))
+}
+
+fn event_log_path_in(repo_root: &Path) -> PathBuf {
+    repo_root.join(".claudectl").join("events.jsonl")
+}
+
+/// Append a single line to the project's event log.
+pub fn append_event_log(line: &str) -> FileSystemResult<()> {
+    use std::io::Write;
+
+    let event_log_path = event_log_path()?;
+    let _lock = EventLogLock::acquire(&event_log_path)?;
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&event_log_path)
+        .map_err(|e| {
+            FileSystemError::write_failed(
+                &format!("IO error: {e}"),
+                &event_log_path.to_string_lossy(),
+            )
+        })?;
+
+    writeln!(file, "{line}").map_err(|e| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &event_log_path.to_string_lossy())
+    })
+}
+
+/// Keeps other claudectl processes from writing to the event log until it
+/// is dropped, so one that reads the log and writes it back does not lose
+/// events appended in between.
+#[derive(Debug)]
+pub struct EventLogLock(PathBuf);
+
+impl EventLogLock {
+    fn acquire(event_log_path: &Path) -> FileSystemResult<Self> {
+        let io_error = |e: std::io::Error| {
+            FileSystemError::write_failed(
+                &format!("IO error: {e}"),
+                &event_log_path.to_string_lossy(),
+            )
+        };
+        match wait_for_lock_file(event_log_path, EVENT_LOG_LOCK_WAIT).map_err(io_error)? {
+            Some(lock) => Ok(Self(lock)),
+            None => Err(FileSystemError::write_failed(
+                "Another claudectl process is writing to the event log",
+                &event_log_path.to_string_lossy(),
+            )),
+        }
+    }
+}
+
+impl Drop for EventLogLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Lock the project's event log for a read followed by
+/// [`write_event_log`].
+pub fn lock_event_log() -> FileSystemResult<EventLogLock> {
+    lock_event_log_in(&current_dir()?)
+}
+
+/// Like [`lock_event_log`], for the project whose repository is at `repo_root`.
+pub fn lock_event_log_in(repo_root: &Path) -> FileSystemResult<EventLogLock> {
+    EventLogLock::acquire(&event_log_path_in(repo_root))
+}
+
+/// Replace the project's event log.
+pub fn write_event_log(content: &str) -> FileSystemResult<()> {
+    write_event_log_in(&current_dir()?, content)
+}
+
+/// Like [`write_event_log`], for the project whose repository is at `repo_root`.
+pub fn write_event_log_in(repo_root: &Path, content: &str) -> FileSystemResult<()> {
+    write_atomic(&event_log_path_in(repo_root), content)
+}
+
+/// Read the project's event log, returning an empty string if nothing has been logged.
+pub fn read_event_log() -> FileSystemResult<String> {
+    read_event_log_in(&current_dir()?)
+}
+
+/// Like [`read_event_log`], for the project whose repository is at `repo_root`.
+pub fn read_event_log_in(repo_root: &Path) -> FileSystemResult<String> {
+    let event_log_path = event_log_path_in(repo_root);
+    if !event_log_path.exists() {
+        return Ok(String::new());
+    }
+
+    std::fs::read_to_string(&event_log_path).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &event_log_path.to_string_lossy())
+    })
+}
+
+/// Where a project's archived tasks are kept, e.g.
+/// `~/.config/claudectl/archives/<project>`.
+pub fn archives_dir(project_name: &str) -> FileSystemResult<PathBuf> {
+    Ok(config_dir()?.join("archives").join(project_name))
+}
+
+/// Every file in a project's archive directory, sorted by name.
+pub fn archive_files(project_name: &str) -> FileSystemResult<Vec<PathBuf>> {
+    let dir = archives_dir(project_name)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries = std::fs::read_dir(&dir).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &dir.to_string_lossy())
+    })?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// claudectl's own diagnostics from recent runs, shared by every project.
+pub fn log_path() -> FileSystemResult<PathBuf> {
+    Ok(config_dir()?.join(LOG_FILE))
+}
+
+/// The diagnostics log, open for appending. Each write holds the log's
+/// lock, so a trim never reads the log and writes it back around a line
+/// another process appends.
+#[derive(Debug)]
+pub struct LogFile {
+    file: std::fs::File,
+    path: PathBuf,
+}
+
+impl Write for LogFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // A stuck lock shouldn't cost the line
+        let lock = wait_for_lock_file(&self.path, LOG_LOCK_WAIT).unwrap_or(None);
+        let written = self.file.write_all(buf);
+        if let Some(lock) = lock {
+            let _ = std::fs::remove_file(lock);
+        }
+        written.map(|()| buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Open the diagnostics log for appending, first dropping all but its last
+/// `keep` lines so it never grows without bound.
+pub fn open_log(keep: usize) -> FileSystemResult<LogFile> {
+    let path = log_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            FileSystemError::new(
+                &format!("Failed to create config directory ({e})"),
+                &dir.to_string_lossy(),
+            )
+        })?;
+    }
+    trim_log(keep)?;
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| {
+            FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+        })?;
+    Ok(LogFile { file, path })
+}
+
+/// Drop all but the last `keep` lines of the diagnostics log, e.g. every so
+/// often in long-running commands. The file is rewritten in place, so
+/// processes appending to it keep writing to it, under the lock each
+/// [`LogFile`] write takes; it is left untrimmed when the lock stays taken.
+pub fn trim_log(keep: usize) -> FileSystemResult<()> {
+    trim_lines_in(&log_path()?, keep)
+}
+
+fn trim_lines_in(path: &Path, keep: usize) -> FileSystemResult<()> {
+    let io_error = |e: std::io::Error| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    };
+    let lock = match wait_for_lock_file(path, LOG_LOCK_WAIT) {
+        Ok(Some(lock)) => lock,
+        // Another claudectl process is trimming or writing to it
+        Ok(None) => return Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(io_error(e)),
+    };
+
+    let trimmed = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .and_then(|mut file| {
+            let mut log = String::new();
+            file.read_to_string(&mut log)?;
+            let kept = last_lines(&log, keep);
+            if kept.len() < log.len() {
+                file.set_len(0)?;
+                file.seek(SeekFrom::Start(0))?;
+                file.write_all(kept.as_bytes())?;
+            }
+            Ok(())
+        });
+    let _ = std::fs::remove_file(&lock);
+    match trimmed {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        result => result.map_err(io_error),
+    }
+}
+
+/// Take `<path>.lock` like [`try_lock_file`], retrying for up to `wait`
+/// while another process holds it.
+fn wait_for_lock_file(path: &Path, wait: std::time::Duration) -> std::io::Result<Option<PathBuf>> {
+    let waited = std::time::Instant::now();
+    loop {
+        if let Some(lock) = try_lock_file(path)? {
+            return Ok(Some(lock));
+        }
+        if waited.elapsed() > wait {
+            return Ok(None);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
+
+/// Take `<path>.lock` for this process, returning its path, or `None` if
+/// another process holds it. A lock older than [`LOCK_FILE_TIMEOUT`] was
+/// left by a process that died holding it and is taken over.
+fn try_lock_file(path: &Path) -> std::io::Result<Option<PathBuf>> {
+    let mut lock = path.as_os_str().to_owned();
+    lock.push(".lock");
+    let lock = PathBuf::from(lock);
+    let stale = std::fs::metadata(&lock)
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| modified.elapsed().unwrap_or_default() > LOCK_FILE_TIMEOUT);
+    if stale {
+        let _ = std::fs::remove_file(&lock);
+    }
+    match std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&lock)
+    {
+        Ok(_) => Ok(Some(lock)),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Read the diagnostics log, returning an empty string if nothing has been logged.
+pub fn read_log() -> FileSystemResult<String> {
+    let path = log_path()?;
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    std::fs::read_to_string(&path).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    })
+}
+
+/// The end of `text` holding its last `count` lines.
+pub fn last_lines(text: &str, count: usize) -> &str {
+    if count == 0 {
+        return "";
+    }
+    let body = text.strip_suffix('\n').unwrap_or(text);
+    match body.rmatch_indices('\n').nth(count - 1) {
+        Some((index, _)) => &text[index + 1..],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_last_lines() {
+        assert_eq!(last_lines("a\nb\nc\n", 2), "b\nc\n");
+        assert_eq!(last_lines("a\nb\nc", 2), "b\nc");
+        assert_eq!(last_lines("a\nb\n", 5), "a\nb\n");
+        assert_eq!(last_lines("a\n", 0), "");
+        assert_eq!(last_lines("", 3), "");
+    }
+
+    #[test]
+    fn test_trimming_keeps_open_handles_writing_to_the_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("claudectl.log");
+        fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        let mut handle = fs::OpenOptions::new().append(true).open(&path).unwrap();
+
+        trim_lines_in(&path, 2).unwrap();
+        handle.write_all(b"four\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two\nthree\nfour\n");
+
+        trim_lines_in(&dir.path().join("missing.log"), 2).unwrap();
+
+        // Left alone while another process is trimming it
+        fs::write(dir.path().join("claudectl.log.lock"), "").unwrap();
+        trim_lines_in(&path, 1).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two\nthree\nfour\n");
+    }
+
+    #[test]
+    fn test_log_writes_wait_for_a_trim_to_finish() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("claudectl.log");
+        fs::write(&path, "one\n").unwrap();
+        let file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        let mut log = LogFile {
+            file,
+            path: path.clone(),
+        };
+
+        // Another process is trimming the log
+        let trim = try_lock_file(&path).unwrap().unwrap();
+        let writer = std::thread::spawn(move || {
+            log.write_all(b"two\n").unwrap();
+            log
+        });
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\n");
+        fs::remove_file(trim).unwrap();
+
+        let mut log = writer.join().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+        // The writer gave the lock back
+        trim_lines_in(&path, 1).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two\n");
+        log.write_all(b"three\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "two\nthree\n");
+    }
+
+    #[test]
+    fn test_event_log_lock_holds_off_writers_until_dropped() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join(".claudectl")).unwrap();
+        let path = event_log_path_in(dir.path());
+
+        let lock = lock_event_log_in(dir.path()).unwrap();
+        assert_eq!(try_lock_file(&path).unwrap(), None);
+        drop(lock);
+        assert!(try_lock_file(&path).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_read_ignore_rules() {
+        let root = TempDir::new().unwrap();
+        let rules = read_ignore_rules(root.path()).unwrap();
+        assert!(!is_ignored(&rules, "node_modules/left-pad/index.js"));
+
+        fs::write(
+            root.path().join(IGNORE_FILE),
+            "# build output\nnode_modules/\n*.log\n!keep.log\n",
+        )
+        .unwrap();
+        let rules = read_ignore_rules(root.path()).unwrap();
+        assert!(is_ignored(&rules, "node_modules/left-pad/index.js"));
+        assert!(is_ignored(&rules, "tests/debug.log"));
+        assert!(!is_ignored(&rules, "keep.log"));
+        assert!(!is_ignored(&rules, "tests/login.rs"));
+    }
+
+    #[test]
+    fn test_sync_claude_files_copies_and_skips_unchanged() {
+        let source = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+        fs::write(source.path().join("CLAUDE.md"), "# Rules\n").unwrap();
+        fs::create_dir_all(source.path().join(".claude/commands")).unwrap();
+        fs::write(source.path().join(".claude/commands/review.md"), "review").unwrap();
+
+        assert!(has_claude_files(source.path()));
+        assert!(!has_claude_files(dest.path()));
+
+        let updated = sync_claude_files(source.path(), dest.path()).unwrap();
+        assert_eq!(updated, 2);
+        assert_eq!(
+            fs::read_to_string(dest.path().join(".claude/commands/review.md")).unwrap(),
+            "review"
+        );
+
+        let updated = sync_claude_files(source.path(), dest.path()).unwrap();
+        assert_eq!(updated, 0);
+
+        fs::write(source.path().join("CLAUDE.md"), "# New rules\n").unwrap();
+        let updated = sync_claude_files(source.path(), dest.path()).unwrap();
+        assert_eq!(updated, 1);
+        assert_eq!(
+            fs::read_to_string(dest.path().join("CLAUDE.md")).unwrap(),
+            "# New rules\n"
+        );
+    }
+}
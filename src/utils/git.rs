@@ -1,5 +1,7 @@
 use crate::utils::errors::{GitAction, GitError};
-use std::process::Command;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
 use tracing::{debug, info, instrument, warn};
 
 type GitResult<T> = Result<T, GitError>;
@@ -16,29 +18,106 @@ pub fn is_git_repository() -> GitResult<bool> {
     }
 }
 
-#[instrument]
-pub fn fetch_origin() -> GitResult<()> {
+/// The root of the repository containing the current directory.
+pub fn repo_toplevel() -> GitResult<String> {
+    git_in(".", &["rev-parse", "--show-toplevel"], &[], GitAction::Repo)
+}
+
+#[instrument(skip(on_progress))]
+pub fn fetch_origin(on_progress: &mut dyn FnMut(&GitProgress)) -> GitResult<()> {
     info!("Fetching latest changes from origin");
-    let output = Command::new("git")
-        .args(["fetch", "origin"])
-        .output()
+    git_with_progress(
+        ".",
+        &["fetch", "--progress", "origin"],
+        GitAction::Fetch,
+        on_progress,
+    )?;
+
+    info!("Successfully fetched from origin");
+    Ok(())
+}
+
+/// How far along a long-running git operation is, parsed from the progress
+/// lines git writes to stderr, e.g. `Receiving objects:  45% (450/1000)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitProgress {
+    pub label: String,
+    pub percent: u8,
+}
+
+impl GitProgress {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim().trim_start_matches("remote:").trim_start();
+        let (before, _) = line.split_once('%')?;
+        let (label, percent) = before.rsplit_once(':')?;
+        Some(Self {
+            label: label.trim().to_string(),
+            percent: percent.trim().parse().ok()?,
+        })
+    }
+}
+
+/// Run a git command inside `dir`, reporting progress as git writes it.
+///
+/// Git redraws progress in place with carriage returns, so stderr is split
+/// on both `\r` and `\n`; everything that is not progress is kept for the
+/// error message.
+fn git_with_progress(
+    dir: &str,
+    args: &[&str],
+    action: GitAction,
+    on_progress: &mut dyn FnMut(&GitProgress),
+) -> GitResult<()> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
         .map_err(|e| {
             GitError::new(
-                &format!("Failed to execute git fetch command: {e}"),
-                GitAction::Fetch,
+                &format!("Failed to execute git {}: {e}", args.join(" ")),
+                action,
             )
         })?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        warn!("Git fetch failed with stderr: {}", stderr);
+    let mut messages = Vec::new();
+    if let Some(stderr) = child.stderr.take() {
+        let mut segment = Vec::new();
+        for byte in BufReader::new(stderr).bytes() {
+            let Ok(byte) = byte else { break };
+            if byte != b'\r' && byte != b'\n' {
+                segment.push(byte);
+                continue;
+            }
+            let line = String::from_utf8_lossy(&segment).to_string();
+            segment.clear();
+            match GitProgress::parse(&line) {
+                Some(progress) => on_progress(&progress),
+                None if !line.trim().is_empty() => messages.push(line),
+                None => {}
+            }
+        }
+        if !segment.is_empty() {
+            messages.push(String::from_utf8_lossy(&segment).to_string());
+        }
+    }
+
+    let status = child.wait().map_err(|e| {
+        GitError::new(
+            &format!("Failed to wait for git {}: {e}", args.join(" ")),
+            action,
+        )
+    })?;
+    if !status.success() {
+        let stderr = messages.join("\n");
+        warn!("git {} failed with stderr: {}", args.join(" "), stderr);
         return Err(GitError::new(
-            &format!("Git fetch failed: {stderr}"),
-            GitAction::Fetch,
+            &format!("git {} failed: {}", args.join(" "), stderr.trim()),
+            action,
         ));
     }
-
-    info!("Successfully fetched from origin");
     Ok(())
 }
 
@@ -49,7 +128,14 @@ pub struct Worktree {
 }
 
 pub fn worktree_list() -> GitResult<Vec<Worktree>> {
+    worktree_list_in(Path::new("."))
+}
+
+/// List the worktrees of the repository at `repo_root`.
+pub fn worktree_list_in(repo_root: &Path) -> GitResult<Vec<Worktree>> {
     let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_root)
         .args(["worktree", "list"])
         .output()
         .map_err(|e| {
@@ -94,6 +180,59 @@ pub fn worktree_list() -> GitResult<Vec<Worktree>> {
     Ok(worktrees)
 }
 
+/// Branches of every task worktree; the main checkout is listed first by git
+/// and is not a task.
+pub fn task_branches() -> GitResult<Vec<String>> {
+    Ok(worktree_list()?
+        .into_iter()
+        .skip(1)
+        .filter_map(|wt| wt.branch)
+        .collect())
+}
+
+/// Whether a local branch with this name exists.
+pub fn branch_exists(branch_name: &str) -> GitResult<bool> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("refs/heads/{branch_name}"))
+        .output()
+        .map_err(|e| {
+            GitError::new(
+                &format!("Failed to execute git rev-parse command: {e}"),
+                GitAction::WorktreeAdd,
+            )
+        })?;
+    Ok(output.status.success())
+}
+
+/// Whether origin has a branch with this name, as of the last fetch.
+pub fn remote_branch_exists(branch_name: &str) -> GitResult<bool> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet"])
+        .arg(format!("refs/remotes/origin/{branch_name}"))
+        .output()
+        .map_err(|e| {
+            GitError::new(
+                &format!("Failed to execute git rev-parse command: {e}"),
+                GitAction::Merge,
+            )
+        })?;
+    Ok(output.status.success())
+}
+
+/// The local branch a task starting from `base` is merged into: the base
+/// without its remote.
+pub fn local_branch(base: &str) -> &str {
+    base.strip_prefix("origin/").unwrap_or(base)
+}
+
+/// Find the worktree checked out on the given task branch.
+pub fn find_worktree(branch_name: &str) -> GitResult<Option<Worktree>> {
+    Ok(worktree_list()?
+        .into_iter()
+        .find(|wt| wt.branch.as_deref() == Some(branch_name)))
+}
+
 pub fn worktree_exists(worktree_path: &str) -> GitResult<bool> {
     let output = Command::new("git")
         .args(["worktree", "list"])
@@ -117,36 +256,65 @@ pub fn worktree_exists(worktree_path: &str) -> GitResult<bool> {
     Ok(stdout.lines().any(|line| line.contains(worktree_path)))
 }
 
-#[instrument(fields(branch_name = %branch_name, worktree_path = %worktree_path))]
-pub fn create_worktree(branch_name: &str, worktree_path: &str) -> GitResult<()> {
+/// Branch new task worktrees start from unless told otherwise.
+pub const DEFAULT_BASE: &str = "origin/main";
+
+/// Create a worktree on a new branch, reporting checkout progress.
+///
+/// `git worktree add` only reports progress to a terminal, so the worktree
+/// is added without a checkout and the files are checked out separately.
+/// With `sparse_dir`, only that directory (and files at the root) are
+/// checked out.
+#[instrument(skip(on_progress), fields(branch_name = %branch_name, worktree_path = %worktree_path, start_point = %start_point))]
+pub fn create_worktree(
+    branch_name: &str,
+    worktree_path: &str,
+    start_point: &str,
+    sparse_dir: Option<&str>,
+    on_progress: &mut dyn FnMut(&GitProgress),
+) -> GitResult<()> {
     info!(
-        "Creating worktree '{}' at path: {}",
-        branch_name, worktree_path
+        "Creating worktree '{}' at path: {} from {}",
+        branch_name, worktree_path, start_point
     );
-    let output = Command::new("git")
-        .args([
+    git_in(
+        ".",
+        &[
             "worktree",
             "add",
+            "--no-checkout",
             "-b",
             branch_name,
             worktree_path,
-            "origin/main",
-        ])
-        .output()
-        .map_err(|e| {
-            GitError::new(
-                &format!("Failed to execute git worktree add command: {e}"),
-                GitAction::WorktreeAdd,
-            )
-        })?;
+            start_point,
+        ],
+        &[],
+        GitAction::WorktreeAdd,
+    )?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        warn!("Git worktree add failed with stderr: {}", stderr);
-        return Err(GitError::new(
-            &format!("Git worktree add failed: {stderr}"),
+    let sparse = match sparse_dir {
+        Some(dir) => git_in(
+            worktree_path,
+            &["sparse-checkout", "set", "--cone", dir],
+            &[],
             GitAction::WorktreeAdd,
-        ));
+        )
+        .map(|_| ()),
+        None => Ok(()),
+    };
+    let checkout = sparse.and_then(|_| {
+        git_with_progress(
+            worktree_path,
+            &["checkout", "--progress", "--force"],
+            GitAction::WorktreeAdd,
+            on_progress,
+        )
+    });
+    if let Err(e) = checkout {
+        warn!("Checkout failed, removing worktree: {}", e);
+        let _ = remove_worktree(worktree_path);
+        let _ = delete_branch(branch_name);
+        return Err(e);
     }
 
     info!(
@@ -182,6 +350,781 @@ pub fn remove_worktree(worktree_path: &str) -> GitResult<()> {
     Ok(())
 }
 
+/// Force-delete a local branch, e.g. one left behind by an aborted task.
+#[instrument]
+pub fn delete_branch(branch_name: &str) -> GitResult<()> {
+    info!("Deleting branch: {}", branch_name);
+    git_in(
+        ".",
+        &["branch", "-D", branch_name],
+        &[],
+        GitAction::BranchDelete,
+    )?;
+    Ok(())
+}
+
+/// Forget worktrees whose directories no longer exist.
+pub fn prune_worktrees() -> GitResult<()> {
+    info!("Pruning missing worktrees");
+    git_in(".", &["worktree", "prune"], &[], GitAction::WorktreeRemove)?;
+    Ok(())
+}
+
+/// Rename a local branch, including one checked out in a worktree.
+#[instrument]
+pub fn rename_branch(from: &str, to: &str) -> GitResult<()> {
+    info!("Renaming branch {} to {}", from, to);
+    git_in(".", &["branch", "-m", from, to], &[], GitAction::Rename)?;
+    Ok(())
+}
+
+/// Move a worktree's directory, creating the new parent directory if needed.
+#[instrument]
+pub fn move_worktree(from: &str, to: &str) -> GitResult<()> {
+    info!("Moving worktree {} to {}", from, to);
+    if let Some(parent) = Path::new(to).parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            GitError::new(
+                &format!("Failed to create {}: {e}", parent.display()),
+                GitAction::Rename,
+            )
+        })?;
+    }
+    git_in(".", &["worktree", "move", from, to], &[], GitAction::Rename)?;
+    Ok(())
+}
+
+/// Whether every commit on `branch` is also on `target`.
+pub fn is_ancestor(branch: &str, target: &str) -> GitResult<bool> {
+    let output = Command::new("git")
+        .args(["merge-base", "--is-ancestor", branch, target])
+        .output()
+        .map_err(|e| {
+            GitError::new(
+                &format!("Failed to execute git merge-base command: {e}"),
+                GitAction::Log,
+            )
+        })?;
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => Err(GitError::new(
+            &format!(
+                "git merge-base failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            GitAction::Log,
+        )),
+    }
+}
+
+/// Whether a task branch has been merged into `target`: every commit on it
+/// is on `target`, and it has moved on from `fork_point`, the commit it was
+/// created at, so a fresh task is not mistaken for a finished one. Tasks
+/// created before the fork point was recorded go by whether the branch's
+/// reflog shows it ever moved.
+pub fn is_merged(branch: &str, target: &str, fork_point: Option<&str>) -> GitResult<bool> {
+    if !is_ancestor(branch, target)? {
+        return Ok(false);
+    }
+    if let Some(fork_point) = fork_point {
+        return Ok(branch_commit(branch)? != fork_point);
+    }
+    let reflog = git_in(
+        ".",
+        &[
+            "reflog",
+            "show",
+            "--format=%H",
+            &format!("refs/heads/{branch}"),
+        ],
+        &[],
+        GitAction::Log,
+    )?;
+    Ok(reflog.lines().count() > 1)
+}
+
+/// The commit a local branch points at.
+pub fn branch_commit(branch: &str) -> GitResult<String> {
+    git_in(
+        ".",
+        &[
+            "rev-parse",
+            "--verify",
+            &format!("refs/heads/{branch}^{{commit}}"),
+        ],
+        &[],
+        GitAction::Log,
+    )
+}
+
+/// Run a git command inside `dir` and return its trimmed stdout.
+fn git_in(dir: &str, args: &[&str], envs: &[(&str, &str)], action: GitAction) -> GitResult<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .envs(envs.iter().copied())
+        .output()
+        .map_err(|e| {
+            GitError::new(
+                &format!("Failed to execute git {}: {e}", args.join(" ")),
+                action,
+            )
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        warn!("git {} failed with stderr: {}", args.join(" "), stderr);
+        return Err(GitError::new(
+            &format!("git {} failed: {}", args.join(" "), stderr.trim()),
+            action,
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Write the worktree's current files, including untracked ones, to a tree
+/// object using a throwaway index.
+fn snapshot_tree(worktree_path: &str) -> GitResult<String> {
+    let index_path = std::env::temp_dir().join(format!(
+        "claudectl-index-{}-{}",
+        std::process::id(),
+        worktree_path.replace(['/', '\\', ':'], "-")
+    ));
+    let index = index_path.to_string_lossy().to_string();
+    let env = [("GIT_INDEX_FILE", index.as_str())];
+
+    let tree = git_in(
+        worktree_path,
+        &["read-tree", "HEAD"],
+        &env,
+        GitAction::Checkpoint,
+    )
+    .and_then(|_| git_in(worktree_path, &["add", "-A"], &env, GitAction::Checkpoint))
+    .and_then(|_| git_in(worktree_path, &["write-tree"], &env, GitAction::Checkpoint));
+    if Path::new(&index_path).exists() {
+        let _ = std::fs::remove_file(&index_path);
+    }
+    tree
+}
+
+/// How much of a diff to show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffView {
+    Patch,
+    /// Lines changed per file, as `git diff --stat`
+    Stat,
+    /// Only the changed paths
+    NameOnly,
+}
+
+/// Diff everything done in a worktree since `base`, committed or not.
+pub fn worktree_diff(worktree_path: &str, base: &str) -> GitResult<String> {
+    worktree_diff_view(worktree_path, base, DiffView::Patch)
+}
+
+/// Like [`worktree_diff`], shown as `view`.
+pub fn worktree_diff_view(worktree_path: &str, base: &str, view: DiffView) -> GitResult<String> {
+    let tree = snapshot_tree(worktree_path)?;
+    let mut args = vec!["diff"];
+    match view {
+        DiffView::Patch => {}
+        DiffView::Stat => args.push("--stat"),
+        DiffView::NameOnly => args.push("--name-only"),
+    }
+    args.extend([base, tree.as_str()]);
+    git_in(worktree_path, &args, &[], GitAction::Diff)
+}
+
+/// Lines added and deleted in one file of a diff; binary files count none.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    pub added: usize,
+    pub deleted: usize,
+}
+
+/// Per-file line counts of everything done in a worktree since `base`.
+pub fn worktree_changes(worktree_path: &str, base: &str) -> GitResult<Vec<FileChange>> {
+    let tree = snapshot_tree(worktree_path)?;
+    let stdout = git_in(
+        worktree_path,
+        &["diff", "--numstat", base, &tree],
+        &[],
+        GitAction::Diff,
+    )?;
+    Ok(stdout.lines().filter_map(parse_numstat).collect())
+}
+
+/// Parse a `git diff --numstat` line, e.g. `3\t1\tsrc/main.rs`.
+fn parse_numstat(line: &str) -> Option<FileChange> {
+    let mut fields = line.splitn(3, '\t');
+    let added = fields.next()?;
+    let deleted = fields.next()?;
+    Some(FileChange {
+        added: added.parse().unwrap_or(0),
+        deleted: deleted.parse().unwrap_or(0),
+        path: fields.next()?.to_string(),
+    })
+}
+
+/// Diff the current files of two worktrees of the repository, committed or
+/// not: what the second has that the first does not.
+pub fn compare_worktrees(first_path: &str, second_path: &str) -> GitResult<String> {
+    let first = snapshot_tree(first_path)?;
+    let second = snapshot_tree(second_path)?;
+    git_in(first_path, &["diff", &first, &second], &[], GitAction::Diff)
+}
+
+/// Paths with uncommitted changes in a worktree, including untracked files.
+pub fn changed_files(worktree_path: &str) -> GitResult<Vec<String>> {
+    let stdout = git_in(
+        worktree_path,
+        &["status", "--porcelain"],
+        &[],
+        GitAction::Diff,
+    )?;
+    Ok(stdout
+        .lines()
+        .filter_map(|line| line.trim_start().split_once(' '))
+        .map(|(_, path)| path.trim_start().to_string())
+        .collect())
+}
+
+/// Files in a checkout that git tracks or would track, i.e. everything not
+/// ignored, relative to `dir`.
+pub fn tracked_files(dir: &str) -> GitResult<Vec<String>> {
+    let stdout = git_in(
+        dir,
+        &["ls-files", "--cached", "--others", "--exclude-standard"],
+        &[],
+        GitAction::ListFiles,
+    )?;
+    Ok(stdout.lines().map(str::to_string).collect())
+}
+
+/// The last `count` commits in a worktree, one line each.
+pub fn recent_commits(worktree_path: &str, count: usize) -> GitResult<String> {
+    git_in(
+        worktree_path,
+        &[
+            "log",
+            "--oneline",
+            "--no-decorate",
+            "-n",
+            &count.to_string(),
+        ],
+        &[],
+        GitAction::Log,
+    )
+}
+
+/// Subjects of the commits in a worktree since `since`, oldest first.
+pub fn commit_subjects(worktree_path: &str, since: &str) -> GitResult<Vec<String>> {
+    let stdout = git_in(
+        worktree_path,
+        &["log", "--reverse", "--format=%s", &format!("{since}..HEAD")],
+        &[],
+        GitAction::Log,
+    )?;
+    Ok(stdout.lines().map(str::to_string).collect())
+}
+
+/// How many commits a worktree's HEAD has that `base` lacks, and the reverse.
+pub fn ahead_behind(worktree_path: &str, base: &str) -> GitResult<(usize, usize)> {
+    let stdout = git_in(
+        worktree_path,
+        &[
+            "rev-list",
+            "--left-right",
+            "--count",
+            &format!("HEAD...{base}"),
+        ],
+        &[],
+        GitAction::Diff,
+    )?;
+    let mut counts = stdout.split_whitespace().map(|n| n.parse().unwrap_or(0));
+    Ok((counts.next().unwrap_or(0), counts.next().unwrap_or(0)))
+}
+
+/// Paths with unresolved merge conflicts in a worktree.
+pub fn conflicted_files(worktree_path: &str) -> GitResult<Vec<String>> {
+    let stdout = git_in(
+        worktree_path,
+        &["diff", "--name-only", "--diff-filter=U"],
+        &[],
+        GitAction::Diff,
+    )?;
+    Ok(stdout.lines().map(str::to_string).collect())
+}
+
+/// The branch checked out in a checkout, or `None` when HEAD is detached.
+pub fn current_branch(dir: &str) -> GitResult<Option<String>> {
+    let branch = git_in(
+        dir,
+        &["rev-parse", "--abbrev-ref", "HEAD"],
+        &[],
+        GitAction::Merge,
+    )?;
+    Ok(Some(branch).filter(|branch| branch != "HEAD"))
+}
+
+/// The URL of the `origin` remote.
+pub fn origin_url() -> GitResult<String> {
+    git_in(".", &["remote", "get-url", "origin"], &[], GitAction::Push)
+}
+
+/// Whether origin answers, without prompting for credentials.
+pub fn origin_reachable() -> bool {
+    git_in(
+        ".",
+        &["ls-remote", "--heads", "origin"],
+        &[("GIT_TERMINAL_PROMPT", "0")],
+        GitAction::Fetch,
+    )
+    .is_ok()
+}
+
+/// Push a branch to origin and track it there.
+pub fn push_branch(dir: &str, branch: &str) -> GitResult<()> {
+    info!("Pushing {} to origin", branch);
+    git_in(
+        dir,
+        &["push", "--set-upstream", "origin", branch],
+        &[],
+        GitAction::Push,
+    )?;
+    Ok(())
+}
+
+/// Merge `branch` into the branch checked out in `dir`, always recording a
+/// merge commit.
+pub fn merge_branch(dir: &str, branch: &str) -> GitResult<()> {
+    info!("Merging {} in {}", branch, dir);
+    git_in(
+        dir,
+        &["merge", "--no-ff", "--no-edit", branch],
+        &[],
+        GitAction::Merge,
+    )?;
+    Ok(())
+}
+
+/// Merge `branch` into the branch checked out in `dir`, fast-forwarding
+/// when there is nothing to merge.
+pub fn merge_into_worktree(dir: &str, branch: &str) -> GitResult<()> {
+    info!("Merging {} into {}", branch, dir);
+    git_in(dir, &["merge", "--no-edit", branch], &[], GitAction::Merge)?;
+    Ok(())
+}
+
+/// Move the branch checked out in `dir` forward to `target`, failing if
+/// that would need a merge.
+pub fn fast_forward(dir: &str, target: &str) -> GitResult<()> {
+    info!("Fast-forwarding {} to {}", dir, target);
+    git_in(dir, &["merge", "--ff-only", target], &[], GitAction::Merge)?;
+    Ok(())
+}
+
+/// Replay the commits of the branch checked out in `dir` on top of `onto`.
+pub fn rebase(dir: &str, onto: &str) -> GitResult<()> {
+    info!("Rebasing {} onto {}", dir, onto);
+    git_in(dir, &["rebase", onto], &[], GitAction::Merge)?;
+    Ok(())
+}
+
+/// The absolute path of the repository's shared git directory, as seen
+/// from a checkout of it.
+pub fn git_common_dir(dir: &str) -> GitResult<String> {
+    git_in(
+        dir,
+        &["rev-parse", "--path-format=absolute", "--git-common-dir"],
+        &[],
+        GitAction::Config,
+    )
+}
+
+/// Have git ignore files matching `pattern` in every checkout of the
+/// repository at `dir`, through its shared `info/exclude` rather than the
+/// tracked `.gitignore`.
+pub fn exclude_path(dir: &str, pattern: &str) -> GitResult<()> {
+    let exclude = Path::new(&git_common_dir(dir)?)
+        .join("info")
+        .join("exclude");
+    let current = std::fs::read_to_string(&exclude).unwrap_or_default();
+    if current.lines().any(|line| line == pattern) {
+        return Ok(());
+    }
+    let separator = if current.is_empty() || current.ends_with('\n') {
+        ""
+    } else {
+        "\n"
+    };
+    exclude
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&exclude, format!("{current}{separator}{pattern}\n")))
+        .map_err(|e| {
+            GitError::new(
+                &format!("Failed to update {}: {e}", exclude.display()),
+                GitAction::Config,
+            )
+        })
+}
+
+/// Set `key` to `values` for one worktree only, replacing what it had.
+///
+/// Per-worktree settings need the `extensions.worktreeConfig` extension,
+/// which is turned on for the repository the first time.
+pub fn set_worktree_config(dir: &str, key: &str, values: &[&str]) -> GitResult<()> {
+    git_in(
+        dir,
+        &["config", "extensions.worktreeConfig", "true"],
+        &[],
+        GitAction::Config,
+    )?;
+    // Fails when the key is not set yet, which is fine
+    let _ = git_in(
+        dir,
+        &["config", "--worktree", "--unset-all", key],
+        &[],
+        GitAction::Config,
+    );
+    for value in values {
+        git_in(
+            dir,
+            &["config", "--worktree", "--add", key, value],
+            &[],
+            GitAction::Config,
+        )?;
+    }
+    Ok(())
+}
+
+/// The values `key` has for one worktree, empty when it is not set there.
+pub fn worktree_config(dir: &str, key: &str) -> GitResult<Vec<String>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["config", "--worktree", "--get-all", key])
+        .output()
+        .map_err(|e| {
+            GitError::new(
+                &format!("Failed to execute git config: {e}"),
+                GitAction::Config,
+            )
+        })?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+const CHECKPOINT_REF_PREFIX: &str = "refs/claudectl/checkpoints";
+
+pub struct Checkpoint {
+    pub id: u32,
+    pub commit: String,
+    pub created: String,
+    pub message: String,
+}
+
+fn checkpoint_ref(branch_name: &str, id: u32) -> String {
+    format!("{CHECKPOINT_REF_PREFIX}/{branch_name}/{id}")
+}
+
+/// List the checkpoints recorded for a task branch, oldest first.
+pub fn list_checkpoints(worktree_path: &str, branch_name: &str) -> GitResult<Vec<Checkpoint>> {
+    let stdout = git_in(
+        worktree_path,
+        &[
+            "for-each-ref",
+            "--format=%(refname)%09%(objectname:short)%09%(creatordate:iso)%09%(contents:subject)",
+            &format!("{CHECKPOINT_REF_PREFIX}/{branch_name}/"),
+        ],
+        &[],
+        GitAction::Checkpoint,
+    )?;
+
+    let mut checkpoints: Vec<Checkpoint> = stdout
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(4, '\t');
+            let id = parts.next()?.rsplit('/').next()?.parse().ok()?;
+            Some(Checkpoint {
+                id,
+                commit: parts.next()?.to_string(),
+                created: parts.next()?.to_string(),
+                message: parts.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect();
+    checkpoints.sort_by_key(|c| c.id);
+
+    Ok(checkpoints)
+}
+
+/// Move a task's checkpoints to a new branch name, keeping their ids.
+pub fn rename_checkpoints(from: &str, to: &str) -> GitResult<()> {
+    let refs = git_in(
+        ".",
+        &[
+            "for-each-ref",
+            "--format=%(refname)%09%(objectname)",
+            &format!("{CHECKPOINT_REF_PREFIX}/{from}/"),
+        ],
+        &[],
+        GitAction::Rename,
+    )?;
+    for line in refs.lines() {
+        let Some((name, commit)) = line.split_once('\t') else {
+            continue;
+        };
+        let Some(id) = name.rsplit('/').next() else {
+            continue;
+        };
+        let new_name = format!("{CHECKPOINT_REF_PREFIX}/{to}/{id}");
+        git_in(
+            ".",
+            &["update-ref", &new_name, commit, ""],
+            &[],
+            GitAction::Rename,
+        )?;
+        git_in(".", &["update-ref", "-d", name], &[], GitAction::Rename)?;
+    }
+    Ok(())
+}
+
+/// Snapshot the worktree's uncommitted state into a checkpoint commit.
+///
+/// The worktree, the real index and the task branch are left untouched. Returns `None` when the state
+/// matches the last checkpoint, or when the worktree is clean and
+/// `include_clean` is not set.
+#[instrument(fields(worktree_path = %worktree_path, branch_name = %branch_name))]
+pub fn create_checkpoint(
+    worktree_path: &str,
+    branch_name: &str,
+    message: &str,
+    include_clean: bool,
+) -> GitResult<Option<Checkpoint>> {
+    let tree = snapshot_tree(worktree_path)?;
+
+    let rev_parse = |rev: &str| {
+        git_in(
+            worktree_path,
+            &["rev-parse", rev],
+            &[],
+            GitAction::Checkpoint,
+        )
+    };
+    let head = rev_parse("HEAD")?;
+    let existing = list_checkpoints(worktree_path, branch_name)?;
+    let unchanged = match existing.last() {
+        Some(last) => {
+            rev_parse(&format!("{}^{{tree}}", last.commit))? == tree
+                && rev_parse(&format!("{}^", last.commit))? == head
+        }
+        None => false,
+    };
+    if unchanged || (!include_clean && rev_parse("HEAD^{tree}")? == tree) {
+        info!("No changes since last checkpoint for '{}'", branch_name);
+        return Ok(None);
+    }
+
+    let commit = git_in(
+        worktree_path,
+        &["commit-tree", &tree, "-p", "HEAD", "-m", message],
+        &[],
+        GitAction::Checkpoint,
+    )?;
+    let id = existing.last().map_or(1, |c| c.id + 1);
+    git_in(
+        worktree_path,
+        &["update-ref", &checkpoint_ref(branch_name, id), &commit],
+        &[],
+        GitAction::Checkpoint,
+    )?;
+
+    info!(
+        "Created checkpoint {} for '{}' at {}",
+        id, branch_name, commit
+    );
+    Ok(list_checkpoints(worktree_path, branch_name)?
+        .into_iter()
+        .find(|c| c.id == id))
+}
+
+/// The commit a task branch was created from: where it meets `base`, or the
+/// local branch of `base` when the remote one is not fetched.
+pub fn branch_point(worktree_path: &str, base: &str) -> GitResult<String> {
+    git_in(
+        worktree_path,
+        &["merge-base", "HEAD", base],
+        &[],
+        GitAction::Reset,
+    )
+    .or_else(|_| {
+        git_in(
+            worktree_path,
+            &["merge-base", "HEAD", local_branch(base)],
+            &[],
+            GitAction::Reset,
+        )
+    })
+}
+
+/// Hard-reset a worktree to `commit`, discarding untracked files.
+#[instrument(fields(worktree_path = %worktree_path, commit = %commit))]
+pub fn reset_worktree(worktree_path: &str, commit: &str) -> GitResult<()> {
+    git_in(worktree_path, &["clean", "-fdq"], &[], GitAction::Reset)?;
+    git_in(
+        worktree_path,
+        &["reset", "-q", "--hard", commit],
+        &[],
+        GitAction::Reset,
+    )?;
+    info!("Reset worktree {} to {}", worktree_path, commit);
+    Ok(())
+}
+
+/// Restore a worktree to a checkpoint's files, leaving them uncommitted on
+/// top of the commit the checkpoint was taken from.
+#[instrument(fields(worktree_path = %worktree_path, checkpoint = %checkpoint.id))]
+pub fn restore_checkpoint(worktree_path: &str, checkpoint: &Checkpoint) -> GitResult<()> {
+    restore_snapshot(worktree_path, &checkpoint.commit)?;
+    info!("Restored checkpoint {} in {}", checkpoint.id, worktree_path);
+    Ok(())
+}
+
+/// Restore a worktree to the files of a snapshot commit, leaving them
+/// uncommitted on top of the snapshot's parent.
+pub fn restore_snapshot(worktree_path: &str, commit: &str) -> GitResult<()> {
+    reset_worktree(worktree_path, commit)?;
+    git_in(
+        worktree_path,
+        &["reset", "-q", &format!("{commit}^")],
+        &[],
+        GitAction::Reset,
+    )?;
+    Ok(())
+}
+
+const ARCHIVE_REF_PREFIX: &str = "refs/claudectl/archives";
+
+/// Write a task's branch, its checkpoints and a snapshot of its uncommitted
+/// work to a git bundle at `bundle_path`. Commits reachable from `exclude`,
+/// usually where the task branched off, are left out.
+#[instrument(fields(worktree_path = %worktree_path, branch_name = %branch_name))]
+pub fn bundle_task(
+    worktree_path: &str,
+    branch_name: &str,
+    exclude: Option<&str>,
+    bundle_path: &Path,
+) -> GitResult<()> {
+    let tree = snapshot_tree(worktree_path)?;
+    let snapshot = git_in(
+        worktree_path,
+        &[
+            "commit-tree",
+            &tree,
+            "-p",
+            "HEAD",
+            "-m",
+            &format!("Archive of {branch_name}"),
+        ],
+        &[],
+        GitAction::Archive,
+    )?;
+    // Bundles only carry refs, so the snapshot gets one while it is written
+    let snapshot_ref = format!("{ARCHIVE_REF_PREFIX}/{branch_name}");
+    git_in(
+        ".",
+        &["update-ref", &snapshot_ref, &snapshot],
+        &[],
+        GitAction::Archive,
+    )?;
+
+    let checkpoints = git_in(
+        ".",
+        &[
+            "for-each-ref",
+            "--format=%(refname)",
+            &format!("{CHECKPOINT_REF_PREFIX}/{branch_name}/"),
+        ],
+        &[],
+        GitAction::Archive,
+    );
+    let bundled = checkpoints.and_then(|checkpoints| {
+        let path = bundle_path.to_string_lossy();
+        let exclude = exclude.map(|commit| format!("^{commit}"));
+        let mut args = vec!["bundle", "create", "-q", path.as_ref(), &snapshot_ref];
+        args.extend(checkpoints.lines());
+        args.extend(exclude.as_deref());
+        git_in(".", &args, &[], GitAction::Archive)
+    });
+    let _ = git_in(
+        ".",
+        &["update-ref", "-d", &snapshot_ref],
+        &[],
+        GitAction::Archive,
+    );
+    bundled?;
+    info!("Bundled '{}' to {}", branch_name, bundle_path.display());
+    Ok(())
+}
+
+/// Fetch a task written by [`bundle_task`] back into the repository,
+/// restoring its checkpoints. Returns the snapshot commit, whose parent is
+/// the branch's tip.
+#[instrument(fields(branch_name = %branch_name))]
+pub fn unbundle_task(bundle_path: &Path, branch_name: &str) -> GitResult<String> {
+    git_in(
+        ".",
+        &[
+            "fetch",
+            "-q",
+            &bundle_path.to_string_lossy(),
+            "refs/claudectl/*:refs/claudectl/*",
+        ],
+        &[],
+        GitAction::Archive,
+    )?;
+    let snapshot_ref = format!("{ARCHIVE_REF_PREFIX}/{branch_name}");
+    let snapshot = git_in(
+        ".",
+        &["rev-parse", "--verify", &snapshot_ref],
+        &[],
+        GitAction::Archive,
+    )?;
+    git_in(
+        ".",
+        &["update-ref", "-d", &snapshot_ref],
+        &[],
+        GitAction::Archive,
+    )?;
+    Ok(snapshot)
+}
+
+/// Delete every checkpoint of a task branch.
+pub fn delete_checkpoints(branch_name: &str) -> GitResult<()> {
+    let refs = git_in(
+        ".",
+        &[
+            "for-each-ref",
+            "--format=%(refname)",
+            &format!("{CHECKPOINT_REF_PREFIX}/{branch_name}/"),
+        ],
+        &[],
+        GitAction::Checkpoint,
+    )?;
+    for name in refs.lines() {
+        git_in(".", &["update-ref", "-d", name], &[], GitAction::Checkpoint)?;
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,6 +1170,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_git_progress_parse() {
+        assert_eq!(
+            GitProgress::parse("Receiving objects:  45% (450/1000), 1.20 MiB | 1.10 MiB/s"),
+            Some(GitProgress {
+                label: "Receiving objects".to_string(),
+                percent: 45,
+            })
+        );
+        assert_eq!(
+            GitProgress::parse("remote: Counting objects: 100% (12/12), done."),
+            Some(GitProgress {
+                label: "Counting objects".to_string(),
+                percent: 100,
+            })
+        );
+        assert_eq!(GitProgress::parse("From github.com:org/repo"), None);
+        assert_eq!(
+            GitProgress::parse("fatal: couldn't find remote ref 50%"),
+            None
+        );
+    }
+
     #[test]
     fn test_worktree_exists_returns_false_for_nonexistent_path() {
         // This test will only work if we're in a git repository
@@ -240,4 +1206,25 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_parse_numstat() {
+        assert_eq!(
+            parse_numstat("3\t1\tsrc/main.rs"),
+            Some(FileChange {
+                path: "src/main.rs".to_string(),
+                added: 3,
+                deleted: 1,
+            })
+        );
+        assert_eq!(parse_numstat("-\t-\tlogo.png").map(|c| c.added), Some(0));
+        assert_eq!(parse_numstat(""), None);
+    }
+
+    #[test]
+    fn test_local_branch_drops_remote() {
+        assert_eq!(local_branch("origin/main"), "main");
+        assert_eq!(local_branch("origin/release/1.2"), "release/1.2");
+        assert_eq!(local_branch("feat/parent"), "feat/parent");
+    }
 }
@@ -0,0 +1,219 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::utils::errors::{CommandError, FileSystemError};
+use crate::utils::git::{git_common_dir, set_worktree_config, worktree_config};
+
+/// Worktree setting naming the branch a task may rewrite.
+const TASK_BRANCH_KEY: &str = "claudectl.taskBranch";
+
+/// Worktree setting listing the branches no push may rewrite.
+const PROTECTED_BRANCH_KEY: &str = "claudectl.protectedBranch";
+
+/// Hooks git runs on the client. Those without guard rails pass straight
+/// through to the repository's own, so pointing a worktree at claudectl's
+/// hooks does not switch the project's off.
+const CLIENT_HOOKS: &[&str] = &[
+    "applypatch-msg",
+    "pre-applypatch",
+    "post-applypatch",
+    "pre-commit",
+    "pre-merge-commit",
+    "prepare-commit-msg",
+    "commit-msg",
+    "post-commit",
+    "post-checkout",
+    "post-merge",
+    "post-rewrite",
+    "reference-transaction",
+    "pre-auto-gc",
+    "post-index-change",
+];
+
+/// Per-project protection against an agent rewriting history it does not
+/// own.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuardRails {
+    /// Install the hooks in new task worktrees
+    #[serde(default)]
+    pub enabled: bool,
+    /// Branches that may never be force-pushed or deleted from a task
+    #[serde(default = "default_protected_branches")]
+    pub protected_branches: Vec<String>,
+}
+
+impl Default for GuardRails {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            protected_branches: default_protected_branches(),
+        }
+    }
+}
+
+fn default_protected_branches() -> Vec<String> {
+    vec!["main".to_string(), "master".to_string()]
+}
+
+/// Make git in `worktree_path` refuse to rebase any branch but
+/// `task_branch`, and refuse pushes that rewrite or delete any other branch
+/// or a protected one.
+///
+/// The hooks are shared by every worktree of the repository, but only those
+/// set up here use them; the main checkout keeps its own.
+pub fn install_guard_rails(
+    worktree_path: &str,
+    task_branch: &str,
+    rails: &GuardRails,
+) -> Result<(), CommandError> {
+    let hooks_dir = PathBuf::from(git_common_dir(worktree_path)?).join("claudectl/hooks");
+    std::fs::create_dir_all(&hooks_dir).map_err(|e| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &hooks_dir.to_string_lossy())
+    })?;
+    write_hook(&hooks_dir.join("pre-push"), PRE_PUSH)?;
+    write_hook(&hooks_dir.join("pre-rebase"), PRE_REBASE)?;
+    for hook in CLIENT_HOOKS {
+        write_hook(&hooks_dir.join(hook), PASS_THROUGH)?;
+    }
+
+    let protected: Vec<&str> = rails
+        .protected_branches
+        .iter()
+        .map(String::as_str)
+        .collect();
+    set_worktree_config(worktree_path, TASK_BRANCH_KEY, &[task_branch])?;
+    set_worktree_config(worktree_path, PROTECTED_BRANCH_KEY, &protected)?;
+    set_worktree_config(
+        worktree_path,
+        "core.hooksPath",
+        &[&hooks_dir.to_string_lossy()],
+    )?;
+    info!("Installed guard rails in {}", worktree_path);
+    Ok(())
+}
+
+/// Let a worktree with guard rails rewrite its task's branch under a new
+/// name. Worktrees without them are left alone.
+pub fn retarget_guard_rails(worktree_path: &str, task_branch: &str) -> Result<(), CommandError> {
+    if worktree_config(worktree_path, TASK_BRANCH_KEY)?.is_empty() {
+        return Ok(());
+    }
+    set_worktree_config(worktree_path, TASK_BRANCH_KEY, &[task_branch])?;
+    Ok(())
+}
+
+fn write_hook(path: &Path, script: &str) -> Result<(), CommandError> {
+    let write_failed = |e: std::io::Error| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    };
+    std::fs::write(path, script).map_err(write_failed)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+            .map_err(write_failed)?;
+    }
+    Ok(())
+}
+
+/// Shell function printing where the repository's own hooks live, for
+/// hooks to hand over to.
+macro_rules! original_hooks {
+    () => {
+        r#"original_hooks() {
+  git config --local --get core.hooksPath || echo "$(git rev-parse --git-common-dir)/hooks"
+}
+"#
+    };
+}
+
+const PASS_THROUGH: &str = concat!(
+    "#!/bin/sh\n# Installed by claudectl: runs the repository's own hook\n",
+    original_hooks!(),
+    r#"hook="$(original_hooks)/$(basename "$0")"
+[ -x "$hook" ] || exit 0
+exec "$hook" "$@"
+"#
+);
+
+const PRE_PUSH: &str = concat!(
+    "#!/bin/sh\n# Installed by claudectl: refuses pushes that rewrite or delete\n",
+    "# branches other than the task's, or any protected branch\n",
+    r#"task=$(git config --get claudectl.taskBranch)
+protected=$(git config --get-all claudectl.protectedBranch)
+updates=$(cat)
+
+printf '%s\n' "$updates" | while read -r local_ref local_sha remote_ref remote_sha; do
+  [ -n "$remote_ref" ] || continue
+  branch=${remote_ref#refs/heads/}
+  if [ "$branch" = "$task" ] && ! printf '%s\n' "$protected" | grep -qxF "$branch"; then
+    continue
+  fi
+  case "$local_sha" in
+    *[!0]*) ;;
+    *)
+      echo "claudectl: deleting '$branch' is blocked in this task worktree" >&2
+      exit 1
+      ;;
+  esac
+  case "$remote_sha" in
+    *[!0]*)
+      if ! git merge-base --is-ancestor "$remote_sha" "$local_sha" 2>/dev/null; then
+        echo "claudectl: force-pushing '$branch' is blocked in this task worktree; only '$task' may be rewritten" >&2
+        exit 1
+      fi
+      ;;
+  esac
+done || exit 1
+
+"#,
+    original_hooks!(),
+    r#"hook="$(original_hooks)/pre-push"
+[ -x "$hook" ] || exit 0
+if [ -n "$updates" ]; then
+  printf '%s\n' "$updates" | "$hook" "$@"
+else
+  "$hook" "$@" </dev/null
+fi
+"#
+);
+
+const PRE_REBASE: &str = concat!(
+    "#!/bin/sh\n# Installed by claudectl: refuses to rebase branches other than the task's\n",
+    r#"task=$(git config --get claudectl.taskBranch)
+branch=${2:-$(git symbolic-ref --quiet --short HEAD)}
+if [ -n "$task" ] && [ "$branch" != "$task" ]; then
+  echo "claudectl: rebasing '$branch' is blocked in this task worktree; only '$task' may be rewritten" >&2
+  exit 1
+fi
+
+"#,
+    original_hooks!(),
+    r#"hook="$(original_hooks)/pre-rebase"
+[ -x "$hook" ] || exit 0
+exec "$hook" "$@"
+"#
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard_rails_protect_main_by_default() {
+        let rails: GuardRails = serde_json::from_str(r#"{"enabled": true}"#).unwrap();
+        assert!(rails.enabled);
+        assert_eq!(rails.protected_branches, vec!["main", "master"]);
+        assert!(!GuardRails::default().enabled);
+    }
+
+    #[test]
+    fn test_hooks_hand_over_to_the_repository_hooks() {
+        for script in [PASS_THROUGH, PRE_PUSH, PRE_REBASE] {
+            assert!(script.starts_with("#!/bin/sh\n"));
+            assert!(script.contains(original_hooks!()));
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use owo_colors::OwoColorize;
+use tracing::{info, instrument, warn};
+
+use crate::utils::errors::CommandError;
+use crate::utils::fs::current_dir;
+use crate::utils::output::{error as output_error, run_shown, standard};
+use crate::utils::theme::THEME;
+
+/// A point in a task's life where the project's script of the same name in
+/// `.claudectl/hooks/` runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    /// Before `task` creates a worktree; failing cancels the task
+    PreTask,
+    /// After a task's worktree is created and set up
+    PostTask,
+    /// Before an agent session starts in a task; failing cancels the session
+    PreSession,
+    /// After a session ends, in the claudectl process that ran it
+    PostSession,
+    /// Whenever claudectl sees a session end, including ones `kill` stopped
+    /// and ones `watch --supervise` found dead
+    OnSessionExit,
+}
+
+impl Hook {
+    pub const ALL: [Hook; 5] = [
+        Hook::PreTask,
+        Hook::PostTask,
+        Hook::PreSession,
+        Hook::PostSession,
+        Hook::OnSessionExit,
+    ];
+
+    /// The script's file name, e.g. `pre-session`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PreTask => "pre-task",
+            Self::PostTask => "post-task",
+            Self::PreSession => "pre-session",
+            Self::PostSession => "post-session",
+            Self::OnSessionExit => "on-session-exit",
+        }
+    }
+
+    /// Whether the hook failing stops what it runs before.
+    fn cancels(&self) -> bool {
+        matches!(self, Self::PreTask | Self::PreSession)
+    }
+}
+
+/// Whether `file_name` is a lifecycle hook rather than a setup script.
+pub fn is_lifecycle_hook(file_name: &str) -> bool {
+    Hook::ALL.iter().any(|hook| hook.name() == file_name)
+}
+
+/// What a hook is told about the task, as `CLAUDECTL_*` environment
+/// variables.
+#[derive(Debug, Default)]
+pub struct HookContext<'a> {
+    pub task_name: &'a str,
+    /// The task's worktree; for `pre-task`, where it will be created
+    pub worktree: &'a str,
+    /// Set for session hooks, see [`SessionLock::session_id`]
+    ///
+    /// [`SessionLock::session_id`]: crate::utils::lock::SessionLock::session_id
+    pub session_id: Option<String>,
+    /// How the task or session ended up, e.g. `setup-failed` or `crashed`
+    pub status: Option<&'a str>,
+    /// The agent's exit code, when a session ended with one
+    pub exit_code: Option<i32>,
+}
+
+impl HookContext<'_> {
+    fn vars(&self, hook: Hook, main_checkout: &Path) -> Vec<(&'static str, String)> {
+        let mut vars = vec![
+            ("CLAUDECTL_HOOK", hook.name().to_string()),
+            ("CLAUDECTL_TASK", self.task_name.to_string()),
+            ("CLAUDECTL_WORKTREE", self.worktree.to_string()),
+            (
+                "CLAUDECTL_MAIN_CHECKOUT",
+                main_checkout.to_string_lossy().to_string(),
+            ),
+        ];
+        vars.extend(
+            self.session_id
+                .clone()
+                .map(|id| ("CLAUDECTL_SESSION_ID", id)),
+        );
+        vars.extend(
+            self.status
+                .map(|status| ("CLAUDECTL_STATUS", status.to_string())),
+        );
+        vars.extend(
+            self.exit_code
+                .map(|code| ("CLAUDECTL_EXIT_CODE", code.to_string())),
+        );
+        vars
+    }
+}
+
+/// Where the project at `repo_root` keeps its script for `hook`.
+pub fn hook_path_in(repo_root: &Path, hook: Hook) -> PathBuf {
+    repo_root.join(".claudectl").join("hooks").join(hook.name())
+}
+
+/// Run the project's script for `hook`, if it has one, from the main
+/// checkout with its output going to the terminal, or to the capture
+/// buffer.
+///
+/// A `pre-` hook that fails is an error, so the caller stops; any other
+/// hook failing is reported and otherwise ignored.
+#[instrument(skip(context), fields(task_name = %context.task_name))]
+pub fn run_hook(hook: Hook, context: &HookContext) -> Result<(), CommandError> {
+    let repo_root = current_dir()?;
+    let path = hook_path_in(&repo_root, hook);
+    if !path.is_file() {
+        return Ok(());
+    }
+    standard(
+        &format!("Running the {} hook...", hook.name())
+            .color(THEME.muted)
+            .to_string(),
+    );
+    info!("Running {}", path.display());
+    let result = run_shown(
+        Command::new(&path)
+            .current_dir(&repo_root)
+            .envs(context.vars(hook, &repo_root))
+            .stdin(Stdio::null()),
+    );
+
+    let reason = match result {
+        Ok(status) if status.success() => return Ok(()),
+        Ok(status) => format!("exited with {status}"),
+        Err(e) => format!("could not be run ({e}); make sure it is executable"),
+    };
+    warn!("Hook {} {}", hook.name(), reason);
+    let message = format!("The {} hook {reason}", hook.name());
+    if hook.cancels() {
+        return Err(CommandError::new(&message));
+    }
+    output_error(&message);
+    Ok(())
+}
+
+/// Run the hooks for a session that has just ended in this process:
+/// `post-session`, then `on-session-exit`.
+pub fn run_session_end_hooks(context: &HookContext) -> Result<(), CommandError> {
+    run_hook(Hook::PostSession, context)?;
+    run_hook(Hook::OnSessionExit, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_names_and_vars() {
+        assert!(is_lifecycle_hook("on-session-exit"));
+        assert!(!is_lifecycle_hook("10-install.sh"));
+
+        let context = HookContext {
+            task_name: "feat/login",
+            worktree: "/work/feat/login",
+            session_id: Some("4242-1760000000".to_string()),
+            status: Some("crashed"),
+            exit_code: Some(2),
+        };
+        let vars = context.vars(Hook::OnSessionExit, Path::new("/work/repo"));
+        assert_eq!(
+            vars,
+            [
+                ("CLAUDECTL_HOOK", "on-session-exit".to_string()),
+                ("CLAUDECTL_TASK", "feat/login".to_string()),
+                ("CLAUDECTL_WORKTREE", "/work/feat/login".to_string()),
+                ("CLAUDECTL_MAIN_CHECKOUT", "/work/repo".to_string()),
+                ("CLAUDECTL_SESSION_ID", "4242-1760000000".to_string()),
+                ("CLAUDECTL_STATUS", "crashed".to_string()),
+                ("CLAUDECTL_EXIT_CODE", "2".to_string()),
+            ]
+        );
+    }
+}
@@ -15,6 +15,9 @@ pub struct Progress {
     pub braille: &'static [&'static str],
     /// e.g., "○", "◔", "◑", "◕", "●"
     pub circles: &'static [&'static str],
+    /// Filled and empty cells of a progress bar
+    pub bar_filled: &'static str,
+    pub bar_empty: &'static str,
 }
 
 pub struct Arrows {
@@ -102,6 +105,8 @@ pub const ICONS: Icons = Icons {
         unicode: &SPINNER_UNICODE,
         braille: &SPINNER_BRAILLE,
         circles: &SPINNER_CIRCLES,
+        bar_filled: "━",
+        bar_empty: "─",
     },
     arrows: Arrows {
         right: "→",
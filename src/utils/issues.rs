@@ -0,0 +1,88 @@
+use std::path::Path;
+use std::process::Command;
+
+use serde::Deserialize;
+
+use crate::utils::errors::CommandError;
+
+/// A GitHub issue, as `gh issue view --json` reports it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    pub url: String,
+}
+
+impl Issue {
+    /// What the task's branch is named after, e.g. `42 Fix login crash`.
+    pub fn task_name(&self) -> String {
+        format!("{} {}", self.number, self.title)
+    }
+
+    /// The issue as markdown, for the agent's context.
+    pub fn context(&self) -> String {
+        let mut context = format!("# {} (#{})\n\n{}\n", self.title, self.number, self.url);
+        let body = self.body.trim();
+        if !body.is_empty() {
+            context.push('\n');
+            context.push_str(body);
+            context.push('\n');
+        }
+        context
+    }
+}
+
+/// Look up an issue of the repository in `dir` by number or URL with `gh`.
+pub fn fetch_issue(reference: &str, dir: &Path) -> Result<Issue, CommandError> {
+    let output = Command::new("gh")
+        .args([
+            "issue",
+            "view",
+            reference,
+            "--json",
+            "number,title,body,url",
+        ])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| {
+            CommandError::new(&format!(
+                "Failed to run 'gh': {e}. Install it and log in to create tasks from issues"
+            ))
+        })?;
+    if !output.status.success() {
+        return Err(CommandError::new(&format!(
+            "gh failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| {
+        CommandError::new(&format!(
+            "Could not read issue '{reference}' from gh's output: {e}"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_context_and_task_name() {
+        let issue: Issue = serde_json::from_str(
+            r#"{
+                "number": 42,
+                "title": "Login crashes on empty password",
+                "body": "Steps:\n1. Leave the password empty\n",
+                "url": "https://github.com/owner/repo/issues/42"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(issue.task_name(), "42 Login crashes on empty password");
+        assert_eq!(
+            issue.context(),
+            "# Login crashes on empty password (#42)\n\nhttps://github.com/owner/repo/issues/42\n\nSteps:\n1. Leave the password empty\n"
+        );
+    }
+}
@@ -0,0 +1,422 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::utils::errors::CommandError;
+use crate::utils::fs::{
+    create_lock_file, current_dir, lock_file_path_in, lock_files_in, remove_lock_file, write_atomic,
+};
+use crate::utils::process::{is_running, process_info};
+use crate::utils::tasks::task_name_from_file;
+use crate::utils::time::{TimestampStyle, format_timestamp};
+
+/// A running session's exclusive claim on a task's worktree, kept in
+/// `.claudectl/locks/`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SessionLock {
+    /// The claudectl process running the session
+    pub pid: u32,
+    /// What started the session, e.g. `resolve`
+    pub command: String,
+    pub started_at: DateTime<Utc>,
+    /// Set by `kill` before it signals the session, so the session records
+    /// that it was stopped rather than that it crashed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stopping: bool,
+}
+
+impl SessionLock {
+    /// e.g. "resolve (pid 4242)"
+    pub fn holder(&self) -> String {
+        format!("{} (pid {})", self.command, self.pid)
+    }
+
+    /// Whether the claudectl process that took the lock is still running.
+    /// A process with its pid that started after the lock was taken, or
+    /// runs another program, has only reused the pid of one that exited.
+    pub fn is_live(&self) -> bool {
+        if !is_running(self.pid) {
+            return false;
+        }
+        let Some(process) = process_info(self.pid) else {
+            return true;
+        };
+        // `ps` gives the start time to the second
+        let started_in_time = process.started_at <= self.started_at + TimeDelta::seconds(1);
+        let ours = std::env::current_exe().is_ok_and(|exe| {
+            exe.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(&process.program))
+        });
+        if !(started_in_time && ours) {
+            info!(
+                "Pid {} of {} now belongs to {}, started {}",
+                self.pid,
+                self.holder(),
+                process.program,
+                process.started_at
+            );
+        }
+        started_in_time && ours
+    }
+
+    /// Names the session for hooks, e.g. "4242-1760000000".
+    pub fn session_id(&self) -> String {
+        format!("{}-{}", self.pid, self.started_at.timestamp())
+    }
+}
+
+/// How many times [`TaskLock::acquire`] tries to create a lock before
+/// giving up.
+const ACQUIRE_ATTEMPTS: usize = 3;
+
+/// Held while a session runs in a task's worktree; the claim is released
+/// when this is dropped.
+#[derive(Debug)]
+pub struct TaskLock {
+    path: PathBuf,
+}
+
+impl TaskLock {
+    /// Claim the task's worktree for a session started by `command`.
+    ///
+    /// Fails if another live session holds it, unless `force` is set; locks
+    /// left behind by processes that have exited are taken over.
+    pub fn acquire(task_name: &str, command: &str, force: bool) -> Result<Self, CommandError> {
+        let path = lock_file_path_in(&current_dir()?, task_name);
+        Self::acquire_at(path, task_name, command, force)
+    }
+
+    fn acquire_at(
+        path: PathBuf,
+        task_name: &str,
+        command: &str,
+        force: bool,
+    ) -> Result<Self, CommandError> {
+        let lock = SessionLock {
+            pid: std::process::id(),
+            command: command.to_string(),
+            started_at: Utc::now(),
+            stopping: false,
+        };
+        let content = serde_json::to_string_pretty(&lock)
+            .map_err(|e| CommandError::new(&format!("Failed to serialize lock: {e}")))?;
+
+        // Each attempt either locks the task or clears a stale lock out of
+        // the way, unless another session keeps winning the race
+        for _ in 0..ACQUIRE_ATTEMPTS {
+            if create_lock_file(&path, &content)? {
+                info!("Locked task '{}' for {}", task_name, lock.holder());
+                return Ok(Self { path });
+            }
+            match read_lock(&path) {
+                Some(holder) if holder.is_live() => {
+                    if !force {
+                        return Err(CommandError::new(&format!(
+                            "Task '{task_name}' is locked by {}, started {}. Pass --force to start another session anyway",
+                            holder.holder(),
+                            format_timestamp(holder.started_at, TimestampStyle::Relative)
+                        )));
+                    }
+                    warn!(
+                        "Overriding lock on '{}' held by {}",
+                        task_name,
+                        holder.holder()
+                    );
+                    write_atomic(&path, &content)?;
+                    info!("Locked task '{}' for {}", task_name, lock.holder());
+                    return Ok(Self { path });
+                }
+                seen => remove_stale_lock(&path, seen.as_ref())?,
+            }
+        }
+        Err(CommandError::new(&format!(
+            "Could not lock task '{task_name}': other sessions keep taking it"
+        )))
+    }
+
+    /// The id of the session holding the lock, as `kill` and `watch` see it.
+    pub fn session_id(&self) -> Option<String> {
+        read_lock(&self.path).map(|lock| lock.session_id())
+    }
+
+    /// Whether `kill` has asked this session to stop, so that it ending is
+    /// not a crash.
+    pub fn stop_requested(&self) -> bool {
+        read_lock(&self.path).is_some_and(|lock| lock.pid == std::process::id() && lock.stopping)
+    }
+}
+
+impl Drop for TaskLock {
+    fn drop(&mut self) {
+        // A session started with --force may have taken the lock over since
+        let ours = read_lock(&self.path).is_some_and(|lock| lock.pid == std::process::id());
+        if ours {
+            if let Err(e) = remove_lock_file(&self.path) {
+                warn!("Failed to release lock {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+/// The live session holding a task in the current project, if any.
+pub fn current_lock(task_name: &str) -> Option<SessionLock> {
+    current_dir()
+        .ok()
+        .and_then(|repo| current_lock_in(&repo, task_name))
+}
+
+/// The live session holding a task in the project at `repo_root`, if any.
+pub fn current_lock_in(repo_root: &Path, task_name: &str) -> Option<SessionLock> {
+    read_live_lock(&lock_file_path_in(repo_root, task_name))
+}
+
+/// A lock left behind by a session whose claudectl process is gone, e.g.
+/// after a crash or a closed terminal.
+#[derive(Debug)]
+pub struct StaleLock {
+    pub task_name: String,
+    pub lock: SessionLock,
+    path: PathBuf,
+}
+
+impl StaleLock {
+    /// Forget the session, leaving the task free.
+    pub fn clear(&self) -> Result<(), CommandError> {
+        remove_lock_file(&self.path)?;
+        info!("Cleared stale lock on '{}'", self.task_name);
+        Ok(())
+    }
+}
+
+/// Locks in the current project whose sessions are no longer running,
+/// oldest first.
+pub fn stale_locks() -> Vec<StaleLock> {
+    current_dir()
+        .map(|repo| stale_locks_in(&repo))
+        .unwrap_or_default()
+}
+
+/// Locks in the project at `repo_root` whose sessions are no longer
+/// running, oldest first.
+pub fn stale_locks_in(repo_root: &Path) -> Vec<StaleLock> {
+    let paths = lock_files_in(repo_root)
+        .inspect_err(|e| warn!("Failed to list locks: {}", e))
+        .unwrap_or_default();
+    let mut stale: Vec<StaleLock> = paths
+        .into_iter()
+        .filter_map(|path| {
+            let lock = read_lock(&path).filter(|lock| !lock.is_live())?;
+            let file_name = path.file_name()?.to_string_lossy().to_string();
+            Some(StaleLock {
+                task_name: task_name_from_file(&file_name),
+                lock,
+                path,
+            })
+        })
+        .collect();
+    stale.sort_by_key(|stale| stale.lock.started_at);
+    stale
+}
+
+/// How many sessions are running in the project at `repo_root`.
+pub fn running_sessions_in(repo_root: &Path) -> usize {
+    lock_files_in(repo_root)
+        .inspect_err(|e| warn!("Failed to list locks: {}", e))
+        .unwrap_or_default()
+        .iter()
+        .filter(|path| read_live_lock(path).is_some())
+        .count()
+}
+
+/// Tell the session holding a task's lock that it is being stopped on
+/// purpose; see [`TaskLock::stop_requested`].
+pub fn request_stop(task_name: &str, lock: &SessionLock) -> Result<(), CommandError> {
+    let path = lock_file_path_in(&current_dir()?, task_name);
+    if read_lock(&path).as_ref() != Some(lock) {
+        return Ok(());
+    }
+    let stopping = SessionLock {
+        stopping: true,
+        ..lock.clone()
+    };
+    let content = serde_json::to_string_pretty(&stopping)
+        .map_err(|e| CommandError::new(&format!("Failed to serialize lock: {e}")))?;
+    // Replaced whole, so the session never reads a half-written lock
+    write_atomic(&path, &content)?;
+    Ok(())
+}
+
+/// Remove a task's lock if the session holding it is gone, e.g. after it
+/// was killed before it could release the lock itself. Returns whether
+/// there was one to remove.
+pub fn clear_dead_lock(task_name: &str) -> Result<bool, CommandError> {
+    let path = lock_file_path_in(&current_dir()?, task_name);
+    if !path.exists() || read_live_lock(&path).is_some() {
+        return Ok(false);
+    }
+    remove_lock_file(&path)?;
+    info!("Cleared dead lock on '{}'", task_name);
+    Ok(true)
+}
+
+/// Remove the lock at `path`, read as `seen`, that a session which has
+/// exited left behind. A lock another session created since it was read
+/// is put back.
+fn remove_stale_lock(path: &Path, seen: Option<&SessionLock>) -> Result<(), CommandError> {
+    // Moved aside first, so of several processes clearing it only one does
+    let mut aside = path.as_os_str().to_owned();
+    aside.push(format!(".{}.stale", std::process::id()));
+    let aside = PathBuf::from(aside);
+    match std::fs::rename(path, &aside) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => {
+            return Err(CommandError::new(&format!(
+                "Failed to clear stale lock {}: {e}",
+                path.display()
+            )));
+        }
+    }
+    if read_lock(&aside).as_ref() == seen {
+        remove_lock_file(&aside)?;
+        info!("Took over stale lock {}", path.display());
+    } else if let Err(e) = std::fs::rename(&aside, path) {
+        warn!("Failed to restore lock {}: {}", path.display(), e);
+    }
+    Ok(())
+}
+
+fn read_lock(path: &Path) -> Option<SessionLock> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content)
+        .inspect_err(|e| warn!("Ignoring unreadable lock {}: {}", path.display(), e))
+        .ok()
+}
+
+fn read_live_lock(path: &Path) -> Option<SessionLock> {
+    read_lock(path).filter(SessionLock::is_live)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::fs::write_lock_file;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, pid: u32) {
+        let lock = SessionLock {
+            pid,
+            command: "resolve".to_string(),
+            started_at: Utc::now(),
+            stopping: false,
+        };
+        write_lock_file(path, serde_json::to_string(&lock).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_live_locks_are_reported_and_stale_ones_ignored() {
+        let repo = TempDir::new().unwrap();
+        let path = lock_file_path_in(repo.path(), "feat/locked");
+        assert_eq!(current_lock_in(repo.path(), "feat/locked"), None);
+
+        write(&path, std::process::id());
+        let lock = current_lock_in(repo.path(), "feat/locked").unwrap();
+        assert_eq!(
+            lock.holder(),
+            format!("resolve (pid {})", std::process::id())
+        );
+
+        // Far above any default pid_max, so never a running process
+        write(&path, 99_999_999);
+        assert_eq!(current_lock_in(repo.path(), "feat/locked"), None);
+    }
+
+    #[test]
+    fn test_locks_whose_pid_was_reused_are_dead() {
+        let repo = TempDir::new().unwrap();
+        let path = lock_file_path_in(repo.path(), "feat/reused");
+        // This process started long after the session that took the lock
+        let lock = SessionLock {
+            pid: std::process::id(),
+            command: "resolve".to_string(),
+            started_at: "2024-09-01T16:03:00Z".parse().unwrap(),
+            stopping: false,
+        };
+        write_lock_file(&path, serde_json::to_string(&lock).unwrap()).unwrap();
+
+        assert!(!lock.is_live());
+        assert_eq!(current_lock_in(repo.path(), "feat/reused"), None);
+        assert_eq!(stale_locks_in(repo.path()).len(), 1);
+
+        // `sleep` is a live process, but not a claudectl one
+        let mut sleep = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        let lock = SessionLock {
+            pid: sleep.id(),
+            started_at: Utc::now() + TimeDelta::seconds(5),
+            ..lock
+        };
+        assert!(!lock.is_live());
+        sleep.kill().unwrap();
+        sleep.wait().unwrap();
+    }
+
+    #[test]
+    fn test_stale_locks_are_found_and_cleared() {
+        let repo = TempDir::new().unwrap();
+        write(
+            &lock_file_path_in(repo.path(), "feat/live"),
+            std::process::id(),
+        );
+        write(&lock_file_path_in(repo.path(), "feat/crashed"), 99_999_999);
+
+        let stale = stale_locks_in(repo.path());
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].task_name, "feat/crashed");
+        assert_eq!(stale[0].lock.command, "resolve");
+
+        stale[0].clear().unwrap();
+        assert!(stale_locks_in(repo.path()).is_empty());
+        assert!(current_lock_in(repo.path(), "feat/live").is_some());
+    }
+
+    #[test]
+    fn test_dropping_a_lock_releases_only_our_own() {
+        let repo = TempDir::new().unwrap();
+        let path = lock_file_path_in(repo.path(), "feat/locked");
+
+        write(&path, std::process::id());
+        drop(TaskLock { path: path.clone() });
+        assert!(!path.exists());
+
+        write(&path, 1);
+        drop(TaskLock { path: path.clone() });
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_acquire_takes_over_only_dead_locks() {
+        let repo = TempDir::new().unwrap();
+        let path = lock_file_path_in(repo.path(), "feat/locked");
+
+        let lock = TaskLock::acquire_at(path.clone(), "feat/locked", "run", false).unwrap();
+        let err = TaskLock::acquire_at(path.clone(), "feat/locked", "run", false).unwrap_err();
+        assert!(err.to_string().contains("is locked by run"));
+        drop(lock);
+        assert!(!path.exists());
+
+        write(&path, 99_999_999);
+        let lock = TaskLock::acquire_at(path.clone(), "feat/locked", "run", false).unwrap();
+        assert_eq!(read_lock(&path).unwrap().pid, std::process::id());
+        drop(lock);
+
+        std::fs::write(&path, "not a lock").unwrap();
+        TaskLock::acquire_at(path.clone(), "feat/locked", "run", false).unwrap();
+        let leftovers = std::fs::read_dir(path.parent().unwrap()).unwrap().count();
+        assert_eq!(leftovers, 0);
+    }
+}
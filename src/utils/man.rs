@@ -0,0 +1,74 @@
+use std::path::{Path, PathBuf};
+
+use clap::CommandFactory;
+use clap_mangen::Man;
+use directories::BaseDirs;
+
+use crate::utils::errors::{CommandError, FileSystemError};
+
+/// The CLI as man pages are generated from it, with every subcommand's
+/// name resolved.
+fn cli() -> clap::Command {
+    let mut cmd = crate::Cli::command().disable_help_subcommand(true);
+    cmd.build();
+    cmd
+}
+
+/// The roff man page of `claudectl`, or of the subcommand at `path`, e.g.
+/// `["config", "set"]`.
+pub fn render_page(path: &[String]) -> Result<Vec<u8>, CommandError> {
+    let mut cmd = cli();
+    for (depth, name) in path.iter().enumerate() {
+        cmd = cmd
+            .find_subcommand(name)
+            .filter(|sub| !sub.is_hide_set())
+            .cloned()
+            .ok_or_else(|| {
+                CommandError::new(&format!(
+                    "No man page for 'claudectl {}'",
+                    path[..=depth].join(" ")
+                ))
+            })?;
+    }
+    let mut page = Vec::new();
+    Man::new(cmd)
+        .render(&mut page)
+        .map_err(|e| CommandError::new(&format!("Failed to render the man page: {e}")))?;
+    Ok(page)
+}
+
+/// Write the man pages of `claudectl` and each of its subcommands to `dir`,
+/// e.g. `claudectl.1` and `claudectl-config-set.1`, returning their names.
+pub fn write_pages(dir: &Path) -> Result<Vec<String>, CommandError> {
+    let write_failed = |e: std::io::Error| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &dir.to_string_lossy())
+    };
+    std::fs::create_dir_all(dir).map_err(write_failed)?;
+    let cmd = cli();
+    clap_mangen::generate_to(cmd.clone(), dir).map_err(write_failed)?;
+    Ok(page_names(&cmd))
+}
+
+fn page_names(cmd: &clap::Command) -> Vec<String> {
+    let mut names = vec![format!(
+        "{}.1",
+        cmd.get_display_name().unwrap_or_else(|| cmd.get_name())
+    )];
+    for sub in cmd.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        names.extend(page_names(sub));
+    }
+    names
+}
+
+/// Where `man --install` puts the pages: `$XDG_DATA_HOME/man/man1`, or
+/// `~/.local/share/man/man1`, which `man` searches by default on most
+/// systems.
+pub fn install_dir() -> Result<PathBuf, CommandError> {
+    let data_home = match std::env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => BaseDirs::new()
+            .map(|dirs| dirs.home_dir().join(".local/share"))
+            .ok_or_else(|| CommandError::new("Unable to determine the home directory"))?,
+    };
+    Ok(data_home.join("man").join("man1"))
+}
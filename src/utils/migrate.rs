@@ -0,0 +1,139 @@
+use std::path::{Path, PathBuf};
+
+use tracing::info;
+
+use crate::utils::config::Config;
+use crate::utils::errors::CommandError;
+use crate::utils::fs::{config_dir, read_project_file};
+use crate::utils::projects::register_project;
+
+/// Where claudectl's files live, so migrations can be run against any
+/// directory.
+pub struct Layout {
+    /// claudectl's global configuration directory
+    pub config_dir: PathBuf,
+    /// Root of the project being migrated, if there is one
+    pub repo_root: Option<PathBuf>,
+}
+
+impl Layout {
+    /// The layout of the current user, for the project in `repo_root`.
+    pub fn current(repo_root: Option<&Path>) -> Result<Self, CommandError> {
+        Ok(Self {
+            config_dir: config_dir()?,
+            repo_root: repo_root.map(Path::to_path_buf),
+        })
+    }
+
+    fn local_dir(&self) -> Option<PathBuf> {
+        self.repo_root.as_ref().map(|root| root.join(".claudectl"))
+    }
+}
+
+/// One change from an older layout to the current one.
+struct Migration {
+    /// What is migrated, for `doctor`
+    name: &'static str,
+    /// Whether the layout still has something to migrate
+    pending: fn(&Layout) -> bool,
+    /// Migrate, describing what was done
+    run: fn(&Layout) -> Result<String, CommandError>,
+}
+
+/// In the order they run.
+const MIGRATIONS: &[Migration] = &[Migration {
+    name: "unregistered project",
+    pending: registration_pending,
+    run: migrate_registration,
+}];
+
+/// Names of the migrations `layout` still needs.
+pub fn pending_migrations(layout: &Layout) -> Vec<&'static str> {
+    MIGRATIONS
+        .iter()
+        .filter(|migration| (migration.pending)(layout))
+        .map(|migration| migration.name)
+        .collect()
+}
+
+/// Bring `layout` up to date, returning what was done. Migrations that
+/// have already run find nothing to do.
+pub fn run_migrations(layout: &Layout) -> Result<Vec<String>, CommandError> {
+    let mut done = Vec::new();
+    for migration in MIGRATIONS {
+        if (migration.pending)(layout) {
+            let description = (migration.run)(layout)?;
+            info!("Migrated {}: {}", migration.name, description);
+            done.push(description);
+        }
+    }
+    Ok(done)
+}
+
+fn registration_pending(layout: &Layout) -> bool {
+    local_project_dir(layout)
+        .is_some_and(|project_dir| matches!(read_project_file(&project_dir), Ok(None)))
+}
+
+/// Register the project's global directory, which projects initialized
+/// before registration lack, so `overview` finds it.
+fn migrate_registration(layout: &Layout) -> Result<String, CommandError> {
+    let (Some(project_dir), Some(repo_root)) = (local_project_dir(layout), &layout.repo_root)
+    else {
+        return Err(CommandError::new("No project to migrate"));
+    };
+    register_project(&project_dir.to_string_lossy(), repo_root)?;
+    Ok(format!(
+        "Registered {} as the project directory of {}",
+        project_dir.display(),
+        repo_root.display()
+    ))
+}
+
+/// The global project directory the project's config names, if it has a
+/// readable one.
+fn local_project_dir(layout: &Layout) -> Option<PathBuf> {
+    let raw = std::fs::read_to_string(layout.local_dir()?.join("config.json")).ok()?;
+    let config = Config::from_str(&raw).ok()?;
+    (!config.project_dir.is_empty()).then(|| PathBuf::from(config.project_dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn layout(temp_dir: &TempDir) -> Layout {
+        let repo_root = temp_dir.path().join("app");
+        std::fs::create_dir_all(repo_root.join(".claudectl")).unwrap();
+        Layout {
+            config_dir: temp_dir.path().join("config"),
+            repo_root: Some(repo_root),
+        }
+    }
+
+    #[test]
+    fn test_unregistered_project_is_registered_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let layout = layout(&temp_dir);
+        let project_dir = layout.config_dir.join("projects/app");
+        std::fs::write(
+            layout.local_dir().unwrap().join("config.json"),
+            format!(
+                r#"{{ "project_name": "app", "project_dir": "{}" }}"#,
+                project_dir.display()
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(pending_migrations(&layout), ["unregistered project"]);
+        let done = run_migrations(&layout).unwrap();
+        assert_eq!(done.len(), 1);
+        assert!(done[0].starts_with("Registered"));
+        let registration = read_project_file(&project_dir).unwrap().unwrap();
+        assert!(registration.contains("app"));
+
+        assert!(pending_migrations(&layout).is_empty());
+        assert!(run_migrations(&layout).unwrap().is_empty());
+    }
+}
@@ -1,8 +1,41 @@
+pub mod agent;
+pub mod archive;
+pub mod batch;
 pub mod claude;
+pub mod completions;
 pub mod config;
+pub mod config_keys;
+pub mod context;
+pub mod describe;
 pub mod errors;
+pub mod events;
+pub mod export;
+pub mod filter;
 pub mod fs;
 pub mod git;
+pub mod guard;
+pub mod hooks;
 pub mod icons;
+pub mod issues;
+pub mod lock;
+pub mod man;
+pub mod migrate;
+pub mod nushell;
 pub mod output;
+pub mod process;
+pub mod projects;
+pub mod queue;
+pub mod recipes;
+pub mod redact;
+pub mod setup;
+pub mod slug;
+pub mod stats;
+pub mod supervise;
+pub mod tasks;
+pub mod templates;
+pub mod terminal;
 pub mod theme;
+pub mod time;
+pub mod timings;
+pub mod transaction;
+pub mod upgrade;
@@ -0,0 +1,159 @@
+//! Nushell completions, which clap_complete does not generate: one `extern`
+//! signature per subcommand, inside a module the shell `use`s.
+//!
+//! The build script compiles this file too, so it may only depend on clap.
+
+use std::io::{self, Write};
+
+use clap::{Arg, Command};
+
+/// Write completions for `cmd`, invoked as `bin`, to `buf`.
+pub fn generate(cmd: &mut Command, bin: &str, buf: &mut dyn Write) -> io::Result<()> {
+    cmd.build();
+    writeln!(buf, "module completions {{")?;
+    write_command(cmd, bin, buf)?;
+    writeln!(buf, "}}")?;
+    writeln!(buf)?;
+    writeln!(buf, "export use completions *")
+}
+
+fn write_command(cmd: &Command, name: &str, buf: &mut dyn Write) -> io::Result<()> {
+    // Positionals come first, in order; nushell wants them before flags
+    let mut args: Vec<&Arg> = cmd
+        .get_arguments()
+        .filter(|arg| !arg.is_hide_set())
+        .collect();
+    args.sort_by_key(|arg| !arg.is_positional());
+
+    for arg in args.iter().filter(|arg| has_choices(arg)) {
+        let choices: Vec<String> = arg
+            .get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| format!("\"{}\"", value.get_name()))
+            .collect();
+        writeln!(buf)?;
+        writeln!(buf, "  def \"{}\" [] {{", completer_name(name, arg))?;
+        writeln!(buf, "    [ {} ]", choices.join(" "))?;
+        writeln!(buf, "  }}")?;
+    }
+
+    writeln!(buf)?;
+    if let Some(about) = cmd.get_about() {
+        writeln!(buf, "  # {}", first_line(&about.to_string()))?;
+    }
+    if name.contains(' ') {
+        writeln!(buf, "  export extern \"{name}\" [")?;
+    } else {
+        writeln!(buf, "  export extern {name} [")?;
+    }
+    for arg in &args {
+        if let Some(signature) = signature(name, arg) {
+            writeln!(buf, "    {signature}")?;
+        }
+    }
+    writeln!(buf, "  ]")?;
+
+    for subcommand in cmd.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        write_command(
+            subcommand,
+            &format!("{name} {}", subcommand.get_name()),
+            buf,
+        )?;
+    }
+    Ok(())
+}
+
+/// One parameter of an `extern` signature, with its help as a comment.
+fn signature(command: &str, arg: &Arg) -> Option<String> {
+    let takes_value = arg.get_action().takes_values();
+    let mut signature = if arg.is_positional() {
+        let name = arg.get_id().as_str().replace('-', "_");
+        if arg
+            .get_num_args()
+            .is_some_and(|range| range.max_values() > 1)
+        {
+            format!("...{name}: string")
+        } else if arg.is_required_set() {
+            format!("{name}: string")
+        } else {
+            format!("{name}?: string")
+        }
+    } else {
+        let mut flag = match (arg.get_long(), arg.get_short()) {
+            (Some(long), Some(short)) => format!("--{long}(-{short})"),
+            (Some(long), None) => format!("--{long}"),
+            (None, Some(short)) => format!("-{short}"),
+            (None, None) => return None,
+        };
+        if takes_value {
+            flag.push_str(": string");
+        }
+        flag
+    };
+    if has_choices(arg) {
+        signature.push_str(&format!("@\"{}\"", completer_name(command, arg)));
+    }
+    if let Some(help) = arg.get_help() {
+        signature.push_str(&format!("  # {}", first_line(&help.to_string())));
+    }
+    Some(signature)
+}
+
+fn has_choices(arg: &Arg) -> bool {
+    arg.get_action().takes_values() && !arg.get_possible_values().is_empty()
+}
+
+fn completer_name(command: &str, arg: &Arg) -> String {
+    format!("nu-complete {command} {}", arg.get_id())
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default().trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ArgAction;
+
+    fn render(mut cmd: Command) -> String {
+        let mut buf = Vec::new();
+        generate(&mut cmd, "demo", &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_generate_writes_an_extern_per_subcommand() {
+        let cmd = Command::new("demo").about("A demo").subcommand(
+            Command::new("run")
+                .about("Run something")
+                .arg(Arg::new("task_name").help("The task"))
+                .arg(Arg::new("kind").long("kind").value_parser(["feat", "fix"]))
+                .arg(
+                    Arg::new("yes")
+                        .long("yes")
+                        .short('y')
+                        .action(ArgAction::SetTrue),
+                ),
+        );
+
+        let output = render(cmd);
+        assert!(output.starts_with("module completions {\n"));
+        assert!(output.contains("  # A demo\n  export extern demo [\n"));
+        assert!(output.contains("  export extern \"demo run\" [\n"));
+        assert!(output.contains("    task_name?: string  # The task\n"));
+        assert!(output.contains("    --kind: string@\"nu-complete demo run kind\"\n"));
+        assert!(
+            output.contains("  def \"nu-complete demo run kind\" [] {\n    [ \"feat\" \"fix\" ]")
+        );
+        assert!(output.contains("    --yes(-y)\n"));
+        assert!(output.ends_with("export use completions *\n"));
+    }
+
+    #[test]
+    fn test_generate_skips_hidden_subcommands() {
+        let cmd = Command::new("demo").subcommand(Command::new("__complete").hide(true));
+        assert!(!render(cmd).contains("__complete"));
+    }
+}
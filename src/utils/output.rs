@@ -1,47 +1,438 @@
 use crate::utils::{icons::ICONS, theme::THEME};
+use console::Term;
+use dialoguer::{
+    FuzzySelect,
+    theme::{ColorfulTheme, SimpleTheme},
+};
 use owo_colors::OwoColorize;
+use std::cell::RefCell;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tabled::{
     Table, Tabled,
-    settings::{Border, Modify, Remove, object::Rows, style::Style},
+    settings::{
+        Border, Format, Modify, Remove,
+        object::{Rows, Segment},
+        style::Style,
+    },
 };
 
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Switch every helper in this module to plain output: no colors, icons or
+/// box drawing, for screen readers and logs.
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+/// Whether output is plain, as set with [`set_plain`] or while capturing.
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed) || is_capturing()
+}
+
+thread_local! {
+    /// Output collected by [`capture`] instead of being printed
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with everything the helpers in this module would print collected
+/// as plain text instead, and return it alongside `f`'s result.
+///
+/// Nothing is asked of the user while capturing: confirmations are declined
+/// and interactive pickers are unavailable.
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, String) {
+    let outer = CAPTURED.with(|captured| captured.replace(Some(String::new())));
+    let value = f();
+    let output = CAPTURED
+        .with(|captured| captured.replace(outer))
+        .unwrap_or_default();
+    (value, output)
+}
+
+/// Whether output is being collected by [`capture`].
+pub fn is_capturing() -> bool {
+    CAPTURED.with(|captured| captured.borrow().is_some())
+}
+
+/// Append `text` to the capture buffer, returning it back if nothing is
+/// being captured.
+fn try_capture(text: &str) -> Option<&str> {
+    CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+        Some(buffer) => {
+            buffer.push_str(text);
+            None
+        }
+        None => Some(text),
+    })
+}
+
+/// Write `text` to stdout, or to the capture buffer.
+fn emit(text: &str) {
+    if let Some(text) = try_capture(text) {
+        print!("{text}");
+    }
+}
+
+/// Write `text` to stderr, or to the capture buffer.
+fn emit_err(text: &str) {
+    if let Some(text) = try_capture(text) {
+        eprint!("{text}");
+    }
+}
+
+/// Strip what plain output leaves out: ANSI escape sequences, and icons
+/// together with the space that separates them from the text.
+fn plain_text(text: &str) -> String {
+    let mut unstyled = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the escape sequence up to its final letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            unstyled.push(c);
+        }
+    }
+
+    let mut plain = String::with_capacity(unstyled.len());
+    let mut chars = unstyled.chars().peekable();
+    while let Some(c) = chars.next() {
+        if is_icon(c) {
+            if chars.peek() == Some(&' ') {
+                chars.next();
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+/// Symbols used as icons: arrows, box drawing, geometric shapes, dingbats
+/// and emoji.
+fn is_icon(c: char) -> bool {
+    matches!(
+        c,
+        '\u{2139}'
+            | '\u{2190}'..='\u{21FF}'
+            | '\u{2500}'..='\u{25FF}'
+            | '\u{2600}'..='\u{27BF}'
+            | '\u{FE0F}'
+            | '\u{1F000}'..='\u{1FAFF}'
+    )
+}
+
+/// The text to print: unchanged, or stripped in plain mode.
+fn out(text: String) -> String {
+    if is_plain() { plain_text(&text) } else { text }
+}
+
 pub fn blank() {
-    println!();
+    emit("\n");
 }
 
 pub fn standard(message: &str) {
-    println!("{}", message.color(THEME.text));
+    emit(&format!("{}\n", out(message.color(THEME.text).to_string())));
 }
 
 pub fn success(message: &str) {
-    println!(
-        "{} {}",
-        ICONS.status.success.color(THEME.success).bold(),
-        message.color(THEME.text)
-    );
+    emit(&format!(
+        "{}\n",
+        out(format!(
+            "{} {}",
+            ICONS.status.success.color(THEME.success).bold(),
+            message.color(THEME.text)
+        ))
+    ));
 }
 
 pub fn error(message: &str) {
-    eprintln!(
-        "{} {}",
+    if is_plain() {
+        emit_err(&format!("Error: {}\n", plain_text(message)));
+        return;
+    }
+    emit_err(&format!(
+        "{} {}\n",
         ICONS.status.failure.color(THEME.error).bold(),
         message.color(THEME.error)
-    );
+    ));
+}
+
+/// Prompt the user with a yes/no question, defaulting to no.
+pub fn confirm(message: &str) -> io::Result<bool> {
+    if is_capturing() {
+        emit(&format!(
+            "Warning: {} (y/N): declined\n",
+            plain_text(message)
+        ));
+        return Ok(false);
+    }
+    if is_plain() {
+        print!("Warning: {} (y/N): ", plain_text(message));
+    } else {
+        print!(
+            "{} {} (y/N): ",
+            ICONS.status.warning.color(THEME.warning),
+            message
+        );
+    }
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let input = input.trim().to_lowercase();
+    Ok(input == "y" || input == "yes")
+}
+
+/// Ask the user for a line of text, returning `default` when they enter
+/// nothing.
+pub fn ask(message: &str, default: &str) -> io::Result<String> {
+    if is_capturing() {
+        emit(&format!("{} [{default}]: {default}\n", plain_text(message)));
+        return Ok(default.to_string());
+    }
+    if is_plain() {
+        print!("{} [{default}]: ", plain_text(message));
+    } else {
+        print!(
+            "{} {} [{}]: ",
+            ICONS.status.info.color(THEME.info),
+            message,
+            default.color(THEME.muted)
+        );
+    }
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let input = input.trim();
+    Ok(if input.is_empty() { default } else { input }.to_string())
+}
+
+/// Whether the user can answer prompts: both stdin and stdout are terminals.
+pub fn is_interactive() -> bool {
+    !is_capturing() && io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+/// Let the user pick one of `items` by typing to fuzzy-filter the list.
+///
+/// Returns `None` if the user cancels with Esc or q.
+pub fn pick(message: &str, items: &[String]) -> io::Result<Option<usize>> {
+    let theme: Box<dyn dialoguer::theme::Theme> = if is_plain() {
+        Box::new(SimpleTheme)
+    } else {
+        Box::new(ColorfulTheme::default())
+    };
+    FuzzySelect::with_theme(theme.as_ref())
+        .with_prompt(message)
+        .items(items)
+        .default(0)
+        .interact_opt()
+        .map_err(|e| match e {
+            dialoguer::Error::IO(e) => e,
+        })
+}
+
+/// Whether a picker drawn on stderr can be used, as when stdout is being
+/// captured by `$(...)`: both stdin and stderr are terminals.
+pub fn can_pick_on_stderr() -> bool {
+    !is_capturing() && io::stdin().is_terminal() && io::stderr().is_terminal()
+}
+
+/// Whether the terminal cannot draw interactive pickers, e.g. `TERM=dumb`
+/// in editor shells.
+pub fn is_dumb_terminal() -> bool {
+    std::env::var("TERM").map_or(true, |term| term.is_empty() || term == "dumb")
+}
+
+/// Let the user pick one of `items` from a numbered list on stderr, for
+/// terminals that cannot draw [`pick`].
+///
+/// Returns `None` if the user enters nothing or q.
+pub fn pick_numbered(message: &str, items: &[String]) -> io::Result<Option<usize>> {
+    let mut stderr = io::stderr();
+    for (n, item) in items.iter().enumerate() {
+        writeln!(stderr, "{:>3}) {}", n + 1, plain_text(item))?;
+    }
+    loop {
+        write!(
+            stderr,
+            "{} [1-{}, q to cancel]: ",
+            plain_text(message),
+            items.len()
+        )?;
+        stderr.flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            return Ok(None);
+        }
+        match input.trim() {
+            "" | "q" => return Ok(None),
+            input => match input.parse::<usize>() {
+                Ok(n) if (1..=items.len()).contains(&n) => return Ok(Some(n - 1)),
+                _ => writeln!(stderr, "Enter a number from 1 to {}", items.len())?,
+            },
+        }
+    }
 }
 
 pub fn table<T: Tabled>(data: &[T], show_header: bool) {
+    emit(&format!("{}\n", render_table(data, show_header)));
+}
+
+/// Render a table as [`table`] would print it.
+pub fn render_table<T: Tabled>(data: &[T], show_header: bool) -> String {
     let mut table = Table::new(data);
     table.with(Style::empty());
+    if is_plain() {
+        // Strip cells before layout so columns stay aligned
+        table.modify(Segment::all(), Format::content(plain_text));
+    }
 
-    if show_header {
+    if show_header && !is_plain() {
         table.with(Modify::new(Rows::first()).with(Border::new().bottom('─')));
-    } else {
+    } else if !show_header {
         table.with(Remove::row(Rows::first()));
     }
 
-    println!("{table}");
+    table.to_string()
+}
+
+/// Print a line of another program's output as it arrives.
+///
+/// A closed stdout is ignored, so the program's work is not lost with it.
+pub fn passthrough(line: &str) {
+    if let Some(line) = try_capture(&format!("{line}\n")) {
+        let mut stdout = io::stdout();
+        let _ = stdout
+            .write_all(line.as_bytes())
+            .and_then(|_| stdout.flush());
+    }
+}
+
+/// Print another program's error output, or add it to the capture buffer.
+pub fn passthrough_err(text: &str) {
+    emit_err(text);
+}
+
+/// Run another program to completion with its output going to the
+/// terminal, or into the capture buffer while capturing.
+pub fn run_shown(command: &mut Command) -> io::Result<ExitStatus> {
+    if !is_capturing() {
+        return command.status();
+    }
+    let output = command.output()?;
+    emit(&String::from_utf8_lossy(&output.stdout));
+    emit_err(&String::from_utf8_lossy(&output.stderr));
+    Ok(output.status)
 }
 
+/// Pager used when `$PAGER` is not set.
+const DEFAULT_PAGER: &str = "less";
+
+/// Options for `less` unless the user set `$LESS`, as git does: quit if the
+/// content fits on one screen, keep colors, and leave the output on screen.
+const DEFAULT_LESS: &str = "FRX";
+
+/// Print output that may be longer than the terminal through a pager.
+///
+/// Uses `$PAGER`, falling back to `less` and then to built-in paging if no
+/// pager can be started. Output that fits on screen, or that is not going to
+/// a terminal, is printed directly.
+pub fn page(content: &str) {
+    let content = &out(content.to_string());
+    let (rows, _) = Term::stdout().size();
+    if !is_interactive() || content.lines().count() < usize::from(rows) {
+        emit(&format!("{content}\n"));
+        return;
+    }
+
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_string());
+    if pager.trim().is_empty() || pager.trim() == "cat" {
+        println!("{content}");
+        return;
+    }
+    if run_pager(&pager, content).is_err() {
+        page_builtin(content, usize::from(rows).saturating_sub(1).max(1));
+    }
+}
+
+fn run_pager(pager: &str, content: &str) -> io::Result<()> {
+    let mut child = Command::new("sh")
+        .args(["-c", pager])
+        .env(
+            "LESS",
+            std::env::var("LESS").unwrap_or_else(|_| DEFAULT_LESS.to_string()),
+        )
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user quitting the pager early closes the pipe; that is fine
+        let _ = writeln!(stdin, "{content}");
+    }
+    let status = child.wait()?;
+    // 127: the shell could not find the pager
+    if status.code() == Some(127) {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "pager not found"));
+    }
+    Ok(())
+}
+
+/// Show `content` a screen at a time, waiting for Enter between screens.
+fn page_builtin(content: &str, page_size: usize) {
+    let lines: Vec<&str> = content.lines().collect();
+    for (i, chunk) in lines.chunks(page_size).enumerate() {
+        if i > 0 {
+            print!(
+                "{}",
+                "-- more (Enter to continue, q to quit) --".color(THEME.muted)
+            );
+            let _ = io::stdout().flush();
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input).is_err() || input.trim() == "q" {
+                return;
+            }
+        }
+        for line in chunk {
+            println!("{line}");
+        }
+    }
+}
+
+/// Color a unified diff the way `git diff` does.
+pub fn colorize_diff(diff: &str) -> String {
+    diff.lines()
+        .map(|line| {
+            if line.starts_with("diff --git") {
+                line.color(THEME.primary).bold().to_string()
+            } else if line.starts_with("+++")
+                || line.starts_with("---")
+                || line.starts_with("index ")
+            {
+                line.bold().to_string()
+            } else if line.starts_with("@@") {
+                line.color(THEME.info).to_string()
+            } else if line.starts_with('+') {
+                line.color(THEME.success).to_string()
+            } else if line.starts_with('-') {
+                line.color(THEME.error).to_string()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Clone, Copy)]
 pub enum Position {
     First,
     #[allow(dead_code)]
@@ -50,27 +441,163 @@ pub enum Position {
     Last,
 }
 
+/// Cells in a step's progress bar.
+const PROGRESS_BAR_WIDTH: usize = 20;
+
 pub fn step(message: &str, position: Position) {
+    if is_plain() {
+        emit(message);
+        return;
+    }
     let icon = match position {
         Position::First => ICONS.box_draw.corner_tl,
         Position::Normal => ICONS.box_draw.tee_left,
         Position::Last => ICONS.box_draw.corner_bl,
     };
-    print!(
+    emit(&format!(
         "{} {}",
         icon.color(THEME.primary).bold(),
         message.color(THEME.muted)
-    );
+    ));
+}
+
+/// Redraw the current step line with a progress bar after its message, or
+/// without one when `progress` is `None`.
+///
+/// Only terminals can redraw a line, so this does nothing when stdout is
+/// redirected.
+pub fn step_progress(message: &str, position: Position, progress: Option<(&str, u8)>) {
+    if !io::stdout().is_terminal() || is_plain() {
+        return;
+    }
+    print!("\r\x1b[2K");
+    step(message, position);
+    if let Some((label, percent)) = progress {
+        print!(
+            " {} {:>3}% {}",
+            progress_bar(percent).color(THEME.primary),
+            percent,
+            label.color(THEME.muted)
+        );
+    }
+    let _ = io::stdout().flush();
+}
+
+fn progress_bar(percent: u8) -> String {
+    let filled = PROGRESS_BAR_WIDTH * usize::from(percent.min(100)) / 100;
+    format!(
+        "{}{}",
+        ICONS.progress.bar_filled.repeat(filled),
+        ICONS.progress.bar_empty.repeat(PROGRESS_BAR_WIDTH - filled)
+    )
 }
 
 pub fn step_end() {
-    print!("{} ", ICONS.status.success.color(THEME.success).bold());
+    if is_plain() {
+        emit(" done");
+        return;
+    }
+    emit(&format!(
+        "{} ",
+        ICONS.status.success.color(THEME.success).bold()
+    ));
 }
 
 pub fn step_skip() {
-    print!("{} ", ICONS.arrows.right.color(THEME.info).bold());
+    if is_plain() {
+        emit(" skipped");
+        return;
+    }
+    emit(&format!("{} ", ICONS.arrows.right.color(THEME.info).bold()));
 }
 
 pub fn step_fail() {
-    print!("{} ", ICONS.status.failure.color(THEME.error).bold());
+    if is_plain() {
+        emit(" failed");
+        return;
+    }
+    emit(&format!(
+        "{} ",
+        ICONS.status.failure.color(THEME.error).bold()
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_strips_colors_and_icons() {
+        let styled = format!(
+            "{} {}",
+            ICONS.status.success.color(THEME.success),
+            "Café ready".color(THEME.text)
+        );
+        assert_eq!(plain_text(&styled), "Café ready");
+        assert_eq!(plain_text("🔧 Repairing"), "Repairing");
+        assert_eq!(plain_text("⚠️  Repair failed"), " Repair failed");
+        assert_eq!(plain_text("● (Ready)"), "(Ready)");
+    }
+
+    #[test]
+    fn test_capture_collects_plain_output() {
+        let (value, output) = capture(|| {
+            step("Working...", Position::First);
+            step_end();
+            blank();
+            success("Done");
+            error("Warned");
+            confirm("Delete everything?").unwrap()
+        });
+        assert!(!value);
+        assert_eq!(
+            output,
+            "Working... done\nDone\nError: Warned\nWarning: Delete everything? (y/N): declined\n"
+        );
+        assert!(!is_capturing());
+    }
+
+    #[test]
+    fn test_capture_nests() {
+        let (inner, outer) = capture(|| {
+            standard("outer");
+            let ((), inner) = capture(|| standard("inner"));
+            passthrough("after");
+            inner
+        });
+        assert_eq!(inner, "inner\n");
+        assert_eq!(outer, "outer\nafter\n");
+    }
+
+    #[test]
+    fn test_capture_collects_other_programs_output() {
+        let (status, output) = capture(|| {
+            run_shown(Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]))
+        });
+        assert_eq!(status.unwrap().code(), Some(3));
+        assert_eq!(output, "out\nerr\n");
+    }
+
+    #[test]
+    fn test_colorize_diff_keeps_every_line() {
+        let diff = "diff --git a/x b/x\n--- a/x\n+++ b/x\n@@ -1 +1 @@\n-old\n+new\n context";
+        let colored = colorize_diff(diff);
+        assert_eq!(colored.lines().count(), diff.lines().count());
+        for line in diff.lines() {
+            assert!(colored.contains(line));
+        }
+    }
+
+    #[test]
+    fn test_progress_bar_fills_proportionally() {
+        let empty = ICONS.progress.bar_empty.repeat(PROGRESS_BAR_WIDTH);
+        let full = ICONS.progress.bar_filled.repeat(PROGRESS_BAR_WIDTH);
+        assert_eq!(progress_bar(0), empty);
+        assert_eq!(progress_bar(100), full);
+        assert_eq!(progress_bar(250), full);
+        assert_eq!(
+            progress_bar(50).matches(ICONS.progress.bar_filled).count(),
+            10
+        );
+    }
 }
@@ -0,0 +1,290 @@
+use std::process::{Command, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::utils::errors::CommandError;
+
+/// How often a process is checked while waiting for it to exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long to wait for a process to die after SIGKILL.
+const KILL_GRACE: Duration = Duration::from_secs(2);
+
+/// How a process was ended by [`terminate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// It exited after SIGTERM
+    Exited,
+    /// It had to be sent SIGKILL
+    Killed,
+}
+
+/// Whether a process is still running; assumed so where it can't be checked.
+pub fn is_running(pid: u32) -> bool {
+    if !cfg!(unix) {
+        return true;
+    }
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_or(true, |status| status.success())
+}
+
+/// When a running process started and the program it runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    /// To the second, as `ps` reports it
+    pub started_at: DateTime<Utc>,
+    /// The program's file name, e.g. "claudectl"
+    pub program: String,
+}
+
+/// When `pid` started and what it runs, read from `ps`; `None` where it
+/// can't be checked or the process is gone.
+pub fn process_info(pid: u32) -> Option<ProcessInfo> {
+    if !cfg!(unix) {
+        return None;
+    }
+    let output = Command::new("ps")
+        .args(["-o", "lstart=,comm=", "-p", &pid.to_string()])
+        // Day and month names in English, as they are parsed
+        .env("LC_ALL", "C")
+        .stderr(Stdio::null())
+        .output()
+        .inspect_err(|e| warn!("Failed to run ps: {}", e))
+        .ok()?;
+    parse_process_info(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Read a `ps -o lstart=,comm=` line, e.g.
+/// "Sun Oct 18 03:05:51 2026 claudectl".
+fn parse_process_info(ps: &str) -> Option<ProcessInfo> {
+    let fields: Vec<&str> = ps.split_whitespace().collect();
+    if fields.len() < 6 {
+        return None;
+    }
+    let started_at =
+        NaiveDateTime::parse_from_str(&fields[..5].join(" "), "%a %b %d %H:%M:%S %Y").ok()?;
+    let started_at = started_at.and_local_timezone(Local).earliest()?;
+    let program = fields[5..].join(" ");
+    let program = std::path::Path::new(&program).file_name()?;
+    Some(ProcessInfo {
+        started_at: started_at.with_timezone(&Utc),
+        program: program.to_string_lossy().to_string(),
+    })
+}
+
+/// What a process and the programs it started are using right now.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Usage {
+    /// Resident memory, in KiB
+    pub rss_kib: u64,
+    /// Share of one CPU over the processes' lifetimes, as `ps` reports it
+    pub cpu_percent: f64,
+    /// How many processes were counted
+    pub processes: usize,
+}
+
+impl Usage {
+    /// e.g. "412 MB, 35% CPU"
+    pub fn describe(&self) -> String {
+        format!(
+            "{} MB, {:.0}% CPU",
+            self.rss_kib.div_ceil(1024),
+            self.cpu_percent
+        )
+    }
+}
+
+/// The memory and CPU used by `pid` and its descendants, read from `ps`;
+/// `None` where it can't be checked or the process is gone.
+pub fn usage(pid: u32) -> Option<Usage> {
+    if !cfg!(unix) {
+        return None;
+    }
+    let output = Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid=,rss=,pcpu="])
+        .stderr(Stdio::null())
+        .output()
+        .inspect_err(|e| warn!("Failed to run ps: {}", e))
+        .ok()?;
+    tree_usage(&String::from_utf8_lossy(&output.stdout), pid)
+}
+
+/// Add up the `ps` lines of `root` and every process descended from it.
+fn tree_usage(ps: &str, root: u32) -> Option<Usage> {
+    let processes: Vec<(u32, u32, u64, f64)> = ps
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((
+                fields.next()?.parse().ok()?,
+                fields.next()?.parse().ok()?,
+                fields.next()?.parse().ok()?,
+                fields.next()?.parse().ok()?,
+            ))
+        })
+        .collect();
+    if !processes.iter().any(|(pid, ..)| *pid == root) {
+        return None;
+    }
+
+    let mut tree = vec![root];
+    let mut next = 0;
+    while let Some(&parent) = tree.get(next) {
+        tree.extend(
+            processes
+                .iter()
+                .filter(|(pid, ppid, ..)| *ppid == parent && *pid != parent)
+                .map(|(pid, ..)| *pid),
+        );
+        next += 1;
+    }
+    let counted = processes.iter().filter(|(pid, ..)| tree.contains(pid));
+    Some(counted.fold(
+        Usage {
+            rss_kib: 0,
+            cpu_percent: 0.0,
+            processes: 0,
+        },
+        |total, (_, _, rss, cpu)| Usage {
+            rss_kib: total.rss_kib + rss,
+            cpu_percent: total.cpu_percent + cpu,
+            processes: total.processes + 1,
+        },
+    ))
+}
+
+/// End the process `pid` and the programs it started: SIGTERM first, then
+/// SIGKILL if it is still running after `timeout`.
+///
+/// The children are signalled first so a claudectl process sees its
+/// `claude` exit and cleans up after itself.
+pub fn terminate(pid: u32, timeout: Duration) -> Result<Termination, CommandError> {
+    if !cfg!(unix) {
+        return Err(CommandError::new(
+            "Stopping sessions is only supported on Unix",
+        ));
+    }
+    if !signal_children(pid, "TERM") {
+        signal(pid, "TERM");
+    }
+    if wait_for_exit(pid, timeout) {
+        info!("Process {} exited after SIGTERM", pid);
+        return Ok(Termination::Exited);
+    }
+
+    warn!("Process {} ignored SIGTERM; sending SIGKILL", pid);
+    signal_children(pid, "KILL");
+    signal(pid, "KILL");
+    if wait_for_exit(pid, KILL_GRACE) {
+        return Ok(Termination::Killed);
+    }
+    Err(CommandError::new(&format!(
+        "Process {pid} is still running after SIGKILL"
+    )))
+}
+
+/// Send `signal` to the children of `pid`, returning whether there were any.
+fn signal_children(pid: u32, signal: &str) -> bool {
+    Command::new("pkill")
+        .args([&format!("-{signal}"), "-P", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+fn signal(pid: u32, signal: &str) {
+    let sent = Command::new("kill")
+        .args([&format!("-{signal}"), &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    if let Err(e) = sent {
+        warn!("Failed to send SIG{} to {}: {}", signal, pid, e);
+    }
+}
+
+fn wait_for_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while is_running(pid) {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        sleep(POLL_INTERVAL);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_usage_adds_up_descendants() {
+        let ps = "    1     0   1000  0.0
+  200     1  40960 12.5
+  201   200 368640 30.0
+  202   201   2048  1.5
+  300     1  99999 50.0
+";
+        let usage = tree_usage(ps, 200).unwrap();
+        assert_eq!(usage.rss_kib, 40960 + 368640 + 2048);
+        assert_eq!(usage.processes, 3);
+        assert_eq!(usage.describe(), "402 MB, 44% CPU");
+        assert_eq!(tree_usage(ps, 999), None);
+    }
+
+    #[test]
+    fn test_parse_process_info() {
+        let info =
+            parse_process_info("Sun Oct  4 03:05:51 2026 /usr/local/bin/claudectl\n").unwrap();
+        assert_eq!(info.program, "claudectl");
+        let local = info.started_at.with_timezone(&Local);
+        assert_eq!(
+            local.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "2026-10-04 03:05:51"
+        );
+        assert_eq!(parse_process_info(""), None);
+        assert_eq!(parse_process_info("not a date claudectl at all"), None);
+    }
+
+    #[test]
+    fn test_process_info_describes_this_process() {
+        let info = process_info(std::process::id()).unwrap();
+        assert!(info.started_at <= Utc::now());
+        let exe = std::env::current_exe().unwrap();
+        let exe = exe.file_name().unwrap().to_string_lossy();
+        // `ps` may cut long program names short
+        assert!(exe.starts_with(&info.program));
+        assert_eq!(process_info(99_999_999), None);
+    }
+
+    #[test]
+    fn test_terminate_ends_process_and_children() {
+        let child = Command::new("sh")
+            .args(["-c", "sleep 30; true"])
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        // Reap the shell as soon as it exits so it does not linger as a zombie
+        let waiter = std::thread::spawn(move || {
+            let mut child = child;
+            child.wait()
+        });
+
+        assert_eq!(
+            terminate(pid, Duration::from_secs(5)).unwrap(),
+            Termination::Exited
+        );
+        waiter.join().unwrap().unwrap();
+        assert!(!is_running(pid));
+    }
+}
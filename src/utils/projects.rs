@@ -0,0 +1,82 @@
+use crate::utils::errors::{CommandError, ConfigError};
+use crate::utils::fs::{global_project_dirs, read_project_file, write_project_file};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Links a global project directory back to the repository it was created for.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectRegistration {
+    pub repo_path: PathBuf,
+}
+
+impl ProjectRegistration {
+    pub fn from_str(json_str: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(json_str)
+            .map_err(|e| ConfigError::new(&format!("Failed to parse project JSON: {e}")))
+    }
+
+    pub fn to_string(&self) -> Result<String, ConfigError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| ConfigError::serialize_failed(&format!("JSON serialization error: {e}")))
+    }
+}
+
+/// A project found in global storage. Its registration is loaded lazily so
+/// one unreadable project does not prevent listing the others.
+pub struct RegisteredProject {
+    /// Name of the global project directory
+    pub name: String,
+    pub registration: Result<ProjectRegistration, CommandError>,
+}
+
+/// Record that `project_dir` belongs to the repository at `repo_root`.
+pub fn register_project(project_dir: &str, repo_root: &Path) -> Result<(), CommandError> {
+    let registration = ProjectRegistration {
+        repo_path: repo_root.to_path_buf(),
+    };
+    write_project_file(Path::new(project_dir), registration.to_string()?)?;
+    Ok(())
+}
+
+/// Every registered project in global storage.
+///
+/// Directories without a registration file are skipped; they predate
+/// registration or were never used by `init`.
+pub fn registered_projects() -> Result<Vec<RegisteredProject>, CommandError> {
+    let mut projects = Vec::new();
+    for dir in global_project_dirs()? {
+        let registration = match read_project_file(&dir) {
+            Ok(Some(raw)) => ProjectRegistration::from_str(&raw).map_err(CommandError::from),
+            Ok(None) => continue,
+            Err(e) => Err(e.into()),
+        };
+        projects.push(RegisteredProject {
+            name: dir
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            registration,
+        });
+    }
+    Ok(projects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_registration_round_trip() {
+        let registration = ProjectRegistration {
+            repo_path: PathBuf::from("/src/my-app"),
+        };
+
+        let parsed = ProjectRegistration::from_str(&registration.to_string().unwrap()).unwrap();
+        assert_eq!(parsed.repo_path, PathBuf::from("/src/my-app"));
+    }
+
+    #[test]
+    fn test_project_registration_rejects_invalid_json() {
+        assert!(ProjectRegistration::from_str("{ not json").is_err());
+    }
+}
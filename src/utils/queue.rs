@@ -0,0 +1,191 @@
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::utils::errors::CommandError;
+use crate::utils::fs::{
+    current_dir, queue_file_path_in, queue_files_in, remove_queue_file, write_queue_file,
+};
+use crate::utils::lock::running_sessions_in;
+use crate::utils::output::standard;
+use crate::utils::process::is_running;
+use crate::utils::tasks::task_name_from_file;
+
+/// How often a queued session checks whether it may start.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A session waiting for a free slot, kept in `.claudectl/queue/`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct QueueEntry {
+    /// The claudectl process waiting to start the session
+    pub pid: u32,
+    /// What is starting the session, e.g. `run`
+    pub command: String,
+    pub queued_at: DateTime<Utc>,
+}
+
+impl QueueEntry {
+    /// Sessions start in the order they were queued.
+    fn is_ahead_of(&self, other: &QueueEntry) -> bool {
+        (self.queued_at, self.pid) < (other.queued_at, other.pid)
+    }
+}
+
+/// A task's place in the queue, given up when dropped.
+///
+/// Keep it until the session has taken the task's lock, so the session is
+/// counted as queued or as running at every moment.
+#[derive(Debug)]
+pub struct QueueTicket {
+    path: PathBuf,
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        if let Err(e) = remove_queue_file(&self.path) {
+            warn!("Failed to leave queue {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Wait for a free slot when the project limits how many sessions run at
+/// once, saying so if the session for `task_name` has to wait.
+pub fn queue_session(
+    max_sessions: Option<usize>,
+    task_name: &str,
+    command: &str,
+) -> Result<Option<QueueTicket>, CommandError> {
+    let Some(max_sessions) = max_sessions else {
+        return Ok(None);
+    };
+    wait_for_slot(task_name, command, max_sessions, |ahead| {
+        standard(&format!(
+            "{ahead} session(s) are running or queued (max_sessions is {max_sessions}); '{task_name}' starts when a slot is free..."
+        ));
+    })
+    .map(Some)
+}
+
+/// Wait until a session for `task_name` may start: fewer than
+/// `max_sessions` sessions are running and none queued earlier is still
+/// waiting. `on_wait` is called once, with the number of sessions ahead,
+/// if it has to wait at all.
+fn wait_for_slot(
+    task_name: &str,
+    command: &str,
+    max_sessions: usize,
+    on_wait: impl FnOnce(usize),
+) -> Result<QueueTicket, CommandError> {
+    let repo_root = current_dir()?;
+    let entry = QueueEntry {
+        pid: std::process::id(),
+        command: command.to_string(),
+        queued_at: Utc::now(),
+    };
+    let path = queue_file_path_in(&repo_root, task_name);
+    let content = serde_json::to_string_pretty(&entry)
+        .map_err(|e| CommandError::new(&format!("Failed to serialize queue entry: {e}")))?;
+    write_queue_file(&path, content)?;
+    let ticket = QueueTicket { path };
+
+    let mut on_wait = Some(on_wait);
+    loop {
+        let waiting_ahead = queued_in(&repo_root)
+            .iter()
+            .filter(|(_, queued)| queued.is_ahead_of(&entry))
+            .count();
+        let ahead = running_sessions_in(&repo_root) + waiting_ahead;
+        if ahead < max_sessions.max(1) {
+            info!("Session slot free for '{}'", task_name);
+            return Ok(ticket);
+        }
+        if let Some(on_wait) = on_wait.take() {
+            info!("Queued '{}' behind {} session(s)", task_name, ahead);
+            on_wait(ahead);
+        }
+        sleep(POLL_INTERVAL);
+    }
+}
+
+/// The entry of a task waiting for a free session slot, if any.
+pub fn queued_task(task_name: &str) -> Option<QueueEntry> {
+    let repo_root = current_dir().ok()?;
+    read_live_entry(&queue_file_path_in(&repo_root, task_name))
+}
+
+/// The tasks waiting for a free session slot in the project at
+/// `repo_root`, first in line first. Entries left by processes that have
+/// exited are ignored.
+pub fn queued_in(repo_root: &Path) -> Vec<(String, QueueEntry)> {
+    let paths = queue_files_in(repo_root)
+        .inspect_err(|e| warn!("Failed to list the queue: {}", e))
+        .unwrap_or_default();
+    let mut queued: Vec<(String, QueueEntry)> = paths
+        .iter()
+        .filter_map(|path| {
+            let entry = read_live_entry(path)?;
+            let file_name = path.file_name()?.to_string_lossy().to_string();
+            Some((task_name_from_file(&file_name), entry))
+        })
+        .collect();
+    queued.sort_by_key(|(_, entry)| (entry.queued_at, entry.pid));
+    queued
+}
+
+fn read_live_entry(path: &Path) -> Option<QueueEntry> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str::<QueueEntry>(&content)
+        .inspect_err(|e| warn!("Ignoring unreadable queue entry {}: {}", path.display(), e))
+        .ok()
+        .filter(|entry| is_running(entry.pid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(repo_root: &Path, task_name: &str, pid: u32, queued_at: DateTime<Utc>) {
+        let entry = QueueEntry {
+            pid,
+            command: "run".to_string(),
+            queued_at,
+        };
+        write_queue_file(
+            &queue_file_path_in(repo_root, task_name),
+            serde_json::to_string(&entry).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_queue_is_in_order_and_skips_exited_processes() {
+        let repo = TempDir::new().unwrap();
+        let now = Utc::now();
+        let pid = std::process::id();
+        write(repo.path(), "feat/second", pid, now);
+        write(
+            repo.path(),
+            "feat/first",
+            pid,
+            now - chrono::Duration::seconds(5),
+        );
+        // Far above any default pid_max, so never a running process
+        write(
+            repo.path(),
+            "feat/gone",
+            99_999_999,
+            now - chrono::Duration::seconds(9),
+        );
+
+        let names: Vec<String> = queued_in(repo.path())
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["feat/first", "feat/second"]);
+    }
+}
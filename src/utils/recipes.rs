@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Steps that prepare a fresh worktree for one kind of project, e.g.
+/// sharing a Rust build directory with the main checkout.
+///
+/// Recipe commands run like setup commands, with `$CLAUDECTL_MAIN_CHECKOUT`
+/// set to the project's directory in the main checkout.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recipe {
+    /// Files whose presence marks a project the recipe is for
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub detect: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub commands: Vec<String>,
+}
+
+impl Recipe {
+    fn new(detect: &str, description: &str, commands: &[&str]) -> Self {
+        Self {
+            detect: vec![detect.to_string()],
+            description: description.to_string(),
+            commands: commands.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    /// Whether `dir` looks like a project this recipe is for.
+    pub fn applies_to(&self, dir: &Path) -> bool {
+        self.detect.iter().any(|file| dir.join(file).exists())
+    }
+}
+
+/// The recipes claudectl ships with.
+pub fn builtin_recipes() -> BTreeMap<String, Recipe> {
+    BTreeMap::from([
+        (
+            "rust".to_string(),
+            Recipe::new(
+                "Cargo.toml",
+                "share the main checkout's target directory",
+                &[
+                    r#"mkdir -p "$CLAUDECTL_MAIN_CHECKOUT/target""#,
+                    r#"[ -e target ] || ln -s "$CLAUDECTL_MAIN_CHECKOUT/target" target"#,
+                    // `target/` in a .gitignore only matches directories,
+                    // so the link needs an exclude of its own
+                    r#"exclude="$(git rev-parse --git-path info/exclude)" && mkdir -p "$(dirname "$exclude")" && { grep -qsxF target "$exclude" || echo target >> "$exclude"; }"#,
+                ],
+            ),
+        ),
+        (
+            "node".to_string(),
+            Recipe::new(
+                "package.json",
+                "install dependencies with pnpm, linked from its shared store",
+                &["pnpm install --prefer-offline"],
+            ),
+        ),
+        (
+            "python".to_string(),
+            Recipe::new(
+                "pyproject.toml",
+                "create a virtualenv in .venv with the project installed",
+                &[
+                    "python3 -m venv .venv",
+                    ".venv/bin/pip install --quiet --editable .",
+                ],
+            ),
+        ),
+    ])
+}
+
+/// The built-in recipes with the project's own added, replacing built-ins
+/// of the same name.
+pub fn all_recipes(project: &BTreeMap<String, Recipe>) -> BTreeMap<String, Recipe> {
+    let mut recipes = builtin_recipes();
+    recipes.extend(project.clone());
+    recipes
+}
+
+/// Names of the recipes for the kind of project in `dir`.
+pub fn detect_recipes(recipes: &BTreeMap<String, Recipe>, dir: &Path) -> Vec<String> {
+    recipes
+        .iter()
+        .filter(|(_, recipe)| recipe.applies_to(dir))
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_recipes_by_marker_files() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("Cargo.toml"), "").unwrap();
+        std::fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
+
+        let recipes = all_recipes(&BTreeMap::new());
+        assert_eq!(detect_recipes(&recipes, temp_dir.path()), ["node", "rust"]);
+    }
+
+    #[test]
+    fn test_project_recipes_replace_builtins() {
+        let project = BTreeMap::from([(
+            "node".to_string(),
+            Recipe {
+                detect: vec!["package.json".to_string()],
+                description: String::new(),
+                commands: vec!["npm ci".to_string()],
+            },
+        )]);
+        let recipes = all_recipes(&project);
+        assert_eq!(recipes["node"].commands, ["npm ci"]);
+        assert!(recipes.contains_key("rust"));
+    }
+}
@@ -0,0 +1,89 @@
+use std::process::{Command, Stdio};
+
+use owo_colors::OwoColorize;
+use tracing::{info, instrument, warn};
+
+use crate::utils::{
+    output::{run_shown, standard},
+    tasks::SetupFailure,
+    theme::THEME,
+};
+
+/// Run the project's setup commands in a worktree, in order, with their
+/// output going straight to the terminal, or to the capture buffer. Stops
+/// at the first command that fails.
+///
+/// Commands run without stdin so one waiting for input fails instead of
+/// hanging, and with `$CLAUDECTL_MAIN_CHECKOUT` set to the directory
+/// claudectl runs in, for recipes that share files with the main checkout.
+#[instrument(skip(commands))]
+pub fn run_setup_commands(commands: &[String], worktree: &str) -> Result<(), SetupFailure> {
+    let main_checkout = std::env::current_dir().unwrap_or_default();
+    for command in commands {
+        standard(&format!("$ {command}").color(THEME.muted).to_string());
+        info!("Running setup command in {}: {}", worktree, command);
+        let result = run_shown(
+            Command::new("sh")
+                .args(["-c", command])
+                .current_dir(worktree)
+                .env("CLAUDECTL_MAIN_CHECKOUT", &main_checkout)
+                .stdin(Stdio::null()),
+        );
+
+        let reason = match result {
+            Ok(status) if status.success() => continue,
+            Ok(status) => format!("exited with {status}"),
+            Err(e) => format!("could not be run: {e}"),
+        };
+        warn!("Setup command '{}' {}", command, reason);
+        return Err(SetupFailure {
+            command: command.clone(),
+            reason,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_run_setup_commands_stops_at_first_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_str().unwrap();
+        let commands = vec![
+            "touch first".to_string(),
+            "exit 3".to_string(),
+            "touch third".to_string(),
+        ];
+
+        let failure = run_setup_commands(&commands, dir).unwrap_err();
+        assert_eq!(failure.command, "exit 3");
+        assert!(failure.reason.contains('3'));
+        assert!(temp_dir.path().join("first").exists());
+        assert!(!temp_dir.path().join("third").exists());
+    }
+
+    #[test]
+    fn test_run_setup_commands_output_can_be_captured() {
+        let temp_dir = TempDir::new().unwrap();
+        let commands = vec!["echo prepared; echo warned >&2".to_string()];
+        let (result, output) = crate::utils::output::capture(|| {
+            run_setup_commands(&commands, temp_dir.path().to_str().unwrap())
+        });
+        assert!(result.is_ok());
+        assert_eq!(
+            output,
+            "$ echo prepared; echo warned >&2\nprepared\nwarned\n"
+        );
+    }
+
+    #[test]
+    fn test_run_setup_commands_succeeds() {
+        let temp_dir = TempDir::new().unwrap();
+        let commands = vec!["true".to_string()];
+        assert!(run_setup_commands(&commands, temp_dir.path().to_str().unwrap()).is_ok());
+    }
+}
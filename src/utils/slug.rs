@@ -0,0 +1,77 @@
+/// Turn a free-form task name into one that is safe to use as both a branch
+/// name and a directory path.
+///
+/// Each `/`-separated segment is lowercased, accented Latin letters lose their
+/// accents, and any run of other characters becomes a single `-`. Segments
+/// left empty are dropped, so the result is empty only when the name has
+/// nothing usable in it.
+pub fn slugify(name: &str) -> String {
+    name.split('/')
+        .map(slugify_segment)
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn slugify_segment(segment: &str) -> String {
+    let mut slug = String::with_capacity(segment.len());
+    for c in segment.chars().flat_map(char::to_lowercase) {
+        let c = fold_accent(c);
+        if c.is_ascii_alphanumeric() || c == '_' {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// The unaccented form of common accented Latin letters.
+fn fold_accent(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'ç' => 'c',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'ñ' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify_keeps_valid_names() {
+        assert_eq!(slugify("feat/new-thing"), "feat/new-thing");
+        assert_eq!(slugify("fix/issue_42"), "fix/issue_42");
+    }
+
+    #[test]
+    fn test_slugify_normalizes_spaces_case_and_punctuation() {
+        assert_eq!(slugify("Fix Login  Crash!"), "fix-login-crash");
+        assert_eq!(slugify("feat/Add OAuth (v2)"), "feat/add-oauth-v2");
+        assert_eq!(
+            slugify("  -leading and trailing-  "),
+            "leading-and-trailing"
+        );
+        assert_eq!(slugify("release..1.2"), "release-1-2");
+    }
+
+    #[test]
+    fn test_slugify_handles_unicode() {
+        assert_eq!(slugify("Café crème"), "cafe-creme");
+        assert_eq!(slugify("修复 bug"), "bug");
+        assert_eq!(slugify("🚀"), "");
+    }
+
+    #[test]
+    fn test_slugify_drops_empty_segments() {
+        assert_eq!(slugify("/feat//thing/"), "feat/thing");
+        assert_eq!(slugify("feat/!!!"), "feat");
+    }
+}
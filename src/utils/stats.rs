@@ -0,0 +1,297 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::utils::events::{Event, EventKind};
+
+/// Tasks created and completed in the week starting on `week`, a Monday.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WeekStats {
+    pub week: NaiveDate,
+    pub created: usize,
+    pub completed: usize,
+}
+
+/// What a project's event log says about its tasks and sessions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    /// Most recent week last
+    pub weeks: Vec<WeekStats>,
+    pub tasks_created: usize,
+    /// Tasks merged or sent for review as a pull request
+    pub tasks_completed: usize,
+    /// Tasks removed or archived, which the average lifetime is taken over
+    pub tasks_finished: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_lifetime_secs: Option<u64>,
+    /// Interactive sessions that have ended
+    pub sessions: usize,
+    pub session_secs: u64,
+    /// `claudectl run`s that have finished
+    pub runs: usize,
+    pub run_secs: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost: Option<Cost>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Cost {
+    pub usd: f64,
+    pub source: CostSource,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostSource {
+    /// Every claude session in the project's worktrees, as ccusage reports
+    Ccusage,
+    /// Only what `claudectl run` recorded
+    Runs,
+}
+
+/// Tally `events`, oldest first, with a row for each of the `weeks` weeks
+/// up to and including the one holding `today`.
+pub fn compute_stats(events: &[Event], weeks: u64, today: NaiveDate) -> Stats {
+    let current_week = week_of(today);
+    let first_week = current_week - Days::new((weeks.max(1) - 1) * 7);
+    let mut by_week: BTreeMap<NaiveDate, WeekStats> = (0..weeks.max(1))
+        .map(|n| first_week + Days::new(n * 7))
+        .map(|week| {
+            (
+                week,
+                WeekStats {
+                    week,
+                    created: 0,
+                    completed: 0,
+                },
+            )
+        })
+        .collect();
+
+    let mut stats = Stats {
+        weeks: Vec::new(),
+        tasks_created: 0,
+        tasks_completed: 0,
+        tasks_finished: 0,
+        average_lifetime_secs: None,
+        sessions: 0,
+        session_secs: 0,
+        runs: 0,
+        run_secs: 0,
+        cost: None,
+    };
+    let mut created_at: HashMap<&str, DateTime<Utc>> = HashMap::new();
+    let mut completed: HashSet<&str> = HashSet::new();
+    let mut lifetimes = Vec::new();
+    for event in events {
+        let week = by_week.get_mut(&week_of(event.at.with_timezone(&Local).date_naive()));
+        match event.kind {
+            EventKind::TaskCreated => {
+                stats.tasks_created += 1;
+                created_at.insert(&event.task, event.at);
+                // A new task may reuse the name of one that was completed
+                completed.remove(event.task.as_str());
+                if let Some(week) = week {
+                    week.created += 1;
+                }
+            }
+            EventKind::Merged | EventKind::PullRequestOpened if completed.insert(&event.task) => {
+                stats.tasks_completed += 1;
+                if let Some(week) = week {
+                    week.completed += 1;
+                }
+            }
+            EventKind::TaskRemoved | EventKind::TaskArchived => {
+                if let Some(created) = created_at.remove(event.task.as_str()) {
+                    stats.tasks_finished += 1;
+                    lifetimes.push((event.at - created).num_seconds().max(0) as u64);
+                }
+            }
+            EventKind::SessionEnded => {
+                stats.sessions += 1;
+                stats.session_secs += event.duration_secs.unwrap_or(0);
+            }
+            EventKind::RunFinished => {
+                stats.runs += 1;
+                stats.run_secs += event.duration_secs.unwrap_or(0);
+            }
+            _ => {}
+        }
+    }
+    if !lifetimes.is_empty() {
+        stats.average_lifetime_secs = Some(lifetimes.iter().sum::<u64>() / lifetimes.len() as u64);
+    }
+    stats.weeks = by_week.into_values().collect();
+    stats
+}
+
+/// The Monday starting the week `day` is in.
+fn week_of(day: NaiveDate) -> NaiveDate {
+    day - Days::new(day.weekday().num_days_from_monday().into())
+}
+
+/// What claude sessions whose directory is under one of `roots` cost, as
+/// ccusage reports it. `None` when ccusage is not installed or fails.
+pub fn ccusage_cost(roots: &[String]) -> Option<f64> {
+    let output = Command::new("ccusage")
+        .args(["session", "--json"])
+        .output()
+        .inspect_err(|e| info!("ccusage is not available: {}", e))
+        .ok()?;
+    if !output.status.success() {
+        warn!(
+            "ccusage failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    let report: Value = serde_json::from_slice(&output.stdout)
+        .inspect_err(|e| warn!("Could not read ccusage's output: {}", e))
+        .ok()?;
+    Some(sessions_cost(&report, roots))
+}
+
+/// Total cost of the sessions in a `ccusage session --json` report that ran
+/// under one of `roots`. ccusage names sessions after claude's project
+/// directories, which are the session's path with every other character
+/// than letters and digits replaced by `-`.
+fn sessions_cost(report: &Value, roots: &[String]) -> f64 {
+    report
+        .get("sessions")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|session| {
+            ["sessionId", "projectPath"].iter().any(|field| {
+                session
+                    .get(field)
+                    .and_then(Value::as_str)
+                    .is_some_and(|name| roots.iter().any(|root| is_under(name, root)))
+            })
+        })
+        .filter_map(|session| session.get("totalCost").and_then(Value::as_f64))
+        .sum()
+}
+
+/// Whether a session named `name`, by its path or its claude project
+/// directory, ran in `root` or a directory below it. `/app` does not cover
+/// `/app2`, nor `/tmp/app`.
+fn is_under(name: &str, root: &str) -> bool {
+    if name.starts_with('/') {
+        return Path::new(name).starts_with(root);
+    }
+    claude_project_name(name)
+        .strip_prefix(&claude_project_name(root))
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('-'))
+}
+
+fn claude_project_name(path: &str) -> String {
+    path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: EventKind, task: &str, at: &str, duration_secs: Option<u64>) -> Event {
+        let mut event = Event::new(kind, task, None);
+        event.at = DateTime::parse_from_rfc3339(at).unwrap().to_utc();
+        event.duration_secs = duration_secs;
+        event
+    }
+
+    #[test]
+    fn test_compute_stats() {
+        let events = vec![
+            event(
+                EventKind::TaskCreated,
+                "feat/a",
+                "2024-09-02T12:00:00Z",
+                None,
+            ),
+            event(
+                EventKind::SessionEnded,
+                "feat/a",
+                "2024-09-02T13:00:00Z",
+                Some(3_600),
+            ),
+            event(
+                EventKind::TaskCreated,
+                "feat/b",
+                "2024-09-04T12:00:00Z",
+                None,
+            ),
+            event(
+                EventKind::RunFinished,
+                "feat/b",
+                "2024-09-04T12:10:00Z",
+                Some(600),
+            ),
+            event(
+                EventKind::PullRequestOpened,
+                "feat/a",
+                "2024-09-10T12:00:00Z",
+                None,
+            ),
+            event(EventKind::Merged, "feat/a", "2024-09-11T12:00:00Z", None),
+            event(
+                EventKind::TaskRemoved,
+                "feat/a",
+                "2024-09-12T12:00:00Z",
+                None,
+            ),
+        ];
+        let today = NaiveDate::from_ymd_opt(2024, 9, 12).unwrap();
+        let stats = compute_stats(&events, 3, today);
+
+        assert_eq!(
+            stats
+                .weeks
+                .iter()
+                .map(|w| w.week.to_string())
+                .collect::<Vec<_>>(),
+            ["2024-08-26", "2024-09-02", "2024-09-09"]
+        );
+        assert_eq!(stats.weeks[1].created, 2);
+        assert_eq!(stats.weeks[2].completed, 1);
+        assert_eq!(stats.tasks_created, 2);
+        assert_eq!(stats.tasks_completed, 1);
+        assert_eq!(stats.tasks_finished, 1);
+        assert_eq!(stats.average_lifetime_secs, Some(10 * 86_400));
+        assert_eq!((stats.sessions, stats.session_secs), (1, 3_600));
+        assert_eq!((stats.runs, stats.run_secs), (1, 600));
+    }
+
+    #[test]
+    fn test_sessions_cost_matches_project_directories() {
+        let report: Value = serde_json::from_str(
+            r#"{ "sessions": [
+                { "sessionId": "-home-ana--config-claudectl-projects-app-feat-login", "totalCost": 1.5 },
+                { "sessionId": "-home-ana-other", "totalCost": 4.0 },
+                { "projectPath": "-home-ana--config-claudectl-projects-app-fix-crash", "totalCost": 0.25 }
+            ] }"#,
+        )
+        .unwrap();
+        let roots = ["/home/ana/.config/claudectl/projects/app".to_string()];
+        assert_eq!(sessions_cost(&report, &roots), 1.75);
+
+        let others: Value = serde_json::from_str(
+            r#"{ "sessions": [
+                { "sessionId": "-home-ana--config-claudectl-projects-app2", "totalCost": 1.0 },
+                { "sessionId": "-tmp-home-ana--config-claudectl-projects-app", "totalCost": 2.0 },
+                { "projectPath": "/home/ana/.config/claudectl/projects/app/feat/x", "totalCost": 0.5 },
+                { "projectPath": "/home/ana/.config/claudectl/projects/apps", "totalCost": 8.0 }
+            ] }"#,
+        )
+        .unwrap();
+        assert_eq!(sessions_cost(&others, &roots), 0.5);
+        assert_eq!(sessions_cost(&Value::Null, &roots), 0.0);
+    }
+}
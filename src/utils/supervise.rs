@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::utils::errors::CommandError;
+use crate::utils::events::{EventKind, record_event};
+use crate::utils::git::find_worktree;
+use crate::utils::hooks::{Hook, HookContext, run_hook};
+use crate::utils::lock::StaleLock;
+use crate::utils::tasks::load_task;
+
+/// What `watch --supervise` does about sessions that crash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Supervision {
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// Restarts given to a task before its crashes are only recorded
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    /// Seconds to wait before the first restart; each later one waits
+    /// twice as long as the one before
+    #[serde(default = "default_backoff_secs")]
+    pub backoff_secs: u64,
+    /// What the restarted session is asked to do
+    #[serde(default = "default_restart_prompt")]
+    pub restart_prompt: String,
+}
+
+impl Default for Supervision {
+    fn default() -> Self {
+        Self {
+            restart: RestartPolicy::default(),
+            max_restarts: default_max_restarts(),
+            backoff_secs: default_backoff_secs(),
+            restart_prompt: default_restart_prompt(),
+        }
+    }
+}
+
+fn default_max_restarts() -> u32 {
+    3
+}
+
+fn default_backoff_secs() -> u64 {
+    5
+}
+
+fn default_restart_prompt() -> String {
+    "The previous session in this worktree stopped unexpectedly. Review the changes made so far and carry on with the task.".to_string()
+}
+
+/// The longest wait before a restart, however many came before.
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Mark crashed sessions stopped
+    #[default]
+    Never,
+    /// Run claude again in the task of a crashed session
+    #[serde(alias = "on-failure")]
+    OnCrash,
+    /// Also run claude again when a restarted session finishes cleanly
+    Always,
+}
+
+/// What became of a session that ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// It will be restarted once the backoff has passed
+    Restarting(Duration),
+    Stopped,
+    /// It finished cleanly and is left alone
+    Finished,
+}
+
+/// Tracks the sessions `watch --supervise` has restarted.
+pub struct Supervisor {
+    policy: Supervision,
+    /// Restarts so far, by task
+    restarts: BTreeMap<String, u32>,
+    /// Tasks to restart, and when
+    pending: Vec<(String, Instant)>,
+    /// Restarted runs, with when each was started
+    running: Vec<(String, Child, DateTime<Utc>)>,
+}
+
+impl Supervisor {
+    pub fn new(policy: Supervision) -> Self {
+        Self {
+            policy,
+            restarts: BTreeMap::new(),
+            pending: Vec::new(),
+            running: Vec::new(),
+        }
+    }
+
+    /// Clear a crashed session's lock and run the project's
+    /// `on-session-exit` hook, then schedule a restart if the policy allows.
+    pub fn handle_crash(&mut self, session: &StaleLock) -> Result<Outcome, CommandError> {
+        session.clear()?;
+        let worktree = find_worktree(&session.task_name)?
+            .map(|worktree| worktree.path)
+            .unwrap_or_default();
+        run_hook(
+            Hook::OnSessionExit,
+            &HookContext {
+                task_name: &session.task_name,
+                worktree: &worktree,
+                session_id: Some(session.lock.session_id()),
+                status: Some("crashed"),
+                exit_code: None,
+            },
+        )?;
+        self.handle_exit(&session.task_name, false, &session.lock.holder())
+    }
+
+    /// Apply the policy to a session in `task_name` that has ended, e.g. one
+    /// reported by [`Supervisor::reap`]. `cause` describes it in the task's
+    /// history.
+    pub fn handle_exit(
+        &mut self,
+        task_name: &str,
+        succeeded: bool,
+        cause: &str,
+    ) -> Result<Outcome, CommandError> {
+        let restarts = self.restarts.get(task_name).copied().unwrap_or(0);
+        if !should_restart(&self.policy, restarts, succeeded) || find_worktree(task_name)?.is_none()
+        {
+            if succeeded {
+                return Ok(Outcome::Finished);
+            }
+            record_event(
+                EventKind::SessionStopped,
+                task_name,
+                Some(cause.to_string()),
+            );
+            return Ok(Outcome::Stopped);
+        }
+
+        let delay = backoff(&self.policy, restarts);
+        info!("Restarting '{}' in {:?}", task_name, delay);
+        self.pending
+            .push((task_name.to_string(), Instant::now() + delay));
+        Ok(Outcome::Restarting(delay))
+    }
+
+    /// Start the restarts whose backoff has passed, returning each task and
+    /// its restart number or why it could not be restarted.
+    pub fn start_due(&mut self) -> Vec<(String, Result<u32, CommandError>)> {
+        let now = Instant::now();
+        let (due, waiting) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|(_, at)| *at <= now);
+        self.pending = waiting;
+
+        due.into_iter()
+            .map(|(task_name, _)| {
+                let started = spawn_run(&task_name, &self.policy.restart_prompt).map(|child| {
+                    let restart = self.restarts.get(&task_name).copied().unwrap_or(0) + 1;
+                    self.restarts.insert(task_name.clone(), restart);
+                    record_event(
+                        EventKind::SessionRestarted,
+                        &task_name,
+                        Some(format!("restart {restart} of {}", self.policy.max_restarts)),
+                    );
+                    info!("Restarted session in '{}' as pid {}", task_name, child.id());
+                    self.running.push((task_name.clone(), child, Utc::now()));
+                    restart
+                });
+                (task_name, started)
+            })
+            .collect()
+    }
+
+    /// Tasks whose restarted sessions have exited since the last call, and
+    /// whether each succeeded. Runs that `kill` stopped are left out, so
+    /// they are not restarted.
+    pub fn reap(&mut self) -> Vec<(String, bool)> {
+        let mut finished = Vec::new();
+        self.running
+            .retain_mut(|(task_name, child, started_at)| match child.try_wait() {
+                Ok(Some(_)) if was_stopped(task_name, *started_at) => {
+                    info!("Restarted session in '{}' was stopped", task_name);
+                    false
+                }
+                Ok(Some(status)) => {
+                    finished.push((task_name.clone(), status.success()));
+                    false
+                }
+                Ok(None) => true,
+                Err(e) => {
+                    warn!("Failed to check on session in '{}': {}", task_name, e);
+                    false
+                }
+            });
+        finished
+    }
+}
+
+/// Whether the task's last session ended since `since` and was stopped on
+/// purpose.
+fn was_stopped(task_name: &str, since: DateTime<Utc>) -> bool {
+    load_task(task_name)
+        .ok()
+        .and_then(|record| record.last_session)
+        .is_some_and(|session| session.stopped && session.ended_at >= since)
+}
+
+fn should_restart(policy: &Supervision, restarts: u32, succeeded: bool) -> bool {
+    let wanted = match policy.restart {
+        RestartPolicy::Never => false,
+        RestartPolicy::OnCrash => !succeeded,
+        RestartPolicy::Always => true,
+    };
+    wanted && restarts < policy.max_restarts
+}
+
+/// How long to wait before restarting a task that has been restarted
+/// `restarts` times already.
+fn backoff(policy: &Supervision, restarts: u32) -> Duration {
+    let factor = 1u64 << restarts.min(16);
+    Duration::from_secs(policy.backoff_secs.saturating_mul(factor)).min(MAX_BACKOFF)
+}
+
+/// Run claude in the task from a separate `claudectl run`, which holds the
+/// task's lock and keeps the run's record like any other.
+fn spawn_run(task_name: &str, prompt: &str) -> Result<Child, CommandError> {
+    let exe = std::env::current_exe()
+        .map_err(|e| CommandError::new(&format!("Failed to find claudectl itself: {e}")))?;
+    Command::new(exe)
+        .args(["run", task_name, "--", prompt])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            CommandError::new(&format!(
+                "Failed to restart the session in '{task_name}': {e}"
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restarts_follow_the_policy() {
+        let mut policy: Supervision =
+            serde_json::from_str(r#"{ "restart": "on-crash", "max_restarts": 2 }"#).unwrap();
+        assert!(should_restart(&policy, 0, false));
+        assert!(should_restart(&policy, 1, false));
+        assert!(!should_restart(&policy, 2, false));
+        assert!(!should_restart(&policy, 0, true));
+
+        policy.restart = RestartPolicy::Always;
+        assert!(should_restart(&policy, 0, true));
+        assert!(!should_restart(&policy, 2, true));
+
+        policy.restart = RestartPolicy::Never;
+        assert!(!should_restart(&policy, 0, false));
+        assert_eq!(Supervision::default().max_restarts, 3);
+
+        let alias: Supervision = serde_json::from_str(r#"{ "restart": "on-failure" }"#).unwrap();
+        assert_eq!(alias.restart, RestartPolicy::OnCrash);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_a_limit() {
+        let policy = Supervision::default();
+        assert_eq!(backoff(&policy, 0), Duration::from_secs(5));
+        assert_eq!(backoff(&policy, 1), Duration::from_secs(10));
+        assert_eq!(backoff(&policy, 3), Duration::from_secs(40));
+        assert_eq!(backoff(&policy, 30), MAX_BACKOFF);
+    }
+}
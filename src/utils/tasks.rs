@@ -0,0 +1,630 @@
+use crate::utils::config::Config;
+use crate::utils::errors::{ClaudeError, CommandError, ConfigError};
+use crate::utils::fs::{
+    read_local_config_file, read_task_file, read_task_file_in, read_task_files, read_task_files_in,
+    write_task_file,
+};
+use crate::utils::git::{
+    DEFAULT_BASE, Worktree, changed_files, find_worktree, is_merged, local_branch, worktree_list,
+};
+use crate::utils::output::{is_interactive, pick};
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+type TaskResult<T> = Result<T, ConfigError>;
+
+/// Metadata claudectl keeps about a task alongside its worktree.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TaskRecord {
+    pub name: String,
+    /// The name as it was typed, when it had to be slugified into `name`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// The ref the task's branch was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// The commit the task's branch was created at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cloned_from: Option<String>,
+    /// URL of the issue the task was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Set explicitly at creation; otherwise inferred from the branch name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<TaskKind>,
+    /// Profile the task's sessions use instead of the project's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<Summary>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<Note>,
+    /// Prompts claudectl has sent to the task's sessions, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompts: Vec<PromptRecord>,
+    /// Non-interactive runs started with `claudectl run`, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub runs: Vec<RunRecord>,
+    /// Set while the project's setup commands have not succeeded in the
+    /// task's worktree
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setup_failure: Option<SetupFailure>,
+    /// How the task's most recent interactive session ended
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_session: Option<SessionRecord>,
+}
+
+/// What sort of work a task is.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskKind {
+    /// New functionality (feat/, feature/)
+    Feature,
+    /// A bug fix (fix/, bugfix/, hotfix/)
+    Bugfix,
+    /// Maintenance such as dependency bumps or refactors (chore/, refactor/)
+    Chore,
+    /// Exploratory work that may be thrown away (exp/, experiment/, spike/)
+    Experiment,
+}
+
+impl TaskKind {
+    /// Infer the kind from a conventional branch prefix such as `feat/`.
+    pub fn from_branch(branch_name: &str) -> Option<Self> {
+        let (prefix, _) = branch_name.split_once('/')?;
+        match prefix.to_lowercase().as_str() {
+            "feat" | "feature" => Some(Self::Feature),
+            "fix" | "bugfix" | "hotfix" => Some(Self::Bugfix),
+            "chore" | "refactor" => Some(Self::Chore),
+            "exp" | "experiment" | "spike" => Some(Self::Experiment),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Feature => "feature",
+            Self::Bugfix => "bugfix",
+            Self::Chore => "chore",
+            Self::Experiment => "experiment",
+        }
+    }
+
+    /// The branch prefix used when naming a task of this kind.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::Feature => "feat",
+            Self::Bugfix => "fix",
+            Self::Chore => "chore",
+            Self::Experiment => "exp",
+        }
+    }
+
+    /// Prefix `task_name` with this kind's branch prefix unless it already
+    /// has a prefix of its own.
+    pub fn branch_name(&self, task_name: &str) -> String {
+        if task_name.contains('/') {
+            task_name.to_string()
+        } else {
+            format!("{}/{task_name}", self.prefix())
+        }
+    }
+}
+
+/// A free-form, timestamped note attached to a task.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Note {
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The setup command that failed in a task's worktree, and how.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SetupFailure {
+    pub command: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for SetupFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Setup command '{}' failed: {}",
+            self.command, self.reason
+        )
+    }
+}
+
+/// A prompt sent to one of a task's sessions.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptRecord {
+    pub text: String,
+    /// The command that sent it, e.g. `resolve`
+    pub source: String,
+    pub sent_at: DateTime<Utc>,
+}
+
+/// The most of a run's output kept with the task, in bytes. Longer output
+/// keeps its end, where claude sums up what it did.
+pub const RUN_OUTPUT_LIMIT: usize = 64 * 1024;
+
+/// The end of `output`, at most `limit` bytes long and starting on a new
+/// line where one is in reach.
+pub fn output_tail(output: &str, limit: usize) -> &str {
+    if output.len() <= limit {
+        return output;
+    }
+    let mut start = output.len() - limit;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    let tail = &output[start..];
+    match tail.find('\n') {
+        Some(index) if index + 1 < tail.len() => &tail[index + 1..],
+        _ => tail,
+    }
+}
+
+/// A non-interactive claude run in a task's worktree.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunRecord {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// `None` when claude was killed by a signal
+    pub exit_code: Option<i32>,
+    /// What claude printed, with secrets redacted; at most
+    /// [`RUN_OUTPUT_LIMIT`] bytes
+    pub output: String,
+    /// Whether `output` holds only the end of what claude printed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub output_truncated: bool,
+    /// What the agent reported the run cost, in US dollars
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl RunRecord {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+
+    /// How the run ended, e.g. `exited with code 1`.
+    pub fn status(&self) -> String {
+        match self.exit_code {
+            Some(code) => format!("exited with code {code}"),
+            None => "was killed by a signal".to_string(),
+        }
+    }
+}
+
+/// How an interactive session in a task's worktree ended.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SessionRecord {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// `None` when the agent was killed by a signal or never started
+    pub exit_code: Option<i32>,
+    /// Why the agent could not be started
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Set when `kill` or `restart` ended the session on purpose
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub stopped: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl SessionRecord {
+    /// A session started at `started_at` that has just ended with
+    /// `result`: the agent's exit code, or why it could not be started.
+    pub fn ended(started_at: DateTime<Utc>, result: &Result<Option<i32>, ClaudeError>) -> Self {
+        let (exit_code, error) = match result {
+            Ok(code) => (*code, None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        Self {
+            started_at,
+            ended_at: Utc::now(),
+            exit_code,
+            error,
+            stopped: false,
+        }
+    }
+
+    /// Whether the session failed rather than being ended by the user.
+    pub fn crashed(&self) -> bool {
+        !self.stopped && self.exit_code != Some(0)
+    }
+
+    /// `succeeded`, `crashed` or `stopped`, as hooks are told.
+    pub fn outcome(&self) -> &'static str {
+        if self.stopped {
+            "stopped"
+        } else if self.crashed() {
+            "crashed"
+        } else {
+            "succeeded"
+        }
+    }
+
+    /// How the session ended, e.g. `exited with code 1`.
+    pub fn status(&self) -> String {
+        match (&self.error, self.exit_code) {
+            _ if self.stopped => "was stopped".to_string(),
+            (Some(error), _) => format!("failed to start: {error}"),
+            (None, Some(code)) => format!("exited with code {code}"),
+            (None, None) => "was killed by a signal".to_string(),
+        }
+    }
+
+    /// The error a crashed session in `task_name` is reported with.
+    pub fn failure(&self, task_name: &str) -> CommandError {
+        CommandError::new(&format!("claude {} in '{task_name}'", self.status()))
+    }
+}
+
+/// A generated description of the work done in a task.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Summary {
+    pub text: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl TaskRecord {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn from_str(json_str: &str) -> TaskResult<Self> {
+        serde_json::from_str(json_str)
+            .map_err(|e| ConfigError::new(&format!("Failed to parse task JSON: {e}")))
+    }
+
+    pub fn to_string(&self) -> TaskResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| ConfigError::serialize_failed(&format!("JSON serialization error: {e}")))
+    }
+
+    pub fn set_summary(&mut self, text: &str) {
+        self.summary = Some(Summary {
+            text: text.trim().to_string(),
+            generated_at: Utc::now(),
+        });
+    }
+
+    /// The task's kind, falling back to what its branch name implies.
+    pub fn effective_kind(&self) -> Option<TaskKind> {
+        self.kind.or_else(|| TaskKind::from_branch(&self.name))
+    }
+
+    /// The ref the task's branch was created from; tasks created before
+    /// this was recorded started from the default.
+    pub fn base(&self) -> &str {
+        self.base.as_deref().unwrap_or(DEFAULT_BASE)
+    }
+
+    /// Copy the settings a cloned task inherits from `source`.
+    ///
+    /// Work products such as summaries and notes stay with the original.
+    pub fn inherit_from(&mut self, source: &TaskRecord) {
+        self.base = source.base.clone();
+        self.tags = source.tags.clone();
+        self.kind = source.kind;
+        self.profile = source.profile.clone();
+        self.cloned_from = Some(source.name.clone());
+    }
+
+    /// Record a prompt as it was given, before the project's preamble and
+    /// context are added.
+    pub fn add_prompt(&mut self, text: &str, source: &str) {
+        self.prompts.push(PromptRecord {
+            text: text.to_string(),
+            source: source.to_string(),
+            sent_at: Utc::now(),
+        });
+    }
+
+    pub fn add_note(&mut self, text: &str) {
+        self.notes.push(Note {
+            text: text.trim().to_string(),
+            created_at: Utc::now(),
+        });
+    }
+}
+
+/// Load a task's record, starting a fresh one if none has been saved.
+pub fn load_task(task_name: &str) -> Result<TaskRecord, CommandError> {
+    match read_task_file(task_name)? {
+        Some(raw) => Ok(TaskRecord::from_str(&raw)?),
+        None => Ok(TaskRecord::new(task_name)),
+    }
+}
+
+/// Like [`load_task`], for the project whose repository is at `repo_root`.
+pub fn load_task_in(repo_root: &Path, task_name: &str) -> Result<TaskRecord, CommandError> {
+    match read_task_file_in(repo_root, task_name)? {
+        Some(raw) => Ok(TaskRecord::from_str(&raw)?),
+        None => Ok(TaskRecord::new(task_name)),
+    }
+}
+
+/// Every task record stored for the current project, whether or not its
+/// worktree still exists.
+pub fn stored_tasks() -> Result<Vec<TaskRecord>, CommandError> {
+    read_task_files()?
+        .iter()
+        .map(|raw| Ok(TaskRecord::from_str(raw)?))
+        .collect()
+}
+
+/// Like [`stored_tasks`], for the project whose repository is at `repo_root`.
+pub fn stored_tasks_in(repo_root: &Path) -> Result<Vec<TaskRecord>, CommandError> {
+    read_task_files_in(repo_root)?
+        .iter()
+        .map(|raw| Ok(TaskRecord::from_str(raw)?))
+        .collect()
+}
+
+/// The worktrees of the current project's tasks, without the main
+/// checkout. The sub-projects of a repository share its worktrees, so only
+/// those in the project directory, or with a task record here, are listed.
+pub fn project_worktrees() -> Result<Vec<Worktree>, CommandError> {
+    let config = Config::from_str(&read_local_config_file()?)?;
+    let mut worktrees = Vec::new();
+    // The main checkout is listed first and is not a task
+    for worktree in worktree_list()?.into_iter().skip(1) {
+        let ours = Path::new(&worktree.path).starts_with(&config.project_dir)
+            || match &worktree.branch {
+                Some(branch) => read_task_file(branch)?.is_some(),
+                None => false,
+            };
+        if ours {
+            worktrees.push(worktree);
+        }
+    }
+    Ok(worktrees)
+}
+
+/// The names of the current project's tasks, as [`project_worktrees`]
+/// finds them.
+pub fn project_task_names() -> Result<Vec<String>, CommandError> {
+    Ok(project_worktrees()?
+        .into_iter()
+        .filter_map(|worktree| worktree.branch)
+        .collect())
+}
+
+/// Use the given task name, or ask the user to pick a task when it was
+/// omitted and a terminal is attached.
+pub fn resolve_task_name(task_name: Option<&str>) -> Result<String, CommandError> {
+    if let Some(name) = task_name {
+        return Ok(name.to_string());
+    }
+    if !is_interactive() {
+        return Err(CommandError::new(
+            "No task name given. Pass one when not running in a terminal",
+        ));
+    }
+
+    let mut names = project_task_names()?;
+    if names.is_empty() {
+        return Err(CommandError::new("No tasks to choose from"));
+    }
+    let selection = pick("Select a task", &names)
+        .map_err(|e| CommandError::new(&format!("Failed to read input: {e}")))?
+        .ok_or_else(|| CommandError::new("No task selected"))?;
+    Ok(names.swap_remove(selection))
+}
+
+/// The worktree of an existing task.
+pub fn task_worktree(task_name: &str) -> Result<Worktree, CommandError> {
+    find_worktree(task_name)
+        .map_err(|e| CommandError::new(&format!("Failed to get tasks: {e}")))?
+        .ok_or_else(|| CommandError::new(&format!("Task '{task_name}' not found")))
+}
+
+/// The local branch a task's work lands in, e.g. `main` for `origin/main`.
+pub fn target_branch(record: &TaskRecord) -> String {
+    local_branch(record.base()).to_string()
+}
+
+/// Whether everything done in a task has landed in its target branch: the
+/// branch is merged and its worktree has no uncommitted changes.
+pub fn is_task_merged(record: &TaskRecord, worktree_path: &str) -> Result<bool, CommandError> {
+    // A base that no longer exists can't say whether the task is done
+    Ok(is_merged(
+        &record.name,
+        &target_branch(record),
+        record.base_commit.as_deref(),
+    )
+    .unwrap_or(false)
+        && changed_files(worktree_path)?.is_empty())
+}
+
+pub fn save_task(record: &TaskRecord) -> Result<(), CommandError> {
+    write_task_file(&record.name, record.to_string()?)?;
+    Ok(())
+}
+
+/// Note that the task's session, which started at `started_at`, was stopped
+/// on purpose. A session killed before it could record how it ended gets a
+/// record here.
+pub fn mark_session_stopped(
+    task_name: &str,
+    started_at: DateTime<Utc>,
+) -> Result<(), CommandError> {
+    let mut record = load_task(task_name)?;
+    match &mut record.last_session {
+        Some(session) if session.started_at >= started_at => session.stopped = true,
+        last_session => {
+            *last_session = Some(SessionRecord {
+                started_at,
+                ended_at: Utc::now(),
+                exit_code: None,
+                error: None,
+                stopped: true,
+            });
+        }
+    }
+    save_task(&record)
+}
+
+/// File name used to store a task's record; branch separators are flattened.
+pub fn task_file_name(task_name: &str) -> String {
+    format!("{}.json", task_name.replace('/', "__"))
+}
+
+/// The task a file named by [`task_file_name`] belongs to.
+pub fn task_name_from_file(file_name: &str) -> String {
+    file_name
+        .strip_suffix(".json")
+        .unwrap_or(file_name)
+        .replace("__", "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_record_round_trip() {
+        let mut record = TaskRecord::new("feat/summary");
+        record.set_summary("  Adds a summary.\n");
+
+        let json = record.to_string().unwrap();
+        let parsed = TaskRecord::from_str(&json).unwrap();
+        assert_eq!(parsed.name, "feat/summary");
+        assert_eq!(parsed.summary.unwrap().text, "Adds a summary.");
+    }
+
+    #[test]
+    fn test_task_record_from_minimal_json() {
+        let record = TaskRecord::from_str(r#"{ "name": "feat/x" }"#).unwrap();
+        assert_eq!(record.name, "feat/x");
+        assert!(record.summary.is_none());
+        assert!(record.notes.is_empty());
+    }
+
+    #[test]
+    fn test_task_record_notes_keep_order() {
+        let mut record = TaskRecord::new("feat/notes");
+        record.add_note("waiting on design input");
+        record.add_note(" unblocked \n");
+
+        let parsed = TaskRecord::from_str(&record.to_string().unwrap()).unwrap();
+        let notes: Vec<&str> = parsed.notes.iter().map(|n| n.text.as_str()).collect();
+        assert_eq!(notes, vec!["waiting on design input", "unblocked"]);
+    }
+
+    #[test]
+    fn test_inherit_from_copies_settings_only() {
+        let mut source = TaskRecord::new("feat/original");
+        source.base = Some("origin/develop".to_string());
+        source.tags = vec!["auth".to_string()];
+        source.add_note("tried approach A");
+
+        let mut clone = TaskRecord::new("feat/retry");
+        clone.inherit_from(&source);
+        assert_eq!(clone.base.as_deref(), Some("origin/develop"));
+        assert_eq!(clone.cloned_from.as_deref(), Some("feat/original"));
+        assert_eq!(clone.tags, vec!["auth"]);
+        assert!(clone.notes.is_empty());
+    }
+
+    #[test]
+    fn test_task_record_prompts_round_trip() {
+        let mut record = TaskRecord::new("feat/history");
+        assert!(!record.to_string().unwrap().contains("\"prompts\""));
+        record.add_prompt("fix the bug\nthen add a test", "task --start");
+
+        let parsed = TaskRecord::from_str(&record.to_string().unwrap()).unwrap();
+        assert_eq!(parsed.prompts.len(), 1);
+        assert_eq!(parsed.prompts[0].text, "fix the bug\nthen add a test");
+        assert_eq!(parsed.prompts[0].source, "task --start");
+    }
+
+    #[test]
+    fn test_output_tail_keeps_the_end_from_a_line_start() {
+        assert_eq!(output_tail("short\n", 64), "short\n");
+        assert_eq!(output_tail("first\nsecond\nthird\n", 10), "third\n");
+        assert_eq!(output_tail("one long line", 4), "line");
+        // Never splits a character
+        assert_eq!(output_tail("héé", 3), "é");
+    }
+
+    #[test]
+    fn test_session_record_tells_crashes_from_clean_exits() {
+        let started_at = Utc::now();
+        let clean = SessionRecord::ended(started_at, &Ok(Some(0)));
+        assert!(!clean.crashed());
+
+        let mut failed = SessionRecord::ended(started_at, &Ok(Some(2)));
+        assert!(failed.crashed());
+        assert_eq!(failed.status(), "exited with code 2");
+        failed.stopped = true;
+        assert!(!failed.crashed());
+
+        let signalled = SessionRecord::ended(started_at, &Ok(None));
+        assert_eq!(signalled.status(), "was killed by a signal");
+        let missing = SessionRecord::ended(started_at, &Err(ClaudeError::new("no claude")));
+        assert!(missing.crashed());
+        assert!(missing.status().starts_with("failed to start: "));
+
+        let json = serde_json::to_string(&clean).unwrap();
+        assert!(!json.contains("stopped") && !json.contains("error"));
+    }
+
+    #[test]
+    fn test_task_file_name_flattens_branch_separators() {
+        assert_eq!(task_file_name("feat/new-thing"), "feat__new-thing.json");
+        assert_eq!(task_file_name("simple"), "simple.json");
+    }
+
+    #[test]
+    fn test_task_kind_from_branch() {
+        assert_eq!(TaskKind::from_branch("feat/login"), Some(TaskKind::Feature));
+        assert_eq!(
+            TaskKind::from_branch("Hotfix/crash"),
+            Some(TaskKind::Bugfix)
+        );
+        assert_eq!(
+            TaskKind::from_branch("spike/idea"),
+            Some(TaskKind::Experiment)
+        );
+        assert_eq!(TaskKind::from_branch("docs/readme"), None);
+        assert_eq!(TaskKind::from_branch("login"), None);
+    }
+
+    #[test]
+    fn test_task_kind_branch_name() {
+        assert_eq!(
+            TaskKind::Bugfix.branch_name("login-crash"),
+            "fix/login-crash"
+        );
+        assert_eq!(
+            TaskKind::Bugfix.branch_name("team/login-crash"),
+            "team/login-crash"
+        );
+    }
+
+    #[test]
+    fn test_effective_kind_prefers_explicit_kind() {
+        let mut record = TaskRecord::new("feat/tune-cache");
+        assert_eq!(record.effective_kind(), Some(TaskKind::Feature));
+        record.kind = Some(TaskKind::Experiment);
+        assert_eq!(record.effective_kind(), Some(TaskKind::Experiment));
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::utils::config::Config;
+use crate::utils::errors::{CommandError, ConfigError, FileSystemError};
+use crate::utils::fs::{CLAUDE_FILES, config_dir};
+use crate::utils::hooks::is_lifecycle_hook;
+
+/// Directory, in a project's `.claudectl/` or the global config directory,
+/// that holds one directory per template.
+const TEMPLATES_DIR: &str = "templates";
+
+/// Project config settings a template sets, merged over the project's.
+const TEMPLATE_CONFIG: &str = "config.json";
+
+/// Scripts a template adds; they run in each new worktree like setup
+/// commands.
+const HOOKS_DIR: &str = "hooks";
+
+/// Settings that belong to one project and that templates cannot set.
+const PROJECT_KEYS: [&str; 3] = ["project_name", "project_dir", "subproject"];
+
+/// A reusable starting point for a project: config settings, Claude
+/// instructions and worktree hook scripts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+/// What applying a template did.
+#[derive(Debug)]
+pub struct Applied {
+    pub config: Config,
+    /// Files added to the project, relative to its directory
+    pub files: Vec<String>,
+}
+
+/// Templates available to the project in `project_dir` by name. The
+/// project's own, in `.claudectl/templates/`, win over global ones of the
+/// same name.
+pub fn available_templates(project_dir: &Path) -> BTreeMap<String, Template> {
+    let mut roots = vec![project_dir.join(".claudectl").join(TEMPLATES_DIR)];
+    if let Ok(dir) = config_dir() {
+        roots.push(dir.join(TEMPLATES_DIR));
+    }
+
+    let mut templates = BTreeMap::new();
+    for root in roots {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if dir.is_dir() {
+                templates
+                    .entry(name.clone())
+                    .or_insert(Template { name, dir });
+            }
+        }
+    }
+    templates
+}
+
+/// The template called `name`, or an error listing the ones there are.
+pub fn find_template(project_dir: &Path, name: &str) -> Result<Template, CommandError> {
+    let mut templates = available_templates(project_dir);
+    templates.remove(name).ok_or_else(|| {
+        let known = if templates.is_empty() {
+            "none are installed".to_string()
+        } else {
+            format!(
+                "use one of {}",
+                templates.keys().cloned().collect::<Vec<_>>().join(", ")
+            )
+        };
+        CommandError::new(&format!(
+            "Unknown template '{name}'; {known}. Templates are directories in .claudectl/templates/ or ~/.config/claudectl/templates/"
+        ))
+    })
+}
+
+impl Template {
+    /// Apply the template to the project in `project_dir`: merge its
+    /// settings over `config`, add the Claude instruction files the project
+    /// lacks, and copy its hook scripts into `.claudectl/hooks/`. New
+    /// worktrees run them as setup commands, except lifecycle hooks such as
+    /// `pre-session`, which run at their own time.
+    ///
+    /// Files the project already has are left alone.
+    pub fn apply(&self, config: &Config, project_dir: &Path) -> Result<Applied, CommandError> {
+        let mut config = self.merge_config(config)?;
+
+        let mut files = Vec::new();
+        for name in CLAUDE_FILES {
+            let source = self.dir.join(name);
+            if source.exists() {
+                copy_missing(&source, &project_dir.join(name), name, &mut files)?;
+            }
+        }
+
+        for hook in self.hooks()? {
+            let relative = format!(".claudectl/{HOOKS_DIR}/{hook}");
+            copy_missing(
+                &self.dir.join(HOOKS_DIR).join(&hook),
+                &project_dir.join(&relative),
+                &relative,
+                &mut files,
+            )?;
+            make_executable(&project_dir.join(&relative))?;
+            // Lifecycle hooks run at their own time, not as setup
+            if is_lifecycle_hook(&hook) {
+                continue;
+            }
+            let command = format!("\"$CLAUDECTL_MAIN_CHECKOUT/{relative}\"");
+            if !config.setup_commands.contains(&command) {
+                config.setup_commands.push(command);
+            }
+        }
+        Ok(Applied { config, files })
+    }
+
+    fn merge_config(&self, config: &Config) -> Result<Config, CommandError> {
+        let path = self.dir.join(TEMPLATE_CONFIG);
+        if !path.exists() {
+            return Ok(config.clone());
+        }
+        let raw = std::fs::read_to_string(&path).map_err(|e| {
+            FileSystemError::read_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+        })?;
+        let mut settings: Value = serde_json::from_str(&raw).map_err(|e| {
+            ConfigError::invalid(&format!(
+                "Template '{}' has an invalid {TEMPLATE_CONFIG}: {e}",
+                self.name
+            ))
+        })?;
+        if let Value::Object(settings) = &mut settings {
+            for key in PROJECT_KEYS {
+                settings.remove(key);
+            }
+        }
+
+        let mut merged = serde_json::to_value(config)
+            .map_err(|e| ConfigError::new(&format!("Failed to serialize config: {e}")))?;
+        merge(&mut merged, settings);
+        serde_json::from_value(merged).map_err(|e| {
+            ConfigError::invalid(&format!(
+                "Template '{}' has an invalid setting: {e}",
+                self.name
+            ))
+            .into()
+        })
+    }
+
+    /// Names of the template's hook scripts, in order.
+    fn hooks(&self) -> Result<Vec<String>, CommandError> {
+        let dir = self.dir.join(HOOKS_DIR);
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let entries = std::fs::read_dir(&dir).map_err(|e| {
+            FileSystemError::read_failed(&format!("IO error: {e}"), &dir.to_string_lossy())
+        })?;
+        let mut hooks: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+            .collect();
+        hooks.sort();
+        Ok(hooks)
+    }
+}
+
+/// Set everything in `over` on `base`, keeping what `over` does not
+/// mention in objects.
+fn merge(base: &mut Value, over: Value) {
+    match (base, over) {
+        (Value::Object(base), Value::Object(over)) => {
+            for (key, value) in over {
+                merge(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, over) => *base = over,
+    }
+}
+
+/// Copy `source` to `dest` unless it exists, recording each file created
+/// by its path relative to the project.
+fn copy_missing(
+    source: &Path,
+    dest: &Path,
+    relative: &str,
+    created: &mut Vec<String>,
+) -> Result<(), CommandError> {
+    if source.is_dir() {
+        std::fs::create_dir_all(dest).map_err(|e| {
+            FileSystemError::write_failed(&format!("IO error: {e}"), &dest.to_string_lossy())
+        })?;
+        let entries = std::fs::read_dir(source).map_err(|e| {
+            FileSystemError::read_failed(&format!("IO error: {e}"), &source.to_string_lossy())
+        })?;
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+            .collect();
+        names.sort();
+        for name in names {
+            copy_missing(
+                &source.join(&name),
+                &dest.join(&name),
+                &format!("{relative}/{name}"),
+                created,
+            )?;
+        }
+        return Ok(());
+    }
+
+    if dest.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            FileSystemError::write_failed(&format!("IO error: {e}"), &parent.to_string_lossy())
+        })?;
+    }
+    std::fs::copy(source, dest).map_err(|e| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &dest.to_string_lossy())
+    })?;
+    created.push(relative.to_string());
+    Ok(())
+}
+
+fn make_executable(path: &Path) -> Result<(), CommandError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).map_err(|e| {
+            FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+        })?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn template(temp_dir: &TempDir, config: &str) -> Template {
+        let dir = temp_dir.path().join("templates/web");
+        std::fs::create_dir_all(dir.join("hooks")).unwrap();
+        std::fs::write(dir.join(TEMPLATE_CONFIG), config).unwrap();
+        std::fs::write(dir.join("CLAUDE.md"), "# Web\n").unwrap();
+        std::fs::write(dir.join("hooks/10-install.sh"), "pnpm install\n").unwrap();
+        std::fs::write(dir.join("hooks/pre-session"), "git fetch\n").unwrap();
+        Template {
+            name: "web".to_string(),
+            dir,
+        }
+    }
+
+    #[test]
+    fn test_apply_merges_settings_and_seeds_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        std::fs::create_dir_all(&project).unwrap();
+        let template = template(
+            &temp_dir,
+            r#"{
+                "project_name": "other",
+                "permissions": { "allowed_tools": ["Bash(pnpm:*)"] },
+                "default_task_kind": "feature"
+            }"#,
+        );
+        let mut config = Config::new("app", "/projects/app");
+        config.permissions.require_approval = false;
+
+        let applied = template.apply(&config, &project).unwrap();
+        assert_eq!(applied.config.project_name, "app");
+        assert_eq!(applied.config.permissions.allowed_tools, ["Bash(pnpm:*)"]);
+        // Settings the template leaves out are kept
+        assert!(!applied.config.permissions.require_approval);
+        assert_eq!(
+            applied.config.setup_commands,
+            ["\"$CLAUDECTL_MAIN_CHECKOUT/.claudectl/hooks/10-install.sh\""]
+        );
+        // Lifecycle hooks are copied but not run as setup
+        assert_eq!(
+            applied.files,
+            [
+                "CLAUDE.md",
+                ".claudectl/hooks/10-install.sh",
+                ".claudectl/hooks/pre-session"
+            ]
+        );
+
+        // Applying again keeps the project's own files and adds nothing twice
+        std::fs::write(project.join("CLAUDE.md"), "# Mine\n").unwrap();
+        let again = template.apply(&applied.config, &project).unwrap();
+        assert!(again.files.is_empty());
+        assert_eq!(again.config.setup_commands.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(project.join("CLAUDE.md")).unwrap(),
+            "# Mine\n"
+        );
+    }
+
+    #[test]
+    fn test_apply_rejects_invalid_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let template = template(&temp_dir, r#"{ "permissions": { "mode": "yolo" } }"#);
+        let error = template
+            .apply(&Config::new("app", "/projects/app"), temp_dir.path())
+            .unwrap_err();
+        assert!(error.to_string().contains("Template 'web'"));
+    }
+
+    #[test]
+    fn test_project_templates_win_over_global_ones() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join(".claudectl/templates/web")).unwrap();
+        let templates = available_templates(temp_dir.path());
+        assert_eq!(
+            templates["web"].dir,
+            temp_dir.path().join(".claudectl/templates/web")
+        );
+        assert!(find_template(temp_dir.path(), "missing").is_err());
+    }
+}
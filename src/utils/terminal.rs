@@ -0,0 +1,147 @@
+use std::io::{self, IsTerminal, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::config::GlobalConfig;
+use crate::utils::output::is_plain;
+
+/// How claudectl uses the terminal it runs in, beyond printing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TerminalSettings {
+    /// Show what claudectl is doing in the window or tab title
+    #[serde(default = "default_true")]
+    pub title: bool,
+    /// Notify and show progress with OSC 9 on terminals that support it
+    #[serde(default = "default_true")]
+    pub notifications: bool,
+}
+
+impl Default for TerminalSettings {
+    fn default() -> Self {
+        Self {
+            title: true,
+            notifications: true,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// States of the OSC 9;4 progress indicator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Progress {
+    Clear = 0,
+    Error = 2,
+    Indeterminate = 3,
+}
+
+/// Title the project's sessions, e.g. "claudectl — 3 running, 1 waiting".
+pub fn show_session_counts(working: usize, waiting: usize) {
+    let mut counts = vec![format!("{working} running")];
+    if waiting > 0 {
+        counts.push(format!("{waiting} waiting"));
+    }
+    set_title(&counts.join(", "));
+}
+
+/// Show that a session is running in `task` until it finishes.
+pub fn session_started(task: &str) {
+    set_title(task);
+    set_progress(Progress::Indeterminate);
+}
+
+/// Show that the session in `task` has finished, and notify the user, who
+/// may be looking at another window.
+pub fn session_finished(task: &str, succeeded: bool) {
+    let outcome = if succeeded { "finished" } else { "failed" };
+    set_title(&format!("{task} {outcome}"));
+    set_progress(if succeeded {
+        Progress::Clear
+    } else {
+        Progress::Error
+    });
+    if settings().notifications && supports_osc9() {
+        emit(&notification(&format!("claudectl: {task} {outcome}")));
+    }
+}
+
+fn set_title(text: &str) {
+    if settings().title {
+        emit(&title(&format!("claudectl — {text}")));
+    }
+}
+
+fn set_progress(progress: Progress) {
+    if settings().notifications && supports_osc9() {
+        emit(&progress_sequence(progress));
+    }
+}
+
+fn settings() -> TerminalSettings {
+    GlobalConfig::load()
+        .map(|config| config.terminal)
+        .unwrap_or_default()
+}
+
+/// Whether the terminal understands OSC 9 notifications and progress.
+/// Terminals without it would print them as text, so only those known to
+/// support it get them.
+fn supports_osc9() -> bool {
+    let program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+    matches!(program.as_str(), "iTerm.app" | "WezTerm" | "ghostty")
+        || std::env::var_os("WT_SESSION").is_some()
+        || std::env::var("ConEmuANSI").is_ok_and(|value| value == "ON")
+}
+
+/// Write an escape sequence to the terminal. Output that is piped, captured
+/// or plain gets none.
+fn emit(sequence: &str) {
+    let mut stdout = io::stdout();
+    if is_plain() || !stdout.is_terminal() || std::env::var("TERM").is_ok_and(|t| t == "dumb") {
+        return;
+    }
+    let _ = stdout.write_all(sequence.as_bytes());
+    let _ = stdout.flush();
+}
+
+fn title(text: &str) -> String {
+    format!("\x1b]0;{}\x07", sanitize(text))
+}
+
+fn notification(text: &str) -> String {
+    format!("\x1b]9;{}\x07", sanitize(text))
+}
+
+fn progress_sequence(progress: Progress) -> String {
+    format!("\x1b]9;4;{}\x07", progress as u8)
+}
+
+/// Drop control characters, which would end the sequence early.
+fn sanitize(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequences() {
+        assert_eq!(
+            title("claudectl — 3 running"),
+            "\x1b]0;claudectl — 3 running\x07"
+        );
+        assert_eq!(notification("done\x07\n"), "\x1b]9;done\x07");
+        assert_eq!(progress_sequence(Progress::Indeterminate), "\x1b]9;4;3\x07");
+        assert_eq!(progress_sequence(Progress::Clear), "\x1b]9;4;0\x07");
+    }
+
+    #[test]
+    fn test_settings_default_on() {
+        let settings: TerminalSettings = serde_json::from_str(r#"{"title": false}"#).unwrap();
+        assert!(!settings.title);
+        assert!(settings.notifications);
+    }
+}
@@ -0,0 +1,88 @@
+use chrono::{DateTime, Local, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// How timestamps are rendered in command output.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TimestampStyle {
+    /// Local date and time, e.g. `2024-09-01 16:03`
+    #[default]
+    Absolute,
+    /// Time elapsed since, e.g. `3h ago`
+    Relative,
+}
+
+/// Past this age a relative timestamp says less than the date itself.
+const RELATIVE_MAX_DAYS: i64 = 30;
+
+/// Render a timestamp in the user's local time zone.
+pub fn format_timestamp(at: DateTime<Utc>, style: TimestampStyle) -> String {
+    match style {
+        TimestampStyle::Absolute => format_absolute(at),
+        TimestampStyle::Relative => format_relative(at, Utc::now()),
+    }
+}
+
+fn format_absolute(at: DateTime<Utc>) -> String {
+    at.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+fn format_relative(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now.signed_duration_since(at);
+    if elapsed.num_days() >= RELATIVE_MAX_DAYS {
+        return at.with_timezone(&Local).format("%Y-%m-%d").to_string();
+    }
+    // Clock skew can put recent timestamps slightly in the future
+    match elapsed.num_seconds() {
+        s if s < 60 => "just now".to_string(),
+        s if s < 60 * 60 => format!("{}m ago", s / 60),
+        s if s < 24 * 60 * 60 => format!("{}h ago", s / (60 * 60)),
+        s => format!("{}d ago", s / (24 * 60 * 60)),
+    }
+}
+
+/// A length of time in its two largest units, e.g. `3h 12m` or `45s`.
+pub fn format_span(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{secs}s"),
+        (0, 0, _) => format!("{minutes}m"),
+        (0, _, _) => format!("{hours}h {minutes}m"),
+        _ => format!("{days}d {hours}h"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_format_relative() {
+        let now = Utc::now();
+        assert_eq!(format_relative(now - Duration::seconds(5), now), "just now");
+        assert_eq!(format_relative(now + Duration::seconds(5), now), "just now");
+        assert_eq!(format_relative(now - Duration::minutes(12), now), "12m ago");
+        assert_eq!(format_relative(now - Duration::hours(3), now), "3h ago");
+        assert_eq!(format_relative(now - Duration::days(2), now), "2d ago");
+    }
+
+    #[test]
+    fn test_format_relative_falls_back_to_date_for_old_timestamps() {
+        let now = Utc::now();
+        let at = now - Duration::days(45);
+        let expected = at.with_timezone(&Local).format("%Y-%m-%d").to_string();
+        assert_eq!(format_relative(at, now), expected);
+    }
+
+    #[test]
+    fn test_format_span() {
+        assert_eq!(format_span(45), "45s");
+        assert_eq!(format_span(12 * 60 + 5), "12m");
+        assert_eq!(format_span(3 * 3_600 + 12 * 60), "3h 12m");
+        assert_eq!(format_span(2 * 86_400 + 5 * 3_600 + 59), "2d 5h");
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tabled::Tabled;
+use tracing::span::{Attributes, Id};
+use tracing::{Subscriber, warn};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::utils::errors::{CommandError, FileSystemError};
+use crate::utils::fs::{config_dir, write_atomic};
+use crate::utils::output::{blank, table};
+
+/// Git and storage operations taking at least this long are kept in the
+/// slow-operation stats `doctor` reports.
+const SLOW: Duration = Duration::from_secs(2);
+
+/// Modules whose operations are tracked for `doctor`. Others, such as agent
+/// sessions, take as long as the user keeps them open.
+const TRACKED_TARGETS: [&str; 2] = ["claudectl::utils::git", "claudectl::utils::fs"];
+
+/// Slow operations seen this often are reported as a problem.
+const RECURRING: u32 = 3;
+
+const STATS_FILE: &str = "timings.json";
+
+/// Spans kept per run. A long-running command such as `watch` keeps its
+/// latest.
+const MAX_TIMINGS: usize = 10_000;
+
+/// How long one span of this run took.
+#[derive(Debug, Clone)]
+pub struct Timing {
+    pub name: &'static str,
+    pub target: String,
+    /// Spans it ran inside of
+    pub depth: usize,
+    pub started: Instant,
+    pub elapsed: Duration,
+}
+
+impl Timing {
+    /// Whether it is a git or storage operation slow enough for `doctor`.
+    fn is_slow_operation(&self) -> bool {
+        self.elapsed >= SLOW && TRACKED_TARGETS.contains(&self.target.as_str())
+    }
+}
+
+static TIMINGS: Mutex<VecDeque<Timing>> = Mutex::new(VecDeque::new());
+
+struct Started(Instant);
+
+/// Records how long claudectl's spans took: every span for `--timings`, or
+/// otherwise only the slow operations kept for `doctor`.
+pub struct TimingLayer {
+    every_span: bool,
+}
+
+impl TimingLayer {
+    pub fn new(every_span: bool) -> Self {
+        Self { every_span }
+    }
+}
+
+impl<S> Layer<S> for TimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Started(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(started) = span.extensions().get::<Started>().map(|started| started.0) else {
+            return;
+        };
+        let timing = Timing {
+            name: span.name(),
+            target: span.metadata().target().to_string(),
+            depth: span.scope().skip(1).count(),
+            started,
+            elapsed: started.elapsed(),
+        };
+        if !self.every_span && !timing.is_slow_operation() {
+            return;
+        }
+        if let Ok(mut timings) = TIMINGS.lock() {
+            if timings.len() == MAX_TIMINGS {
+                timings.pop_front();
+            }
+            timings.push_back(timing);
+        }
+    }
+}
+
+/// The spans of this run kept so far, in the order they started.
+pub fn timings() -> Vec<Timing> {
+    let mut timings: Vec<Timing> = TIMINGS
+        .lock()
+        .map(|timings| timings.iter().cloned().collect())
+        .unwrap_or_default();
+    timings.sort_by_key(|timing| timing.started);
+    timings
+}
+
+#[derive(Tabled)]
+struct TimingRow {
+    step: String,
+    time: String,
+}
+
+/// Print how long each step of this run took, nested by the step it ran in.
+pub fn print_timings(timings: &[Timing]) {
+    let rows: Vec<TimingRow> = timings
+        .iter()
+        .map(|timing| TimingRow {
+            step: format!("{}{}", "  ".repeat(timing.depth), timing.name),
+            time: format_duration(timing.elapsed),
+        })
+        .collect();
+    blank();
+    table(&rows, true);
+}
+
+fn format_duration(elapsed: Duration) -> String {
+    if elapsed < Duration::from_secs(1) {
+        format!("{}ms", elapsed.as_millis())
+    } else {
+        format!("{:.2}s", elapsed.as_secs_f64())
+    }
+}
+
+/// What is known about one operation that has been slow.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlowOperation {
+    /// Times it was slow
+    pub count: u32,
+    pub total_ms: u64,
+    pub max_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl SlowOperation {
+    pub fn average(&self) -> Duration {
+        Duration::from_millis(self.total_ms / u64::from(self.count.max(1)))
+    }
+
+    pub fn is_recurring(&self) -> bool {
+        self.count >= RECURRING
+    }
+}
+
+/// Slow git and storage operations across runs, by span name.
+pub type SlowOperations = BTreeMap<String, SlowOperation>;
+
+/// Add this run's slow git and storage operations to the stats. Runs with
+/// none leave the file alone.
+pub fn record_slow_operations(timings: &[Timing]) {
+    let slow: Vec<&Timing> = timings
+        .iter()
+        .filter(|timing| timing.is_slow_operation())
+        .collect();
+    if slow.is_empty() {
+        return;
+    }
+    let result = load_slow_operations().and_then(|mut stats| {
+        add_slow(&mut stats, &slow, Utc::now());
+        save_slow_operations(&stats)
+    });
+    if let Err(e) = result {
+        warn!("Failed to record slow operations: {}", e);
+    }
+}
+
+fn add_slow(stats: &mut SlowOperations, slow: &[&Timing], now: DateTime<Utc>) {
+    for timing in slow {
+        let millis = u64::try_from(timing.elapsed.as_millis()).unwrap_or(u64::MAX);
+        let operation = stats.entry(timing.name.to_string()).or_default();
+        operation.count += 1;
+        operation.total_ms = operation.total_ms.saturating_add(millis);
+        operation.max_ms = operation.max_ms.max(millis);
+        operation.last_seen = Some(now);
+    }
+}
+
+/// The slow-operation stats recorded so far.
+pub fn load_slow_operations() -> Result<SlowOperations, CommandError> {
+    let path = config_dir()?.join(STATS_FILE);
+    if !path.exists() {
+        return Ok(SlowOperations::new());
+    }
+    let raw = std::fs::read_to_string(&path).map_err(|e| {
+        FileSystemError::read_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    })?;
+    // Stats are only advisory, so unreadable ones start over
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+fn save_slow_operations(stats: &SlowOperations) -> Result<(), CommandError> {
+    let dir = config_dir()?;
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &dir.to_string_lossy())
+    })?;
+    let json = serde_json::to_string_pretty(stats)
+        .map_err(|e| CommandError::new(&format!("Failed to serialize timings: {e}")))?;
+    write_atomic(&dir.join(STATS_FILE), &json)?;
+    Ok(())
+}
+
+/// e.g. "fetch_origin: 3 times, 4.10s on average, at most 9.02s"
+pub fn describe_slow(name: &str, operation: &SlowOperation) -> String {
+    format!(
+        "{name}: {} time(s), {} on average, at most {}",
+        operation.count,
+        format_duration(operation.average()),
+        format_duration(Duration::from_millis(operation.max_ms))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(name: &'static str, secs: u64) -> Timing {
+        Timing {
+            name,
+            target: "claudectl::utils::git".to_string(),
+            depth: 1,
+            started: Instant::now(),
+            elapsed: Duration::from_secs(secs),
+        }
+    }
+
+    #[test]
+    fn test_add_slow_accumulates() {
+        let mut stats = SlowOperations::new();
+        let first = timing("fetch_origin", 3);
+        let second = timing("fetch_origin", 5);
+        add_slow(&mut stats, &[&first], Utc::now());
+        add_slow(&mut stats, &[&second], Utc::now());
+
+        let fetch = &stats["fetch_origin"];
+        assert_eq!(fetch.count, 2);
+        assert_eq!(fetch.max_ms, 5000);
+        assert_eq!(fetch.average(), Duration::from_secs(4));
+        assert!(!fetch.is_recurring());
+        assert_eq!(
+            describe_slow("fetch_origin", fetch),
+            "fetch_origin: 2 time(s), 4.00s on average, at most 5.00s"
+        );
+    }
+
+    #[test]
+    fn test_only_slow_git_and_storage_operations_are_kept_for_doctor() {
+        assert!(timing("fetch_origin", 3).is_slow_operation());
+        assert!(!timing("fetch_origin", 1).is_slow_operation());
+
+        let mut session = timing("launch_session", 600);
+        session.target = "claudectl::utils::claude".to_string();
+        assert!(!session.is_slow_operation());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(42)), "42ms");
+        assert_eq!(format_duration(Duration::from_millis(1500)), "1.50s");
+    }
+}
@@ -0,0 +1,278 @@
+use std::path::PathBuf;
+
+use crate::utils::errors::FileSystemError;
+use crate::utils::fs::{
+    journal_files, new_journal_path, remove_task_file, write_atomic, write_task_file,
+};
+use crate::utils::git::{
+    delete_branch, move_worktree, remove_worktree, rename_branch, rename_checkpoints,
+};
+use crate::utils::process::is_running;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// A change made during a multi-step operation, and how to undo it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Step {
+    /// A worktree was added at this path
+    WorktreeAdded(String),
+    /// A branch was created
+    BranchCreated(String),
+    /// A task record was written; holds its previous contents, if any
+    TaskFileWritten {
+        task_name: String,
+        previous: Option<String>,
+    },
+    /// A branch was renamed
+    BranchRenamed { from: String, to: String },
+    /// A task's checkpoints were moved to its new name
+    CheckpointsRenamed { from: String, to: String },
+    /// A worktree's directory was moved
+    WorktreeMoved { from: String, to: String },
+}
+
+impl Step {
+    fn undo(&self) -> Result<(), String> {
+        match self {
+            Self::WorktreeAdded(path) => remove_worktree(path).map_err(|e| e.to_string()),
+            Self::BranchCreated(name) => delete_branch(name).map_err(|e| e.to_string()),
+            Self::TaskFileWritten {
+                task_name,
+                previous: Some(content),
+            } => write_task_file(task_name, content.clone()).map_err(|e| e.to_string()),
+            Self::TaskFileWritten {
+                task_name,
+                previous: None,
+            } => remove_task_file(task_name).map_err(|e| e.to_string()),
+            Self::BranchRenamed { from, to } => rename_branch(to, from).map_err(|e| e.to_string()),
+            Self::CheckpointsRenamed { from, to } => {
+                rename_checkpoints(to, from).map_err(|e| e.to_string())
+            }
+            Self::WorktreeMoved { from, to } => move_worktree(to, from).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// What an operation's journal holds: the process running it, which owns
+/// the journal until it exits, and the steps so far.
+#[derive(Debug, Serialize, Deserialize)]
+struct Journal {
+    pid: u32,
+    steps: Vec<Step>,
+}
+
+/// Records the steps of an operation that spans git and the filesystem so
+/// they can be undone together.
+///
+/// Steps are undone in reverse order when the transaction is dropped without
+/// being committed, so returning early with `?` rolls everything back. A
+/// journaled transaction also keeps its steps on disk, in a journal of its
+/// own, until it finishes. An operation cut short by a crash or kill is
+/// rolled back by [`recover_interrupted`] once its process has exited.
+#[derive(Debug, Default)]
+pub struct Transaction {
+    steps: Vec<Step>,
+    committed: bool,
+    journal: Option<PathBuf>,
+}
+
+/// What [`recover_interrupted`] undid.
+#[derive(Debug)]
+pub struct Recovery {
+    pub undone: usize,
+    pub failures: Vec<String>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A transaction that journals its steps in the current project. The
+    /// journal is claimed for this process straight away, so other
+    /// claudectl runs leave the operation alone while it runs.
+    pub fn journaled() -> Result<Self, FileSystemError> {
+        let mut transaction = Self::new();
+        transaction.journal = Some(new_journal_path()?);
+        transaction.write_journal()?;
+        Ok(transaction)
+    }
+
+    /// Record a step just before attempting it, so one interrupted halfway
+    /// is undone too. Undoing a step that never happened fails harmlessly.
+    pub fn record(&mut self, step: Step) -> Result<(), FileSystemError> {
+        self.steps.push(step);
+        self.write_journal()
+    }
+
+    fn write_journal(&self) -> Result<(), FileSystemError> {
+        let Some(journal) = &self.journal else {
+            return Ok(());
+        };
+        let content = journal_content(&self.steps, journal)?;
+        write_atomic(journal, &content)
+    }
+
+    /// Keep every recorded change.
+    pub fn commit(mut self) {
+        self.committed = true;
+        self.remove_journal();
+    }
+
+    /// Undo every recorded step, newest first.
+    ///
+    /// Undoing continues past failures so one stuck step does not leave the
+    /// others in place; failures are returned for reporting.
+    pub fn rollback(&mut self) -> Vec<String> {
+        let mut failures = Vec::new();
+        while let Some(step) = self.steps.pop() {
+            info!("Rolling back: {:?}", step);
+            if let Err(e) = step.undo() {
+                warn!("Failed to roll back {:?}: {}", step, e);
+                failures.push(e);
+            }
+        }
+        self.remove_journal();
+        failures
+    }
+
+    fn remove_journal(&mut self) {
+        if let Some(journal) = self.journal.take() {
+            if let Err(e) = std::fs::remove_file(&journal) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Failed to remove journal {}: {}", journal.display(), e);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        if !self.committed && !self.steps.is_empty() {
+            self.rollback();
+        }
+    }
+}
+
+fn journal_content(steps: &[Step], path: &Path) -> Result<String, FileSystemError> {
+    let journal = Journal {
+        pid: std::process::id(),
+        steps: steps.to_vec(),
+    };
+    serde_json::to_string_pretty(&journal).map_err(|e| {
+        FileSystemError::write_failed(&format!("JSON error: {e}"), &path.to_string_lossy())
+    })
+}
+
+/// Roll back the operations that a crash or kill interrupted, using the
+/// journals they left in the current project. Journals of processes that
+/// are still running belong to operations in progress and are left alone.
+pub fn recover_interrupted() -> Result<Vec<Recovery>, FileSystemError> {
+    let mut recoveries = Vec::new();
+    for path in journal_files()? {
+        let path_str = path.to_string_lossy().to_string();
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            // Finished, or recovered by another run, since it was listed
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(FileSystemError::read_failed(
+                    &format!("IO error: {e}"),
+                    &path_str,
+                ));
+            }
+        };
+        let journal: Journal = serde_json::from_str(&content)
+            .map_err(|e| FileSystemError::read_failed(&format!("JSON error: {e}"), &path_str))?;
+        if journal.pid != std::process::id() && is_running(journal.pid) {
+            continue;
+        }
+        let Some(claimed) = claim(&path, &journal.steps)? else {
+            continue;
+        };
+
+        warn!("Recovering interrupted operation from {}", path_str);
+        let undone = journal.steps.len();
+        let mut transaction = Transaction {
+            steps: journal.steps,
+            committed: false,
+            journal: Some(claimed),
+        };
+        let failures = transaction.rollback();
+        recoveries.push(Recovery { undone, failures });
+    }
+    Ok(recoveries)
+}
+
+/// Take over the journal at `path` of a process that has exited, so no
+/// other run recovers it too: copy it into a new journal owned by this
+/// process, then remove the original. Whoever removes the original wins;
+/// `None` means another run got there first.
+fn claim(path: &Path, steps: &[Step]) -> Result<Option<std::path::PathBuf>, FileSystemError> {
+    let claimed = new_journal_path()?;
+    let content = journal_content(steps, &claimed)?;
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&claimed)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| {
+            FileSystemError::write_failed(&format!("IO error: {e}"), &claimed.to_string_lossy())
+        })?;
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(Some(claimed)),
+        Err(e) => {
+            info!("Journal {} was claimed elsewhere: {}", path.display(), e);
+            let _ = std::fs::remove_file(&claimed);
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback_undoes_newest_first_and_continues_past_failures() {
+        let mut transaction = Transaction::new();
+        transaction
+            .record(Step::BranchCreated("claudectl-test/missing-a".to_string()))
+            .unwrap();
+        transaction
+            .record(Step::WorktreeAdded(
+                "/nonexistent/claudectl-test".to_string(),
+            ))
+            .unwrap();
+
+        let failures = transaction.rollback();
+        assert_eq!(failures.len(), 2);
+        assert!(failures[0].contains("worktree"));
+        assert!(transaction.steps.is_empty());
+    }
+
+    #[test]
+    fn test_journaled_steps_round_trip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let journal = temp_dir.path().join("journal.json");
+        let mut transaction = Transaction::new();
+        transaction.journal = Some(journal.clone());
+        let step = Step::TaskFileWritten {
+            task_name: "feat/x".to_string(),
+            previous: None,
+        };
+        transaction.record(step.clone()).unwrap();
+
+        let content = std::fs::read_to_string(&journal).unwrap();
+        let written: Journal = serde_json::from_str(&content).unwrap();
+        assert_eq!(written.pid, std::process::id());
+        assert_eq!(written.steps, [step]);
+
+        transaction.commit();
+        assert!(!journal.exists());
+    }
+}
@@ -0,0 +1,378 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::Value;
+use tracing::{info, instrument, warn};
+
+use crate::utils::errors::{CommandError, FileSystemError};
+
+/// Where releases are published, with a binary for each platform.
+pub const GITHUB_REPO: &str = "HoganMcDonald/claudectl";
+
+/// Overrides the GitHub API URL of the latest release, for mirrors and tests.
+const RELEASES_URL_ENV: &str = "CLAUDECTL_RELEASES_URL";
+
+/// The release asset listing each binary's SHA-256, as `sha256sum` prints it.
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+const NPM_DIST_TAGS_URL: &str = "https://registry.npmjs.org/-/package/claudectl/dist-tags";
+
+/// The version of this binary.
+pub fn current_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// The newest published release, and where to get the binary for this
+/// platform.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub version: String,
+    /// The binary's name among the release's assets
+    pub asset: String,
+    pub download_url: String,
+    pub checksums_url: String,
+}
+
+/// Look up the latest release on GitHub, falling back to the version npm
+/// has when GitHub can't be reached or rate-limits the request.
+pub fn latest_release() -> Result<Release, CommandError> {
+    let asset = asset_name().ok_or_else(|| {
+        CommandError::new(&format!(
+            "No claudectl releases are built for {} {}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ))
+    })?;
+    let releases_url = std::env::var(RELEASES_URL_ENV)
+        .unwrap_or_else(|_| format!("https://api.github.com/repos/{GITHUB_REPO}/releases/latest"));
+    match fetch(&releases_url).and_then(|body| github_release(&body, asset)) {
+        Ok(release) => Ok(release),
+        Err(e) => {
+            warn!("Falling back to npm for the latest version: {}", e);
+            let version = fetch(NPM_DIST_TAGS_URL)
+                .and_then(|body| npm_latest(&body))
+                .map_err(|npm| {
+                    CommandError::new(&format!(
+                        "Failed to find the latest release. GitHub: {}. npm: {}",
+                        e.message(),
+                        npm.message()
+                    ))
+                })?;
+            let release_url =
+                format!("https://github.com/{GITHUB_REPO}/releases/download/v{version}");
+            Ok(Release {
+                asset: asset.to_string(),
+                download_url: format!("{release_url}/{asset}"),
+                checksums_url: format!("{release_url}/{CHECKSUMS_ASSET}"),
+                version,
+            })
+        }
+    }
+}
+
+/// Name of the release binary built for this platform.
+pub fn asset_name() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Some("claudectl-linux-x64"),
+        ("linux", "aarch64") => Some("claudectl-linux-arm64"),
+        ("macos", "x86_64") => Some("claudectl-macos-x64"),
+        ("macos", "aarch64") => Some("claudectl-macos-arm64"),
+        ("windows", "x86_64") => Some("claudectl-windows-x64.exe"),
+        _ => None,
+    }
+}
+
+/// The body of `url`, fetched with curl.
+#[instrument]
+fn fetch(url: &str) -> Result<Vec<u8>, CommandError> {
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location", url])
+        .output()
+        .map_err(|e| CommandError::new(&format!("Failed to run curl: {e}")))?;
+    if !output.status.success() {
+        return Err(CommandError::new(&format!(
+            "Failed to fetch {url}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output.stdout)
+}
+
+/// Read the version and this platform's binary from a GitHub release.
+fn github_release(body: &[u8], asset: &str) -> Result<Release, CommandError> {
+    let release: Value = serde_json::from_slice(body)
+        .map_err(|e| CommandError::new(&format!("Unexpected release response: {e}")))?;
+    let version = release
+        .get("tag_name")
+        .and_then(Value::as_str)
+        .map(|tag| tag.trim_start_matches('v').to_string())
+        .ok_or_else(|| CommandError::new("The release has no tag"))?;
+    let asset_url = |name: &str| {
+        release
+            .get("assets")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .find(|a| a.get("name").and_then(Value::as_str) == Some(name))
+            .and_then(|a| a.get("browser_download_url").and_then(Value::as_str))
+            .map(str::to_string)
+    };
+    let download_url = asset_url(asset)
+        .ok_or_else(|| CommandError::new(&format!("Release {version} has no {asset} binary")))?;
+    let checksums_url = asset_url(CHECKSUMS_ASSET)
+        .ok_or_else(|| CommandError::new(&format!("Release {version} has no {CHECKSUMS_ASSET}")))?;
+    Ok(Release {
+        version,
+        asset: asset.to_string(),
+        download_url,
+        checksums_url,
+    })
+}
+
+/// Read the `latest` tag from npm's dist-tags.
+fn npm_latest(body: &[u8]) -> Result<String, CommandError> {
+    let tags: Value = serde_json::from_slice(body)
+        .map_err(|e| CommandError::new(&format!("Unexpected dist-tags response: {e}")))?;
+    tags.get("latest")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| CommandError::new("npm has no latest version"))
+}
+
+/// Whether `latest` is a newer version than `current`. Versions that can't
+/// be read are never newer.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// `1.2.3` or `v1.2.3` as numbers; pre-release suffixes are ignored.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Whether `exe` was installed by npm, which should then do the upgrade so
+/// it keeps track of the version.
+pub fn installed_by_npm(exe: &Path) -> bool {
+    exe.components()
+        .any(|component| component.as_os_str() == "node_modules")
+}
+
+/// Replace `exe` with the binary of `release`.
+///
+/// The new binary is downloaded next to `exe`. It must match the release's
+/// published SHA-256 before it is run at all, and then report the release's
+/// version before it is swapped in. The old binary is kept until the swap
+/// has succeeded, and put back if it fails.
+#[instrument(skip(release), fields(version = %release.version))]
+pub fn install_release(release: &Release, exe: &Path) -> Result<(), CommandError> {
+    let download = sibling(exe, "upgrade");
+    let backup = sibling(exe, "old");
+    let result = download_to(&release.download_url, &download)
+        .and_then(|()| check_sum(release, &download))
+        .and_then(|()| verify(&download, &release.version))
+        .and_then(|()| swap(&download, exe, &backup));
+    if download.exists() {
+        let _ = std::fs::remove_file(&download);
+    }
+    result?;
+    // A running binary can't be deleted on Windows; it is then replaced on
+    // the next upgrade
+    if let Err(e) = std::fs::remove_file(&backup) {
+        info!("Keeping {}: {}", backup.display(), e);
+    }
+    Ok(())
+}
+
+/// e.g. `/usr/local/bin/.claudectl.upgrade`
+fn sibling(exe: &Path, suffix: &str) -> PathBuf {
+    let name = exe
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "claudectl".to_string());
+    exe.with_file_name(format!(".{name}.{suffix}"))
+}
+
+fn download_to(url: &str, path: &Path) -> Result<(), CommandError> {
+    info!("Downloading {} to {}", url, path.display());
+    let output = Command::new("curl")
+        .args([
+            "--fail",
+            "--silent",
+            "--show-error",
+            "--location",
+            "--output",
+        ])
+        .arg(path)
+        .arg(url)
+        .output()
+        .map_err(|e| CommandError::new(&format!("Failed to run curl: {e}")))?;
+    if !output.status.success() {
+        return Err(CommandError::new(&format!(
+            "Failed to download {url}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).map_err(|e| {
+            FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+        })?;
+    }
+    Ok(())
+}
+
+/// Check the downloaded binary against the SHA-256 the release lists for it.
+fn check_sum(release: &Release, binary: &Path) -> Result<(), CommandError> {
+    let sums = fetch(&release.checksums_url)?;
+    let expected =
+        listed_sum(&String::from_utf8_lossy(&sums), &release.asset).ok_or_else(|| {
+            CommandError::new(&format!(
+                "The release's {CHECKSUMS_ASSET} has no checksum for {}",
+                release.asset
+            ))
+        })?;
+    let actual = sha256(binary)?;
+    if !actual.eq_ignore_ascii_case(&expected) {
+        return Err(CommandError::new(&format!(
+            "The downloaded binary does not match the release's checksum (expected {expected}, got {actual}); not installing it"
+        )));
+    }
+    info!("Checksum of {} matches the release", release.asset);
+    Ok(())
+}
+
+/// The checksum `sums` lists for `asset`, from lines like
+/// `<hex>  claudectl-linux-x64`.
+fn listed_sum(sums: &str, asset: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (sum, name) = line.trim().split_once(char::is_whitespace)?;
+        // `sha256sum --binary` marks the name with a '*'
+        let name = name.trim_start().trim_start_matches('*');
+        (name == asset).then(|| sum.to_string())
+    })
+}
+
+/// The SHA-256 of `path` in hex, from whichever of the platform's tools is
+/// installed.
+fn sha256(path: &Path) -> Result<String, CommandError> {
+    let path = path.to_string_lossy();
+    let tools: [&[&str]; 3] = [
+        &["sha256sum", &path],
+        &["shasum", "-a", "256", &path],
+        &["certutil", "-hashfile", &path, "SHA256"],
+    ];
+    for tool in tools {
+        let Ok(output) = Command::new(tool[0]).args(&tool[1..]).output() else {
+            continue;
+        };
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let sum = stdout
+            .split_whitespace()
+            .find(|word| word.len() == 64 && word.chars().all(|c| c.is_ascii_hexdigit()));
+        if let (true, Some(sum)) = (output.status.success(), sum) {
+            return Ok(sum.to_lowercase());
+        }
+    }
+    Err(CommandError::new(
+        "Failed to checksum the download: none of sha256sum, shasum or certutil is installed",
+    ))
+}
+
+/// Check that the downloaded binary runs and is the expected version.
+fn verify(binary: &Path, version: &str) -> Result<(), CommandError> {
+    let output = Command::new(binary)
+        .arg("--version")
+        .output()
+        .map_err(|e| CommandError::new(&format!("The downloaded binary does not run: {e}")))?;
+    let reported = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() || !reported.split_whitespace().any(|word| word == version) {
+        return Err(CommandError::new(&format!(
+            "The downloaded binary is not claudectl {version} (it reports '{}')",
+            reported.trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Move `exe` to `backup` and `download` into its place, moving `exe` back
+/// if the second step fails.
+fn swap(download: &Path, exe: &Path, backup: &Path) -> Result<(), CommandError> {
+    let move_failed = |e: std::io::Error, path: &Path| {
+        FileSystemError::write_failed(&format!("IO error: {e}"), &path.to_string_lossy())
+    };
+    if backup.exists() {
+        std::fs::remove_file(backup).map_err(|e| move_failed(e, backup))?;
+    }
+    std::fs::rename(exe, backup).map_err(|e| move_failed(e, exe))?;
+    if let Err(e) = std::fs::rename(download, exe) {
+        warn!("Restoring {} after a failed upgrade", exe.display());
+        std::fs::rename(backup, exe).map_err(|e| move_failed(e, exe))?;
+        return Err(move_failed(e, exe).into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_compare_numerically() {
+        assert!(is_newer("0.10.0", "0.9.3"));
+        assert!(is_newer("v1.0.0", "0.2.6"));
+        assert!(!is_newer("0.2.6", "0.2.6"));
+        assert!(!is_newer("0.2.5", "0.2.6"));
+        assert!(!is_newer("latest", "0.2.6"));
+        assert_eq!(parse_version("1.2.3-beta.1"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2"), None);
+    }
+
+    #[test]
+    fn test_github_release_picks_this_platforms_binary() {
+        let body = br#"{
+            "tag_name": "v0.3.0",
+            "assets": [
+                { "name": "claudectl-linux-x64", "browser_download_url": "https://example.com/linux" },
+                { "name": "claudectl-macos-arm64", "browser_download_url": "https://example.com/macos" },
+                { "name": "SHA256SUMS", "browser_download_url": "https://example.com/sums" }
+            ]
+        }"#;
+        let release = github_release(body, "claudectl-macos-arm64").unwrap();
+        assert_eq!(release.version, "0.3.0");
+        assert_eq!(release.asset, "claudectl-macos-arm64");
+        assert_eq!(release.download_url, "https://example.com/macos");
+        assert_eq!(release.checksums_url, "https://example.com/sums");
+        assert!(github_release(body, "claudectl-windows-x64.exe").is_err());
+        assert_eq!(npm_latest(br#"{ "latest": "0.3.1" }"#).unwrap(), "0.3.1");
+    }
+
+    #[test]
+    fn test_listed_sum_finds_the_assets_line() {
+        let sums = "0a1b  claudectl-linux-x64\n2c3d *claudectl-macos-arm64\n";
+        assert_eq!(
+            listed_sum(sums, "claudectl-macos-arm64").as_deref(),
+            Some("2c3d")
+        );
+        assert_eq!(
+            listed_sum(sums, "claudectl-linux-x64").as_deref(),
+            Some("0a1b")
+        );
+        assert_eq!(listed_sum(sums, "claudectl-linux-arm64"), None);
+    }
+
+    #[test]
+    fn test_installed_by_npm() {
+        assert!(installed_by_npm(Path::new(
+            "/usr/lib/node_modules/claudectl/npm/bin/claudectl-linux-x64"
+        )));
+        assert!(!installed_by_npm(Path::new("/usr/local/bin/claudectl")));
+    }
+}
@@ -0,0 +1,153 @@
+// A scriptable stand-in for the `claude` CLI
+
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// Builds a fake `claude` executable with a canned response.
+///
+/// ```ignore
+/// let claude = FakeClaude::new(&temp_dir).responds("Adds a file.").install();
+/// claudectl(&repo).env("PATH", claude.path()).args(["summarize", "feat/x"]);
+/// assert!(claude.last_invocation().stdin.contains("diff --git"));
+/// ```
+pub struct FakeClaude {
+    program: String,
+    root: PathBuf,
+    stdout: String,
+    stderr: String,
+    exit_code: i32,
+}
+
+/// A fake `claude` on disk, recording every call made to it.
+pub struct InstalledClaude {
+    bin_dir: PathBuf,
+    calls_dir: PathBuf,
+}
+
+/// One call to the fake `claude`.
+#[derive(Debug)]
+pub struct Invocation {
+    pub cwd: PathBuf,
+    pub args: Vec<String>,
+    pub stdin: String,
+    pub env: HashMap<String, String>,
+}
+
+impl FakeClaude {
+    /// A fake that succeeds without output; it lives in `temp_dir`.
+    pub fn new(temp_dir: &TempDir) -> Self {
+        Self::named(temp_dir, "claude")
+    }
+
+    /// The same fake standing in for another CLI, e.g. `gh`.
+    pub fn named(temp_dir: &TempDir, program: &str) -> Self {
+        Self {
+            program: program.to_string(),
+            root: temp_dir.path().join(format!("fake-{program}")),
+            stdout: String::new(),
+            stderr: String::new(),
+            exit_code: 0,
+        }
+    }
+
+    /// Print `text` on stdout.
+    pub fn responds(mut self, text: &str) -> Self {
+        self.stdout = format!("{text}\n");
+        self
+    }
+
+    /// Print `message` on stderr and exit with `code`.
+    pub fn fails(mut self, code: i32, message: &str) -> Self {
+        self.stderr = format!("{message}\n");
+        self.exit_code = code;
+        self
+    }
+
+    pub fn install(self) -> InstalledClaude {
+        let bin_dir = self.root.join("bin");
+        let calls_dir = self.root.join("calls");
+        fs::create_dir_all(&bin_dir).unwrap();
+        fs::create_dir_all(&calls_dir).unwrap();
+        // Responses are read from files so they need no shell quoting
+        fs::write(self.root.join("stdout"), &self.stdout).unwrap();
+        fs::write(self.root.join("stderr"), &self.stderr).unwrap();
+
+        let script = format!(
+            r#"#!/bin/sh
+calls="{calls}"
+call="$calls/$(printf '%04d' "$(ls "$calls" | wc -l)")"
+mkdir -p "$call"
+pwd > "$call/cwd"
+for arg in "$@"; do printf '%s\0' "$arg"; done > "$call/args"
+cat > "$call/stdin"
+env > "$call/env"
+cat "{root}/stdout"
+cat "{root}/stderr" >&2
+exit {code}
+"#,
+            calls = calls_dir.display(),
+            root = self.root.display(),
+            code = self.exit_code,
+        );
+        let claude = bin_dir.join(&self.program);
+        fs::write(&claude, script).unwrap();
+        fs::set_permissions(&claude, fs::Permissions::from_mode(0o755)).unwrap();
+
+        InstalledClaude { bin_dir, calls_dir }
+    }
+}
+
+impl InstalledClaude {
+    /// A PATH value that resolves the fake first.
+    pub fn path(&self) -> String {
+        format!(
+            "{}:{}",
+            self.bin_dir.display(),
+            std::env::var("PATH").unwrap_or_default()
+        )
+    }
+
+    /// How many calls have started, including any still running.
+    pub fn call_count(&self) -> usize {
+        fs::read_dir(&self.calls_dir).unwrap().count()
+    }
+
+    /// Every call made so far, oldest first.
+    pub fn invocations(&self) -> Vec<Invocation> {
+        let mut calls: Vec<PathBuf> = fs::read_dir(&self.calls_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        calls.sort();
+        calls
+            .into_iter()
+            .map(|call| read_invocation(&call))
+            .collect()
+    }
+
+    pub fn last_invocation(&self) -> Invocation {
+        self.invocations()
+            .pop()
+            .expect("fake claude was never called")
+    }
+}
+
+fn read_invocation(call: &std::path::Path) -> Invocation {
+    let read = |name: &str| fs::read_to_string(call.join(name)).unwrap();
+    Invocation {
+        cwd: PathBuf::from(read("cwd").trim_end()),
+        args: read("args")
+            .split_terminator('\0')
+            .map(str::to_string)
+            .collect(),
+        stdin: read("stdin"),
+        env: read("env")
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+    }
+}
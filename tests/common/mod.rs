@@ -1 +1,164 @@
-// Common test utilities - currently empty but preserved for future use
+// Common test utilities shared by the integration tests
+#![allow(dead_code)]
+
+mod fake_claude;
+mod snapshot;
+
+pub use fake_claude::FakeClaude;
+pub use snapshot::{assert_snapshot, normalize};
+
+use assert_cmd::Command;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command as StdCommand, Stdio};
+use std::thread::JoinHandle;
+use tempfile::TempDir;
+
+const GIT_IDENTITY: [(&str, &str); 4] = [
+    ("GIT_AUTHOR_NAME", "claudectl-test"),
+    ("GIT_AUTHOR_EMAIL", "test@claudectl.invalid"),
+    ("GIT_COMMITTER_NAME", "claudectl-test"),
+    ("GIT_COMMITTER_EMAIL", "test@claudectl.invalid"),
+];
+
+/// Run git in `dir` with a fixed identity, panicking on failure.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = StdCommand::new("git")
+        .args(args)
+        .current_dir(dir)
+        .envs(GIT_IDENTITY)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// A `claudectl` command running in `dir` with a fixed git identity.
+pub fn claudectl(dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("claudectl").unwrap();
+    cmd.current_dir(dir).envs(GIT_IDENTITY);
+    cmd
+}
+
+/// Like [`claudectl`], but as a std command that can be spawned, for
+/// commands that run until stopped such as `watch`.
+pub fn claudectl_process(dir: &Path) -> StdCommand {
+    let mut cmd = StdCommand::new(assert_cmd::cargo::cargo_bin("claudectl"));
+    cmd.current_dir(dir).envs(GIT_IDENTITY);
+    cmd
+}
+
+/// Create a real git repository with one commit and a claudectl config
+/// whose worktree directory lives inside the temp dir.
+pub fn init_project(temp_dir: &TempDir) -> PathBuf {
+    let repo = temp_dir.path().join("repo");
+    let worktrees = temp_dir.path().join("worktrees");
+    fs::create_dir_all(&repo).unwrap();
+    fs::create_dir_all(&worktrees).unwrap();
+
+    git(&repo, &["init", "-q", "-b", "main"]);
+    fs::write(repo.join("README.md"), "hello\n").unwrap();
+    git(&repo, &["add", "README.md"]);
+    git(&repo, &["commit", "-q", "-m", "initial"]);
+
+    let config_dir = repo.join(".claudectl");
+    fs::create_dir(&config_dir).unwrap();
+    let config = format!(
+        r#"{{ "project_name": "test-project", "project_dir": "{}" }}"#,
+        worktrees.display()
+    );
+    fs::write(config_dir.join("config.json"), config).unwrap();
+    fs::write(repo.join(".git/info/exclude"), ".claudectl/\n").unwrap();
+
+    repo
+}
+
+/// Add a task worktree on a new branch, returning its path.
+pub fn add_task(repo: &Path, task_name: &str) -> PathBuf {
+    let worktree = repo.parent().unwrap().join("worktrees").join(task_name);
+    git(
+        repo,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            task_name,
+            &worktree.to_string_lossy(),
+            "HEAD",
+        ],
+    );
+    worktree
+}
+
+/// Give the project a bare `origin` remote with `main` pushed, so commands
+/// that fetch and branch from `origin/main` work.
+pub fn add_origin(repo: &Path) -> PathBuf {
+    let origin = repo.parent().unwrap().join("origin.git");
+    git(
+        repo.parent().unwrap(),
+        &["init", "-q", "--bare", &origin.to_string_lossy()],
+    );
+    git(
+        repo,
+        &["remote", "add", "origin", &origin.to_string_lossy()],
+    );
+    git(repo, &["push", "-q", "origin", "main"]);
+    git(repo, &["fetch", "-q", "origin"]);
+    origin
+}
+
+/// Register `repo` as project `name` in a global config dir rooted at
+/// `config_home` (used as `XDG_CONFIG_HOME`).
+pub fn register_project(config_home: &Path, name: &str, repo: &Path) {
+    let project_dir = config_home.join("claudectl").join("projects").join(name);
+    fs::create_dir_all(&project_dir).unwrap();
+    fs::write(
+        project_dir.join("project.json"),
+        format!(r#"{{ "repo_path": "{}" }}"#, repo.display()),
+    )
+    .unwrap();
+}
+
+/// Start `script` as a session holding `task_name`, returning its pid and a
+/// thread that reaps it once it exits. The shell runs as `claudectl`, so
+/// the lock passes for one a claudectl process took.
+pub fn hold_task(repo: &Path, task_name: &str, script: &str) -> (u32, JoinHandle<()>) {
+    let bin = repo.parent().unwrap().join("holder");
+    let shell = bin.join("claudectl");
+    if !shell.exists() {
+        fs::create_dir_all(&bin).unwrap();
+        std::os::unix::fs::symlink("/bin/sh", &shell).unwrap();
+    }
+    let mut child = StdCommand::new(&shell)
+        .args(["-c", script])
+        // The shell reports the signals that end its children
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let pid = child.id();
+    let locks = repo.join(".claudectl/locks");
+    fs::create_dir_all(&locks).unwrap();
+    fs::write(
+        locks.join(format!("{}.json", task_name.replace('/', "__"))),
+        format!(
+            r#"{{ "pid": {pid}, "command": "task --start", "started_at": "{}" }}"#,
+            chrono::Utc::now().to_rfc3339()
+        ),
+    )
+    .unwrap();
+    let reaper = std::thread::spawn(move || {
+        child.wait().unwrap();
+    });
+    (pid, reaper)
+}
+
+pub fn lock_exists(repo: &Path, task_name: &str) -> bool {
+    repo.join(".claudectl/locks")
+        .join(format!("{}.json", task_name.replace('/', "__")))
+        .exists()
+}
@@ -0,0 +1,62 @@
+// Snapshot assertions for output that is easier to review than to assert on
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Set to rewrite snapshots from the current output instead of comparing.
+const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+
+fn snapshot_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{name}.snap"))
+}
+
+/// Compare `actual` with `tests/snapshots/<name>.snap`.
+///
+/// Run with `UPDATE_SNAPSHOTS=1` to create or refresh snapshots, then review
+/// the changes with `git diff`.
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = snapshot_path(name);
+    if std::env::var_os(UPDATE_VAR).is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Snapshot {} does not exist; run with {UPDATE_VAR}=1 to create it",
+            path.display()
+        )
+    });
+    assert!(
+        expected == actual,
+        "Snapshot {name} does not match; run with {UPDATE_VAR}=1 to update it\n\
+         --- expected\n{expected}\n--- actual\n{actual}"
+    );
+}
+
+/// Make output comparable across runs: strip colors and replace the test's
+/// temporary directory with `[TEMP]`.
+pub fn normalize(output: &str, temp_dir: &Path) -> String {
+    let mut normalized = String::with_capacity(output.len());
+    let mut chars = output.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the escape sequence up to its final letter
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            normalized.push(c);
+        }
+    }
+    let temp = temp_dir.canonicalize().unwrap_or(temp_dir.to_path_buf());
+    normalized
+        .replace(&*temp.to_string_lossy(), "[TEMP]")
+        .replace(&*temp_dir.to_string_lossy(), "[TEMP]")
+}
@@ -0,0 +1,116 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, git, init_project};
+
+#[test]
+fn test_archive_and_restore_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let config_home = temp_dir.path().join("config");
+    let worktree = add_task(&repo, "feat/login");
+    fs::write(worktree.join("done.txt"), "committed\n").unwrap();
+    git(&worktree, &["add", "done.txt"]);
+    git(&worktree, &["commit", "-q", "-m", "Add done"]);
+    fs::write(worktree.join("wip.txt"), "uncommitted\n").unwrap();
+    let head = git(&worktree, &["rev-parse", "HEAD"]);
+
+    for args in [
+        &["checkpoint", "feat/login"][..],
+        &["note", "feat/login", "half way there"][..],
+    ] {
+        let output = claudectl(&repo)
+            .args(args)
+            .env("XDG_CONFIG_HOME", &config_home)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    }
+
+    let output = claudectl(&repo)
+        .args(["archive", "feat/login"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!worktree.exists());
+    assert!(git(&repo, &["branch", "--list", "feat/login"]).is_empty());
+    assert!(git(&repo, &["for-each-ref", "refs/claudectl"]).is_empty());
+    assert!(!repo.join(".claudectl/tasks/feat__login.json").exists());
+
+    let output = claudectl(&repo)
+        .args(["--plain", "archive", "--list"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("feat__login-"), "{stdout}");
+
+    let output = claudectl(&repo)
+        .args(["restore", "feat/login"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The branch, uncommitted work, checkpoints and record are back
+    assert_eq!(git(&worktree, &["rev-parse", "HEAD"]), head);
+    assert_eq!(git(&worktree, &["branch", "--show-current"]), "feat/login");
+    assert_eq!(
+        fs::read_to_string(worktree.join("wip.txt")).unwrap(),
+        "uncommitted\n"
+    );
+    assert!(git(&worktree, &["status", "--porcelain"]).contains("wip.txt"));
+    assert!(git(&repo, &["for-each-ref", "refs/claudectl"]).contains("checkpoints/feat/login/1"));
+    let record = fs::read_to_string(repo.join(".claudectl/tasks/feat__login.json")).unwrap();
+    assert!(record.contains("half way there"));
+
+    // Restoring used the archive up
+    let output = claudectl(&repo)
+        .args(["--plain", "archive", "--list"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No archived tasks"));
+}
+
+#[test]
+fn test_restore_refuses_to_overwrite_a_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let config_home = temp_dir.path().join("config");
+    add_task(&repo, "feat/login");
+
+    let output = claudectl(&repo)
+        .args(["archive", "feat/login"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    add_task(&repo, "feat/login");
+
+    let output = claudectl(&repo)
+        .args(["restore", "feat/login"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 'feat/login' already exists"));
+
+    let output = claudectl(&repo)
+        .args(["archive", "--purge", "feat/login"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .write_stdin("y\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Deleted archive"));
+}
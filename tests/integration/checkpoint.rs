@@ -0,0 +1,167 @@
+use std::fs;
+use std::process::Stdio;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, claudectl_process, git, init_project};
+
+#[test]
+fn test_checkpoint_fails_for_nonexistent_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["checkpoint", "missing-task"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Task 'missing-task' not found"));
+}
+
+#[test]
+fn test_checkpoint_records_uncommitted_work_without_touching_branch() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/checkpoint");
+    let head_before = git(&worktree, &["rev-parse", "HEAD"]);
+
+    fs::write(worktree.join("new.txt"), "work in progress\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["checkpoint", "feat/checkpoint", "-m", "wip"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Created checkpoint 1"));
+
+    // Branch and worktree are untouched
+    assert_eq!(git(&worktree, &["rev-parse", "HEAD"]), head_before);
+    assert!(git(&worktree, &["status", "--porcelain"]).contains("new.txt"));
+
+    // The checkpoint contains the untracked file
+    let files = git(
+        &worktree,
+        &[
+            "ls-tree",
+            "--name-only",
+            "refs/claudectl/checkpoints/feat/checkpoint/1",
+        ],
+    );
+    assert!(files.contains("new.txt"));
+
+    // A second checkpoint without changes is skipped
+    let output = claudectl(&repo)
+        .args(["checkpoint", "feat/checkpoint"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No changes"));
+
+    let output = claudectl(&repo)
+        .args(["checkpoint", "feat/checkpoint", "--list"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("wip"));
+}
+
+#[test]
+fn test_checkpoint_list_shows_each_checkpoint_in_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/listed");
+
+    let output = claudectl(&repo)
+        .args(["checkpoint", "feat/listed", "--list"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No checkpoints for 'feat/listed'"));
+
+    fs::write(worktree.join("notes.txt"), "first draft\n").unwrap();
+    claudectl(&repo)
+        .args(["checkpoint", "feat/listed", "-m", "before refactor"])
+        .assert()
+        .success();
+    fs::write(worktree.join("notes.txt"), "second draft\n").unwrap();
+    claudectl(&repo)
+        .args(["checkpoint", "feat/listed", "-m", "after refactor"])
+        .assert()
+        .success();
+
+    let output = claudectl(&repo)
+        .args(["checkpoint", "feat/listed", "--list", "--plain"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let first = stdout.find("before refactor").expect("first checkpoint");
+    let second = stdout.find("after refactor").expect("second checkpoint");
+    assert!(first < second, "{stdout}");
+
+    // Each checkpoint keeps the worktree as it was when it was taken
+    for (id, content) in [("1", "first draft"), ("2", "second draft")] {
+        let file = git(
+            &worktree,
+            &[
+                "show",
+                &format!("refs/claudectl/checkpoints/feat/listed/{id}:notes.txt"),
+            ],
+        );
+        assert_eq!(file, content);
+    }
+}
+
+#[test]
+fn test_checkpoint_interval_checkpoints_until_stopped() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/timed");
+    fs::write(worktree.join("new.txt"), "work in progress\n").unwrap();
+
+    let mut child = claudectl_process(&repo)
+        .args(["checkpoint", "feat/timed", "--interval", "1"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let checkpoints = || git(&repo, &["for-each-ref", "refs/claudectl/checkpoints"]);
+    let deadline = Instant::now() + Duration::from_secs(20);
+    while checkpoints().is_empty() && Instant::now() < deadline {
+        sleep(Duration::from_millis(200));
+    }
+    // Still running, waiting for the next one
+    assert!(child.try_wait().unwrap().is_none());
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert!(checkpoints().contains("refs/claudectl/checkpoints/feat/timed/1"));
+    let output = claudectl(&repo)
+        .args(["checkpoint", "feat/timed", "--list"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("claudectl checkpoint of feat/timed"));
+}
+
+#[test]
+fn test_checkpoint_interval_must_be_at_least_a_minute() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/timed");
+
+    let output = claudectl(&repo)
+        .args(["checkpoint", "feat/timed", "--interval", "0"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(git(&repo, &["for-each-ref", "refs/claudectl/checkpoints"]).is_empty());
+}
@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, git, hold_task, init_project};
+
+fn commit_file(dir: &Path, file: &str, content: &str, message: &str) {
+    fs::write(dir.join(file), content).unwrap();
+    git(dir, &["add", file]);
+    git(dir, &["commit", "-q", "-m", message]);
+}
+
+/// A project with a merged task, a fresh task, an unfinished task whose
+/// worktree was deleted by hand, and a record left behind by a removed task.
+fn stale_project(temp_dir: &TempDir) -> std::path::PathBuf {
+    let repo = init_project(temp_dir);
+    let merged = add_task(&repo, "feat/merged");
+    fs::write(merged.join("merged.txt"), "draft\n").unwrap();
+    claudectl(&repo)
+        .args(["checkpoint", "feat/merged"])
+        .assert()
+        .success();
+    commit_file(&merged, "merged.txt", "done\n", "finish merged");
+    git(&repo, &["merge", "-q", "--no-edit", "feat/merged"]);
+
+    add_task(&repo, "feat/fresh");
+
+    let deleted = add_task(&repo, "feat/deleted");
+    commit_file(&deleted, "wip.txt", "wip\n", "unfinished work");
+    fs::remove_dir_all(&deleted).unwrap();
+
+    fs::create_dir_all(repo.join(".claudectl/tasks")).unwrap();
+    fs::write(
+        repo.join(".claudectl/tasks/feat__gone.json"),
+        r#"{ "name": "feat/gone" }"#,
+    )
+    .unwrap();
+    repo
+}
+
+#[test]
+fn test_clean_dry_run_lists_stale_tasks_without_removing_them() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = stale_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["--plain", "clean", "--dry-run"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("merged into main"));
+    assert!(stdout.contains("worktree directory missing"));
+    assert!(stdout.contains("record without a worktree"));
+    assert!(stdout.contains("keep (unmerged commits)"));
+    assert!(!stdout.contains("feat/fresh"));
+    assert!(stdout.contains("3 task(s) would be cleaned up"));
+    assert!(repo.join("../worktrees/feat/merged").exists());
+    assert!(repo.join(".claudectl/tasks/feat__gone.json").exists());
+}
+
+#[test]
+fn test_clean_removes_stale_tasks_and_keeps_unmerged_branches() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = stale_project(&temp_dir);
+
+    let output = claudectl(&repo).args(["clean", "--yes"]).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!repo.join("../worktrees/feat/merged").exists());
+    assert_eq!(git(&repo, &["branch", "--list", "feat/merged"]), "");
+    assert_eq!(
+        git(&repo, &["for-each-ref", "refs/claudectl/checkpoints"]),
+        ""
+    );
+    assert!(!repo.join(".claudectl/tasks/feat__gone.json").exists());
+
+    let worktrees = git(&repo, &["worktree", "list"]);
+    assert!(!worktrees.contains("feat/deleted"));
+    assert!(worktrees.contains("feat/fresh"));
+    // Its commits are not on main, so the branch survives
+    assert_ne!(git(&repo, &["branch", "--list", "feat/deleted"]), "");
+
+    let output = claudectl(&repo).args(["clean", "--yes"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Nothing to clean up"));
+}
+
+#[test]
+fn test_clean_requires_yes_without_terminal() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = stale_project(&temp_dir);
+
+    let output = claudectl(&repo).args(["clean"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Pass --yes"));
+    assert!(repo.join("../worktrees/feat/merged").exists());
+}
+
+#[test]
+fn test_clean_leaves_merged_tasks_that_are_in_use() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    for task in ["feat/editing", "feat/running"] {
+        let worktree = add_task(&repo, task);
+        commit_file(&worktree, "done.txt", task, "finish");
+        git(&repo, &["merge", "-q", "--no-edit", task]);
+    }
+    let editing = repo.join("../worktrees/feat/editing");
+    fs::write(editing.join("more.txt"), "follow-up\n").unwrap();
+    let (pid, reaper) = hold_task(&repo, "feat/running", "sleep 30; true");
+
+    let output = claudectl(&repo).args(["clean", "--yes"]).output().unwrap();
+
+    // The stand-in session ends with its sleep
+    StdCommand::new("pkill")
+        .args(["-P", &pid.to_string()])
+        .status()
+        .unwrap();
+    reaper.join().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Nothing to clean up"));
+    assert!(editing.join("more.txt").exists());
+    assert!(repo.join("../worktrees/feat/running").exists());
+}
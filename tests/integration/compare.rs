@@ -0,0 +1,65 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project};
+
+#[test]
+fn test_compare_lists_changes_of_both_tasks() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let first = add_task(&repo, "feat/first-try");
+    let second = add_task(&repo, "feat/second-try");
+    fs::write(first.join("shared.txt"), "one\ntwo\n").unwrap();
+    fs::write(second.join("shared.txt"), "one\n").unwrap();
+    fs::write(second.join("extra.txt"), "more\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["--plain", "compare", "feat/first-try", "feat/second-try"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let shared = stdout.lines().find(|l| l.contains("shared.txt")).unwrap();
+    assert!(shared.contains("+2 -0") && shared.contains("+1 -0"));
+    let extra = stdout.lines().find(|l| l.contains("extra.txt")).unwrap();
+    assert!(extra.contains("+1 -0"));
+    assert!(stdout.contains("feat/first-try: no runs"));
+}
+
+#[test]
+fn test_compare_diff_shows_how_tasks_differ() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let first = add_task(&repo, "feat/left");
+    let second = add_task(&repo, "feat/right");
+    fs::write(first.join("answer.txt"), "left answer\n").unwrap();
+    fs::write(second.join("answer.txt"), "right answer\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["compare", "feat/left", "feat/right", "--diff"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("-left answer"));
+    assert!(stdout.contains("+right answer"));
+}
+
+#[test]
+fn test_compare_output_needs_runs() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/a");
+    add_task(&repo, "feat/b");
+
+    let output = claudectl(&repo)
+        .args(["compare", "feat/a", "feat/b", "--output"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 'feat/a' has no runs"));
+}
@@ -0,0 +1,59 @@
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project, register_project};
+
+#[test]
+fn test_complete_tasks_lists_task_branches_only() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/one");
+    add_task(&repo, "feat/two");
+
+    let output = claudectl(&repo)
+        .args(["__complete", "tasks"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let names: Vec<&str> = stdout.lines().collect();
+    assert_eq!(names, vec!["feat/one", "feat/two"]);
+}
+
+#[test]
+fn test_complete_tasks_is_silent_outside_a_repository() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["__complete", "tasks"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn test_complete_projects_lists_registered_projects() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config");
+    let repo = init_project(&temp_dir);
+    register_project(&config_home, "test-project", &repo);
+
+    let output = claudectl(temp_dir.path())
+        .args(["__complete", "projects"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        "test-project"
+    );
+}
+
+#[test]
+fn test_complete_is_hidden_from_help() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path()).arg("--help").output().unwrap();
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("__complete"));
+}
@@ -0,0 +1,117 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::claudectl;
+
+#[test]
+fn test_completions_generates_nushell_externs() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["completions", "nu"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("module completions {"));
+    assert!(stdout.contains("export extern \"claudectl show\" ["));
+    assert!(!stdout.contains("export extern \"claudectl __complete\""));
+}
+
+#[test]
+fn test_completions_call_back_for_task_names() {
+    let temp_dir = TempDir::new().unwrap();
+
+    for shell in ["bash", "zsh", "fish", "nu", "powershell"] {
+        let output = claudectl(temp_dir.path())
+            .args(["completions", shell])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{shell}");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("claudectl __complete tasks"), "{shell}");
+    }
+}
+
+#[test]
+fn test_completions_verify_checks_nushell_profile() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path();
+    let nushell_dir = home.join(".config/nushell");
+    fs::create_dir_all(nushell_dir.join("completions")).unwrap();
+    let completion_file = nushell_dir.join("completions/claudectl.nu");
+    fs::write(
+        &completion_file,
+        "# Version: 1.0.0\ndef _claudectl_tasks [] {}\n",
+    )
+    .unwrap();
+
+    let verify = || {
+        claudectl(home)
+            .args(["--plain", "completions", "--verify"])
+            .env("HOME", home)
+            .env("SHELL", "/usr/bin/nu")
+            .env_remove("XDG_CONFIG_HOME")
+            .output()
+            .unwrap()
+    };
+
+    let output = verify();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Not loaded from"), "{stderr}");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("source \""));
+
+    fs::write(
+        nushell_dir.join("config.nu"),
+        format!("source \"{}\"\n", completion_file.display()),
+    )
+    .unwrap();
+    let output = verify();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Loaded from"));
+}
+
+#[test]
+fn test_completions_install_and_uninstall_bash() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path();
+    let bashrc = home.join(".bashrc");
+    fs::write(&bashrc, "export EDITOR=vim\n").unwrap();
+
+    let completions = |flag: &str| {
+        claudectl(home)
+            .args(["--plain", "completions", flag])
+            .env("HOME", home)
+            .env("SHELL", "/bin/bash")
+            .env_remove("XDG_CONFIG_HOME")
+            .output()
+            .unwrap()
+    };
+
+    let output = completions("--install");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let completion_file = home.join(".bash_completion.d/claudectl");
+    let script = fs::read_to_string(&completion_file).unwrap();
+    assert!(script.starts_with("# Version: "));
+    assert!(script.contains("_claudectl_tasks"));
+    let rc = fs::read_to_string(&bashrc).unwrap();
+    assert!(rc.starts_with("export EDITOR=vim\n"));
+    assert!(rc.contains("# Added by claudectl"));
+    assert!(rc.contains(&completion_file.display().to_string()));
+    assert_eq!(
+        fs::read_to_string(home.join(".bashrc.claudectl-backup")).unwrap(),
+        "export EDITOR=vim\n"
+    );
+
+    // Installing again leaves the startup file alone
+    assert!(completions("--install").status.success());
+    assert_eq!(fs::read_to_string(&bashrc).unwrap(), rc);
+
+    let output = completions("--uninstall");
+    assert!(output.status.success());
+    assert!(!completion_file.exists());
+    assert_eq!(fs::read_to_string(&bashrc).unwrap(), "export EDITOR=vim\n");
+}
@@ -0,0 +1,88 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{claudectl, init_project};
+
+#[test]
+fn test_config_set_and_get_project_setting() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["config", "set", "permissions.mode", "plan"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let config = fs::read_to_string(repo.join(".claudectl/config.json")).unwrap();
+    assert!(config.contains("\"mode\": \"plan\""));
+    assert!(config.contains("\"project_name\": \"test-project\""));
+
+    let output = claudectl(&repo)
+        .args(["config", "get", "permissions.mode"])
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "plan\n");
+
+    let output = claudectl(&repo)
+        .args(["--plain", "config", "list"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("permissions.mode = \"plan\""));
+    assert!(stdout.contains("context.max_chars = 20000"));
+}
+
+#[test]
+fn test_config_set_validates_values_and_keys() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let before = fs::read_to_string(repo.join(".claudectl/config.json")).unwrap();
+
+    let output = claudectl(&repo)
+        .args(["config", "set", "progress", "sometimes"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid value for 'progress'"));
+
+    let output = claudectl(&repo)
+        .args(["config", "set", "colour", "blue"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown config key 'colour'"));
+
+    let after = fs::read_to_string(repo.join(".claudectl/config.json")).unwrap();
+    assert_eq!(before, after);
+}
+
+#[test]
+fn test_config_set_global_creates_global_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let config_home = temp_dir.path().join("config");
+
+    let output = claudectl(&repo)
+        .args(["config", "set", "--global", "profiles.work.model", "opus"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let global = fs::read_to_string(config_home.join("claudectl/config.json")).unwrap();
+    assert!(global.contains("\"model\": \"opus\""));
+
+    let output = claudectl(&repo)
+        .args(["config", "get", "--global", "profiles.work.model"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "opus\n");
+}
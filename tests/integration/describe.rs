@@ -0,0 +1,84 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, git, init_project};
+
+#[test]
+fn test_describe_assembles_commits_and_notes() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/described");
+    for (file, message) in [("a.txt", "add a"), ("b.txt", "add b")] {
+        fs::write(worktree.join(file), "content\n").unwrap();
+        git(&worktree, &["add", file]);
+        git(&worktree, &["commit", "-q", "-m", message]);
+    }
+    claudectl(&repo)
+        .args(["note", "feat/described", "needs a second look"])
+        .assert()
+        .success();
+
+    let output = claudectl(&repo)
+        .args(["describe", "feat/described"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "## Changes\n\n- add a\n- add b\n\n## Notes\n\n- needs a second look\n"
+    );
+}
+
+#[test]
+fn test_describe_uses_project_template() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/templated");
+    fs::write(
+        repo.join("PR_TEMPLATE.md"),
+        "Closes nothing yet.\n\nTask: {task}\n",
+    )
+    .unwrap();
+    let config_path = repo.join(".claudectl/config.json");
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        r#""project_name""#,
+        r#""pr_template": "PR_TEMPLATE.md", "project_name""#,
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let output = claudectl(&repo)
+        .args(["describe", "feat/templated"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Closes nothing yet.\n\nTask: feat/templated\n"
+    );
+}
+
+#[test]
+fn test_describe_reports_missing_template() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/untemplated");
+    let config_path = repo.join(".claudectl/config.json");
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        r#""project_name""#,
+        r#""pr_template": "MISSING.md", "project_name""#,
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let output = claudectl(&repo)
+        .args(["describe", "feat/untemplated"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to read PR template"));
+    assert!(stderr.contains("MISSING.md"));
+}
@@ -0,0 +1,85 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, git, init_project};
+
+#[test]
+fn test_diff_includes_committed_and_uncommitted_work() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/diffed");
+    fs::write(worktree.join("done.txt"), "committed line\n").unwrap();
+    git(&worktree, &["add", "done.txt"]);
+    git(&worktree, &["commit", "-q", "-m", "add done"]);
+    fs::write(worktree.join("wip.txt"), "draft line\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["diff", "feat/diffed"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("+committed line"));
+    assert!(stdout.contains("+draft line"));
+
+    let output = claudectl(&repo)
+        .args(["diff", "feat/diffed", "--stat"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2 files changed"));
+    assert!(!stdout.contains("+draft line"));
+}
+
+#[test]
+fn test_diff_name_only_against_another_base() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    git(&repo, &["checkout", "-q", "-b", "develop"]);
+    fs::write(repo.join("develop.txt"), "develop\n").unwrap();
+    git(&repo, &["add", "develop.txt"]);
+    git(&repo, &["commit", "-q", "-m", "develop work"]);
+    git(&repo, &["checkout", "-q", "main"]);
+    let worktree = add_task(&repo, "feat/on-develop");
+    git(&worktree, &["reset", "-q", "--hard", "develop"]);
+    fs::write(worktree.join("task.txt"), "task\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["diff", "feat/on-develop", "--name-only"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        ["develop.txt", "task.txt"]
+    );
+
+    let output = claudectl(&repo)
+        .args([
+            "diff",
+            "feat/on-develop",
+            "--name-only",
+            "--base",
+            "develop",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "task.txt");
+}
+
+#[test]
+fn test_diff_reports_no_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/untouched");
+
+    let output = claudectl(&repo)
+        .args(["diff", "feat/untouched"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No changes in 'feat/untouched'"));
+}
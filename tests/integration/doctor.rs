@@ -0,0 +1,93 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_origin, claudectl, init_project};
+
+#[test]
+fn test_doctor_project_reports_missing_prerequisites() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["--plain", "doctor", "--project"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("git remote add origin"));
+    assert!(stdout.contains("Add a CLAUDE.md"));
+    assert!(stdout.contains("Add setup_commands"));
+    assert!(!stdout.contains("agent installed"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 check(s) failed"));
+}
+
+#[test]
+fn test_doctor_project_passes_when_set_up() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    fs::write(repo.join("CLAUDE.md"), "# Instructions\n").unwrap();
+    let config = fs::read_to_string(repo.join(".claudectl/config.json")).unwrap();
+    fs::write(
+        repo.join(".claudectl/config.json"),
+        config.replacen('{', r#"{ "setup_commands": ["true"],"#, 1),
+    )
+    .unwrap();
+    // As `init` registers it
+    fs::write(
+        temp_dir.path().join("worktrees/project.json"),
+        format!(r#"{{ "repo_path": "{}" }}"#, repo.display()),
+    )
+    .unwrap();
+
+    let output = claudectl(&repo)
+        .args(["--plain", "doctor", "--project"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("warning"));
+    assert!(stdout.contains("1 defined"));
+}
+
+#[test]
+fn test_doctor_outside_a_project_suggests_init() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["--plain", "doctor", "--project"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("claudectl init"));
+}
+
+#[test]
+fn test_doctor_migrate_registers_the_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let config_home = temp_dir.path().join("config");
+
+    let output = claudectl(&repo)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["--plain", "doctor", "--project"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Found unregistered project"));
+    assert!(stdout.contains("claudectl doctor --migrate"));
+
+    let output = claudectl(&repo)
+        .env("XDG_CONFIG_HOME", &config_home)
+        .args(["--plain", "doctor", "--project", "--migrate"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Registered"));
+    assert!(stdout.contains("No files from older releases"));
+    assert!(temp_dir.path().join("worktrees/project.json").exists());
+}
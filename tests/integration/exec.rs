@@ -0,0 +1,60 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project};
+
+#[test]
+fn test_exec_runs_in_worktree() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/exec");
+
+    let output = claudectl(&repo)
+        .args([
+            "exec",
+            "feat/exec",
+            "--",
+            "sh",
+            "-c",
+            "pwd; echo \"$CLAUDECTL_TASK\" > task.txt",
+        ])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("worktrees/feat/exec"));
+    assert_eq!(
+        fs::read_to_string(worktree.join("task.txt")).unwrap(),
+        "feat/exec\n"
+    );
+}
+
+#[test]
+fn test_exec_passes_on_exit_code() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/failing");
+
+    let output = claudectl(&repo)
+        .args(["exec", "feat/failing", "--", "sh", "-c", "exit 42"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(42));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("exited with code 42"));
+}
+
+#[test]
+fn test_exec_fails_for_unknown_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["exec", "feat/missing", "--", "true"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 'feat/missing' not found"));
+}
@@ -0,0 +1,135 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{claudectl, git, init_project, register_project};
+
+#[test]
+fn test_export_and_import_move_projects_between_machines() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let old_home = temp_dir.path().join("old-config");
+    let new_home = temp_dir.path().join("new-config");
+    register_project(&old_home, "test-project", &repo);
+    fs::write(
+        old_home.join("claudectl/config.json"),
+        r#"{ "profiles": { "work": { "model": "opus", "env": { "ANTHROPIC_API_KEY": "sk-secret" } } } }"#,
+    )
+    .unwrap();
+    fs::create_dir_all(repo.join(".claudectl/tasks")).unwrap();
+    fs::write(
+        repo.join(".claudectl/tasks/feat__login.json"),
+        r#"{ "name": "feat/login", "tags": ["auth"] }"#,
+    )
+    .unwrap();
+    fs::write(
+        repo.join(".claudectl/events.jsonl"),
+        "{\"at\":\"2024-09-01T16:03:00Z\",\"kind\":\"task_created\",\"task\":\"feat/login\"}\n",
+    )
+    .unwrap();
+
+    let export = temp_dir.path().join("claudectl.json");
+    let output = claudectl(&repo)
+        .env("XDG_CONFIG_HOME", &old_home)
+        .args(["--plain", "export"])
+        .arg(&export)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Exported 1 project(s)"));
+    let exported = fs::read_to_string(&export).unwrap();
+    assert!(exported.contains("feat/login"));
+    assert!(!exported.contains("sk-secret"));
+
+    // The other machine keeps its code somewhere else
+    let moved = temp_dir.path().join("moved");
+    fs::create_dir(&moved).unwrap();
+    git(&moved, &["clone", "-q", &repo.to_string_lossy(), "repo"]);
+    let new_repo = moved.join("repo");
+    let map = format!("{}={}", temp_dir.path().display(), moved.display());
+    let output = claudectl(&new_repo)
+        .env("XDG_CONFIG_HOME", &new_home)
+        .args(["--plain", "import", "--map", &map])
+        .arg(&export)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Imported 1 project(s)"), "{stdout}");
+    assert!(
+        stdout.contains("Added 1 profile(s) and filter(s)"),
+        "{stdout}"
+    );
+
+    let registration =
+        fs::read_to_string(new_home.join("claudectl/projects/test-project/project.json")).unwrap();
+    assert!(registration.contains(&*new_repo.to_string_lossy()));
+    let config = fs::read_to_string(new_repo.join(".claudectl/config.json")).unwrap();
+    assert!(
+        config.contains(
+            &*new_home
+                .join("claudectl/projects/test-project")
+                .to_string_lossy()
+        )
+    );
+    assert!(new_repo.join(".claudectl/tasks/feat__login.json").exists());
+    let output = claudectl(&new_repo)
+        .env("XDG_CONFIG_HOME", &new_home)
+        .args(["history", "--task", "feat/login"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("task created"));
+
+    // Importing again leaves what is already there alone
+    let output = claudectl(&new_repo)
+        .env("XDG_CONFIG_HOME", &new_home)
+        .args(["--plain", "import", "--map", &map])
+        .arg(&export)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(new_repo.join(".claudectl/events.jsonl"))
+            .unwrap()
+            .lines()
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_import_reports_missing_repositories() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let export = temp_dir.path().join("claudectl.json");
+    fs::write(
+        &export,
+        r#"{
+            "version": 1,
+            "exported_at": "2024-09-01T16:03:00Z",
+            "projects": [{ "name": "gone", "repo_path": "/nonexistent/gone" }]
+        }"#,
+    )
+    .unwrap();
+
+    let output = claudectl(&repo)
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .args(["--plain", "import"])
+        .arg(&export)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("No git repository at /nonexistent/gone")
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("1 of 1 project(s) could not be imported")
+    );
+}
@@ -0,0 +1,156 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project};
+
+#[test]
+fn test_history_fails_without_config() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join(".git")).unwrap();
+
+    let output = claudectl(temp_dir.path()).arg("history").output().unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Configuration file not found"));
+}
+
+#[test]
+fn test_history_rejects_invalid_dates() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["history", "--since", "yesterday"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("YYYY-MM-DD"));
+}
+
+#[test]
+fn test_history_lists_and_filters_events() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/one");
+    add_task(&repo, "feat/two");
+
+    for (task, text) in [("feat/one", "first"), ("feat/two", "second")] {
+        let output = claudectl(&repo)
+            .args(["note", task, text])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    }
+
+    let output = claudectl(&repo).arg("history").output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("first"));
+    assert!(stdout.contains("second"));
+
+    let output = claudectl(&repo)
+        .args(["history", "--task", "feat/two", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let events: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let events = events.as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["kind"], "note_added");
+    assert_eq!(events[0]["detail"], "second");
+
+    let output = claudectl(&repo)
+        .args(["history", "--until", "2000-01-01"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No matching events"));
+}
+
+#[test]
+fn test_history_uses_relative_timestamps_when_configured() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/recent");
+    let config_path = repo.join(".claudectl/config.json");
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        r#""project_name""#,
+        r#""timestamps": "relative", "project_name""#,
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let output = claudectl(&repo)
+        .args(["note", "feat/recent", "just started"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = claudectl(&repo).arg("history").output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("just now"));
+}
+
+#[test]
+fn test_history_skips_unreadable_event_log_lines() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    fs::write(
+        repo.join(".claudectl/events.jsonl"),
+        concat!(
+            r#"{"at":"2024-03-05T12:00:00Z","kind":"note_added","task":"feat/a","detail":"kept"}"#,
+            "\n",
+            r#"{"at":"2024-03-05T13:00:00Z","kind":"note_"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    let output = claudectl(&repo)
+        .args(["history", "--json"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let events: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let events = events.as_array().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0]["detail"], "kept");
+}
+
+#[test]
+fn test_history_heatmap_counts_sessions_per_day() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let events = [
+        ("2024-03-05T12:00:00Z", "session_started"),
+        ("2024-03-05T13:00:00Z", "session_started"),
+        ("2024-03-05T14:00:00Z", "note_added"),
+        ("2024-03-07T12:00:00Z", "session_started"),
+        ("2024-04-01T12:00:00Z", "session_started"),
+    ]
+    .map(|(at, kind)| format!(r#"{{"at":"{at}","kind":"{kind}","task":"feat/a"}}"#))
+    .join("\n");
+    fs::write(repo.join(".claudectl/events.jsonl"), events + "\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["--plain", "history", "--heatmap"])
+        .args(["--since", "2024-03-04", "--until", "2024-03-10"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Sessions started per day, 2024-03-04 to 2024-03-10"));
+    assert!(stdout.contains("Tue  2"));
+    assert!(stdout.contains("Thu  1"));
+    assert!(stdout.contains("3 session(s) on 2 day(s); busiest was 2024-03-05 with 2"));
+}
@@ -2,6 +2,8 @@ use assert_cmd::Command;
 use std::fs;
 use tempfile::TempDir;
 
+use crate::common::{FakeClaude, add_origin, claudectl, git};
+
 #[test]
 fn test_init_in_git_repo() {
     let temp_dir = TempDir::new().unwrap();
@@ -68,3 +70,209 @@ fn test_init_shows_help_message() {
     assert!(stdout.contains("Initializing project"));
     assert!(stdout.contains("Verifying dependencies"));
 }
+
+#[test]
+fn test_init_rejects_invalid_permission_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join(".git")).unwrap();
+
+    let mut cmd = Command::cargo_bin("claudectl").unwrap();
+    let output = cmd
+        .args(["init", "--permission-mode", "yolo"])
+        .current_dir(&temp_dir)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invalid value"));
+}
+
+#[test]
+fn test_init_subproject_sessions_start_in_sparse_worktree() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    for dir in ["packages/api", "packages/web"] {
+        fs::create_dir_all(repo.join(dir)).unwrap();
+        fs::write(repo.join(dir).join("lib.rs"), "\n").unwrap();
+    }
+    fs::write(repo.join("README.md"), "hello\n").unwrap();
+    git(&repo, &["init", "-q", "-b", "main"]);
+    git(&repo, &["add", "."]);
+    git(&repo, &["commit", "-q", "-m", "initial"]);
+    add_origin(&repo);
+    let claude = FakeClaude::new(&temp_dir).install();
+    let config_home = temp_dir.path().join("config");
+
+    let output = claudectl(&repo)
+        .args(["init", "--subproject", "packages/api"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let config = fs::read_to_string(repo.join("packages/api/.claudectl/config.json")).unwrap();
+    assert!(config.contains(r#""subproject": "packages/api""#));
+
+    let output = claudectl(&repo.join("packages/api"))
+        .args(["task", "feat/api", "--start"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let call = claude.last_invocation();
+    assert!(call.cwd.ends_with("feat/api/packages/api"));
+    let worktree = call.cwd.parent().unwrap().parent().unwrap();
+    assert!(worktree.join("README.md").exists());
+    assert!(!worktree.join("packages/web").exists());
+}
+
+#[test]
+fn test_init_recipe_prepares_new_worktrees() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    fs::create_dir_all(&repo).unwrap();
+    fs::write(repo.join("Cargo.toml"), "[package]\n").unwrap();
+    git(&repo, &["init", "-q", "-b", "main"]);
+    git(&repo, &["add", "."]);
+    git(&repo, &["commit", "-q", "-m", "initial"]);
+    add_origin(&repo);
+    let claude = FakeClaude::new(&temp_dir).install();
+    let config_home = temp_dir.path().join("config");
+
+    let output = claudectl(&repo)
+        .args(["init", "--recipe", "rust"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let config = fs::read_to_string(repo.join(".claudectl/config.json")).unwrap();
+    assert!(config.contains(r#""setup_recipes": ["#));
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/shared-target"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let worktree = config_home.join("claudectl/projects/repo/feat/shared-target");
+    let target = fs::read_link(worktree.join("target")).unwrap();
+    assert_eq!(target, repo.join("target"));
+    assert_eq!(git(&worktree, &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn test_init_rejects_unknown_recipe() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "-q", "-b", "main"]);
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["init", "--recipe", "haskell"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown setup recipe 'haskell'"));
+}
+
+#[test]
+fn test_init_template_seeds_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    let template = repo.join(".claudectl/templates/web");
+    fs::create_dir_all(template.join("hooks")).unwrap();
+    fs::write(
+        template.join("config.json"),
+        r#"{ "permissions": { "allowed_tools": ["Bash(pnpm:*)"] }, "default_task_kind": "feature" }"#,
+    )
+    .unwrap();
+    fs::write(template.join("CLAUDE.md"), "# Web conventions\n").unwrap();
+    fs::write(
+        template.join("hooks/install.sh"),
+        "#!/bin/sh\ntouch installed\n",
+    )
+    .unwrap();
+    git(&repo, &["init", "-q", "-b", "main"]);
+    git(&repo, &["add", "."]);
+    git(&repo, &["commit", "-q", "-m", "initial"]);
+    add_origin(&repo);
+    let claude = FakeClaude::new(&temp_dir).install();
+    let config_home = temp_dir.path().join("config");
+
+    let output = claudectl(&repo)
+        .args(["init", "--template", "web"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Added CLAUDE.md"));
+    let config = fs::read_to_string(repo.join(".claudectl/config.json")).unwrap();
+    assert!(config.contains("Bash(pnpm:*)"));
+    assert_eq!(
+        fs::read_to_string(repo.join("CLAUDE.md")).unwrap(),
+        "# Web conventions\n"
+    );
+
+    // New tasks get the template's naming and run its hooks
+    let output = claudectl(&repo)
+        .args(["task", "login"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let worktree = config_home.join("claudectl/projects/repo/feat/login");
+    assert!(worktree.join("installed").exists());
+    assert!(worktree.join("CLAUDE.md").exists());
+}
+
+#[test]
+fn test_init_rejects_unknown_template() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repo");
+    fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "-q", "-b", "main"]);
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["init", "--template", "missing"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown template 'missing'"));
+    assert!(!repo.join(".claudectl/config.json").exists());
+}
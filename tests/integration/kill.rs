@@ -0,0 +1,237 @@
+use std::os::unix::fs::PermissionsExt;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, claudectl_process, hold_task, init_project, lock_exists};
+
+#[test]
+fn test_kill_stops_session_and_clears_lock() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/busy");
+    let (_, reaper) = hold_task(&repo, "feat/busy", "sleep 30; true");
+
+    let output = claudectl(&repo)
+        .args(["kill", "feat/busy"])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Stopped the session in"));
+    reaper.join().unwrap();
+    assert!(!lock_exists(&repo, "feat/busy"));
+}
+
+#[test]
+fn test_kill_sends_sigkill_when_sigterm_is_ignored() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/stubborn");
+    let (_, reaper) = hold_task(&repo, "feat/stubborn", "trap '' TERM; sleep 30; true");
+
+    let output = claudectl(&repo)
+        .args(["kill", "feat/stubborn", "--timeout", "1"])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("after it ignored SIGTERM"));
+    reaper.join().unwrap();
+    assert!(!lock_exists(&repo, "feat/stubborn"));
+}
+
+#[test]
+fn test_kill_waits_for_the_configured_stop_timeout() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/stubborn");
+    claudectl(&repo)
+        .args(["config", "set", "stop_timeout", "1"])
+        .assert()
+        .success();
+    let (_, reaper) = hold_task(&repo, "feat/stubborn", "trap '' TERM; sleep 30; true");
+
+    let started = Instant::now();
+    let output = claudectl(&repo)
+        .args(["kill", "feat/stubborn"])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("after it ignored SIGTERM"));
+    assert!(started.elapsed() < Duration::from_secs(8));
+    reaper.join().unwrap();
+}
+
+#[test]
+fn test_kill_fails_without_running_session() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/idle");
+
+    let output = claudectl(&repo)
+        .args(["kill", "feat/idle"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("No session is running in task 'feat/idle'")
+    );
+
+    let output = claudectl(&repo).args(["kill", "--all"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No sessions are running"));
+}
+
+#[test]
+fn test_kill_leaves_alone_a_process_that_reused_the_sessions_pid() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/gone");
+    // The session exited, and an unrelated process now has its pid
+    let mut unrelated = std::process::Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .unwrap();
+    std::fs::create_dir_all(repo.join(".claudectl/locks")).unwrap();
+    std::fs::write(
+        repo.join(".claudectl/locks/feat__gone.json"),
+        format!(
+            r#"{{ "pid": {}, "command": "task --start", "started_at": "2024-09-01T16:03:00Z" }}"#,
+            unrelated.id()
+        ),
+    )
+    .unwrap();
+
+    let output = claudectl(&repo)
+        .args(["kill", "feat/gone"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("No session is running in task 'feat/gone'")
+    );
+    assert!(unrelated.try_wait().unwrap().is_none());
+    unrelated.kill().unwrap();
+    unrelated.wait().unwrap();
+}
+
+#[test]
+fn test_kill_records_session_as_stopped_not_crashed() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/busy");
+    let (_, reaper) = hold_task(&repo, "feat/busy", "sleep 30; true");
+
+    claudectl(&repo)
+        .args(["kill", "feat/busy"])
+        .assert()
+        .success();
+    reaper.join().unwrap();
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/busy", "--json"])
+        .output()
+        .unwrap();
+    let details: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(details["last_session"]["stopped"], true);
+    assert_ne!(details["status"], "crashed");
+}
+
+#[test]
+fn test_kill_runs_the_on_session_exit_hook() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/hooked");
+    let hook = repo.join(".claudectl/hooks/on-session-exit");
+    std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+    std::fs::write(
+        &hook,
+        "#!/bin/sh\necho \"$CLAUDECTL_TASK $CLAUDECTL_STATUS\" >> \"$CLAUDECTL_MAIN_CHECKOUT/exited\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let (_, reaper) = hold_task(&repo, "feat/hooked", "sleep 30; true");
+
+    claudectl(&repo)
+        .args(["kill", "feat/hooked"])
+        .assert()
+        .success();
+
+    reaper.join().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(repo.join("exited")).unwrap(),
+        "feat/hooked stopped\n"
+    );
+}
+
+#[test]
+fn test_killed_claudectl_session_runs_its_exit_hooks_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/hooked");
+    let hooks = repo.join(".claudectl/hooks");
+    std::fs::create_dir_all(&hooks).unwrap();
+    for name in ["post-session", "on-session-exit"] {
+        std::fs::write(
+            hooks.join(name),
+            "#!/bin/sh\necho \"$CLAUDECTL_HOOK $CLAUDECTL_STATUS\" >> \"$CLAUDECTL_MAIN_CHECKOUT/hooks.log\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(hooks.join(name), std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let bin = temp_dir.path().join("bin");
+    std::fs::create_dir_all(&bin).unwrap();
+    std::fs::write(bin.join("claude"), "#!/bin/sh\nexec sleep 30\n").unwrap();
+    std::fs::set_permissions(bin.join("claude"), std::fs::Permissions::from_mode(0o755)).unwrap();
+    let mut session = claudectl_process(&repo)
+        .args(["restart", "feat/hooked"])
+        .env(
+            "PATH",
+            format!(
+                "{}:{}",
+                bin.display(),
+                std::env::var("PATH").unwrap_or_default()
+            ),
+        )
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    // Reaped as soon as it exits, so `kill` does not wait on a zombie
+    let reaper = std::thread::spawn(move || session.wait().unwrap());
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !lock_exists(&repo, "feat/hooked") && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    claudectl(&repo)
+        .args(["kill", "feat/hooked"])
+        .assert()
+        .success();
+
+    reaper.join().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(repo.join("hooks.log")).unwrap(),
+        "post-session stopped\non-session-exit stopped\n"
+    );
+}
@@ -2,6 +2,8 @@ use assert_cmd::Command;
 use std::fs;
 use tempfile::TempDir;
 
+use crate::common::{add_origin, add_task, claudectl, git, init_project};
+
 #[test]
 fn test_list_command_fails_without_git_repo() {
     let temp_dir = TempDir::new().unwrap();
@@ -153,3 +155,256 @@ fn test_list_command_shows_initialization_message() {
         );
     }
 }
+
+#[test]
+fn test_list_filters_by_tag_and_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+
+    for args in [
+        vec!["task", "feat/api", "--tag", "backend"],
+        vec!["task", "feat/ui", "--tag", "frontend"],
+    ] {
+        let output = claudectl(&repo).args(args).output().unwrap();
+        assert!(output.status.success());
+    }
+
+    let output = claudectl(&repo)
+        .args(["list", "--tag", "backend"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("feat/api"));
+    assert!(!stdout.contains("feat/ui"));
+
+    let output = claudectl(&repo)
+        .args(["list", "--project", "other-project"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("feat/"));
+}
+
+#[test]
+fn test_list_applies_saved_filter() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    let config_home = temp_dir.path().join("config");
+    fs::create_dir_all(config_home.join("claudectl")).unwrap();
+    fs::write(
+        config_home.join("claudectl/config.json"),
+        r#"{ "filters": { "urgent": { "tag": "urgent", "status": "ready" } } }"#,
+    )
+    .unwrap();
+
+    for args in [
+        vec!["task", "feat/hot", "--tag", "urgent"],
+        vec!["task", "feat/cold", "--tag", "background"],
+    ] {
+        let output = claudectl(&repo).args(args).output().unwrap();
+        assert!(output.status.success());
+    }
+
+    let output = claudectl(&repo)
+        .args(["list", "--filter", "urgent"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("feat/hot"));
+    assert!(!stdout.contains("feat/cold"));
+
+    // Options given on the command line override the saved filter's fields
+    let output = claudectl(&repo)
+        .args(["list", "--filter", "urgent", "--tag", "background"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("feat/cold"));
+    assert!(!stdout.contains("feat/hot"));
+
+    let output = claudectl(&repo)
+        .args(["list", "--filter", "missing"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown filter 'missing'"));
+}
+
+#[test]
+fn test_list_sorts_by_name() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/zeta");
+    add_task(&repo, "feat/alpha");
+
+    let output = claudectl(&repo)
+        .args(["list", "--sort", "name"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.find("feat/alpha").unwrap() < stdout.find("feat/zeta").unwrap());
+}
+
+#[test]
+fn test_list_rejects_unknown_sort_key() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["list", "--sort", "cost"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid value"));
+}
+
+#[test]
+fn test_list_filters_by_kind() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+
+    for args in [
+        vec!["task", "--kind", "bugfix", "login-crash"],
+        vec!["task", "feat/signup"],
+        vec!["task", "--kind", "experiment", "feat/cache-idea"],
+    ] {
+        let output = claudectl(&repo).args(args).output().unwrap();
+        assert!(output.status.success());
+    }
+
+    let output = claudectl(&repo)
+        .args(["list", "--kind", "bugfix"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("fix/login-crash"));
+    assert!(!stdout.contains("feat/"));
+
+    // An explicit kind wins over the branch prefix
+    let output = claudectl(&repo)
+        .args(["list", "--kind", "feature"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("feat/signup"));
+    assert!(!stdout.contains("feat/cache-idea"));
+}
+
+#[test]
+fn test_list_plain_output_has_no_colors_or_icons() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/plain");
+
+    let output = claudectl(&repo).args(["--plain", "list"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("feat/plain"));
+    assert!(!stdout.contains('\x1b'));
+    assert!(!stdout.contains('─'));
+    assert!(!stdout.contains('●'));
+}
+
+#[test]
+fn test_plain_errors_are_prefixed() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["show", "--plain", "feat/missing"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Error: "));
+    assert!(!stderr.contains('\x1b'));
+}
+
+#[test]
+fn test_list_command_prints_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/login");
+
+    let output = claudectl(&repo).args(["list", "--json"]).output().unwrap();
+
+    assert!(output.status.success());
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let task = tasks
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|task| task["name"] == "feat/login")
+        .unwrap();
+    assert_eq!(task["kind"], "feature");
+    assert_eq!(task["project"], "test-project");
+    assert_eq!(task["session"], serde_json::Value::Null);
+    assert!(task["worktree"].as_str().unwrap().ends_with("feat/login"));
+
+    let format = claudectl(&repo)
+        .args(["list", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(format.stdout, output.stdout);
+}
+
+#[test]
+fn test_list_leaves_out_other_subprojects_tasks() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/login");
+    // A task of another sub-project of the same repository
+    let other = temp_dir.path().join("other-worktrees/feat/web");
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feat/web",
+            &other.to_string_lossy(),
+            "HEAD",
+        ],
+    );
+
+    for args in [["list", "--json"], ["status", "--json"]] {
+        let output = claudectl(&repo).args(args).output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains("feat/login"), "{stdout}");
+        assert!(!stdout.contains("feat/web"), "{stdout}");
+    }
+}
+
+#[test]
+fn test_list_command_prints_tsv() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "fix/crash");
+
+    let output = claudectl(&repo)
+        .args(["list", "--format", "tsv", "--kind", "bugfix"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("name\tstatus"));
+    assert!(lines[1].starts_with("fix/crash\t"));
+    assert!(!stdout.contains('\x1b'));
+}
@@ -0,0 +1,48 @@
+use tempfile::TempDir;
+
+use crate::common::{claudectl, init_project};
+
+#[test]
+fn test_logs_show_earlier_runs() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let config_home = temp_dir.path().join("config");
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/missing"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(config_home.join("claudectl/claudectl.log").exists());
+
+    let output = claudectl(&repo)
+        .args(["logs"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Command failed"), "{stdout}");
+
+    let output = claudectl(&repo)
+        .args(["logs", "-n", "1"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 1);
+}
+
+#[test]
+fn test_logs_without_a_log() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config");
+
+    let output = claudectl(temp_dir.path())
+        .args(["logs", "--path"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("claudectl.log"));
+}
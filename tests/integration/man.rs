@@ -0,0 +1,62 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::claudectl;
+
+#[test]
+fn test_man_prints_a_page() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["man", "config", "set"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let page = String::from_utf8_lossy(&output.stdout);
+    assert!(page.contains(".TH claudectl-config-set 1"));
+    assert!(page.contains("Change a setting"));
+}
+
+#[test]
+fn test_man_rejects_unknown_commands() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["man", "config", "nope"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("No man page for 'claudectl config nope'")
+    );
+}
+
+#[test]
+fn test_man_install_writes_every_page() {
+    let temp_dir = TempDir::new().unwrap();
+    let data_home = temp_dir.path().join("data");
+
+    let output = claudectl(temp_dir.path())
+        .args(["man", "--install"])
+        .env("XDG_DATA_HOME", &data_home)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let man1 = data_home.join("man/man1");
+    assert!(man1.join("claudectl.1").exists());
+    assert!(man1.join("claudectl-task.1").exists());
+    assert!(man1.join("claudectl-worktree-sync.1").exists());
+    // Hidden commands get no page
+    let pages: Vec<String> = fs::read_dir(&man1)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert!(!pages.iter().any(|page| page.contains("__complete")));
+    assert!(
+        String::from_utf8_lossy(&output.stdout)
+            .contains(&format!("Wrote {} man page(s)", pages.len()))
+    );
+}
@@ -0,0 +1,164 @@
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+use crate::common::{add_origin, add_task, claudectl, git, init_project};
+
+fn commit_file(dir: &Path, file: &str, content: &str, message: &str) {
+    fs::write(dir.join(file), content).unwrap();
+    git(dir, &["add", file]);
+    git(dir, &["commit", "-q", "-m", message]);
+}
+
+#[test]
+fn test_merge_creates_merge_commit_and_deletes_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/done");
+    fs::write(worktree.join("feature.txt"), "draft\n").unwrap();
+    claudectl(&repo)
+        .args(["checkpoint", "feat/done"])
+        .assert()
+        .success();
+    commit_file(&worktree, "feature.txt", "done\n", "add feature");
+
+    let output = claudectl(&repo)
+        .args(["merge", "feat/done", "--delete"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(repo.join("feature.txt").exists());
+    assert_eq!(
+        git(&repo, &["rev-list", "--count", "--merges", "HEAD"]),
+        "1"
+    );
+    assert!(!worktree.exists());
+    assert_eq!(git(&repo, &["branch", "--list", "feat/done"]), "");
+    assert_eq!(
+        git(&repo, &["for-each-ref", "refs/claudectl/checkpoints"]),
+        ""
+    );
+
+    let output = claudectl(&repo).args(["history"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("merged"));
+    assert!(stdout.contains("into main"));
+}
+
+#[test]
+fn test_merge_rebase_fast_forwards_base() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/linear");
+    commit_file(&worktree, "feature.txt", "done\n", "add feature");
+    commit_file(&repo, "other.txt", "main moved on\n", "main change");
+
+    let output = claudectl(&repo)
+        .args(["merge", "feat/linear", "--rebase"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        git(&repo, &["rev-list", "--count", "--merges", "HEAD"]),
+        "0"
+    );
+    assert_eq!(git(&repo, &["log", "-1", "--format=%s"]), "add feature");
+    assert!(worktree.exists());
+}
+
+#[test]
+fn test_merge_rejects_uncommitted_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/dirty");
+    fs::write(worktree.join("wip.txt"), "wip\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["merge", "feat/dirty"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 uncommitted file(s)"));
+}
+
+#[test]
+fn test_merge_requires_base_checked_out() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/elsewhere");
+    commit_file(&worktree, "feature.txt", "done\n", "add feature");
+    git(&repo, &["checkout", "-q", "-b", "scratch"]);
+
+    let output = claudectl(&repo)
+        .args(["merge", "feat/elsewhere"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("The main checkout is on 'scratch'; check out 'main'")
+    );
+}
+
+#[test]
+fn test_merge_rebase_conflict_points_to_resolve() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/clash");
+    commit_file(&worktree, "README.md", "hello from task\n", "task edit");
+    commit_file(&repo, "README.md", "hello from main\n", "main edit");
+
+    let output = claudectl(&repo)
+        .args(["merge", "feat/clash", "--rebase"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("The rebase stopped on conflicts in 1 file(s)"));
+    assert!(stderr.contains("claudectl resolve feat/clash"));
+}
+
+#[test]
+fn test_merge_fetch_fast_forwards_base_from_origin() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let origin = add_origin(&repo);
+    let worktree = add_task(&repo, "feat/fresh");
+    commit_file(&worktree, "feature.txt", "done\n", "add feature");
+
+    // Someone else pushes to origin/main
+    let other = temp_dir.path().join("other");
+    git(
+        temp_dir.path(),
+        &[
+            "clone",
+            "-q",
+            "-b",
+            "main",
+            &origin.to_string_lossy(),
+            &other.to_string_lossy(),
+        ],
+    );
+    commit_file(&other, "upstream.txt", "upstream\n", "upstream change");
+    git(&other, &["push", "-q", "origin", "main"]);
+
+    let output = claudectl(&repo)
+        .args(["merge", "feat/fresh", "--fetch"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(repo.join("upstream.txt").exists());
+    assert!(repo.join("feature.txt").exists());
+}
@@ -1,4 +1,42 @@
+pub mod archive;
+pub mod checkpoint;
+pub mod clean;
+pub mod compare;
+pub mod complete;
+pub mod completions;
+pub mod config;
+pub mod describe;
+pub mod diff;
+pub mod doctor;
+pub mod exec;
+pub mod export;
+pub mod history;
 pub mod init;
+pub mod kill;
 pub mod list;
+pub mod logs;
+pub mod man;
+pub mod merge;
+pub mod note;
+pub mod open;
+pub mod overview;
+pub mod pr;
+pub mod reconcile;
+pub mod rename;
+pub mod resolve;
+pub mod restart;
 pub mod rm;
+pub mod rollback;
+pub mod run;
+pub mod setup;
+pub mod show;
+pub mod stats;
+pub mod status;
+pub mod storage;
+pub mod summarize;
+pub mod switch;
+pub mod sync_claude_md;
 pub mod task;
+pub mod upgrade;
+pub mod watch;
+pub mod worktree;
@@ -0,0 +1,73 @@
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project};
+
+#[test]
+fn test_note_fails_for_nonexistent_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["note", "missing-task", "hello"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Task 'missing-task' not found"));
+}
+
+#[test]
+fn test_note_rejects_empty_text() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/notes");
+
+    let output = claudectl(&repo)
+        .args(["note", "feat/notes", "   "])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Note text cannot be empty"));
+}
+
+#[test]
+fn test_note_added_and_listed() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/notes");
+
+    let output = claudectl(&repo)
+        .args(["note", "feat/notes"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No notes"));
+
+    for text in ["waiting on design input", "design approved"] {
+        let output = claudectl(&repo)
+            .args(["note", "feat/notes", text])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    }
+
+    let output = claudectl(&repo)
+        .args(["note", "feat/notes"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let first = stdout.find("waiting on design input").unwrap();
+    let second = stdout.find("design approved").unwrap();
+    assert!(first < second);
+
+    let output = claudectl(&repo)
+        .args(["list", "--verbose"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("design approved"));
+}
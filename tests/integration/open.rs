@@ -0,0 +1,104 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project};
+
+/// A stand-in for a shell or editor that records its working directory and
+/// arguments.
+fn recorder(temp_dir: &TempDir) -> (PathBuf, PathBuf) {
+    let log = temp_dir.path().join("recorded");
+    let script = temp_dir.path().join("recorder");
+    fs::write(
+        &script,
+        format!("#!/bin/sh\necho \"$PWD $*\" > '{}'\n", log.display()),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    (script, log)
+}
+
+fn canonical(path: &Path) -> String {
+    path.canonicalize().unwrap().to_string_lossy().to_string()
+}
+
+#[test]
+fn test_open_print_prints_worktree_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/open");
+
+    let output = claudectl(&repo)
+        .args(["open", "feat/open", "--print"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let printed = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(canonical(Path::new(printed.trim())), canonical(&worktree));
+}
+
+#[test]
+fn test_open_spawns_shell_in_worktree() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/shell");
+    let (script, log) = recorder(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["open", "feat/shell"])
+        .env("SHELL", &script)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let recorded = fs::read_to_string(log).unwrap();
+    assert_eq!(canonical(Path::new(recorded.trim())), canonical(&worktree));
+}
+
+#[test]
+fn test_open_editor_gets_worktree_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/edit");
+    let (script, log) = recorder(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["open", "feat/edit", "--editor"])
+        .env_remove("VISUAL")
+        .env("EDITOR", format!("{} --wait", script.display()))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let recorded = fs::read_to_string(log).unwrap();
+    assert!(recorded.contains(" --wait "));
+    assert!(recorded.trim_end().ends_with("worktrees/feat/edit"));
+}
+
+#[test]
+fn test_open_editor_requires_editor_variable() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/noeditor");
+
+    let output = claudectl(&repo)
+        .args(["open", "feat/noeditor", "--editor"])
+        .env_remove("VISUAL")
+        .env_remove("EDITOR")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("$EDITOR"));
+}
+
+#[test]
+fn test_open_fails_for_nonexistent_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["open", "feat/missing", "--print"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 'feat/missing' not found"));
+}
@@ -0,0 +1,70 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{FakeClaude, add_task, claudectl, init_project, register_project};
+
+#[test]
+fn test_overview_isolates_broken_projects() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config");
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/overview");
+    register_project(&config_home, "test-project", &repo);
+    register_project(&config_home, "gone", &temp_dir.path().join("missing"));
+
+    let output = claudectl(temp_dir.path())
+        .arg("overview")
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("test-project"));
+    assert!(stdout.contains("feat/overview"));
+    assert!(!stdout.contains(&repo.display().to_string()));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Skipped project 'gone'"));
+}
+
+#[test]
+fn test_overview_without_projects() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .arg("overview")
+        .env("XDG_CONFIG_HOME", temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No projects registered"));
+}
+
+#[test]
+fn test_init_registers_project_for_overview() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config");
+    let repo = init_project(&temp_dir);
+    fs::remove_dir_all(repo.join(".claudectl")).unwrap();
+    let claude = FakeClaude::new(&temp_dir).responds("1.0.0").install();
+
+    let output = claudectl(&repo)
+        .arg("init")
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    add_task(&repo, "feat/registered");
+
+    let output = claudectl(temp_dir.path())
+        .arg("overview")
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("feat/registered"));
+}
@@ -0,0 +1,109 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{FakeClaude, add_origin, claudectl, git, init_project};
+
+#[test]
+fn test_pr_pushes_and_opens_github_pull_request() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let origin = add_origin(&repo);
+    claudectl(&repo)
+        .args(["task", "Fix login crash", "--kind", "bugfix"])
+        .assert()
+        .success();
+    let worktree = temp_dir.path().join("worktrees/fix/fix-login-crash");
+    fs::write(worktree.join("fix.txt"), "fixed\n").unwrap();
+    git(&worktree, &["add", "fix.txt"]);
+    git(
+        &worktree,
+        &["commit", "-q", "-m", "guard against empty sessions"],
+    );
+    let gh = FakeClaude::named(&temp_dir, "gh")
+        .responds("https://github.com/owner/repo/pull/7")
+        .install();
+
+    let output = claudectl(&repo)
+        .args([
+            "pr",
+            "fix/fix-login-crash",
+            "--provider",
+            "github",
+            "--draft",
+        ])
+        .env("PATH", gh.path())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout)
+            .contains("Opened pull request: https://github.com/owner/repo/pull/7")
+    );
+    assert_eq!(
+        git(&origin, &["rev-parse", "fix/fix-login-crash"]),
+        git(&worktree, &["rev-parse", "HEAD"])
+    );
+    assert_eq!(
+        gh.last_invocation().args,
+        [
+            "pr",
+            "create",
+            "--title",
+            "Fix login crash",
+            "--body",
+            "## Changes\n\n- guard against empty sessions\n",
+            "--base",
+            "main",
+            "--head",
+            "fix/fix-login-crash",
+            "--draft"
+        ]
+    );
+
+    let output = claudectl(&repo).args(["history"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("pull request opened"));
+}
+
+#[test]
+fn test_pr_requires_known_provider() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    claudectl(&repo)
+        .args(["task", "feat/unhosted"])
+        .assert()
+        .success();
+
+    let output = claudectl(&repo)
+        .args(["pr", "feat/unhosted"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("pass --provider"));
+}
+
+#[test]
+fn test_pr_reports_cli_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    claudectl(&repo)
+        .args(["task", "feat/rejected"])
+        .assert()
+        .success();
+    let glab = FakeClaude::named(&temp_dir, "glab")
+        .fails(1, "you are not logged in")
+        .install();
+
+    let output = claudectl(&repo)
+        .args(["pr", "feat/rejected", "--provider", "gitlab"])
+        .env("PATH", glab.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("glab failed: you are not logged in"));
+}
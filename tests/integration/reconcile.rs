@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project};
+
+/// Leave a lock behind as a crashed `task --start` would.
+fn write_stale_lock(repo: &Path, file_name: &str) -> std::path::PathBuf {
+    let locks = repo.join(".claudectl/locks");
+    fs::create_dir_all(&locks).unwrap();
+    let path = locks.join(file_name);
+    // Far above any default pid_max, so never a running process
+    fs::write(
+        &path,
+        r#"{ "pid": 99999999, "command": "task --start", "started_at": "2024-09-01T16:03:00Z" }"#,
+    )
+    .unwrap();
+    path
+}
+
+#[test]
+fn test_reconcile_reports_stale_sessions_and_needs_a_choice() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/crashed");
+    let lock = write_stale_lock(&repo, "feat__crashed.json");
+
+    let output = claudectl(&repo).arg("reconcile").output().unwrap();
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Found 1 session(s) that ended without claudectl noticing"));
+    assert!(stdout.contains("feat/crashed"));
+    assert!(stdout.contains("task --start (pid 99999999), started"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("pass --stop"));
+    assert!(lock.exists());
+}
+
+#[test]
+fn test_reconcile_stop_clears_stale_sessions() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/crashed");
+    let lock = write_stale_lock(&repo, "feat__crashed.json");
+
+    let output = claudectl(&repo)
+        .args(["reconcile", "--stop"])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Marked 1 session(s) stopped"));
+    assert!(!lock.exists());
+
+    let output = claudectl(&repo)
+        .args(["history", "--task", "feat/crashed"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("session marked stopped"));
+
+    let output = claudectl(&repo).arg("reconcile").output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No stale sessions"));
+}
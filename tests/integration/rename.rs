@@ -0,0 +1,82 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, git, init_project};
+
+#[test]
+fn test_rename_moves_branch_worktree_and_records() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/login");
+    fs::write(worktree.join("wip.txt"), "work in progress\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["checkpoint", "feat/login"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let output = claudectl(&repo)
+        .args(["note", "feat/login", "keep the old flow"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = claudectl(&repo)
+        .args(["rename", "feat/login", "fix/Sign In"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("Renamed 'feat/login' to 'fix/sign-in'")
+    );
+
+    // The worktree moved with its uncommitted work and is on the new branch
+    let moved = temp_dir.path().join("worktrees/fix/sign-in");
+    assert!(!worktree.exists());
+    assert!(moved.join("wip.txt").exists());
+    assert_eq!(git(&moved, &["branch", "--show-current"]), "fix/sign-in");
+    assert!(git(&repo, &["branch", "--list", "feat/login"]).is_empty());
+
+    // Checkpoints, the task record and history follow the task
+    let refs = git(&repo, &["for-each-ref", "refs/claudectl/checkpoints"]);
+    assert!(refs.contains("checkpoints/fix/sign-in/1"));
+    assert!(!refs.contains("feat/login"));
+    assert!(!repo.join(".claudectl/tasks/feat__login.json").exists());
+    let record = fs::read_to_string(repo.join(".claudectl/tasks/fix__sign-in.json")).unwrap();
+    assert!(record.contains("keep the old flow"));
+
+    let output = claudectl(&repo)
+        .args(["--plain", "history", "--task", "fix/sign-in"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("note"));
+    assert!(stdout.contains("renamed from feat/login"));
+}
+
+#[test]
+fn test_rename_rejects_a_name_in_use() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/a");
+    add_task(&repo, "feat/b");
+
+    let output = claudectl(&repo)
+        .args(["rename", "feat/a", "feat/b"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 'feat/b' already exists"));
+    assert!(temp_dir.path().join("worktrees/feat/a").exists());
+
+    let output = claudectl(&repo)
+        .args(["rename", "feat/missing", "feat/c"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 'feat/missing' not found"));
+}
@@ -0,0 +1,121 @@
+use std::fs;
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+
+use crate::common::{FakeClaude, add_task, claudectl, git, hold_task, init_project};
+
+/// Commit conflicting edits to README.md on `main` and the task branch, then
+/// merge `main` into the task so it stops on the conflict.
+fn conflict(repo: &std::path::Path, worktree: &std::path::Path) {
+    fs::write(repo.join("README.md"), "hello from main\n").unwrap();
+    git(repo, &["commit", "-q", "-am", "main edit"]);
+    fs::write(worktree.join("README.md"), "hello from task\n").unwrap();
+    git(worktree, &["commit", "-q", "-am", "task edit"]);
+
+    let status = StdCommand::new("git")
+        .args([
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@claudectl.invalid",
+        ])
+        .args(["merge", "-q", "main"])
+        .current_dir(worktree)
+        .output()
+        .unwrap()
+        .status;
+    assert!(!status.success());
+}
+
+#[test]
+fn test_resolve_without_conflicts_does_nothing() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/calm");
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["resolve", "feat/calm"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("has no conflicts to resolve"));
+    assert!(claude.invocations().is_empty());
+}
+
+#[test]
+fn test_resolve_sends_hunks_and_reports_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/clash");
+    conflict(&repo, &worktree);
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["resolve", "feat/clash"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let invocation = claude.last_invocation();
+    assert!(invocation.cwd.ends_with("worktrees/feat/clash"));
+    let prompt = invocation.args.last().unwrap();
+    assert!(prompt.contains("## README.md"));
+    assert!(prompt.contains("hello from task"));
+    assert!(prompt.contains("hello from main"));
+
+    // The fake session leaves the conflict in place
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("README.md"));
+    assert!(stdout.contains("conflicted"));
+    assert!(stdout.contains("Resolved 0 of 1 conflicted file(s)"));
+}
+
+#[test]
+fn test_resolve_refuses_a_locked_task_without_force() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/busy");
+    conflict(&repo, &worktree);
+    let (pid, reaper) = hold_task(&repo, "feat/busy", "sleep 30");
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo).arg("list").output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Working"));
+    assert!(stdout.contains(&format!("locked by task --start (pid {pid})")));
+
+    let output = claudectl(&repo)
+        .args(["resolve", "feat/busy"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is locked by task --start"));
+    assert!(claude.invocations().is_empty());
+
+    let output = claudectl(&repo)
+        .args(["resolve", "feat/busy", "--force"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(claude.invocations().len(), 1);
+    // The forced session released its lock when it finished
+    assert!(!repo.join(".claudectl/locks/feat__busy.json").exists());
+
+    // End the stand-in session and its sleep
+    for program in ["pkill -P", "kill"] {
+        StdCommand::new("sh")
+            .args(["-c", &format!("{program} {pid}")])
+            .status()
+            .unwrap();
+    }
+    reaper.join().unwrap();
+}
@@ -0,0 +1,141 @@
+use tempfile::TempDir;
+
+use crate::common::{FakeClaude, add_task, claudectl, hold_task, init_project, lock_exists};
+
+#[test]
+fn test_restart_stops_session_and_continues_conversation() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/busy");
+    let (_, reaper) = hold_task(&repo, "feat/busy", "sleep 30; true");
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["restart", "feat/busy"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    reaper.join().unwrap();
+    let call = claude.last_invocation();
+    assert_eq!(call.cwd, worktree.canonicalize().unwrap());
+    assert_eq!(call.args, ["--permission-mode", "default", "--continue"]);
+    assert!(!lock_exists(&repo, "feat/busy"));
+}
+
+#[test]
+fn test_restart_starts_a_session_when_none_is_running() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/idle");
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["restart", "feat/idle"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No session was running in 'feat/idle'; starting one"));
+    let call = claude.last_invocation();
+    assert_eq!(call.cwd, worktree.canonicalize().unwrap());
+    assert_eq!(call.args, ["--permission-mode", "default", "--continue"]);
+}
+
+#[test]
+fn test_restart_fails_for_nonexistent_task_without_starting_claude() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["restart", "missing-task"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 'missing-task' not found"));
+    assert!(claude.invocations().is_empty());
+}
+
+#[test]
+fn test_restart_marks_task_crashed_until_a_session_ends_cleanly() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/flaky");
+    let failing = FakeClaude::new(&temp_dir).fails(2, "boom").install();
+
+    let output = claudectl(&repo)
+        .args(["restart", "feat/flaky"])
+        .env("PATH", failing.path())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("claude exited with code 2 in 'feat/flaky'")
+    );
+    let crashed = claudectl(&repo)
+        .args(["list", "--status", "crashed", "--format", "tsv"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&crashed.stdout).contains("feat/flaky\tcrashed"));
+
+    let temp_ok = TempDir::new().unwrap();
+    let succeeding = FakeClaude::new(&temp_ok).install();
+    claudectl(&repo)
+        .args(["restart", "feat/flaky"])
+        .env("PATH", succeeding.path())
+        .assert()
+        .success();
+    let crashed = claudectl(&repo)
+        .args(["list", "--status", "crashed", "--format", "tsv"])
+        .output()
+        .unwrap();
+    assert!(!String::from_utf8_lossy(&crashed.stdout).contains("feat/flaky"));
+}
+
+#[test]
+fn test_restart_waits_in_queue_while_max_sessions_are_running() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/busy");
+    add_task(&repo, "feat/next");
+    claudectl(&repo)
+        .args(["config", "set", "max_sessions", "1"])
+        .assert()
+        .success();
+    let (_, reaper) = hold_task(&repo, "feat/busy", "sleep 2; true");
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["restart", "feat/next"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    reaper.join().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("'feat/next' starts when a slot is free")
+    );
+    assert!(
+        claude
+            .last_invocation()
+            .args
+            .contains(&"--continue".to_string())
+    );
+}
@@ -1,7 +1,16 @@
 use assert_cmd::Command;
 use std::fs;
+use std::path::Path;
 use tempfile::TempDir;
 
+use crate::common::{add_task, claudectl, git, hold_task, init_project};
+
+fn commit_file(dir: &Path, file: &str, content: &str, message: &str) {
+    fs::write(dir.join(file), content).unwrap();
+    git(dir, &["add", file]);
+    git(dir, &["commit", "-q", "-m", message]);
+}
+
 #[test]
 fn test_rm_command_fails_without_git_repo() {
     let temp_dir = TempDir::new().unwrap();
@@ -83,14 +92,163 @@ fn test_rm_command_fails_for_nonexistent_task() {
 }
 
 #[test]
-fn test_rm_command_requires_task_argument() {
+fn test_rm_command_requires_task_argument_when_not_interactive() {
     let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/keep");
 
-    let mut cmd = Command::cargo_bin("claudectl").unwrap();
-    let output = cmd.arg("rm").current_dir(&temp_dir).output().unwrap();
+    let output = claudectl(&repo).arg("rm").output().unwrap();
+
+    // Without a terminal there is no picker to fall back on
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No task name given"));
+    assert!(repo.parent().unwrap().join("worktrees/feat/keep").exists());
+}
+
+#[test]
+fn test_rm_command_removes_several_tasks_with_force() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let first = add_task(&repo, "feat/one");
+    let second = add_task(&repo, "feat/two");
+    let kept = add_task(&repo, "feat/three");
+    for (worktree, task) in [(&first, "feat/one"), (&kept, "feat/three")] {
+        fs::write(worktree.join("draft.txt"), "draft\n").unwrap();
+        claudectl(&repo)
+            .args(["checkpoint", task])
+            .assert()
+            .success();
+    }
+
+    let output = claudectl(&repo)
+        .args(["rm", "feat/one", "feat/two", "--force"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Successfully removed task 'feat/one'"));
+    assert!(stdout.contains("Successfully removed task 'feat/two'"));
+    assert!(!first.exists());
+    assert!(!second.exists());
+    assert!(kept.exists());
+    // Only the kept task's checkpoint is left
+    let checkpoints = git(&repo, &["for-each-ref", "refs/claudectl/checkpoints"]);
+    assert!(!checkpoints.contains("feat/one"));
+    assert!(checkpoints.contains("checkpoints/feat/three/1"));
+}
+
+#[test]
+fn test_rm_command_refuses_a_task_in_use_without_force() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/busy");
+    let (pid, reaper) = hold_task(&repo, "feat/busy", "sleep 30; true");
+
+    let output = claudectl(&repo).args(["rm", "feat/busy"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 'feat/busy' is in use by"));
+    assert!(worktree.exists());
+
+    claudectl(&repo)
+        .args(["rm", "feat/busy", "--force"])
+        .assert()
+        .success();
+    assert!(!worktree.exists());
+
+    // The session outlives its task; end it and its sleep
+    for program in ["pkill -P", "kill"] {
+        std::process::Command::new("sh")
+            .args(["-c", &format!("{program} {pid}")])
+            .status()
+            .unwrap();
+    }
+    reaper.join().unwrap();
+}
+
+#[test]
+fn test_rm_command_checks_every_task_before_removing_any() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let task = add_task(&repo, "feat/one");
+
+    let output = claudectl(&repo)
+        .args(["rm", "feat/one", "feat/missing", "--force"])
+        .output()
+        .unwrap();
 
-    // Should fail because no task name provided
     assert!(!output.status.success());
     let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("required") || stderr.contains("TASK_NAME"));
+    assert!(stderr.contains("Task 'feat/missing' not found"));
+    assert!(task.exists());
+}
+
+#[test]
+fn test_rm_command_all_merged_keeps_unmerged_tasks() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let merged = add_task(&repo, "feat/merged");
+    commit_file(&merged, "merged.txt", "done\n", "finish merged");
+    git(&repo, &["merge", "-q", "--no-edit", "feat/merged"]);
+    let fresh = add_task(&repo, "feat/fresh");
+    let unmerged = add_task(&repo, "feat/unmerged");
+    commit_file(&unmerged, "wip.txt", "wip\n", "unfinished work");
+
+    let output = claudectl(&repo)
+        .args(["rm", "--all-merged", "--force"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(!merged.exists());
+    assert!(fresh.exists());
+    assert!(unmerged.exists());
+
+    let output = claudectl(&repo)
+        .args(["rm", "--all-merged", "--force"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No merged tasks to remove"));
+}
+
+#[test]
+fn test_rm_command_all_merged_keeps_tasks_back_at_their_fork_point() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let task = add_task(&repo, "feat/undone");
+    let fork_point = git(&repo, &["rev-parse", "feat/undone"]);
+    fs::create_dir_all(repo.join(".claudectl/tasks")).unwrap();
+    fs::write(
+        repo.join(".claudectl/tasks/feat__undone.json"),
+        format!(r#"{{ "name": "feat/undone", "base_commit": "{fork_point}" }}"#),
+    )
+    .unwrap();
+    commit_file(&task, "wip.txt", "wip\n", "abandoned work");
+    git(&task, &["reset", "-q", "--hard", "HEAD~1"]);
+
+    let output = claudectl(&repo)
+        .args(["rm", "--all-merged", "--force"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No merged tasks to remove"));
+    assert!(task.exists());
+}
+
+#[test]
+fn test_rm_command_all_merged_conflicts_with_task_names() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/keep");
+
+    let output = claudectl(&repo)
+        .args(["rm", "feat/keep", "--all-merged"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(repo.parent().unwrap().join("worktrees/feat/keep").exists());
 }
@@ -0,0 +1,101 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, git, init_project};
+
+#[test]
+fn test_rollback_fails_for_unknown_checkpoint() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/rollback");
+
+    let output = claudectl(&repo)
+        .args(["rollback", "feat/rollback", "--to", "7"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Checkpoint 7 not found"));
+}
+
+#[test]
+fn test_rollback_cancelled_leaves_worktree_untouched() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/rollback");
+    fs::write(worktree.join("work.txt"), "keep me\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["rollback", "feat/rollback"])
+        .write_stdin("n\n")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(worktree.join("work.txt").exists());
+}
+
+#[test]
+fn test_rollback_to_branch_point_and_back_to_checkpoint() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/rollback");
+    let base = git(&worktree, &["rev-parse", "HEAD"]);
+
+    fs::write(worktree.join("committed.txt"), "one\n").unwrap();
+    git(&worktree, &["add", "committed.txt"]);
+    git(&worktree, &["commit", "-q", "-m", "agent commit"]);
+    fs::write(worktree.join("scratch.txt"), "two\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["rollback", "feat/rollback"])
+        .write_stdin("y\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Saved current state as checkpoint 1"));
+
+    assert_eq!(git(&worktree, &["rev-parse", "HEAD"]), base);
+    assert!(!worktree.join("committed.txt").exists());
+    assert!(!worktree.join("scratch.txt").exists());
+
+    // Undo the rollback by restoring the saved checkpoint
+    let output = claudectl(&repo)
+        .args(["rollback", "feat/rollback", "--to", "1"])
+        .write_stdin("y\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    assert_ne!(git(&worktree, &["rev-parse", "HEAD"]), base);
+    assert!(worktree.join("committed.txt").exists());
+    assert!(worktree.join("scratch.txt").exists());
+    assert!(git(&worktree, &["status", "--porcelain"]).contains("scratch.txt"));
+}
+
+#[test]
+fn test_rollback_saves_commits_from_a_clean_worktree() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/rollback");
+
+    fs::write(worktree.join("committed.txt"), "one\n").unwrap();
+    git(&worktree, &["add", "committed.txt"]);
+    git(&worktree, &["commit", "-q", "-m", "agent commit"]);
+    let agent_head = git(&worktree, &["rev-parse", "HEAD"]);
+
+    let output = claudectl(&repo)
+        .args(["rollback", "feat/rollback"])
+        .write_stdin("y\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let saved_parent = git(
+        &worktree,
+        &["rev-parse", "refs/claudectl/checkpoints/feat/rollback/1^"],
+    );
+    assert_eq!(saved_parent, agent_head);
+}
@@ -0,0 +1,293 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use tempfile::TempDir;
+
+use crate::common::{
+    FakeClaude, add_origin, add_task, claudectl, claudectl_process, hold_task, init_project,
+};
+
+#[test]
+fn test_run_creates_task_and_records_result() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    let claude = FakeClaude::new(&temp_dir)
+        .responds("Fixed the bug.")
+        .install();
+
+    let output = claudectl(&repo)
+        .args(["run", "feat/scripted", "--", "fix", "the bug"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Fixed the bug."));
+    let call = claude.last_invocation();
+    assert!(call.cwd.ends_with("worktrees/feat/scripted"));
+    assert_eq!(
+        call.args,
+        ["--permission-mode", "default", "-p", "--", "fix the bug"]
+    );
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/scripted", "--json"])
+        .output()
+        .unwrap();
+    let details: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(details["prompts"][0]["source"], "run");
+    assert_eq!(details["runs"][0]["exit_code"], 0);
+    assert_eq!(details["runs"][0]["output"], "Fixed the bug.\n");
+}
+
+#[test]
+fn test_run_in_existing_task_reports_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/existing");
+    let claude = FakeClaude::new(&temp_dir)
+        .fails(3, "rate limited")
+        .install();
+
+    let output = claudectl(&repo)
+        .args(["run", "feat/existing", "--", "try again"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("rate limited"));
+    assert!(stderr.contains("claude exited with code 3 in 'feat/existing'"));
+    assert_eq!(claude.call_count(), 1);
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/existing", "--json"])
+        .output()
+        .unwrap();
+    let details: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(details["runs"][0]["exit_code"], 3);
+}
+
+#[test]
+fn test_run_requires_prompt() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["run", "feat/scripted"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("PROMPT"));
+}
+
+#[test]
+fn test_run_waits_in_queue_while_max_sessions_are_running() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/busy");
+    add_task(&repo, "feat/next");
+    claudectl(&repo)
+        .args(["config", "set", "max_sessions", "1"])
+        .assert()
+        .success();
+    let (_, reaper) = hold_task(&repo, "feat/busy", "sleep 3; true");
+    let claude = FakeClaude::new(&temp_dir).responds("Done.").install();
+
+    let run = claudectl_process(&repo)
+        .args(["run", "feat/next", "--", "go"])
+        .env("PATH", claude.path())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let queued = claudectl(&repo)
+            .args(["list", "--status", "queued", "--format", "tsv"])
+            .output()
+            .unwrap();
+        if String::from_utf8_lossy(&queued.stdout).contains("feat/next\tqueued") {
+            break;
+        }
+        assert!(Instant::now() < deadline, "feat/next was never queued");
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    let output = run.wait_with_output().unwrap();
+    reaper.join().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("'feat/next' starts when a slot is free"));
+    assert!(stdout.contains("Done."));
+}
+
+#[test]
+fn test_run_keeps_the_prompt_apart_from_allowed_tools() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/tools");
+    let claude = FakeClaude::new(&temp_dir).install();
+    claudectl(&repo)
+        .args([
+            "config",
+            "set",
+            "permissions.allowed_tools",
+            r#"["Read", "Bash(git:*)"]"#,
+        ])
+        .assert()
+        .success();
+
+    let output = claudectl(&repo)
+        .args(["run", "feat/tools", "--", "tidy up"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        claude.last_invocation().args,
+        [
+            "--permission-mode",
+            "default",
+            "--allowedTools",
+            "Read,Bash(git:*)",
+            "-p",
+            "--",
+            "tidy up"
+        ]
+    );
+}
+
+#[test]
+fn test_run_uses_the_projects_claude_command_and_extra_options() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/custom");
+    let claude = FakeClaude::named(&temp_dir, "claude-nightly").install();
+    for (key, value) in [
+        ("claude.path", "claude-nightly"),
+        ("claude.args", r#"["--model", "sonnet"]"#),
+        ("claude.env.DISABLE_TELEMETRY", "1"),
+    ] {
+        claudectl(&repo)
+            .args(["config", "set", key, value])
+            .assert()
+            .success();
+    }
+
+    let output = claudectl(&repo)
+        .args([
+            "run",
+            "feat/custom",
+            "--env",
+            "DISABLE_TELEMETRY=0",
+            "--env",
+            "TRACE=on",
+            "--claude-arg",
+            "--verbose",
+            "--",
+            "go",
+        ])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let call = claude.last_invocation();
+    assert_eq!(
+        call.args,
+        [
+            "--permission-mode",
+            "default",
+            "--model",
+            "sonnet",
+            "--verbose",
+            "-p",
+            "--",
+            "go"
+        ]
+    );
+    assert_eq!(call.env["DISABLE_TELEMETRY"], "0");
+    assert_eq!(call.env["TRACE"], "on");
+}
+
+#[test]
+fn test_run_applies_the_cpu_time_limit() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/limited");
+    let limit = temp_dir.path().join("limit");
+    let agent = temp_dir.path().join("limited-claude");
+    fs::write(
+        &agent,
+        format!("#!/bin/sh\nulimit -t > '{}'\n", limit.display()),
+    )
+    .unwrap();
+    fs::set_permissions(&agent, fs::Permissions::from_mode(0o755)).unwrap();
+    for (key, value) in [
+        ("claude.path", agent.to_str().unwrap()),
+        ("limits.cpu_seconds", "600"),
+    ] {
+        claudectl(&repo)
+            .args(["config", "set", key, value])
+            .assert()
+            .success();
+    }
+
+    let output = claudectl(&repo)
+        .args(["run", "feat/limited", "--", "go"])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::read_to_string(&limit).unwrap().trim(), "600");
+}
+
+#[test]
+fn test_run_records_output_that_is_not_utf8() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/bytes");
+    let agent = temp_dir.path().join("latin1-claude");
+    fs::write(&agent, "#!/bin/sh\nprintf 'caf\\351\\nafter\\n'\n").unwrap();
+    fs::set_permissions(&agent, fs::Permissions::from_mode(0o755)).unwrap();
+    claudectl(&repo)
+        .args(["config", "set", "claude.path", agent.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let output = claudectl(&repo)
+        .args(["run", "feat/bytes", "--", "go"])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let record = fs::read_to_string(repo.join(".claudectl/tasks/feat__bytes.json")).unwrap();
+    assert!(record.contains("caf\u{fffd}\\nafter"));
+}
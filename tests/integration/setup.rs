@@ -0,0 +1,135 @@
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+use crate::common::{FakeClaude, add_origin, claudectl, init_project};
+
+fn set_setup_commands(repo: &Path, commands: &str) {
+    let config_path = repo.join(".claudectl/config.json");
+    let config = fs::read_to_string(&config_path).unwrap();
+    let config = config.replace(
+        r#""project_name""#,
+        &format!(r#""setup_commands": {commands}, "project_name""#),
+    );
+    fs::write(&config_path, config).unwrap();
+}
+
+fn task_details(repo: &Path, task_name: &str) -> serde_json::Value {
+    let output = claudectl(repo)
+        .args(["show", task_name, "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn test_task_runs_setup_commands_before_starting_claude() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    set_setup_commands(&repo, r#"["echo installing", "touch installed"]"#);
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/ready", "--start"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("$ echo installing"));
+    assert!(stdout.contains("\ninstalling\n"));
+    assert!(
+        temp_dir
+            .path()
+            .join("worktrees/feat/ready/installed")
+            .exists()
+    );
+    assert_eq!(claude.call_count(), 1);
+}
+
+#[test]
+fn test_failed_setup_marks_task_and_skips_claude() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    set_setup_commands(&repo, r#"["false", "touch never"]"#);
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/broken", "--start", "--prompt", "go"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Setup command 'false' failed"));
+    assert!(stderr.contains("claudectl setup feat/broken"));
+    assert!(claude.invocations().is_empty());
+    let worktree = temp_dir.path().join("worktrees/feat/broken");
+    assert!(worktree.exists());
+    assert!(!worktree.join("never").exists());
+
+    let details = task_details(&repo, "feat/broken");
+    assert_eq!(details["status"], "setup-failed");
+    assert_eq!(details["setup_failure"]["command"], "false");
+
+    let output = claudectl(&repo)
+        .args(["list", "--status", "setup-failed"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("feat/broken"));
+}
+
+#[test]
+fn test_setup_retry_clears_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    set_setup_commands(&repo, r#"["test -f ready"]"#);
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/retry"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    fs::write(temp_dir.path().join("worktrees/feat/retry/ready"), "").unwrap();
+    let output = claudectl(&repo)
+        .args(["setup", "feat/retry"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Task 'feat/retry' is set up"));
+
+    let details = task_details(&repo, "feat/retry");
+    assert_eq!(details["status"], "ready");
+    assert!(details.get("setup_failure").is_none());
+}
+
+#[test]
+fn test_setup_without_commands_does_nothing() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    claudectl(&repo)
+        .args(["task", "feat/plain"])
+        .assert()
+        .success();
+
+    let output = claudectl(&repo)
+        .args(["setup", "feat/plain"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No setup commands configured"));
+}
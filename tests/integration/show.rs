@@ -0,0 +1,94 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project};
+
+#[test]
+fn test_show_prints_task_details() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/show");
+    fs::write(worktree.join("wip.txt"), "draft\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["note", "feat/show", "halfway there"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/show"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("feat/show"));
+    assert!(stdout.contains("1 uncommitted file(s)"));
+    assert!(stdout.contains("halfway there"));
+    assert!(stdout.contains("Recent activity"));
+}
+
+#[test]
+fn test_show_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/json");
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/json", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["name"], "feat/json");
+    assert_eq!(json["status"], "ready");
+    assert_eq!(json["changed_files"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_show_fails_for_unknown_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/missing"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Task 'feat/missing' not found"));
+}
+
+#[test]
+fn test_show_without_task_name_fails_when_not_interactive() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/picked");
+
+    let output = claudectl(&repo).arg("show").output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No task name given"));
+}
+
+#[test]
+fn test_show_diff_includes_uncommitted_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/diffed");
+    fs::write(worktree.join("wip.txt"), "draft line\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/diffed", "--diff"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Changes"));
+    assert!(stdout.contains("+draft line"));
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/diffed", "--diff", "--json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["diff"].as_str().unwrap().contains("wip.txt"));
+}
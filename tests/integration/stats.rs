@@ -0,0 +1,72 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{FakeClaude, claudectl, init_project};
+
+/// An event log covering one task's whole life and one still in progress.
+const EVENTS: &str = r#"{"at":"2024-09-02T12:00:00Z","kind":"task_created","task":"feat/login"}
+{"at":"2024-09-02T12:00:05Z","kind":"session_started","task":"feat/login"}
+{"at":"2024-09-02T13:00:05Z","kind":"session_ended","task":"feat/login","duration_secs":3600}
+{"at":"2024-09-03T09:00:00Z","kind":"run_finished","task":"feat/login","detail":"succeeded","duration_secs":120}
+{"at":"2024-09-04T12:00:00Z","kind":"merged","task":"feat/login"}
+{"at":"2024-09-04T12:00:00Z","kind":"task_removed","task":"feat/login"}
+{"at":"2024-09-05T12:00:00Z","kind":"task_created","task":"fix/crash"}
+"#;
+
+#[test]
+fn test_stats_json_totals_the_event_log() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    fs::write(repo.join(".claudectl/events.jsonl"), EVENTS).unwrap();
+
+    let output = claudectl(&repo)
+        .args(["stats", "--json", "--weeks", "2"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["tasks_created"], 2);
+    assert_eq!(stats["tasks_completed"], 1);
+    assert_eq!(stats["average_lifetime_secs"], 2 * 86_400);
+    assert_eq!(stats["sessions"], 1);
+    assert_eq!(stats["session_secs"], 3600);
+    assert_eq!(stats["run_secs"], 120);
+    assert_eq!(stats["weeks"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_stats_reports_cost_from_ccusage() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    fs::write(repo.join(".claudectl/events.jsonl"), EVENTS).unwrap();
+    let project = temp_dir
+        .path()
+        .join("worktrees/feat/login")
+        .to_string_lossy()
+        .replace(|c: char| !c.is_ascii_alphanumeric(), "-");
+    let ccusage = FakeClaude::named(&temp_dir, "ccusage")
+        .responds(&format!(
+            r#"{{ "sessions": [
+                {{ "sessionId": "{project}", "totalCost": 2.5 }},
+                {{ "sessionId": "-elsewhere", "totalCost": 9.0 }}
+            ] }}"#
+        ))
+        .install();
+
+    let output = claudectl(&repo)
+        .args(["--plain", "stats"])
+        .env("PATH", ccusage.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Tasks: 2 created, 1 completed"), "{stdout}");
+    assert!(stdout.contains("Average lifetime: 2d 0h over 1 finished task(s)"));
+    assert!(stdout.contains("Sessions: 1, 1h 0m in total"));
+    assert!(stdout.contains("Cost: $2.50"), "{stdout}");
+    assert_eq!(ccusage.last_invocation().args, ["session", "--json"]);
+}
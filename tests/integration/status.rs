@@ -0,0 +1,72 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, git, hold_task, init_project};
+
+#[test]
+fn test_status_reports_changes_and_commits_ahead_of_base() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let busy = add_task(&repo, "feat/busy");
+    add_task(&repo, "feat/idle");
+    for task in ["feat__busy", "feat__idle"] {
+        let name = task.replace("__", "/");
+        fs::create_dir_all(repo.join(".claudectl/tasks")).unwrap();
+        fs::write(
+            repo.join(format!(".claudectl/tasks/{task}.json")),
+            format!(r#"{{ "name": "{name}", "base": "main" }}"#),
+        )
+        .unwrap();
+    }
+    fs::write(busy.join("lib.rs"), "pub fn busy() {}\n").unwrap();
+    git(&busy, &["add", "lib.rs"]);
+    git(&busy, &["commit", "-q", "-m", "busy work"]);
+    fs::write(busy.join("notes.txt"), "draft\n").unwrap();
+
+    let output = claudectl(&repo).arg("status").output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1 uncommitted"));
+    assert!(stdout.contains("1 ahead"));
+    assert!(stdout.contains("up to date"));
+    assert!(stdout.contains("2 task(s): 0 working, 1 with uncommitted changes"));
+
+    let output = claudectl(&repo)
+        .args(["status", "--json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["project"], "test-project");
+    let busy = &json["tasks"][0];
+    assert_eq!(busy["name"], "feat/busy");
+    assert_eq!(busy["uncommitted_files"], 1);
+    assert_eq!(busy["ahead"], 1);
+    assert_eq!(busy["behind"], 0);
+}
+
+#[test]
+fn test_status_reports_memory_and_cpu_of_running_sessions() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/busy");
+    add_task(&repo, "feat/idle");
+    let (pid, reaper) = hold_task(&repo, "feat/busy", "sleep 2; true");
+
+    let output = claudectl(&repo)
+        .args(["status", "--json"])
+        .output()
+        .unwrap();
+    reaper.join().unwrap();
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let busy = &json["tasks"][0];
+    assert_eq!(busy["session"]["pid"], pid);
+    assert!(busy["usage"]["rss_kib"].as_u64().unwrap() > 0);
+    // The shell and its sleep
+    assert_eq!(busy["usage"]["processes"], 2);
+    assert!(json["tasks"][1].get("usage").is_none());
+}
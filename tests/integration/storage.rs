@@ -0,0 +1,67 @@
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project, register_project};
+
+#[test]
+fn test_storage_info_shows_project_and_global_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_home = temp_dir.path().join("config");
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/one");
+    register_project(&config_home, "test-project", &repo);
+    let output = claudectl(&repo)
+        .args(["note", "feat/one", "stored"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = claudectl(&repo)
+        .args(["storage", "info"])
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(".claudectl/tasks"));
+    assert!(stdout.contains("events.jsonl"));
+    assert!(stdout.contains(&config_home.join("claudectl/projects").display().to_string()));
+    assert!(stdout.contains("test-project"));
+}
+
+#[test]
+fn test_storage_info_global_only_outside_a_project() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["storage", "info", "--global"])
+        .env("XDG_CONFIG_HOME", temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("global"));
+    assert!(!stdout.contains("project "));
+}
+
+#[test]
+fn test_storage_info_outside_a_project_reports_uninitialized() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["storage", "info", "--project"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("not initialized"));
+}
+
+#[test]
+fn test_storage_info_scopes_conflict() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["storage", "info", "--global", "--project"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
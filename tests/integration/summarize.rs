@@ -0,0 +1,125 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{FakeClaude, add_task, assert_snapshot, claudectl, init_project};
+
+#[test]
+fn test_summarize_fails_for_nonexistent_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args(["summarize", "missing-task"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Task 'missing-task' not found"));
+}
+
+#[test]
+fn test_summarize_fails_without_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/summary");
+
+    let output = claudectl(&repo)
+        .args(["summarize", "feat/summary"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no changes to summarize"));
+}
+
+#[test]
+fn test_summarize_stores_summary_shown_in_verbose_list() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/summary");
+    fs::write(worktree.join("greeting.txt"), "hello\n").unwrap();
+
+    let claude = FakeClaude::new(&temp_dir)
+        .responds("Adds a greeting file.")
+        .install();
+
+    let output = claudectl(&repo)
+        .env("PATH", claude.path())
+        .args(["summarize", "feat/summary"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    // The prompt goes in the arguments and the diff on stdin
+    let call = claude.last_invocation();
+    assert_eq!(call.cwd, worktree.canonicalize().unwrap());
+    assert_snapshot(
+        "summarize_claude_call",
+        &format!("args: {:?}\n\nstdin:\n{}", call.args, call.stdin),
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Adds a greeting file."));
+
+    let record = fs::read_to_string(repo.join(".claudectl/tasks/feat__summary.json")).unwrap();
+    assert!(record.contains("Adds a greeting file."));
+
+    let output = claudectl(&repo)
+        .args(["list", "--verbose"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Adds a greeting file."));
+}
+
+#[test]
+fn test_summarize_reports_claude_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/summary");
+    fs::write(worktree.join("greeting.txt"), "hello\n").unwrap();
+
+    let claude = FakeClaude::new(&temp_dir)
+        .fails(1, "rate limited")
+        .install();
+
+    let output = claudectl(&repo)
+        .env("PATH", claude.path())
+        .args(["summarize", "feat/summary"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Claude command failed: rate limited"));
+}
+
+#[test]
+fn test_summarize_redacts_secrets_before_storing() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let worktree = add_task(&repo, "feat/secret");
+    fs::write(worktree.join("config.txt"), "setting\n").unwrap();
+    let config_path = repo.join(".claudectl/config.json");
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        r#""project_name""#,
+        r#""redaction": { "patterns": ["ACME-[0-9]+"] }, "project_name""#,
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let claude = FakeClaude::new(&temp_dir)
+        .responds("Uses sk-ant-REDACTED for ACME-1234 as env-secret-value.")
+        .install();
+
+    let output = claudectl(&repo)
+        .env("PATH", claude.path())
+        .env("CLAUDECTL_TEST_TOKEN", "env-secret-value")
+        .args(["summarize", "feat/secret"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let record = fs::read_to_string(repo.join(".claudectl/tasks/feat__secret.json")).unwrap();
+    assert!(record.contains("Uses [REDACTED] for [REDACTED] as [REDACTED]."));
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("sk-ant"));
+}
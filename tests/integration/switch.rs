@@ -0,0 +1,52 @@
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project};
+
+#[test]
+fn test_switch_prints_the_matching_worktree() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let login = add_task(&repo, "feat/login");
+    add_task(&repo, "fix/crash");
+
+    let output = claudectl(&repo).args(["switch", "flog"]).output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.trim(), login.to_string_lossy());
+}
+
+#[test]
+fn test_switch_prefers_an_exact_task_name() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let api = add_task(&repo, "feat/api");
+    add_task(&repo, "feat/api-v2");
+
+    let output = claudectl(&repo)
+        .args(["switch", "feat/api"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        api.to_string_lossy()
+    );
+}
+
+#[test]
+fn test_switch_needs_a_single_match_when_not_interactive() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/login");
+    add_task(&repo, "feat/logout");
+
+    let output = claudectl(&repo).args(["switch", "log"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Several tasks match"));
+
+    let output = claudectl(&repo).args(["switch", "zzz"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No task matches 'zzz'"));
+}
@@ -0,0 +1,47 @@
+use std::fs;
+use tempfile::TempDir;
+
+use crate::common::{add_task, claudectl, init_project};
+
+#[test]
+fn test_sync_claude_md_without_instructions() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo).arg("sync-claude-md").output().unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("No CLAUDE.md"));
+}
+
+#[test]
+fn test_sync_claude_md_updates_task_worktrees() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let first = add_task(&repo, "feat/one");
+    let second = add_task(&repo, "feat/two");
+
+    fs::write(repo.join("CLAUDE.md"), "# Shared rules\n").unwrap();
+    fs::create_dir(repo.join(".claude")).unwrap();
+    fs::write(repo.join(".claude/settings.json"), "{}").unwrap();
+
+    let output = claudectl(&repo).arg("sync-claude-md").output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Synced Claude instructions into 2 task(s)"));
+
+    for worktree in [&first, &second] {
+        assert_eq!(
+            fs::read_to_string(worktree.join("CLAUDE.md")).unwrap(),
+            "# Shared rules\n"
+        );
+        assert!(worktree.join(".claude/settings.json").exists());
+    }
+
+    // The main checkout is not treated as a task
+    let output = claudectl(&repo).arg("sync-claude-md").output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("All task worktrees are up to date"));
+}
@@ -1,7 +1,12 @@
 use assert_cmd::Command;
 use std::fs;
+use std::os::unix::fs::PermissionsExt;
 use tempfile::TempDir;
 
+use crate::common::{
+    FakeClaude, add_origin, assert_snapshot, claudectl, git, init_project, normalize,
+};
+
 #[test]
 fn test_task_command_fails_without_git_repo() {
     let temp_dir = TempDir::new().unwrap();
@@ -78,3 +83,716 @@ fn test_task_command_validation_with_empty_task_name() {
     // Should fail with argument validation error
     assert!(!output.status.success());
 }
+
+#[test]
+fn test_task_command_creates_worktree_and_record() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/created"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_snapshot(
+        "task_created_output",
+        &normalize(&String::from_utf8_lossy(&output.stdout), temp_dir.path()),
+    );
+    assert!(
+        temp_dir
+            .path()
+            .join("worktrees/feat/created/README.md")
+            .exists()
+    );
+    let record = fs::read_to_string(repo.join(".claudectl/tasks/feat__created.json")).unwrap();
+    assert!(record.contains("\"base\": \"origin/main\""));
+}
+
+#[test]
+fn test_task_clone_fails_for_nonexistent_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+
+    let output = claudectl(&repo)
+        .args(["task", "--clone", "feat/missing", "feat/retry"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Task 'feat/missing' not found"));
+}
+
+#[test]
+fn test_task_from_branch_requires_clone() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["task", "--from-branch", "feat/retry"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--clone"));
+}
+
+#[test]
+fn test_task_clone_from_existing_branch() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/original"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let original = temp_dir.path().join("worktrees/feat/original");
+    fs::write(original.join("attempt.txt"), "first try\n").unwrap();
+    git(&original, &["add", "attempt.txt"]);
+    git(&original, &["commit", "-q", "-m", "first attempt"]);
+
+    // Cloning from the base does not include the original's work
+    let output = claudectl(&repo)
+        .args(["task", "--clone", "feat/original", "feat/retry"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(
+        !temp_dir
+            .path()
+            .join("worktrees/feat/retry/attempt.txt")
+            .exists()
+    );
+    let record = fs::read_to_string(repo.join(".claudectl/tasks/feat__retry.json")).unwrap();
+    assert!(record.contains("\"cloned_from\": \"feat/original\""));
+
+    // Cloning from the branch continues where the original left off
+    let output = claudectl(&repo)
+        .args([
+            "task",
+            "--clone",
+            "feat/original",
+            "--from-branch",
+            "feat/continue",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(
+        temp_dir
+            .path()
+            .join("worktrees/feat/continue/attempt.txt")
+            .exists()
+    );
+}
+
+#[test]
+fn test_task_start_launches_claude_in_worktree() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/started", "--start", "--prompt", "fix the bug"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let call = claude.last_invocation();
+    assert!(call.cwd.ends_with("worktrees/feat/started"));
+    assert_eq!(
+        call.args,
+        ["--permission-mode", "default", "--", "fix the bug"]
+    );
+}
+
+#[test]
+fn test_task_start_records_prompt() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args([
+            "task",
+            "feat/recorded",
+            "--start",
+            "--prompt",
+            "fix the bug",
+        ])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/recorded", "--json"])
+        .output()
+        .unwrap();
+    let details: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let prompts = details["prompts"].as_array().unwrap();
+    assert_eq!(prompts.len(), 1);
+    assert_eq!(prompts[0]["text"], "fix the bug");
+    assert_eq!(prompts[0]["source"], "task --start");
+
+    let output = claudectl(&repo)
+        .args(["show", "feat/recorded"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Prompts"));
+    assert!(stdout.contains("(task --start)"));
+    assert!(stdout.contains(" fix the bug"));
+}
+
+#[test]
+fn test_task_prompt_requires_start() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = claudectl(temp_dir.path())
+        .args(["task", "feat/x", "--prompt", "hello"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--start"));
+}
+
+#[test]
+fn test_task_rolls_back_worktree_and_branch_on_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    // `.claude` is a file on origin/main but a directory in the main checkout,
+    // so syncing it into the new worktree fails after the worktree exists
+    fs::write(repo.join(".claude"), "file\n").unwrap();
+    git(&repo, &["add", ".claude"]);
+    git(&repo, &["commit", "-q", "-m", "add .claude file"]);
+    add_origin(&repo);
+    fs::remove_file(repo.join(".claude")).unwrap();
+    fs::create_dir(repo.join(".claude")).unwrap();
+    fs::write(repo.join(".claude/settings.json"), "{}\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/doomed"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(!temp_dir.path().join("worktrees/feat/doomed").exists());
+    assert_eq!(git(&repo, &["branch", "--list", "feat/doomed"]), "");
+    assert!(!repo.join(".claudectl/tasks/feat__doomed.json").exists());
+}
+
+#[test]
+fn test_task_start_prepends_prompt_preamble() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    let config_path = repo.join(".claudectl/config.json");
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        r#""project_name""#,
+        r#""prompt_preamble": "Run cargo test before finishing.", "project_name""#,
+    );
+    fs::write(&config_path, config).unwrap();
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args([
+            "task",
+            "feat/preamble",
+            "--start",
+            "--prompt",
+            "fix the bug",
+        ])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_snapshot(
+        "task_start_prompt_with_preamble",
+        &claude.last_invocation().args.join("\n"),
+    );
+}
+
+#[test]
+fn test_task_start_prepends_project_context() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    let config_path = repo.join(".claudectl/config.json");
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        r#""project_name""#,
+        r#""context": { "files": ["README.md", "MISSING.md"], "git_log": 5 }, "project_name""#,
+    );
+    fs::write(&config_path, config).unwrap();
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/context", "--start", "--prompt", "fix the bug"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let prompt = claude.last_invocation().args.last().unwrap().clone();
+    assert!(
+        prompt.starts_with("# Project context\n\n## README.md\n\nhello\n\n## Recent commits\n\n")
+    );
+    assert!(prompt.contains(" initial\n\nfix the bug"));
+    assert!(!prompt.contains("MISSING.md"));
+}
+
+#[test]
+fn test_task_slugifies_names_and_detects_collisions() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+
+    let output = claudectl(&repo)
+        .args(["task", "Fix Login Crash"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(temp_dir.path().join("worktrees/fix-login-crash").exists());
+    let record = fs::read_to_string(repo.join(".claudectl/tasks/fix-login-crash.json")).unwrap();
+    assert!(record.contains("\"display_name\": \"Fix Login Crash\""));
+
+    let output = claudectl(&repo)
+        .args(["task", "fix login crash!"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("becomes 'fix-login-crash', which is already in use"));
+}
+
+#[test]
+fn test_task_start_uses_claude_profile() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    let config_home = temp_dir.path().join("config");
+    fs::create_dir_all(config_home.join("claudectl")).unwrap();
+    fs::write(
+        config_home.join("claudectl/config.json"),
+        r#"{ "profiles": { "work": { "config_dir": "/opt/claude-work" } } }"#,
+    )
+    .unwrap();
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/work", "--start", "--profile", "work"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        claude.last_invocation().env["CLAUDE_CONFIG_DIR"],
+        "/opt/claude-work"
+    );
+    let record = fs::read_to_string(repo.join(".claudectl/tasks/feat__work.json")).unwrap();
+    assert!(record.contains("\"profile\": \"work\""));
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/personal", "--profile", "personal"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown profile 'personal'"));
+    assert!(!temp_dir.path().join("worktrees/feat/personal").exists());
+}
+
+#[test]
+fn test_task_uses_profile_from_environment() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    let config_home = temp_dir.path().join("config");
+    let worktree_root = temp_dir.path().join("work-trees");
+    fs::create_dir_all(config_home.join("claudectl")).unwrap();
+    fs::write(
+        config_home.join("claudectl/config.json"),
+        format!(
+            r#"{{ "profiles": {{ "work": {{ "model": "opus", "worktree_root": "{}" }} }} }}"#,
+            worktree_root.display()
+        ),
+    )
+    .unwrap();
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/env-profile", "--start"])
+        .env("PATH", claude.path())
+        .env("XDG_CONFIG_HOME", &config_home)
+        .env("CLAUDECTL_PROFILE", "work")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let invocation = claude.last_invocation();
+    assert_eq!(invocation.env["ANTHROPIC_MODEL"], "opus");
+    assert!(
+        invocation
+            .cwd
+            .ends_with("work-trees/test-project/feat/env-profile")
+    );
+    let record = fs::read_to_string(repo.join(".claudectl/tasks/feat__env-profile.json")).unwrap();
+    assert!(record.contains("\"profile\": \"work\""));
+}
+
+#[test]
+fn test_interrupted_task_creation_is_rolled_back_on_next_run() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    // What a `task` run killed after writing the record leaves behind
+    git(&repo, &["branch", "feat/interrupted"]);
+    fs::create_dir_all(repo.join(".claudectl/tasks")).unwrap();
+    fs::write(
+        repo.join(".claudectl/tasks/feat__interrupted.json"),
+        r#"{ "name": "feat/interrupted" }"#,
+    )
+    .unwrap();
+    // Its process has exited; no pid can be this high
+    fs::create_dir_all(repo.join(".claudectl/journals")).unwrap();
+    fs::write(
+        repo.join(".claudectl/journals/99999999-1.json"),
+        r#"{
+  "pid": 99999999,
+  "steps": [
+    { "branch-created": "feat/interrupted" },
+    { "task-file-written": { "task_name": "feat/interrupted", "previous": null } }
+  ]
+}"#,
+    )
+    .unwrap();
+
+    let output = claudectl(&repo).args(["list"]).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Rolled back 2 step(s) of an operation that was interrupted"));
+    assert_eq!(
+        fs::read_dir(repo.join(".claudectl/journals"))
+            .unwrap()
+            .count(),
+        0
+    );
+    assert!(
+        !repo
+            .join(".claudectl/tasks/feat__interrupted.json")
+            .exists()
+    );
+    assert!(git(&repo, &["branch", "--list", "feat/interrupted"]).is_empty());
+}
+
+#[test]
+fn test_operation_in_progress_is_not_rolled_back() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    git(&repo, &["branch", "feat/in-progress"]);
+    // Journaled by a process that is still running: this test
+    let pid = std::process::id();
+    let journal = repo.join(format!(".claudectl/journals/{pid}-1.json"));
+    fs::create_dir_all(repo.join(".claudectl/journals")).unwrap();
+    fs::write(
+        &journal,
+        format!(r#"{{ "pid": {pid}, "steps": [{{ "branch-created": "feat/in-progress" }}] }}"#),
+    )
+    .unwrap();
+
+    let output = claudectl(&repo).args(["list"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Rolled back"));
+    assert!(journal.exists());
+    assert!(!git(&repo, &["branch", "--list", "feat/in-progress"]).is_empty());
+}
+
+#[test]
+fn test_task_guard_rails_block_rewriting_other_branches() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    let output = claudectl(&repo)
+        .args(["config", "set", "guard_rails.enabled", "true"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    // The project's own hooks keep running in the task worktree
+    let hook = repo.join(".git/hooks/pre-commit");
+    fs::write(
+        &hook,
+        "#!/bin/sh\ntouch \"$(git rev-parse --git-common-dir)/hook-ran\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/guarded"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let worktree = temp_dir.path().join("worktrees/feat/guarded");
+    let run = |args: &[&str]| {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(&worktree)
+            .env("GIT_AUTHOR_NAME", "claudectl-test")
+            .env("GIT_AUTHOR_EMAIL", "test@claudectl.invalid")
+            .env("GIT_COMMITTER_NAME", "claudectl-test")
+            .env("GIT_COMMITTER_EMAIL", "test@claudectl.invalid")
+            .output()
+            .unwrap()
+    };
+
+    git(&worktree, &["commit", "-q", "--amend", "-m", "rewritten"]);
+    assert!(repo.join(".git/hook-ran").exists());
+    let push = run(&["push", "--force", "origin", "HEAD:main"]);
+    assert!(!push.status.success());
+    assert!(String::from_utf8_lossy(&push.stderr).contains("force-pushing 'main' is blocked"));
+
+    // The task's own branch may be rewritten
+    git(&worktree, &["push", "-q", "origin", "HEAD:feat/guarded"]);
+    git(
+        &worktree,
+        &["commit", "-q", "--amend", "-m", "rewritten again"],
+    );
+    git(
+        &worktree,
+        &["push", "-q", "--force", "origin", "HEAD:feat/guarded"],
+    );
+
+    git(&repo, &["branch", "other", "main"]);
+    let rebase = run(&["rebase", "HEAD", "other"]);
+    assert!(!rebase.status.success());
+    assert!(String::from_utf8_lossy(&rebase.stderr).contains("rebasing 'other' is blocked"));
+
+    // The main checkout is left alone
+    let hooks_path = std::process::Command::new("git")
+        .args(["config", "--get", "core.hooksPath"])
+        .current_dir(&repo)
+        .output()
+        .unwrap();
+    assert!(!hooks_path.status.success());
+}
+
+#[test]
+fn test_task_from_issue_names_branch_and_writes_context() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    // Unlike the tests' projects, real ones don't have git ignore `.claudectl`
+    fs::write(repo.join(".git/info/exclude"), "").unwrap();
+    add_origin(&repo);
+    let gh = FakeClaude::named(&temp_dir, "gh")
+        .responds(
+            r#"{"number":42,"title":"Login crashes on empty password","body":"Leave the password empty.","url":"https://github.com/owner/repo/issues/42"}"#,
+        )
+        .install();
+
+    let output = claudectl(&repo)
+        .args(["task", "--from-issue", "42", "--kind", "bugfix"])
+        .env("PATH", gh.path())
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        gh.last_invocation().args,
+        ["issue", "view", "42", "--json", "number,title,body,url"]
+    );
+
+    let worktree = temp_dir
+        .path()
+        .join("worktrees/fix/42-login-crashes-on-empty-password");
+    let context = fs::read_to_string(worktree.join(".claudectl/task.md")).unwrap();
+    assert!(context.starts_with("# Login crashes on empty password (#42)\n"));
+    assert!(context.contains("Leave the password empty."));
+    // Not something the task changed
+    assert_eq!(git(&worktree, &["status", "--porcelain"]), "");
+
+    let output = claudectl(&repo)
+        .args(["show", "fix/42-login-crashes-on-empty-password", "--json"])
+        .output()
+        .unwrap();
+    let details: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(details["display_name"], "Login crashes on empty password");
+    assert_eq!(details["issue"], "https://github.com/owner/repo/issues/42");
+}
+
+#[test]
+fn test_task_requires_a_name_or_issue() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo).args(["task"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("<TASK_NAME>"));
+}
+
+#[test]
+fn test_task_timings_reports_steps() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+
+    let output = claudectl(&repo)
+        .args(["--plain", "--timings", "task", "feat/timed"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("task_command"));
+    assert!(stdout.contains("  fetch_origin"));
+    assert!(stdout.contains("  create_worktree"));
+}
+
+#[test]
+fn test_task_file_creates_each_task_and_reports_failures() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    // Unlike the tests' projects, real ones don't have git ignore `.claudectl`
+    fs::write(repo.join(".git/info/exclude"), "").unwrap();
+    add_origin(&repo);
+    let file = temp_dir.path().join("tasks.yaml");
+    fs::write(
+        &file,
+        "tasks:\n  - name: Fix login crash\n    kind: bugfix\n    prompt: Make the login test pass\n  - name: docs\n    tags: [docs]\n  - name: fix/fix-login-crash\n",
+    )
+    .unwrap();
+
+    let output = claudectl(&repo)
+        .args(["--plain", "task", "-f"])
+        .arg(&file)
+        .args(["--tag", "batch"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("fix/fix-login-crash  created"));
+    assert!(stdout.contains("docs                 created"));
+    assert!(stdout.contains("Task 'fix/fix-login-crash' already exists"));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("1 of 3 task(s) could not be created")
+    );
+
+    let worktree = temp_dir.path().join("worktrees/fix/fix-login-crash");
+    assert_eq!(
+        fs::read_to_string(worktree.join(".claudectl/task.md")).unwrap(),
+        "Make the login test pass"
+    );
+    assert_eq!(git(&worktree, &["status", "--porcelain"]), "");
+    let output = claudectl(&repo)
+        .args(["show", "docs", "--json"])
+        .output()
+        .unwrap();
+    let details: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(details["tags"], serde_json::json!(["batch", "docs"]));
+
+    let output = claudectl(&repo)
+        .args(["show", "fix/fix-login-crash", "--json"])
+        .output()
+        .unwrap();
+    let details: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(details["prompts"][0]["text"], "Make the login test pass");
+    assert_eq!(details["prompts"][0]["source"], "task --file");
+}
+
+/// Install a lifecycle hook that appends what it was told to `hooks.log`.
+fn install_hook(repo: &std::path::Path, name: &str, exit_code: i32) {
+    let hooks = repo.join(".claudectl/hooks");
+    fs::create_dir_all(&hooks).unwrap();
+    let hook = hooks.join(name);
+    fs::write(
+        &hook,
+        format!(
+            "#!/bin/sh\necho \"$CLAUDECTL_HOOK $CLAUDECTL_TASK ${{CLAUDECTL_STATUS:--}} ${{CLAUDECTL_EXIT_CODE:--}} ${{CLAUDECTL_SESSION_ID:+session}}\" >> \"$CLAUDECTL_MAIN_CHECKOUT/hooks.log\"\nexit {exit_code}\n"
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn test_task_start_runs_lifecycle_hooks_in_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    for name in [
+        "pre-task",
+        "post-task",
+        "pre-session",
+        "post-session",
+        "on-session-exit",
+    ] {
+        install_hook(&repo, name, 0);
+    }
+    let claude = FakeClaude::new(&temp_dir).fails(2, "boom").install();
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/hooked", "--start"])
+        .env("PATH", claude.path())
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        fs::read_to_string(repo.join("hooks.log")).unwrap(),
+        "pre-task feat/hooked - - \n\
+         post-task feat/hooked ready - \n\
+         pre-session feat/hooked - - session\n\
+         post-session feat/hooked crashed 2 session\n\
+         on-session-exit feat/hooked crashed 2 session\n"
+    );
+}
+
+#[test]
+fn test_failing_pre_task_hook_cancels_the_task() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    install_hook(&repo, "pre-task", 1);
+
+    let output = claudectl(&repo)
+        .args(["task", "feat/vetoed"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("The pre-task hook exited with"));
+    assert!(!temp_dir.path().join("worktrees/feat/vetoed").exists());
+    assert!(git(&repo, &["branch", "--list", "feat/vetoed"]).is_empty());
+}
@@ -0,0 +1,225 @@
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+use crate::common::claudectl;
+
+const ASSETS: [&str; 5] = [
+    "claudectl-linux-x64",
+    "claudectl-linux-arm64",
+    "claudectl-macos-x64",
+    "claudectl-macos-arm64",
+    "claudectl-windows-x64.exe",
+];
+
+/// The SHA-256 of `path` in hex, as `sha256sum` prints it.
+fn sha256(path: &Path) -> String {
+    let output = std::process::Command::new("sha256sum")
+        .arg(path)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.split_whitespace().next().unwrap().to_string()
+}
+
+/// A GitHub release response for `version` whose binaries are all
+/// `binary`, returning its URL. Its SHA256SUMS lists `sum` for each, or
+/// the binary's own checksum.
+fn fake_release_with_sum(dir: &Path, version: &str, binary: &Path, sum: Option<&str>) -> String {
+    let sum = match sum {
+        Some(sum) => sum.to_string(),
+        None if binary.exists() => sha256(binary),
+        None => "0".repeat(64),
+    };
+    let sums = dir.join("SHA256SUMS");
+    let lines: Vec<String> = ASSETS
+        .iter()
+        .map(|name| format!("{sum}  {name}\n"))
+        .collect();
+    fs::write(&sums, lines.concat()).unwrap();
+    let mut assets: Vec<String> = ASSETS
+        .iter()
+        .map(|name| {
+            format!(
+                r#"{{ "name": "{name}", "browser_download_url": "file://{}" }}"#,
+                binary.display()
+            )
+        })
+        .collect();
+    assets.push(format!(
+        r#"{{ "name": "SHA256SUMS", "browser_download_url": "file://{}" }}"#,
+        sums.display()
+    ));
+    let release = dir.join("latest.json");
+    fs::write(
+        &release,
+        format!(
+            r#"{{ "tag_name": "v{version}", "assets": [{}] }}"#,
+            assets.join(", ")
+        ),
+    )
+    .unwrap();
+    format!("file://{}", release.display())
+}
+
+fn fake_release(dir: &Path, version: &str, binary: &Path) -> String {
+    fake_release_with_sum(dir, version, binary, None)
+}
+
+#[test]
+fn test_upgrade_check_reports_a_newer_release() {
+    let temp_dir = TempDir::new().unwrap();
+    let url = fake_release(temp_dir.path(), "99.0.0", &temp_dir.path().join("bin"));
+
+    let output = claudectl(temp_dir.path())
+        .args(["upgrade", "--check"])
+        .env("CLAUDECTL_RELEASES_URL", &url)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("claudectl 99.0.0 is available"));
+    assert!(stdout.contains(env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn test_upgrade_when_up_to_date() {
+    let temp_dir = TempDir::new().unwrap();
+    let url = fake_release(temp_dir.path(), "0.0.1", &temp_dir.path().join("bin"));
+
+    let output = claudectl(temp_dir.path())
+        .arg("upgrade")
+        .env("CLAUDECTL_RELEASES_URL", &url)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is up to date"));
+}
+
+#[cfg(unix)]
+mod replace {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::process::Command;
+
+    /// A copy of claudectl that can be replaced without touching the one
+    /// the other tests run.
+    fn installed_copy(dir: &Path) -> std::path::PathBuf {
+        let bin = dir.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        let exe = bin.join("claudectl");
+        fs::copy(assert_cmd::cargo::cargo_bin("claudectl"), &exe).unwrap();
+        exe
+    }
+
+    fn script(path: &Path, body: &str) {
+        fs::write(path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_upgrade_replaces_the_binary() {
+        let temp_dir = TempDir::new().unwrap();
+        let exe = installed_copy(temp_dir.path());
+        let new = temp_dir.path().join("new-claudectl");
+        let installs = temp_dir.path().join("installs");
+        script(
+            &new,
+            &format!(
+                r#"case "$1" in
+  --version) echo "claudectl 99.0.0" ;;
+  completions) echo "$@" >> "{}" ;;
+esac"#,
+                installs.display()
+            ),
+        );
+        let url = fake_release(temp_dir.path(), "99.0.0", &new);
+
+        let output = Command::new(&exe)
+            .arg("upgrade")
+            .current_dir(temp_dir.path())
+            .env("CLAUDECTL_RELEASES_URL", &url)
+            .output()
+            .unwrap();
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(String::from_utf8_lossy(&output.stdout).contains("Upgraded claudectl to 99.0.0"));
+        let version = Command::new(&exe).arg("--version").output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&version.stdout).trim(),
+            "claudectl 99.0.0"
+        );
+        assert_eq!(
+            fs::read_to_string(&installs).unwrap().trim(),
+            "completions --install"
+        );
+        // Nothing is left next to the binary
+        assert_eq!(fs::read_dir(exe.parent().unwrap()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_upgrade_refuses_a_download_that_does_not_match_its_checksum() {
+        let temp_dir = TempDir::new().unwrap();
+        let exe = installed_copy(temp_dir.path());
+        let tampered = temp_dir.path().join("tampered");
+        let ran = temp_dir.path().join("ran");
+        script(
+            &tampered,
+            &format!(r#"touch "{}"; echo "claudectl 99.0.0""#, ran.display()),
+        );
+        let url =
+            fake_release_with_sum(temp_dir.path(), "99.0.0", &tampered, Some(&"ab".repeat(32)));
+
+        let output = Command::new(&exe)
+            .arg("upgrade")
+            .current_dir(temp_dir.path())
+            .env("CLAUDECTL_RELEASES_URL", &url)
+            .output()
+            .unwrap();
+
+        assert!(!output.status.success());
+        assert!(
+            String::from_utf8_lossy(&output.stderr)
+                .contains("does not match the release's checksum")
+        );
+        // The download was never run, nor swapped in
+        assert!(!ran.exists());
+        let version = Command::new(&exe).arg("--version").output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&version.stdout).trim(),
+            format!("claudectl {}", env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(fs::read_dir(exe.parent().unwrap()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_upgrade_keeps_the_binary_when_the_download_is_broken() {
+        let temp_dir = TempDir::new().unwrap();
+        let exe = installed_copy(temp_dir.path());
+        let broken = temp_dir.path().join("broken");
+        script(&broken, "exit 1");
+        let url = fake_release(temp_dir.path(), "99.0.0", &broken);
+
+        let output = Command::new(&exe)
+            .arg("upgrade")
+            .current_dir(temp_dir.path())
+            .env("CLAUDECTL_RELEASES_URL", &url)
+            .output()
+            .unwrap();
+
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("is not claudectl 99.0.0"));
+        let version = Command::new(&exe).arg("--version").output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&version.stdout).trim(),
+            format!("claudectl {}", env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(fs::read_dir(exe.parent().unwrap()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,316 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::process::Stdio;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+use crate::common::{
+    FakeClaude, add_origin, add_task, claudectl, claudectl_process, init_project, lock_exists,
+};
+
+#[test]
+fn test_watch_requires_a_known_template() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo)
+        .args([
+            "watch",
+            "--on-change",
+            "tests/**",
+            "--template",
+            "fix-tests",
+        ])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Unknown prompt template 'fix-tests'")
+    );
+}
+
+#[test]
+fn test_watch_starts_a_task_when_files_change() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    fs::write(
+        repo.join(".claudectl/config.json"),
+        format!(
+            r#"{{
+                "project_name": "test-project",
+                "project_dir": "{}",
+                "prompt_templates": {{ "fix-tests": "Fix these:\n{{files}}" }}
+            }}"#,
+            temp_dir.path().join("worktrees").display()
+        ),
+    )
+    .unwrap();
+    fs::create_dir(repo.join("tests")).unwrap();
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let mut child = claudectl_process(&repo)
+        .args([
+            "watch",
+            "--on-change",
+            "tests/**",
+            "--template",
+            "fix-tests",
+        ])
+        .args(["--interval", "1"])
+        .env("PATH", claude.path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    sleep(Duration::from_millis(1500));
+    fs::write(repo.join("README.md"), "not watched\n").unwrap();
+    fs::write(repo.join("tests/login.rs"), "#[test] fn login() {}\n").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(20);
+    while claude.call_count() == 0 && Instant::now() < deadline {
+        sleep(Duration::from_millis(200));
+    }
+    // Let the session finish recording its invocation
+    sleep(Duration::from_secs(1));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let invocations = claude.invocations();
+    assert_eq!(invocations.len(), 1);
+    assert_eq!(
+        invocations[0].args.last().unwrap(),
+        "Fix these:\ntests/login.rs"
+    );
+    let cwd = invocations[0].cwd.to_string_lossy().to_string();
+    assert!(cwd.contains("worktrees/watch/fix-tests-"), "{cwd}");
+}
+
+#[test]
+fn test_watch_supervise_restarts_crashed_sessions() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    fs::write(
+        repo.join(".claudectl/config.json"),
+        format!(
+            r#"{{
+                "project_name": "test-project",
+                "project_dir": "{}",
+                "supervision": {{ "restart": "on-crash", "backoff_secs": 0, "restart_prompt": "Carry on" }}
+            }}"#,
+            temp_dir.path().join("worktrees").display()
+        ),
+    )
+    .unwrap();
+    add_task(&repo, "feat/crashed");
+    // Far above any default pid_max, so never a running process
+    let locks = repo.join(".claudectl/locks");
+    fs::create_dir_all(&locks).unwrap();
+    fs::write(
+        locks.join("feat__crashed.json"),
+        r#"{ "pid": 99999999, "command": "task --start", "started_at": "2024-09-01T16:03:00Z" }"#,
+    )
+    .unwrap();
+    let claude = FakeClaude::new(&temp_dir).install();
+
+    let mut child = claudectl_process(&repo)
+        .args(["watch", "--supervise", "--interval", "1"])
+        .env("PATH", claude.path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let deadline = Instant::now() + Duration::from_secs(20);
+    while claude.call_count() == 0 && Instant::now() < deadline {
+        sleep(Duration::from_millis(200));
+    }
+    // Let the restarted run finish and release the task
+    while lock_exists(&repo, "feat/crashed") && Instant::now() < deadline {
+        sleep(Duration::from_millis(200));
+    }
+    sleep(Duration::from_secs(1));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let invocations = claude.invocations();
+    assert_eq!(invocations.len(), 1);
+    assert!(invocations[0].args.last().unwrap().ends_with("Carry on"));
+    let cwd = invocations[0].cwd.to_string_lossy().to_string();
+    assert!(cwd.ends_with("worktrees/feat/crashed"), "{cwd}");
+
+    let output = claudectl(&repo)
+        .args(["history", "--task", "feat/crashed"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("session restarted"), "{stdout}");
+    assert!(stdout.contains("run finished"), "{stdout}");
+}
+
+#[test]
+fn test_watch_supervise_restarts_sessions_that_exit_with_an_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    fs::write(
+        repo.join(".claudectl/config.json"),
+        format!(
+            r#"{{
+                "project_name": "test-project",
+                "project_dir": "{}",
+                "supervision": {{ "restart": "on-failure", "max_restarts": 1, "backoff_secs": 0 }}
+            }}"#,
+            temp_dir.path().join("worktrees").display()
+        ),
+    )
+    .unwrap();
+    add_task(&repo, "feat/flaky");
+    let claude = FakeClaude::new(&temp_dir).fails(1, "boom").install();
+
+    let mut child = claudectl_process(&repo)
+        .args(["watch", "--supervise", "--interval", "1"])
+        .env("PATH", claude.path())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    sleep(Duration::from_secs(1));
+    claudectl(&repo)
+        .args(["restart", "feat/flaky"])
+        .env("PATH", claude.path())
+        .assert()
+        .failure();
+
+    // The failed restart is retried once, and that failure is only recorded
+    let deadline = Instant::now() + Duration::from_secs(20);
+    let stopped = || {
+        let output = claudectl(&repo)
+            .args(["history", "--task", "feat/flaky"])
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).contains("session marked stopped")
+    };
+    while !stopped() && Instant::now() < deadline {
+        sleep(Duration::from_millis(200));
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    assert!(stopped());
+    let invocations = claude.invocations();
+    assert_eq!(invocations.len(), 2);
+    assert_eq!(invocations[1].args[2], "-p");
+}
+
+#[test]
+fn test_watch_needs_patterns_or_supervise() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+
+    let output = claudectl(&repo).arg("watch").output().unwrap();
+    assert!(!output.status.success());
+
+    let output = claudectl(&repo)
+        .args(["watch", "--supervise", "--template", "fix-tests"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+}
+
+#[test]
+fn test_watch_supervise_leaves_killed_sessions_stopped() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    fs::write(
+        repo.join(".claudectl/config.json"),
+        format!(
+            r#"{{
+                "project_name": "test-project",
+                "project_dir": "{}",
+                "supervision": {{ "restart": "on-crash", "backoff_secs": 0 }}
+            }}"#,
+            temp_dir.path().join("worktrees").display()
+        ),
+    )
+    .unwrap();
+    add_task(&repo, "feat/busy");
+    // A claude that runs until it is stopped, counting its starts
+    let bin = temp_dir.path().join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    let starts = temp_dir.path().join("starts");
+    fs::write(
+        bin.join("claude"),
+        format!(
+            "#!/bin/sh\necho start >> \"{}\"\nexec sleep 30\n",
+            starts.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(bin.join("claude"), fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin.display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    // Keeps the session's process alive after it records how it ended,
+    // for the supervisor to look in the meantime
+    let hooks = repo.join(".claudectl/hooks");
+    fs::create_dir_all(&hooks).unwrap();
+    fs::write(
+        hooks.join("post-session"),
+        "#!/bin/sh
+sleep 2
+",
+    )
+    .unwrap();
+    fs::set_permissions(
+        hooks.join("post-session"),
+        fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+
+    let mut watch = claudectl_process(&repo)
+        .args(["watch", "--supervise", "--interval", "1"])
+        .env("PATH", &path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut session = claudectl_process(&repo)
+        .args(["restart", "feat/busy"])
+        .env("PATH", &path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    // Reaped as soon as it exits, so `kill` does not wait on a zombie
+    let reaper = std::thread::spawn(move || session.wait().unwrap());
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !starts.exists() && Instant::now() < deadline {
+        sleep(Duration::from_millis(100));
+    }
+    claudectl(&repo)
+        .args(["kill", "feat/busy"])
+        .assert()
+        .success();
+    reaper.join().unwrap();
+
+    // Long enough for a few passes of the supervisor
+    sleep(Duration::from_secs(3));
+    watch.kill().unwrap();
+    watch.wait().unwrap();
+    assert_eq!(fs::read_to_string(&starts).unwrap(), "start\n");
+    let output = claudectl(&repo)
+        .args(["history", "--task", "feat/busy"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("session restarted"), "{stdout}");
+}
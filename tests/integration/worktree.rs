@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+use crate::common::{add_origin, add_task, claudectl, git, init_project};
+
+fn commit_file(dir: &Path, file: &str, content: &str, message: &str) {
+    fs::write(dir.join(file), content).unwrap();
+    git(dir, &["add", file]);
+    git(dir, &["commit", "-q", "-m", message]);
+}
+
+#[test]
+fn test_worktree_sync_rebases_onto_fetched_base() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_origin(&repo);
+    let task = add_task(&repo, "feat/login");
+    commit_file(&task, "login.txt", "login\n", "add login");
+    commit_file(&repo, "main.txt", "main\n", "move main on");
+    git(&repo, &["push", "-q", "origin", "main"]);
+    // Only a fetch finds the new commit
+    git(&repo, &["update-ref", "refs/remotes/origin/main", "HEAD~1"]);
+
+    let output = claudectl(&repo)
+        .args(["worktree", "sync", "feat/login"])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("Synced 'feat/login' with origin/main")
+    );
+    assert!(task.join("main.txt").exists());
+    // Rebased: the task's commit sits on top of main's, with no merge commit
+    assert_eq!(
+        git(&task, &["rev-list", "--count", "--merges", "HEAD"]),
+        "0"
+    );
+    assert_eq!(git(&task, &["log", "-1", "--format=%s"]), "add login");
+}
+
+#[test]
+fn test_worktree_sync_merges_with_merge() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let task = add_task(&repo, "feat/login");
+    commit_file(&task, "login.txt", "login\n", "add login");
+    commit_file(&repo, "main.txt", "main\n", "move main on");
+
+    let output = claudectl(&repo)
+        .args(["worktree", "sync", "feat/login", "--merge"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(task.join("main.txt").exists());
+    assert_eq!(
+        git(&task, &["rev-list", "--count", "--merges", "HEAD"]),
+        "1"
+    );
+}
+
+#[test]
+fn test_worktree_sync_reports_when_up_to_date() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    add_task(&repo, "feat/login");
+
+    let output = claudectl(&repo)
+        .args(["worktree", "sync", "feat/login"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is up to date with main"));
+}
+
+#[test]
+fn test_worktree_sync_lists_conflicting_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let task = add_task(&repo, "feat/readme");
+    commit_file(&task, "README.md", "task\n", "task readme");
+    commit_file(&repo, "README.md", "main\n", "main readme");
+
+    let output = claudectl(&repo)
+        .args(["worktree", "sync", "feat/readme"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("stopped on conflicts in 1 file(s)"));
+    assert!(stdout.contains("README.md"));
+    assert!(stdout.contains("claudectl resolve feat/readme"));
+    assert!(stderr.contains("git rebase --abort"));
+}
+
+#[test]
+fn test_worktree_sync_refuses_uncommitted_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let task = add_task(&repo, "feat/login");
+    fs::write(task.join("wip.txt"), "wip\n").unwrap();
+
+    let output = claudectl(&repo)
+        .args(["worktree", "sync", "feat/login"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 uncommitted file(s)"));
+}
+
+#[test]
+fn test_worktree_sync_keeps_an_untouched_task_from_counting_as_merged() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = init_project(&temp_dir);
+    let task = add_task(&repo, "feat/untouched");
+    let fork_point = git(&repo, &["rev-parse", "feat/untouched"]);
+    fs::create_dir_all(repo.join(".claudectl/tasks")).unwrap();
+    fs::write(
+        repo.join(".claudectl/tasks/feat__untouched.json"),
+        format!(r#"{{ "name": "feat/untouched", "base_commit": "{fork_point}" }}"#),
+    )
+    .unwrap();
+    commit_file(&repo, "main.txt", "main\n", "move main on");
+
+    let output = claudectl(&repo)
+        .args(["worktree", "sync", "feat/untouched"])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(task.join("main.txt").exists());
+
+    let output = claudectl(&repo)
+        .args(["--plain", "clean", "--dry-run"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("feat/untouched"));
+}